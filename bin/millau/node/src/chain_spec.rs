@@ -139,6 +139,8 @@ impl Alternative {
 								millau_runtime::Runtime,
 								millau_runtime::WithRialtoMessagesInstance,
 							>::relayer_fund_account_id(),
+							// sovereign account pays for messages, sent by the forwarding pallet
+							derive_account_from_rialto_id(bp_runtime::SourceAccount::Root),
 							derive_account_from_rialto_id(bp_runtime::SourceAccount::Account(
 								get_account_id_from_seed::<sr25519::Public>("Alice"),
 							)),
@@ -211,6 +213,7 @@ fn testnet_genesis(
 		},
 		bridge_rialto_messages: BridgeRialtoMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>("RialtoMessagesOwner")),
+			opened_lanes: vec![[0, 0, 0, 0], *b"swap", *b"frwd"],
 			..Default::default()
 		},
 	}
@@ -34,7 +34,7 @@ pub mod rialto_messages;
 
 use crate::rialto_messages::{ToRialtoMessagePayload, WithRialtoMessageBridge};
 
-use bridge_runtime_common::messages::{source::estimate_message_dispatch_and_delivery_fee, MessageBridge};
+use bridge_runtime_common::messages::{source::estimate_message_dispatch_and_delivery_fee_at_lane, MessageBridge};
 use pallet_grandpa::{fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use pallet_transaction_payment::{FeeDetails, Multiplier, RuntimeDispatchInfo};
 use sp_api::impl_runtime_apis;
@@ -209,12 +209,20 @@ impl pallet_randomness_collective_flip::Config for Runtime {}
 impl pallet_aura::Config for Runtime {
 	type AuthorityId = AuraId;
 }
+
+parameter_types! {
+	pub const MaxDecompressedMessageCallSize: u32 = bp_millau::MAX_DECOMPRESSED_MESSAGE_CALL_SIZE;
+	pub const MessageCallDecompressionWeight: Weight = bp_millau::MESSAGE_CALL_DECOMPRESSION_WEIGHT;
+}
+
 impl pallet_bridge_dispatch::Config for Runtime {
 	type Event = Event;
 	type MessageId = (bp_messages::LaneId, bp_messages::MessageNonce);
 	type Call = Call;
 	type CallFilter = ();
 	type EncodedCall = crate::rialto_messages::FromRialtoEncodedCall;
+	type MaxDecompressedCallSize = MaxDecompressedMessageCallSize;
+	type CallDecompressionWeight = MessageCallDecompressionWeight;
 	type SourceChainAccountId = bp_rialto::AccountId;
 	type TargetChainAccountPublic = MultiSigner;
 	type TargetChainSignature = MultiSignature;
@@ -291,6 +299,31 @@ impl pallet_transaction_payment::Config for Runtime {
 	>;
 }
 
+parameter_types! {
+	pub const AssetDeposit: Balance = 1_000_000;
+	pub const ApprovalDeposit: Balance = 1_000;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 1_000;
+	pub const MetadataDepositPerByte: Balance = 10;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = bp_runtime::messages::AssetId;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	// TODO: update me (https://github.com/paritytech/parity-bridges-common/issues/78)
+	type WeightInfo = ();
+}
+
 impl pallet_sudo::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -332,9 +365,11 @@ parameter_types! {
 
 pub type RialtoGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_rialto::Rialto;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeaderAge = ();
 
 	// TODO [#391]: Use weights generated for the Millau runtime instead of Rialto ones.
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
@@ -342,14 +377,29 @@ impl pallet_bridge_grandpa::Config for Runtime {
 
 pub type WestendGrandpaInstance = pallet_bridge_grandpa::Instance1;
 impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_westend::Westend;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeaderAge = ();
 
 	// TODO [#391]: Use weights generated for the Millau runtime instead of Rialto ones.
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const RialtoParasPalletName: &'static str = "Paras";
+	pub const RialtoParachainHeadsToKeep: u32 = 1024;
+}
+
+impl pallet_bridge_parachains::Config for Runtime {
+	type Event = Event;
+	type BridgesGrandpaPalletInstance = RialtoGrandpaInstance;
+	type ParasPalletName = RialtoParasPalletName;
+	type TrackedParachains = ();
+	type HeadsToKeep = RialtoParachainHeadsToKeep;
+}
+
 impl pallet_shift_session_manager::Config for Runtime {}
 
 parameter_types! {
@@ -358,6 +408,10 @@ parameter_types! {
 		bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE;
+	// 10% of the block weight is reserved for dispatching queued inbound messages
+	pub const QueuedMessagesDispatchWeight: Weight = bp_millau::MAXIMUM_BLOCK_WEIGHT / 10;
+	pub const QueuedMessageByteDeposit: Balance = 1_000;
+	pub const AccountRateLimitPeriod: BlockNumber = bp_millau::MINUTES;
 	// `IdentityFee` is used by Millau => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_millau::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
@@ -376,6 +430,10 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type QueuedMessagesDispatchWeight = QueuedMessagesDispatchWeight;
+	type AccountRateLimitPeriod = AccountRateLimitPeriod;
+	type MaxMessagesPerAccountPerPeriod = rialto_messages::MaxMessagesPerAccountPerPeriod;
+	type MaxLaneBytesPerBlock = rialto_messages::MaxLaneBytesPerBlock;
 
 	type OutboundPayload = crate::rialto_messages::ToRialtoMessagePayload;
 	type OutboundMessageFee = Balance;
@@ -388,22 +446,46 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 
 	type TargetHeaderChain = crate::rialto_messages::Rialto;
 	type LaneMessageVerifier = crate::rialto_messages::ToRialtoMessageVerifier;
-	type MessageDeliveryAndDispatchPayment = pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+	type MessageDeliveryAndDispatchPayment = pallet_bridge_relayers::MessageDeliveryAndDispatchPaymentAdapter<
 		Runtime,
-		pallet_balances::Pallet<Runtime>,
 		GetDeliveryConfirmationTransactionFee,
 		RootAccountForPayments,
 	>;
 	type OnMessageAccepted = ();
-	type OnDeliveryConfirmed = pallet_bridge_token_swap::Pallet<Runtime, WithRialtoTokenSwapInstance>;
+	type OnDeliveryConfirmed = ();
+	type MessageOwners = (
+		pallet_bridge_token_swap::Pallet<Runtime, WithRialtoTokenSwapInstance>,
+		pallet_bridge_forwarding::Pallet<Runtime, WithRialtoForwardingInstance>,
+	);
 
 	type SourceHeaderChain = crate::rialto_messages::Rialto;
-	type MessageDispatch = crate::rialto_messages::FromRialtoMessageDispatch;
+	type MessageDispatch = crate::rialto_messages::FromRialtoMessageRouter;
+	type RelayersRegistry = BridgeRelayers;
+	type QueuedMessagesDeposit = pallet_bridge_messages::queued_messages_deposit::CurrencyQueuedMessagesDeposit<
+		pallet_balances::Pallet<Runtime>,
+		QueuedMessageByteDeposit,
+	>;
 	type BridgedChainId = RialtoChainId;
 }
 
+parameter_types! {
+	pub RelayersFundAccount: AccountId =
+		pallet_bridge_messages::Pallet::<Runtime, WithRialtoMessagesInstance>::relayer_fund_account_id();
+	pub const RelayerStake: Balance = 1_000_000;
+	pub const RelayerSlashAmount: Balance = 100_000;
+}
+
+impl pallet_bridge_relayers::Config for Runtime {
+	type Event = Event;
+	type Currency = pallet_balances::Pallet<Runtime>;
+	type RelayersFundAccount = RelayersFundAccount;
+	type RelayerStake = RelayerStake;
+	type RelayerSlashAmount = RelayerSlashAmount;
+}
+
 parameter_types! {
 	pub const TokenSwapMessagesLane: bp_messages::LaneId = *b"swap";
+	pub const TokenSwapMessagesOwner: bp_messages::source_chain::MessageOwnerId = *b"swap";
 }
 
 /// Instance of the with-Rialto token swap pallet.
@@ -414,6 +496,7 @@ impl pallet_bridge_token_swap::Config<WithRialtoTokenSwapInstance> for Runtime {
 
 	type BridgedChainId = RialtoChainId;
 	type OutboundMessageLaneId = TokenSwapMessagesLane;
+	type OutboundMessagesOwnerId = TokenSwapMessagesOwner;
 	type MessagesBridge = pallet_bridge_messages::Pallet<Runtime, WithRialtoMessagesInstance>;
 	type ThisCurrency = pallet_balances::Pallet<Runtime>;
 	type FromSwapToThisAccountIdConverter = bp_rialto::AccountIdConverter;
@@ -422,6 +505,38 @@ impl pallet_bridge_token_swap::Config<WithRialtoTokenSwapInstance> for Runtime {
 	type FromBridgedToThisAccountIdConverter = bp_millau::AccountIdConverter;
 }
 
+parameter_types! {
+	pub const ForwardingMessagesOwner: bp_messages::source_chain::MessageOwnerId = *b"frwd";
+	// TODO: https://github.com/paritytech/parity-bridges-common/issues/390
+	pub const ForwardMessageWeight: Weight = WEIGHT_PER_SECOND / 100;
+	pub const SendReportWeight: Weight = WEIGHT_PER_SECOND / 100;
+}
+
+/// Instance of the forwarding pallet that is forwarding messages of Rialto.
+pub type WithRialtoForwardingInstance = ();
+
+impl pallet_bridge_forwarding::Config<WithRialtoForwardingInstance> for Runtime {
+	type Event = Event;
+
+	type SourceChainId = RialtoChainId;
+	type SourceChainAccountIdConverter = bp_millau::AccountIdConverter;
+
+	type InboundMessageFee = bp_rialto::Balance;
+	type MessageDispatch = crate::rialto_messages::FromRialtoMessageDispatch;
+	type ForwardMessageWeight = ForwardMessageWeight;
+
+	type OutboundMessagesOwnerId = ForwardingMessagesOwner;
+	type OutboundMessageFee = Balance;
+	type NextHopPayload = crate::rialto_messages::ToRialtoMessagePayload;
+	type NextHopBridge = pallet_bridge_messages::Pallet<Runtime, WithRialtoMessagesInstance>;
+
+	type ReportPayload = crate::rialto_messages::ToRialtoMessagePayload;
+	type ReportPayloadConverter = crate::rialto_messages::ToRialtoDispatchReportConverter;
+	type ReportBridge = pallet_bridge_messages::Pallet<Runtime, WithRialtoMessagesInstance>;
+	type ReportDeliveryAndDispatchFee = crate::rialto_messages::ToRialtoDispatchReportFee;
+	type SendReportWeight = SendReportWeight;
+}
+
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
@@ -437,6 +552,7 @@ construct_runtime!(
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 
 		// Consensus support.
 		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
@@ -445,13 +561,18 @@ construct_runtime!(
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
 
 		// Rialto bridge modules.
-		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
+		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
+		BridgeRialtoParachains: pallet_bridge_parachains::{Pallet, Call, Storage, Event<T>},
 		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
 		BridgeRialtoMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 		BridgeRialtoTokenSwap: pallet_bridge_token_swap::{Pallet, Call, Storage, Event<T>},
+		BridgeRialtoForwarding: pallet_bridge_forwarding::{Pallet, Storage, Event<T>},
 
 		// Westend bridge modules.
-		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage},
+		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage, Event<T>},
+
+		// Relayers rewards ledger.
+		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -482,8 +603,25 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signatu
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive =
-	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPallets>;
+pub type Executive = frame_executive::Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllPallets,
+	(
+		pallet_bridge_messages::migration::MigrateToV1<Runtime, WithRialtoMessagesInstance>,
+		pallet_bridge_messages::migration::RegisterMessagesOwner<
+			Runtime,
+			WithRialtoMessagesInstance,
+			pallet_bridge_token_swap::Pallet<Runtime, WithRialtoTokenSwapInstance>,
+			TokenSwapMessagesLane,
+			pallet_bridge_token_swap::PendingMessagesNonces<Runtime, WithRialtoTokenSwapInstance>,
+		>,
+		pallet_bridge_grandpa::migration::MigrateToV1<Runtime, RialtoGrandpaInstance>,
+		pallet_bridge_grandpa::migration::MigrateToV1<Runtime, WestendGrandpaInstance>,
+	),
+>;
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -623,6 +761,10 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_rialto::Hash) -> bool {
 			BridgeRialtoGrandpa::is_known_header(hash)
 		}
+
+		fn finalized_header_at_or_before(number: bp_rialto::BlockNumber) -> Option<bp_rialto::Header> {
+			BridgeRialtoGrandpa::finalized_header_at_or_before(number)
+		}
 	}
 
 	impl bp_westend::WestendFinalityApi<Block> for Runtime {
@@ -634,14 +776,19 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_westend::Hash) -> bool {
 			BridgeWestendGrandpa::is_known_header(hash)
 		}
+
+		fn finalized_header_at_or_before(number: bp_westend::BlockNumber) -> Option<bp_westend::Header> {
+			BridgeWestendGrandpa::finalized_header_at_or_before(number)
+		}
 	}
 
-	impl bp_rialto::ToRialtoOutboundLaneApi<Block, Balance, ToRialtoMessagePayload> for Runtime {
+	impl bp_rialto::ToRialtoOutboundLaneApi<Block, AccountId, Balance, ToRialtoMessagePayload> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
-			_lane_id: bp_messages::LaneId,
+			lane_id: bp_messages::LaneId,
 			payload: ToRialtoMessagePayload,
 		) -> Option<Balance> {
-			estimate_message_dispatch_and_delivery_fee::<WithRialtoMessageBridge>(
+			estimate_message_dispatch_and_delivery_fee_at_lane::<WithRialtoMessageBridge>(
+				&pallet_bridge_messages::OutboundLanes::<Runtime, WithRialtoMessagesInstance>::get(lane_id),
 				&payload,
 				WithRialtoMessageBridge::RELAYER_FEE_PERCENT,
 			).ok()
@@ -651,7 +798,7 @@ impl_runtime_apis! {
 			lane: bp_messages::LaneId,
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
-		) -> Vec<bp_messages::MessageDetails<Balance>> {
+		) -> Vec<bp_messages::MessageDetails<Balance, AccountId>> {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithRialtoMessagesInstance,
@@ -666,6 +813,24 @@ impl_runtime_apis! {
 		fn latest_generated_nonce(lane: bp_messages::LaneId) -> bp_messages::MessageNonce {
 			BridgeRialtoMessages::outbound_latest_generated_nonce(lane)
 		}
+
+		fn lanes() -> Vec<(bp_messages::LaneId, bp_messages::LaneState)> {
+			BridgeRialtoMessages::lanes()
+		}
+
+		fn message_payloads(
+			lane: bp_messages::LaneId,
+			begin: bp_messages::MessageNonce,
+			end: bp_messages::MessageNonce,
+		) -> Vec<bp_messages::MessagePayloadDetails<Balance>> {
+			BridgeRialtoMessages::outbound_message_payloads(lane, begin, end)
+		}
+	}
+
+	impl bp_relayers::RelayersApi<Block, AccountId, Balance> for Runtime {
+		fn unclaimed_rewards(relayer: AccountId, lane: bp_messages::LaneId) -> Balance {
+			BridgeRelayers::unclaimed_rewards(relayer, lane)
+		}
 	}
 
 	impl bp_rialto::FromRialtoInboundLaneApi<Block> for Runtime {
@@ -680,6 +845,28 @@ impl_runtime_apis! {
 		fn unrewarded_relayers_state(lane: bp_messages::LaneId) -> bp_messages::UnrewardedRelayersState {
 			BridgeRialtoMessages::inbound_unrewarded_relayers_state(lane)
 		}
+
+		fn latest_queued_nonce(lane: bp_messages::LaneId) -> Option<bp_messages::MessageNonce> {
+			BridgeRialtoMessages::inbound_latest_queued_nonce(lane)
+		}
+
+		fn inbound_lane_data(lane: bp_messages::LaneId) -> bp_messages::InboundLaneData<bp_rialto::AccountId> {
+			BridgeRialtoMessages::inbound_lane_data(lane)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> (Weight, Weight) {
+			let weight = Executive::try_runtime_upgrade().unwrap();
+			(weight, bp_millau::MAXIMUM_BLOCK_WEIGHT)
+		}
+
+		fn execute_block_no_check(block: Block) -> Weight {
+			let weight = Executive::execute_block_no_check(block);
+			BridgeRialtoMessages::do_try_state().expect("Messages pallet invariants are broken");
+			weight
+		}
 	}
 }
 
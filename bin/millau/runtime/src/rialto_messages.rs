@@ -18,40 +18,62 @@
 
 use crate::Runtime;
 
+use bp_message_dispatch::{
+	CallEncoding, CallOrigin, ForwardedMessageDispatchReport, ForwardingPayload, MessagePayload,
+};
 use bp_messages::{
-	source_chain::TargetHeaderChain,
-	target_chain::{ProvedMessages, SourceHeaderChain},
-	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+	source_chain::{LaneMessageVerifier, Sender, TargetHeaderChain},
+	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch, ProvedMessages, SourceHeaderChain},
+	InboundLaneData, LaneId, Message, MessageNonce, OutboundLaneData, Parameter as MessagesParameter,
+};
+use bp_runtime::{
+	messages::{AssetId, DispatchFeePayment, MessageDispatchResult},
+	ChainId, SourceAccount, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID,
 };
-use bp_runtime::{ChainId, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
 use bridge_runtime_common::messages::{self, MessageBridge, MessageTransaction};
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
 use frame_support::{
 	parameter_types,
-	weights::{DispatchClass, Weight},
+	traits::Get,
+	weights::{DispatchClass, Weight, WeightToFeePolynomial},
 	RuntimeDebug,
 };
-use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
+use sp_runtime::{
+	traits::{Convert, Saturating},
+	FixedPointNumber, FixedU128,
+};
 use sp_std::{convert::TryFrom, ops::RangeInclusive};
 
 /// Initial value of `RialtoToMillauConversionRate` parameter.
 pub const INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
 /// Initial value of `RialtoFeeMultiplier` parameter.
 pub const INITIAL_RIALTO_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `MaxMessagesPerAccountPerPeriod` parameter.
+pub const INITIAL_MAX_MESSAGES_PER_ACCOUNT_PER_PERIOD: MessageNonce = 128;
+/// Initial value of `MaxLaneBytesPerBlock` parameter.
+pub const INITIAL_MAX_LANE_BYTES_PER_BLOCK: u32 = 8 * 1024 * 1024;
+/// Number of pending messages at the outbound lane that don't affect the message fee.
+pub const FREE_PENDING_MESSAGES_AT_OUTBOUND_LANE: MessageNonce = 128;
+/// Increase of the message fee (in percents) for every pending message over the
+/// `FREE_PENDING_MESSAGES_AT_OUTBOUND_LANE`.
+pub const CONGESTION_FEE_PERCENT_PER_MESSAGE: u32 = 1;
+/// Maximal congestion multiplier (in percents) of the message fee.
+pub const MAX_CONGESTION_FEE_MULTIPLIER_PERCENT: u32 = 1_000;
 
 parameter_types! {
 	/// Rialto to Millau conversion rate. Initially we treat both tokens as equal.
 	pub storage RialtoToMillauConversionRate: FixedU128 = INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE;
 	/// Fee multiplier value at Rialto chain.
 	pub storage RialtoFeeMultiplier: FixedU128 = INITIAL_RIALTO_FEE_MULTIPLIER;
+	/// Maximal number of messages that single account may send to Rialto during `AccountRateLimitPeriod`.
+	pub storage MaxMessagesPerAccountPerPeriod: MessageNonce = INITIAL_MAX_MESSAGES_PER_ACCOUNT_PER_PERIOD;
+	/// Maximal total size of messages that may be sent to Rialto over single lane in a single block.
+	pub storage MaxLaneBytesPerBlock: u32 = INITIAL_MAX_LANE_BYTES_PER_BLOCK;
 }
 
 /// Message payload for Millau -> Rialto messages.
 pub type ToRialtoMessagePayload = messages::source::FromThisChainMessagePayload<WithRialtoMessageBridge>;
 
-/// Message verifier for Millau -> Rialto messages.
-pub type ToRialtoMessageVerifier = messages::source::FromThisChainMessageVerifier<WithRialtoMessageBridge>;
-
 /// Message payload for Rialto -> Millau messages.
 pub type FromRialtoMessagePayload = messages::target::FromBridgedChainMessagePayload<WithRialtoMessageBridge>;
 
@@ -69,9 +91,259 @@ pub type FromRialtoMessageDispatch = messages::target::FromBridgedChainMessageDi
 	WithRialtoMessageBridge,
 	crate::Runtime,
 	pallet_balances::Pallet<Runtime>,
+	FromRialtoDispatchFeeAssets,
 	(),
 >;
 
+/// Non-native Millau assets that may be used to pay dispatch fee of Rialto -> Millau messages.
+pub type FromRialtoDispatchFeeAssets =
+	messages::target::FungiblesDispatchFeeAssets<pallet_assets::Pallet<Runtime>, FromRialtoDispatchFeeAssetConverter>;
+
+/// Converter of Rialto -> Millau messages dispatch weight into fee, paid in Millau assets.
+///
+/// Only assets from `bp_millau::DISPATCH_FEE_ASSETS` are accepted. In our testnets, the fee in these
+/// assets is the same as the fee in Millau tokens.
+#[derive(RuntimeDebug)]
+pub struct FromRialtoDispatchFeeAssetConverter;
+
+impl messages::target::WeightToAssetFee<bp_millau::Balance> for FromRialtoDispatchFeeAssetConverter {
+	fn weight_to_asset_fee(asset_id: AssetId, dispatch_weight: Weight) -> Option<bp_millau::Balance> {
+		if !bp_millau::DISPATCH_FEE_ASSETS.contains(&asset_id) {
+			return None;
+		}
+
+		let unadjusted_weight_fee =
+			<Runtime as pallet_transaction_payment::Config>::WeightToFee::calc(&dispatch_weight);
+		let fee_multiplier = pallet_transaction_payment::Pallet::<Runtime>::next_fee_multiplier();
+		Some(fee_multiplier.saturating_mul_int(unadjusted_weight_fee))
+	}
+}
+
+/// Forwarding payload of Millau -> Rialto messages, sent over the `FORWARDING_LANE`.
+pub type ToRialtoForwardingPayload = ForwardingPayload<ToRialtoMessagePayload, bp_rialto::Balance>;
+
+/// Forwarding payload of Rialto -> Millau messages, sent over the `FORWARDING_LANE`.
+pub type FromRialtoForwardingPayload =
+	pallet_bridge_forwarding::ForwardingPayloadOf<Runtime, crate::WithRialtoForwardingInstance>;
+
+/// Lane that is used to exchange forwarding messages with Rialto.
+///
+/// Messages of this lane are regular call dispatch messages, but their call is the SCALE-encoded
+/// `ForwardingPayload`. They are dispatched by the forwarding pallet, so the `spec_version` of
+/// these messages is ignored. The declared `weight` of the message must cover the weight of its
+/// dispatch by the forwarding pallet.
+pub const FORWARDING_LANE: LaneId = *b"frwd";
+
+const BAD_FORWARDING_MESSAGE: &str = "The forwarding message is malformed.";
+const BAD_FORWARDING_MESSAGE_SENDER: &str = "The sender is not allowed to send the forwarding message.";
+
+/// Message verifier for Millau -> Rialto messages.
+///
+/// Messages of the `FORWARDING_LANE` are additionally checked, so that only privileged senders are
+/// able to send forwarded messages and dispatch reports.
+#[derive(RuntimeDebug)]
+pub struct ToRialtoMessageVerifier;
+
+impl LaneMessageVerifier<bp_millau::AccountId, ToRialtoMessagePayload, bp_millau::Balance> for ToRialtoMessageVerifier {
+	type Error = &'static str;
+
+	fn verify_message(
+		submitter: &Sender<bp_millau::AccountId>,
+		delivery_and_dispatch_fee: &bp_millau::Balance,
+		lane: &LaneId,
+		lane_outbound_data: &OutboundLaneData,
+		payload: &ToRialtoMessagePayload,
+	) -> Result<(), Self::Error> {
+		if *lane == FORWARDING_LANE {
+			verify_forwarding_message(submitter, payload)?;
+		}
+
+		messages::source::FromThisChainMessageVerifier::<WithRialtoMessageBridge>::verify_message(
+			submitter,
+			delivery_and_dispatch_fee,
+			lane,
+			lane_outbound_data,
+			payload,
+		)
+	}
+}
+
+/// Verify Millau -> Rialto message of the `FORWARDING_LANE`.
+fn verify_forwarding_message(
+	submitter: &Sender<bp_millau::AccountId>,
+	payload: &ToRialtoMessagePayload,
+) -> Result<(), &'static str> {
+	if payload.call_encoding() != CallEncoding::Plain
+		|| payload.dispatch_fee_payment != DispatchFeePayment::AtSourceChain
+	{
+		return Err(BAD_FORWARDING_MESSAGE);
+	}
+
+	let forwarding_payload =
+		ToRialtoForwardingPayload::decode_all(&payload.call[..]).map_err(|_| BAD_FORWARDING_MESSAGE)?;
+	let sovereign_account =
+		pallet_bridge_forwarding::source_chain_sovereign_account::<Runtime, crate::WithRialtoForwardingInstance>();
+	if !pallet_bridge_forwarding::is_allowed_sender(submitter, &sovereign_account, &forwarding_payload) {
+		return Err(BAD_FORWARDING_MESSAGE_SENDER);
+	}
+
+	Ok(())
+}
+
+/// Dispatch of Rialto -> Millau messages.
+///
+/// Messages of the `FORWARDING_LANE` are dispatched by the forwarding pallet. All other messages are
+/// dispatched by the call dispatch pallet.
+pub struct FromRialtoMessageRouter;
+
+impl MessageDispatch<bp_millau::AccountId, bp_rialto::Balance> for FromRialtoMessageRouter {
+	type DispatchPayload = FromRialtoMessagePayload;
+
+	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, bp_rialto::Balance>) -> Weight {
+		if message.key.lane_id != FORWARDING_LANE {
+			return FromRialtoMessageDispatch::dispatch_weight(message);
+		}
+
+		// the sender has paid for the declared weight, so we can't spend more
+		message.data.payload.as_ref().map(|payload| payload.weight).unwrap_or(0)
+	}
+
+	fn dispatch_fee_payment(
+		message: &DispatchMessage<Self::DispatchPayload, bp_rialto::Balance>,
+	) -> DispatchFeePayment {
+		if message.key.lane_id != FORWARDING_LANE {
+			return FromRialtoMessageDispatch::dispatch_fee_payment(message);
+		}
+
+		DispatchFeePayment::AtSourceChain
+	}
+
+	fn dispatch(
+		relayer_account: &bp_millau::AccountId,
+		message: DispatchMessage<Self::DispatchPayload, bp_rialto::Balance>,
+	) -> MessageDispatchResult {
+		if message.key.lane_id != FORWARDING_LANE {
+			return FromRialtoMessageDispatch::dispatch(relayer_account, message);
+		}
+
+		let declared_weight = Self::dispatch_weight(&message);
+		let rejected = MessageDispatchResult {
+			dispatch_result: false,
+			unspent_weight: declared_weight,
+			dispatch_fee_paid_during_dispatch: false,
+		};
+
+		let nonce = message.key.nonce;
+		let forwarding_message = match into_forwarding_message(message) {
+			Some(forwarding_message) => forwarding_message,
+			None => {
+				log::trace!(
+					target: "runtime::bridge-forwarding",
+					"Rejected forwarding message {} from Rialto: malformed message or unknown sender",
+					nonce,
+				);
+				return rejected;
+			}
+		};
+
+		let forwarding_weight =
+			<crate::BridgeRialtoForwarding as MessageDispatch<_, _>>::dispatch_weight(&forwarding_message);
+		if forwarding_weight > declared_weight {
+			log::trace!(
+				target: "runtime::bridge-forwarding",
+				"Rejected forwarding message {} from Rialto: declared weight {} is less than actual weight {}",
+				nonce,
+				declared_weight,
+				forwarding_weight,
+			);
+			return rejected;
+		}
+
+		let mut dispatch_result =
+			<crate::BridgeRialtoForwarding as MessageDispatch<_, _>>::dispatch(relayer_account, forwarding_message);
+		dispatch_result.unspent_weight = dispatch_result
+			.unspent_weight
+			.saturating_add(declared_weight - forwarding_weight);
+		dispatch_result
+	}
+}
+
+/// Convert Rialto -> Millau message of the `FORWARDING_LANE` into the forwarding pallet message.
+///
+/// Returns `None` if the message is malformed or if its sender is not allowed to send it.
+fn into_forwarding_message(
+	message: DispatchMessage<FromRialtoMessagePayload, bp_rialto::Balance>,
+) -> Option<DispatchMessage<FromRialtoForwardingPayload, bp_rialto::Balance>> {
+	let DispatchMessage { key, data } = message;
+	let payload = data.payload.ok()?;
+	if payload.call_encoding() != CallEncoding::Plain
+		|| payload.dispatch_fee_payment != DispatchFeePayment::AtSourceChain
+	{
+		return None;
+	}
+
+	let forwarding_payload = FromRialtoForwardingPayload::decode_all(payload.call.as_ref()).ok()?;
+
+	// the message has been verified at Rialto, but it is the Millau sovereign account that is at risk,
+	// so we are checking the sender again
+	let sender = match payload.origin {
+		CallOrigin::SourceRoot => Sender::Root,
+		CallOrigin::SourceAccount(account) => Sender::Signed(account),
+		CallOrigin::TargetAccount(..) => return None,
+	};
+	let rialto_sovereign_account = bp_rialto::derive_account_from_millau_id(SourceAccount::Root);
+	if !pallet_bridge_forwarding::is_allowed_sender(&sender, &rialto_sovereign_account, &forwarding_payload) {
+		return None;
+	}
+
+	Some(DispatchMessage {
+		key,
+		data: DispatchMessageData {
+			payload: Ok(forwarding_payload),
+			fee: data.fee,
+		},
+	})
+}
+
+/// Converter of dispatch reports into Millau -> Rialto messages of the `FORWARDING_LANE`.
+pub struct ToRialtoDispatchReportConverter;
+
+impl Convert<ForwardedMessageDispatchReport, ToRialtoMessagePayload> for ToRialtoDispatchReportConverter {
+	fn convert(report: ForwardedMessageDispatchReport) -> ToRialtoMessagePayload {
+		MessagePayload {
+			// messages of the forwarding lane are not dispatched by the call dispatch pallet
+			spec_version: 0,
+			// dispatch report is a single write at Rialto (Rialto has the same `DbWeight`)
+			weight: crate::DbWeight::get().writes(1),
+			origin: CallOrigin::SourceAccount(pallet_bridge_forwarding::source_chain_sovereign_account::<
+				Runtime,
+				crate::WithRialtoForwardingInstance,
+			>()),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			call: ToRialtoForwardingPayload::from(report).encode(),
+		}
+	}
+}
+
+/// Delivery and dispatch fee of the dispatch report, sent to Rialto over the `FORWARDING_LANE`.
+pub struct ToRialtoDispatchReportFee;
+
+impl Get<bp_millau::Balance> for ToRialtoDispatchReportFee {
+	fn get() -> bp_millau::Balance {
+		let report = ToRialtoDispatchReportConverter::convert(ForwardedMessageDispatchReport {
+			lane: FORWARDING_LANE,
+			nonce: 0,
+			dispatch_result: false,
+		});
+		messages::source::estimate_message_dispatch_and_delivery_fee_at_lane::<WithRialtoMessageBridge>(
+			&pallet_bridge_messages::OutboundLanes::<Runtime, crate::WithRialtoMessagesInstance>::get(FORWARDING_LANE),
+			&report,
+			WithRialtoMessageBridge::RELAYER_FEE_PERCENT,
+		)
+		.unwrap_or(bp_millau::Balance::MAX)
+	}
+}
+
 /// Millau <-> Rialto message bridge.
 #[derive(RuntimeDebug, Clone, Copy)]
 pub struct WithRialtoMessageBridge;
@@ -115,6 +387,15 @@ impl messages::ThisChainWithMessages for Millau {
 		MessageNonce::MAX
 	}
 
+	fn congestion_fee_multiplier_percent(pending_messages: MessageNonce) -> u32 {
+		messages::linear_congestion_fee_multiplier_percent(
+			pending_messages,
+			FREE_PENDING_MESSAGES_AT_OUTBOUND_LANE,
+			CONGESTION_FEE_PERCENT_PER_MESSAGE,
+			MAX_CONGESTION_FEE_MULTIPLIER_PERCENT,
+		)
+	}
+
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
 		let inbound_data_size = InboundLaneData::<bp_millau::AccountId>::encoded_size_hint(
 			bp_millau::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
@@ -177,6 +458,14 @@ impl messages::BridgedChainWithMessages for Rialto {
 		0..=upper_limit
 	}
 
+	fn call_decompression_weight() -> Weight {
+		bp_rialto::MESSAGE_CALL_DECOMPRESSION_WEIGHT
+	}
+
+	fn is_dispatch_fee_asset_supported(asset_id: AssetId) -> bool {
+		bp_rialto::DISPATCH_FEE_ASSETS.contains(&asset_id)
+	}
+
 	fn estimate_delivery_transaction(
 		message_payload: &[u8],
 		include_pay_dispatch_fee_cost: bool,
@@ -263,6 +552,10 @@ impl SourceHeaderChain<bp_rialto::Balance> for Rialto {
 pub enum MillauToRialtoMessagesParameter {
 	/// The conversion formula we use is: `MillauTokens = RialtoTokens * conversion_rate`.
 	RialtoToMillauConversionRate(FixedU128),
+	/// Maximal number of messages that single account may send during `AccountRateLimitPeriod`.
+	MaxMessagesPerAccountPerPeriod(MessageNonce),
+	/// Maximal total size of messages that may be sent over single lane in a single block.
+	MaxLaneBytesPerBlock(u32),
 }
 
 impl MessagesParameter for MillauToRialtoMessagesParameter {
@@ -271,6 +564,12 @@ impl MessagesParameter for MillauToRialtoMessagesParameter {
 			MillauToRialtoMessagesParameter::RialtoToMillauConversionRate(ref conversion_rate) => {
 				RialtoToMillauConversionRate::set(conversion_rate)
 			}
+			MillauToRialtoMessagesParameter::MaxMessagesPerAccountPerPeriod(ref max_messages) => {
+				MaxMessagesPerAccountPerPeriod::set(max_messages)
+			}
+			MillauToRialtoMessagesParameter::MaxLaneBytesPerBlock(ref max_bytes) => {
+				MaxLaneBytesPerBlock::set(max_bytes)
+			}
 		}
 	}
 }
@@ -155,6 +155,8 @@ impl Alternative {
 								rialto_runtime::Runtime,
 								rialto_runtime::WithMillauMessagesInstance,
 							>::relayer_fund_account_id(),
+							// sovereign account pays for messages, sent by the forwarding pallet
+							derive_account_from_millau_id(bp_runtime::SourceAccount::Root),
 							derive_account_from_millau_id(bp_runtime::SourceAccount::Account(
 								get_account_id_from_seed::<sr25519::Public>("Alice"),
 							)),
@@ -295,6 +297,7 @@ fn testnet_genesis(
 		paras: Default::default(),
 		bridge_millau_messages: BridgeMillauMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>("MillauMessagesOwner")),
+			opened_lanes: vec![[0, 0, 0, 0], *b"swap", *b"frwd"],
 			..Default::default()
 		},
 	}
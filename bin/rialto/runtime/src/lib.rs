@@ -41,7 +41,7 @@ pub mod rialto_poa;
 
 use crate::millau_messages::{ToMillauMessagePayload, WithMillauMessageBridge};
 
-use bridge_runtime_common::messages::{source::estimate_message_dispatch_and_delivery_fee, MessageBridge};
+use bridge_runtime_common::messages::{source::estimate_message_dispatch_and_delivery_fee_at_lane, MessageBridge};
 use pallet_grandpa::{fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use pallet_transaction_payment::{FeeDetails, Multiplier, RuntimeDispatchInfo};
 use sp_api::impl_runtime_apis;
@@ -289,12 +289,19 @@ impl pallet_bridge_currency_exchange::Config<KovanCurrencyExchange> for Runtime
 	type DepositInto = DepositInto;
 }
 
+parameter_types! {
+	pub const MaxDecompressedMessageCallSize: u32 = bp_rialto::MAX_DECOMPRESSED_MESSAGE_CALL_SIZE;
+	pub const MessageCallDecompressionWeight: Weight = bp_rialto::MESSAGE_CALL_DECOMPRESSION_WEIGHT;
+}
+
 impl pallet_bridge_dispatch::Config for Runtime {
 	type Event = Event;
 	type MessageId = (bp_messages::LaneId, bp_messages::MessageNonce);
 	type Call = Call;
 	type CallFilter = ();
 	type EncodedCall = crate::millau_messages::FromMillauEncodedCall;
+	type MaxDecompressedCallSize = MaxDecompressedMessageCallSize;
+	type CallDecompressionWeight = MessageCallDecompressionWeight;
 	type SourceChainAccountId = bp_millau::AccountId;
 	type TargetChainAccountPublic = MultiSigner;
 	type TargetChainSignature = MultiSignature;
@@ -428,6 +435,31 @@ impl pallet_transaction_payment::Config for Runtime {
 	>;
 }
 
+parameter_types! {
+	pub const AssetDeposit: Balance = 1_000_000;
+	pub const ApprovalDeposit: Balance = 1_000;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 1_000;
+	pub const MetadataDepositPerByte: Balance = 10;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = bp_runtime::messages::AssetId;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	// TODO: update me (https://github.com/paritytech/parity-bridges-common/issues/78)
+	type WeightInfo = ();
+}
+
 impl pallet_sudo::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -479,9 +511,11 @@ parameter_types! {
 
 pub type MillauGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_millau::Millau;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeaderAge = ();
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
 }
 
@@ -493,6 +527,10 @@ parameter_types! {
 		bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE;
+	// 10% of the block weight is reserved for dispatching queued inbound messages
+	pub const QueuedMessagesDispatchWeight: Weight = bp_rialto::MAXIMUM_BLOCK_WEIGHT / 10;
+	pub const QueuedMessageByteDeposit: Balance = 1_000;
+	pub const AccountRateLimitPeriod: BlockNumber = bp_rialto::MINUTES;
 	// `IdentityFee` is used by Rialto => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_rialto::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
@@ -510,6 +548,10 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type QueuedMessagesDispatchWeight = QueuedMessagesDispatchWeight;
+	type AccountRateLimitPeriod = AccountRateLimitPeriod;
+	type MaxMessagesPerAccountPerPeriod = millau_messages::MaxMessagesPerAccountPerPeriod;
+	type MaxLaneBytesPerBlock = millau_messages::MaxLaneBytesPerBlock;
 
 	type OutboundPayload = crate::millau_messages::ToMillauMessagePayload;
 	type OutboundMessageFee = Balance;
@@ -530,12 +572,50 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	>;
 	type OnMessageAccepted = ();
 	type OnDeliveryConfirmed = ();
+	type MessageOwners = (pallet_bridge_forwarding::Pallet<Runtime, WithMillauForwardingInstance>,);
 
 	type SourceHeaderChain = crate::millau_messages::Millau;
-	type MessageDispatch = crate::millau_messages::FromMillauMessageDispatch;
+	type MessageDispatch = crate::millau_messages::FromMillauMessageRouter;
+	type RelayersRegistry = ();
+	type QueuedMessagesDeposit = pallet_bridge_messages::queued_messages_deposit::CurrencyQueuedMessagesDeposit<
+		pallet_balances::Pallet<Runtime>,
+		QueuedMessageByteDeposit,
+	>;
 	type BridgedChainId = BridgedChainId;
 }
 
+parameter_types! {
+	pub const ForwardingMessagesOwner: bp_messages::source_chain::MessageOwnerId = *b"frwd";
+	// TODO: https://github.com/paritytech/parity-bridges-common/issues/390
+	pub const ForwardMessageWeight: Weight = WEIGHT_PER_SECOND / 100;
+	pub const SendReportWeight: Weight = WEIGHT_PER_SECOND / 100;
+}
+
+/// Instance of the forwarding pallet that is forwarding messages of Millau.
+pub type WithMillauForwardingInstance = ();
+
+impl pallet_bridge_forwarding::Config<WithMillauForwardingInstance> for Runtime {
+	type Event = Event;
+
+	type SourceChainId = BridgedChainId;
+	type SourceChainAccountIdConverter = bp_rialto::AccountIdConverter;
+
+	type InboundMessageFee = bp_millau::Balance;
+	type MessageDispatch = crate::millau_messages::FromMillauMessageDispatch;
+	type ForwardMessageWeight = ForwardMessageWeight;
+
+	type OutboundMessagesOwnerId = ForwardingMessagesOwner;
+	type OutboundMessageFee = Balance;
+	type NextHopPayload = crate::millau_messages::ToMillauMessagePayload;
+	type NextHopBridge = pallet_bridge_messages::Pallet<Runtime, WithMillauMessagesInstance>;
+
+	type ReportPayload = crate::millau_messages::ToMillauMessagePayload;
+	type ReportPayloadConverter = crate::millau_messages::ToMillauDispatchReportConverter;
+	type ReportBridge = pallet_bridge_messages::Pallet<Runtime, WithMillauMessagesInstance>;
+	type ReportDeliveryAndDispatchFee = crate::millau_messages::ToMillauDispatchReportFee;
+	type SendReportWeight = SendReportWeight;
+}
+
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
@@ -551,6 +631,7 @@ construct_runtime!(
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 
 		// Consensus support.
 		AuthorityDiscovery: pallet_authority_discovery::{Pallet, Config},
@@ -566,9 +647,10 @@ construct_runtime!(
 		BridgeKovanCurrencyExchange: pallet_bridge_currency_exchange::<Instance2>::{Pallet, Call},
 
 		// Millau bridge modules.
-		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
+		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
 		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
 		BridgeMillauMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
+		BridgeMillauForwarding: pallet_bridge_forwarding::{Pallet, Storage, Event<T>},
 
 		// Parachain modules.
 		ParachainsOrigin: polkadot_runtime_parachains::origin::{Pallet, Origin},
@@ -618,8 +700,17 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signatu
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive =
-	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPallets>;
+pub type Executive = frame_executive::Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllPallets,
+	(
+		pallet_bridge_messages::migration::MigrateToV1<Runtime, WithMillauMessagesInstance>,
+		pallet_bridge_grandpa::migration::MigrateToV1<Runtime, MillauGrandpaInstance>,
+	),
+>;
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -718,6 +809,10 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_millau::Hash) -> bool {
 			BridgeMillauGrandpa::is_known_header(hash)
 		}
+
+		fn finalized_header_at_or_before(number: bp_millau::BlockNumber) -> Option<bp_millau::Header> {
+			BridgeMillauGrandpa::finalized_header_at_or_before(number)
+		}
 	}
 
 	impl bp_currency_exchange::RialtoCurrencyExchangeApi<Block, exchange::EthereumTransactionInclusionProof> for Runtime {
@@ -941,12 +1036,13 @@ impl_runtime_apis! {
 		}
 	}
 
-	impl bp_millau::ToMillauOutboundLaneApi<Block, Balance, ToMillauMessagePayload> for Runtime {
+	impl bp_millau::ToMillauOutboundLaneApi<Block, AccountId, Balance, ToMillauMessagePayload> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
-			_lane_id: bp_messages::LaneId,
+			lane_id: bp_messages::LaneId,
 			payload: ToMillauMessagePayload,
 		) -> Option<Balance> {
-			estimate_message_dispatch_and_delivery_fee::<WithMillauMessageBridge>(
+			estimate_message_dispatch_and_delivery_fee_at_lane::<WithMillauMessageBridge>(
+				&pallet_bridge_messages::OutboundLanes::<Runtime, WithMillauMessagesInstance>::get(lane_id),
 				&payload,
 				WithMillauMessageBridge::RELAYER_FEE_PERCENT,
 			).ok()
@@ -956,7 +1052,7 @@ impl_runtime_apis! {
 			lane: bp_messages::LaneId,
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
-		) -> Vec<bp_messages::MessageDetails<Balance>> {
+		) -> Vec<bp_messages::MessageDetails<Balance, AccountId>> {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithMillauMessagesInstance,
@@ -971,6 +1067,18 @@ impl_runtime_apis! {
 		fn latest_generated_nonce(lane: bp_messages::LaneId) -> bp_messages::MessageNonce {
 			BridgeMillauMessages::outbound_latest_generated_nonce(lane)
 		}
+
+		fn lanes() -> Vec<(bp_messages::LaneId, bp_messages::LaneState)> {
+			BridgeMillauMessages::lanes()
+		}
+
+		fn message_payloads(
+			lane: bp_messages::LaneId,
+			begin: bp_messages::MessageNonce,
+			end: bp_messages::MessageNonce,
+		) -> Vec<bp_messages::MessagePayloadDetails<Balance>> {
+			BridgeMillauMessages::outbound_message_payloads(lane, begin, end)
+		}
 	}
 
 	impl bp_millau::FromMillauInboundLaneApi<Block> for Runtime {
@@ -985,6 +1093,28 @@ impl_runtime_apis! {
 		fn unrewarded_relayers_state(lane: bp_messages::LaneId) -> bp_messages::UnrewardedRelayersState {
 			BridgeMillauMessages::inbound_unrewarded_relayers_state(lane)
 		}
+
+		fn latest_queued_nonce(lane: bp_messages::LaneId) -> Option<bp_messages::MessageNonce> {
+			BridgeMillauMessages::inbound_latest_queued_nonce(lane)
+		}
+
+		fn inbound_lane_data(lane: bp_messages::LaneId) -> bp_messages::InboundLaneData<bp_millau::AccountId> {
+			BridgeMillauMessages::inbound_lane_data(lane)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> (Weight, Weight) {
+			let weight = Executive::try_runtime_upgrade().unwrap();
+			(weight, bp_rialto::MAXIMUM_BLOCK_WEIGHT)
+		}
+
+		fn execute_block_no_check(block: Block) -> Weight {
+			let weight = Executive::execute_block_no_check(block);
+			BridgeMillauMessages::do_try_state().expect("Messages pallet invariants are broken");
+			weight
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
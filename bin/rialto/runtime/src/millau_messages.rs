@@ -18,40 +18,62 @@
 
 use crate::Runtime;
 
+use bp_message_dispatch::{
+	CallEncoding, CallOrigin, ForwardedMessageDispatchReport, ForwardingPayload, MessagePayload,
+};
 use bp_messages::{
-	source_chain::TargetHeaderChain,
-	target_chain::{ProvedMessages, SourceHeaderChain},
-	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+	source_chain::{LaneMessageVerifier, Sender, TargetHeaderChain},
+	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch, ProvedMessages, SourceHeaderChain},
+	InboundLaneData, LaneId, Message, MessageNonce, OutboundLaneData, Parameter as MessagesParameter,
+};
+use bp_runtime::{
+	messages::{AssetId, DispatchFeePayment, MessageDispatchResult},
+	ChainId, SourceAccount, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID,
 };
-use bp_runtime::{ChainId, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
 use bridge_runtime_common::messages::{self, MessageBridge, MessageTransaction};
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
 use frame_support::{
 	parameter_types,
-	weights::{DispatchClass, Weight},
+	traits::Get,
+	weights::{DispatchClass, Weight, WeightToFeePolynomial},
 	RuntimeDebug,
 };
-use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
+use sp_runtime::{
+	traits::{Convert, Saturating},
+	FixedPointNumber, FixedU128,
+};
 use sp_std::{convert::TryFrom, ops::RangeInclusive};
 
 /// Initial value of `MillauToRialtoConversionRate` parameter.
 pub const INITIAL_MILLAU_TO_RIALTO_CONVERSION_RATE: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
 /// Initial value of `MillauFeeMultiplier` parameter.
 pub const INITIAL_MILLAU_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `MaxMessagesPerAccountPerPeriod` parameter.
+pub const INITIAL_MAX_MESSAGES_PER_ACCOUNT_PER_PERIOD: MessageNonce = 128;
+/// Initial value of `MaxLaneBytesPerBlock` parameter.
+pub const INITIAL_MAX_LANE_BYTES_PER_BLOCK: u32 = 8 * 1024 * 1024;
+/// Number of pending messages at the outbound lane that don't affect the message fee.
+pub const FREE_PENDING_MESSAGES_AT_OUTBOUND_LANE: MessageNonce = 128;
+/// Increase of the message fee (in percents) for every pending message over the
+/// `FREE_PENDING_MESSAGES_AT_OUTBOUND_LANE`.
+pub const CONGESTION_FEE_PERCENT_PER_MESSAGE: u32 = 1;
+/// Maximal congestion multiplier (in percents) of the message fee.
+pub const MAX_CONGESTION_FEE_MULTIPLIER_PERCENT: u32 = 1_000;
 
 parameter_types! {
 	/// Millau to Rialto conversion rate. Initially we treat both tokens as equal.
 	pub storage MillauToRialtoConversionRate: FixedU128 = INITIAL_MILLAU_TO_RIALTO_CONVERSION_RATE;
 	/// Fee multiplier value at Millau chain.
 	pub storage MillauFeeMultiplier: FixedU128 = INITIAL_MILLAU_FEE_MULTIPLIER;
+	/// Maximal number of messages that single account may send to Millau during `AccountRateLimitPeriod`.
+	pub storage MaxMessagesPerAccountPerPeriod: MessageNonce = INITIAL_MAX_MESSAGES_PER_ACCOUNT_PER_PERIOD;
+	/// Maximal total size of messages that may be sent to Millau over single lane in a single block.
+	pub storage MaxLaneBytesPerBlock: u32 = INITIAL_MAX_LANE_BYTES_PER_BLOCK;
 }
 
 /// Message payload for Rialto -> Millau messages.
 pub type ToMillauMessagePayload = messages::source::FromThisChainMessagePayload<WithMillauMessageBridge>;
 
-/// Message verifier for Rialto -> Millau messages.
-pub type ToMillauMessageVerifier = messages::source::FromThisChainMessageVerifier<WithMillauMessageBridge>;
-
 /// Message payload for Millau -> Rialto messages.
 pub type FromMillauMessagePayload = messages::target::FromBridgedChainMessagePayload<WithMillauMessageBridge>;
 
@@ -63,15 +85,265 @@ pub type FromMillauMessageDispatch = messages::target::FromBridgedChainMessageDi
 	WithMillauMessageBridge,
 	crate::Runtime,
 	pallet_balances::Pallet<Runtime>,
+	FromMillauDispatchFeeAssets,
 	(),
 >;
 
+/// Non-native Rialto assets that may be used to pay dispatch fee of Millau -> Rialto messages.
+pub type FromMillauDispatchFeeAssets =
+	messages::target::FungiblesDispatchFeeAssets<pallet_assets::Pallet<Runtime>, FromMillauDispatchFeeAssetConverter>;
+
+/// Converter of Millau -> Rialto messages dispatch weight into fee, paid in Rialto assets.
+///
+/// Only assets from `bp_rialto::DISPATCH_FEE_ASSETS` are accepted. In our testnets, the fee in these
+/// assets is the same as the fee in Rialto tokens.
+#[derive(RuntimeDebug)]
+pub struct FromMillauDispatchFeeAssetConverter;
+
+impl messages::target::WeightToAssetFee<bp_rialto::Balance> for FromMillauDispatchFeeAssetConverter {
+	fn weight_to_asset_fee(asset_id: AssetId, dispatch_weight: Weight) -> Option<bp_rialto::Balance> {
+		if !bp_rialto::DISPATCH_FEE_ASSETS.contains(&asset_id) {
+			return None;
+		}
+
+		let unadjusted_weight_fee =
+			<Runtime as pallet_transaction_payment::Config>::WeightToFee::calc(&dispatch_weight);
+		let fee_multiplier = pallet_transaction_payment::Pallet::<Runtime>::next_fee_multiplier();
+		Some(fee_multiplier.saturating_mul_int(unadjusted_weight_fee))
+	}
+}
+
 /// Messages proof for Millau -> Rialto messages.
 pub type FromMillauMessagesProof = messages::target::FromBridgedChainMessagesProof<bp_millau::Hash>;
 
 /// Messages delivery proof for Rialto -> Millau messages.
 pub type ToMillauMessagesDeliveryProof = messages::source::FromBridgedChainMessagesDeliveryProof<bp_millau::Hash>;
 
+/// Forwarding payload of Rialto -> Millau messages, sent over the `FORWARDING_LANE`.
+pub type ToMillauForwardingPayload = ForwardingPayload<ToMillauMessagePayload, bp_millau::Balance>;
+
+/// Forwarding payload of Millau -> Rialto messages, sent over the `FORWARDING_LANE`.
+pub type FromMillauForwardingPayload =
+	pallet_bridge_forwarding::ForwardingPayloadOf<Runtime, crate::WithMillauForwardingInstance>;
+
+/// Lane that is used to exchange forwarding messages with Millau.
+///
+/// Messages of this lane are regular call dispatch messages, but their call is the SCALE-encoded
+/// `ForwardingPayload`. They are dispatched by the forwarding pallet, so the `spec_version` of
+/// these messages is ignored. The declared `weight` of the message must cover the weight of its
+/// dispatch by the forwarding pallet.
+pub const FORWARDING_LANE: LaneId = *b"frwd";
+
+const BAD_FORWARDING_MESSAGE: &str = "The forwarding message is malformed.";
+const BAD_FORWARDING_MESSAGE_SENDER: &str = "The sender is not allowed to send the forwarding message.";
+
+/// Message verifier for Rialto -> Millau messages.
+///
+/// Messages of the `FORWARDING_LANE` are additionally checked, so that only privileged senders are
+/// able to send forwarded messages and dispatch reports.
+#[derive(RuntimeDebug)]
+pub struct ToMillauMessageVerifier;
+
+impl LaneMessageVerifier<bp_rialto::AccountId, ToMillauMessagePayload, bp_rialto::Balance> for ToMillauMessageVerifier {
+	type Error = &'static str;
+
+	fn verify_message(
+		submitter: &Sender<bp_rialto::AccountId>,
+		delivery_and_dispatch_fee: &bp_rialto::Balance,
+		lane: &LaneId,
+		lane_outbound_data: &OutboundLaneData,
+		payload: &ToMillauMessagePayload,
+	) -> Result<(), Self::Error> {
+		if *lane == FORWARDING_LANE {
+			verify_forwarding_message(submitter, payload)?;
+		}
+
+		messages::source::FromThisChainMessageVerifier::<WithMillauMessageBridge>::verify_message(
+			submitter,
+			delivery_and_dispatch_fee,
+			lane,
+			lane_outbound_data,
+			payload,
+		)
+	}
+}
+
+/// Verify Rialto -> Millau message of the `FORWARDING_LANE`.
+fn verify_forwarding_message(
+	submitter: &Sender<bp_rialto::AccountId>,
+	payload: &ToMillauMessagePayload,
+) -> Result<(), &'static str> {
+	if payload.call_encoding() != CallEncoding::Plain
+		|| payload.dispatch_fee_payment != DispatchFeePayment::AtSourceChain
+	{
+		return Err(BAD_FORWARDING_MESSAGE);
+	}
+
+	let forwarding_payload =
+		ToMillauForwardingPayload::decode_all(&payload.call[..]).map_err(|_| BAD_FORWARDING_MESSAGE)?;
+	let sovereign_account =
+		pallet_bridge_forwarding::source_chain_sovereign_account::<Runtime, crate::WithMillauForwardingInstance>();
+	if !pallet_bridge_forwarding::is_allowed_sender(submitter, &sovereign_account, &forwarding_payload) {
+		return Err(BAD_FORWARDING_MESSAGE_SENDER);
+	}
+
+	Ok(())
+}
+
+/// Dispatch of Millau -> Rialto messages.
+///
+/// Messages of the `FORWARDING_LANE` are dispatched by the forwarding pallet. All other messages are
+/// dispatched by the call dispatch pallet.
+pub struct FromMillauMessageRouter;
+
+impl MessageDispatch<bp_rialto::AccountId, bp_millau::Balance> for FromMillauMessageRouter {
+	type DispatchPayload = FromMillauMessagePayload;
+
+	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, bp_millau::Balance>) -> Weight {
+		if message.key.lane_id != FORWARDING_LANE {
+			return FromMillauMessageDispatch::dispatch_weight(message);
+		}
+
+		// the sender has paid for the declared weight, so we can't spend more
+		message.data.payload.as_ref().map(|payload| payload.weight).unwrap_or(0)
+	}
+
+	fn dispatch_fee_payment(
+		message: &DispatchMessage<Self::DispatchPayload, bp_millau::Balance>,
+	) -> DispatchFeePayment {
+		if message.key.lane_id != FORWARDING_LANE {
+			return FromMillauMessageDispatch::dispatch_fee_payment(message);
+		}
+
+		DispatchFeePayment::AtSourceChain
+	}
+
+	fn dispatch(
+		relayer_account: &bp_rialto::AccountId,
+		message: DispatchMessage<Self::DispatchPayload, bp_millau::Balance>,
+	) -> MessageDispatchResult {
+		if message.key.lane_id != FORWARDING_LANE {
+			return FromMillauMessageDispatch::dispatch(relayer_account, message);
+		}
+
+		let declared_weight = Self::dispatch_weight(&message);
+		let rejected = MessageDispatchResult {
+			dispatch_result: false,
+			unspent_weight: declared_weight,
+			dispatch_fee_paid_during_dispatch: false,
+		};
+
+		let nonce = message.key.nonce;
+		let forwarding_message = match into_forwarding_message(message) {
+			Some(forwarding_message) => forwarding_message,
+			None => {
+				log::trace!(
+					target: "runtime::bridge-forwarding",
+					"Rejected forwarding message {} from Millau: malformed message or unknown sender",
+					nonce,
+				);
+				return rejected;
+			}
+		};
+
+		let forwarding_weight =
+			<crate::BridgeMillauForwarding as MessageDispatch<_, _>>::dispatch_weight(&forwarding_message);
+		if forwarding_weight > declared_weight {
+			log::trace!(
+				target: "runtime::bridge-forwarding",
+				"Rejected forwarding message {} from Millau: declared weight {} is less than actual weight {}",
+				nonce,
+				declared_weight,
+				forwarding_weight,
+			);
+			return rejected;
+		}
+
+		let mut dispatch_result =
+			<crate::BridgeMillauForwarding as MessageDispatch<_, _>>::dispatch(relayer_account, forwarding_message);
+		dispatch_result.unspent_weight = dispatch_result
+			.unspent_weight
+			.saturating_add(declared_weight - forwarding_weight);
+		dispatch_result
+	}
+}
+
+/// Convert Millau -> Rialto message of the `FORWARDING_LANE` into the forwarding pallet message.
+///
+/// Returns `None` if the message is malformed or if its sender is not allowed to send it.
+fn into_forwarding_message(
+	message: DispatchMessage<FromMillauMessagePayload, bp_millau::Balance>,
+) -> Option<DispatchMessage<FromMillauForwardingPayload, bp_millau::Balance>> {
+	let DispatchMessage { key, data } = message;
+	let payload = data.payload.ok()?;
+	if payload.call_encoding() != CallEncoding::Plain
+		|| payload.dispatch_fee_payment != DispatchFeePayment::AtSourceChain
+	{
+		return None;
+	}
+
+	let forwarding_payload = FromMillauForwardingPayload::decode_all(payload.call.as_ref()).ok()?;
+
+	// the message has been verified at Millau, but it is the Rialto sovereign account that is at risk,
+	// so we are checking the sender again
+	let sender = match payload.origin {
+		CallOrigin::SourceRoot => Sender::Root,
+		CallOrigin::SourceAccount(account) => Sender::Signed(account),
+		CallOrigin::TargetAccount(..) => return None,
+	};
+	let millau_sovereign_account = bp_millau::derive_account_from_rialto_id(SourceAccount::Root);
+	if !pallet_bridge_forwarding::is_allowed_sender(&sender, &millau_sovereign_account, &forwarding_payload) {
+		return None;
+	}
+
+	Some(DispatchMessage {
+		key,
+		data: DispatchMessageData {
+			payload: Ok(forwarding_payload),
+			fee: data.fee,
+		},
+	})
+}
+
+/// Converter of dispatch reports into Rialto -> Millau messages of the `FORWARDING_LANE`.
+pub struct ToMillauDispatchReportConverter;
+
+impl Convert<ForwardedMessageDispatchReport, ToMillauMessagePayload> for ToMillauDispatchReportConverter {
+	fn convert(report: ForwardedMessageDispatchReport) -> ToMillauMessagePayload {
+		MessagePayload {
+			// messages of the forwarding lane are not dispatched by the call dispatch pallet
+			spec_version: 0,
+			// dispatch report is a single write at Millau (Millau has the same `DbWeight`)
+			weight: crate::DbWeight::get().writes(1),
+			origin: CallOrigin::SourceAccount(pallet_bridge_forwarding::source_chain_sovereign_account::<
+				Runtime,
+				crate::WithMillauForwardingInstance,
+			>()),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			call: ToMillauForwardingPayload::from(report).encode(),
+		}
+	}
+}
+
+/// Delivery and dispatch fee of the dispatch report, sent to Millau over the `FORWARDING_LANE`.
+pub struct ToMillauDispatchReportFee;
+
+impl Get<bp_rialto::Balance> for ToMillauDispatchReportFee {
+	fn get() -> bp_rialto::Balance {
+		let report = ToMillauDispatchReportConverter::convert(ForwardedMessageDispatchReport {
+			lane: FORWARDING_LANE,
+			nonce: 0,
+			dispatch_result: false,
+		});
+		messages::source::estimate_message_dispatch_and_delivery_fee_at_lane::<WithMillauMessageBridge>(
+			&pallet_bridge_messages::OutboundLanes::<Runtime, crate::WithMillauMessagesInstance>::get(FORWARDING_LANE),
+			&report,
+			WithMillauMessageBridge::RELAYER_FEE_PERCENT,
+		)
+		.unwrap_or(bp_rialto::Balance::MAX)
+	}
+}
+
 /// Millau <-> Rialto message bridge.
 #[derive(RuntimeDebug, Clone, Copy)]
 pub struct WithMillauMessageBridge;
@@ -115,6 +387,15 @@ impl messages::ThisChainWithMessages for Rialto {
 		MessageNonce::MAX
 	}
 
+	fn congestion_fee_multiplier_percent(pending_messages: MessageNonce) -> u32 {
+		messages::linear_congestion_fee_multiplier_percent(
+			pending_messages,
+			FREE_PENDING_MESSAGES_AT_OUTBOUND_LANE,
+			CONGESTION_FEE_PERCENT_PER_MESSAGE,
+			MAX_CONGESTION_FEE_MULTIPLIER_PERCENT,
+		)
+	}
+
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
 		let inbound_data_size = InboundLaneData::<bp_rialto::AccountId>::encoded_size_hint(
 			bp_rialto::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
@@ -177,6 +458,14 @@ impl messages::BridgedChainWithMessages for Millau {
 		0..=upper_limit
 	}
 
+	fn call_decompression_weight() -> Weight {
+		bp_millau::MESSAGE_CALL_DECOMPRESSION_WEIGHT
+	}
+
+	fn is_dispatch_fee_asset_supported(asset_id: AssetId) -> bool {
+		bp_millau::DISPATCH_FEE_ASSETS.contains(&asset_id)
+	}
+
 	fn estimate_delivery_transaction(
 		message_payload: &[u8],
 		include_pay_dispatch_fee_cost: bool,
@@ -263,6 +552,10 @@ impl SourceHeaderChain<bp_millau::Balance> for Millau {
 pub enum RialtoToMillauMessagesParameter {
 	/// The conversion formula we use is: `RialtoTokens = MillauTokens * conversion_rate`.
 	MillauToRialtoConversionRate(FixedU128),
+	/// Maximal number of messages that single account may send during `AccountRateLimitPeriod`.
+	MaxMessagesPerAccountPerPeriod(MessageNonce),
+	/// Maximal total size of messages that may be sent over single lane in a single block.
+	MaxLaneBytesPerBlock(u32),
 }
 
 impl MessagesParameter for RialtoToMillauMessagesParameter {
@@ -271,6 +564,12 @@ impl MessagesParameter for RialtoToMillauMessagesParameter {
 			RialtoToMillauMessagesParameter::MillauToRialtoConversionRate(ref conversion_rate) => {
 				MillauToRialtoConversionRate::set(conversion_rate)
 			}
+			RialtoToMillauMessagesParameter::MaxMessagesPerAccountPerPeriod(ref max_messages) => {
+				MaxMessagesPerAccountPerPeriod::set(max_messages)
+			}
+			RialtoToMillauMessagesParameter::MaxLaneBytesPerBlock(ref max_bytes) => {
+				MaxLaneBytesPerBlock::set(max_bytes)
+			}
 		}
 	}
 }
@@ -278,7 +577,7 @@ impl MessagesParameter for RialtoToMillauMessagesParameter {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{AccountId, Call, ExistentialDeposit, Runtime, SystemCall, SystemConfig, VERSION};
+	use crate::{AccountId, Call, ExistentialDeposit, Origin, Runtime, SystemCall, SystemConfig, VERSION};
 	use bp_message_dispatch::CallOrigin;
 	use bp_messages::{
 		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
@@ -287,7 +586,7 @@ mod tests {
 	use bp_runtime::{derive_account_id, messages::DispatchFeePayment, SourceAccount};
 	use bridge_runtime_common::messages::target::{FromBridgedChainEncodedMessageCall, FromBridgedChainMessagePayload};
 	use frame_support::{
-		traits::Currency,
+		traits::{tokens::fungibles, Currency},
 		weights::{GetDispatchInfo, WeightToFeePolynomial},
 	};
 	use sp_runtime::traits::Convert;
@@ -357,4 +656,123 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn transfer_happens_when_dispatch_fee_is_paid_at_target_chain_in_asset() {
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			let bridge = MILLAU_CHAIN_ID;
+			let asset_id = bp_rialto::BRIDGED_MILLAU_TOKEN_ASSET_ID;
+			let call: Call = SystemCall::remark(vec![]).into();
+			let dispatch_weight = call.get_dispatch_info().weight;
+			let dispatch_fee = <Runtime as pallet_transaction_payment::Config>::WeightToFee::calc(&dispatch_weight);
+			assert!(dispatch_fee > 0);
+
+			// create sufficient asset, so that accounts may hold it without having any Rialto tokens
+			let relayer_account: AccountId = [1u8; 32].into();
+			let asset_min_balance = 1;
+			pallet_assets::Pallet::<Runtime>::force_create(
+				Origin::root(),
+				asset_id,
+				relayer_account.clone().into(),
+				true,
+				asset_min_balance,
+			)
+			.unwrap();
+
+			// create relayer account with minimal asset balance
+			let initial_amount = ExistentialDeposit::get();
+			<pallet_assets::Pallet<Runtime> as fungibles::Mutate<AccountId>>::mint_into(
+				asset_id,
+				&relayer_account,
+				initial_amount,
+			)
+			.unwrap();
+
+			// create dispatch account with minimal asset balance + dispatch fee
+			let dispatch_account = derive_account_id::<<Runtime as pallet_bridge_dispatch::Config>::SourceChainAccountId>(
+				bridge,
+				SourceAccount::Root,
+			);
+			let dispatch_account =
+				<Runtime as pallet_bridge_dispatch::Config>::AccountIdConverter::convert(dispatch_account);
+			<pallet_assets::Pallet<Runtime> as fungibles::Mutate<AccountId>>::mint_into(
+				asset_id,
+				&dispatch_account,
+				initial_amount + dispatch_fee,
+			)
+			.unwrap();
+
+			// dispatch message with intention to pay dispatch fee at the target chain in asset
+			let dispatch_result = FromMillauMessageDispatch::dispatch(
+				&relayer_account,
+				DispatchMessage {
+					key: MessageKey {
+						lane_id: Default::default(),
+						nonce: 0,
+					},
+					data: DispatchMessageData {
+						payload: Ok(FromBridgedChainMessagePayload::<WithMillauMessageBridge> {
+							spec_version: VERSION.spec_version,
+							weight: dispatch_weight,
+							origin: CallOrigin::SourceRoot,
+							dispatch_fee_payment: DispatchFeePayment::AtTargetChainInAsset(asset_id),
+							call: FromBridgedChainEncodedMessageCall::new(call.encode()),
+						}),
+						fee: 1,
+					},
+				},
+			);
+			assert!(dispatch_result.dispatch_fee_paid_during_dispatch);
+			assert!(dispatch_result.dispatch_result);
+
+			// ensure that fee has been transferred from dispatch to relayer account
+			assert_eq!(
+				pallet_assets::Pallet::<Runtime>::balance(asset_id, &relayer_account),
+				initial_amount + dispatch_fee,
+			);
+			assert_eq!(
+				pallet_assets::Pallet::<Runtime>::balance(asset_id, &dispatch_account),
+				initial_amount,
+			);
+			assert_eq!(
+				<pallet_balances::Pallet<Runtime> as Currency<AccountId>>::free_balance(&dispatch_account),
+				0,
+			);
+		});
+	}
+
+	#[test]
+	fn message_is_not_dispatched_when_dispatch_fee_asset_is_not_supported() {
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			let call: Call = SystemCall::remark(vec![]).into();
+			let dispatch_weight = call.get_dispatch_info().weight;
+			let relayer_account: AccountId = [1u8; 32].into();
+
+			let dispatch_result = FromMillauMessageDispatch::dispatch(
+				&relayer_account,
+				DispatchMessage {
+					key: MessageKey {
+						lane_id: Default::default(),
+						nonce: 0,
+					},
+					data: DispatchMessageData {
+						payload: Ok(FromBridgedChainMessagePayload::<WithMillauMessageBridge> {
+							spec_version: VERSION.spec_version,
+							weight: dispatch_weight,
+							origin: CallOrigin::SourceRoot,
+							dispatch_fee_payment: DispatchFeePayment::AtTargetChainInAsset(
+								bp_rialto::BRIDGED_MILLAU_TOKEN_ASSET_ID + 1,
+							),
+							call: FromBridgedChainEncodedMessageCall::new(call.encode()),
+						}),
+						fee: 1,
+					},
+				},
+			);
+			assert!(!dispatch_result.dispatch_fee_paid_during_dispatch);
+			assert!(!dispatch_result.dispatch_result);
+		});
+	}
 }
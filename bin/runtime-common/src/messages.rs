@@ -20,19 +20,19 @@
 //! pallet is used to dispatch incoming messages. Message identified by a tuple
 //! of to elements - message lane id and message nonce.
 
-use bp_message_dispatch::MessageDispatch as _;
+use bp_message_dispatch::{CallEncoding, MessageDispatch as _};
 use bp_messages::{
 	source_chain::{LaneMessageVerifier, Sender},
 	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages},
 	InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
 };
 use bp_runtime::{
-	messages::{DispatchFeePayment, MessageDispatchResult},
-	ChainId, Size, StorageProofChecker,
+	messages::{AssetId, DispatchFeePayment, MessageDispatchResult},
+	ChainId, FinalizedStorageProofParser, Size, StorageProofChecker,
 };
 use codec::{Decode, Encode};
 use frame_support::{
-	traits::{Currency, ExistenceRequirement},
+	traits::{tokens::fungibles, Currency, ExistenceRequirement},
 	weights::{Weight, WeightToFeePolynomial},
 	RuntimeDebug,
 };
@@ -108,6 +108,13 @@ pub trait ThisChainWithMessages: ChainWithMessages {
 	/// Any messages over this limit, will be rejected.
 	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce;
 
+	/// Multiplier (in percents) that is applied to the message delivery and dispatch fee, given
+	/// the number of pending (undelivered) messages at the outbound lane.
+	///
+	/// Return `100` if the message fee shall not depend on the lane congestion. Otherwise, see
+	/// `linear_congestion_fee_multiplier_percent`.
+	fn congestion_fee_multiplier_percent(pending_messages: MessageNonce) -> u32;
+
 	/// Estimate size and weight of single message delivery confirmation transaction at This chain.
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>>;
 
@@ -131,6 +138,19 @@ pub trait BridgedChainWithMessages: ChainWithMessages {
 	/// return pure call dispatch weights range.
 	fn message_weight_limits(message_payload: &[u8]) -> RangeInclusive<Self::Weight>;
 
+	/// Returns weight of decompressing the message call at the Bridged chain.
+	///
+	/// This must be the same value that is used by the `pallet-bridge-dispatch` at the Bridged chain
+	/// (see `pallet_bridge_dispatch::Config::CallDecompressionWeight`).
+	fn call_decompression_weight() -> Weight;
+
+	/// Returns true if given asset of the Bridged chain may be used to pay dispatch fee of the message
+	/// (see `DispatchFeePayment::AtTargetChainInAsset`).
+	///
+	/// This must match the set of assets that are accepted by the `DispatchFeeAssets` implementation
+	/// at the Bridged chain.
+	fn is_dispatch_fee_asset_supported(asset_id: AssetId) -> bool;
+
 	/// Estimate size and weight of single message delivery transaction at the Bridged chain.
 	fn estimate_delivery_transaction(
 		message_payload: &[u8],
@@ -182,6 +202,28 @@ pub fn transaction_payment<Balance: AtLeast32BitUnsigned + FixedPointOperand>(
 	base_fee.saturating_add(len_fee).saturating_add(adjusted_weight_fee)
 }
 
+/// Congestion fee multiplier (in percents) that grows linearly with the number of pending messages at
+/// the outbound lane.
+///
+/// The multiplier is 100% while there are at most `free_pending_messages` pending messages at the lane.
+/// Every other pending message adds `percent_per_message` to the multiplier, until it reaches the
+/// `max_multiplier_percent`. The multiplier only depends on the current number of pending messages,
+/// so it decays as the lane drains.
+pub fn linear_congestion_fee_multiplier_percent(
+	pending_messages: MessageNonce,
+	free_pending_messages: MessageNonce,
+	percent_per_message: u32,
+	max_multiplier_percent: u32,
+) -> u32 {
+	let extra_percent = pending_messages
+		.saturating_sub(free_pending_messages)
+		.saturating_mul(percent_per_message.into());
+	u32::try_from(extra_percent)
+		.unwrap_or(u32::MAX)
+		.saturating_add(100)
+		.min(max_multiplier_percent.max(100))
+}
+
 /// Sub-module that is declaring types required for processing This -> Bridged chain messages.
 pub mod source {
 	use super::*;
@@ -238,7 +280,9 @@ pub mod source {
 	/// - message is rejected if its lane is currently blocked;
 	/// - message is rejected if there are too many pending (undelivered) messages at the outbound lane;
 	/// - check that the sender has rights to dispatch the call on target chain using provided dispatch origin;
-	/// - check that the sender has paid enough funds for both message delivery and dispatch.
+	/// - message is rejected if the dispatch fee is paid in the asset that is not supported by the target chain;
+	/// - check that the sender has paid enough funds for both message delivery and dispatch, given the
+	///   current lane congestion.
 	#[derive(RuntimeDebug)]
 	pub struct FromThisChainMessageVerifier<B>(PhantomData<B>);
 
@@ -246,6 +290,8 @@ pub mod source {
 	pub(crate) const TOO_MANY_PENDING_MESSAGES: &str = "Too many pending messages at the lane.";
 	pub(crate) const BAD_ORIGIN: &str = "Unable to match the source origin to expected target origin.";
 	pub(crate) const TOO_LOW_FEE: &str = "Provided fee is below minimal threshold required by the lane.";
+	pub(crate) const UNSUPPORTED_DISPATCH_FEE_ASSET: &str =
+		"The dispatch fee can't be paid in the selected asset at the target chain.";
 
 	impl<B> LaneMessageVerifier<AccountIdOf<ThisChain<B>>, FromThisChainMessagePayload<B>, BalanceOf<ThisChain<B>>>
 		for FromThisChainMessageVerifier<B>
@@ -269,10 +315,7 @@ pub mod source {
 
 			// reject message if there are too many pending messages at this lane
 			let max_pending_messages = ThisChain::<B>::maximal_pending_messages_at_outbound_lane();
-			let pending_messages = lane_outbound_data
-				.latest_generated_nonce
-				.saturating_sub(lane_outbound_data.latest_received_nonce);
-			if pending_messages > max_pending_messages {
+			if pending_messages(lane_outbound_data) > max_pending_messages {
 				return Err(TOO_MANY_PENDING_MESSAGES);
 			}
 
@@ -280,8 +323,18 @@ pub mod source {
 			// `Dispatch`, so we verify the message accordingly.
 			pallet_bridge_dispatch::verify_message_origin(submitter, payload).map_err(|_| BAD_ORIGIN)?;
 
-			let minimal_fee_in_this_tokens =
-				estimate_message_dispatch_and_delivery_fee::<B>(payload, B::RELAYER_FEE_PERCENT)?;
+			// reject message if the target chain won't accept dispatch fee in the selected asset
+			if let DispatchFeePayment::AtTargetChainInAsset(asset_id) = payload.dispatch_fee_payment {
+				if !BridgedChain::<B>::is_dispatch_fee_asset_supported(asset_id) {
+					return Err(UNSUPPORTED_DISPATCH_FEE_ASSET);
+				}
+			}
+
+			let minimal_fee_in_this_tokens = estimate_message_dispatch_and_delivery_fee_at_lane::<B>(
+				lane_outbound_data,
+				payload,
+				B::RELAYER_FEE_PERCENT,
+			)?;
 
 			// compare with actual fee paid
 			if *delivery_and_dispatch_fee < minimal_fee_in_this_tokens {
@@ -339,14 +392,18 @@ pub mod source {
 		//
 		// if we're going to pay dispatch fee at the target chain, then we don't include weight
 		// of the message dispatch in the delivery transaction cost
-		let pay_dispatch_fee_at_target_chain = payload.dispatch_fee_payment == DispatchFeePayment::AtTargetChain;
+		//
+		// the call decompression weight is always paid by the relayer, so it is included in the delivery
+		// transaction cost. The size of the compressed call is used to compute the delivery transaction cost
+		let pay_dispatch_fee_at_target_chain = payload.dispatch_fee_payment.is_paid_at_target_chain();
+		let call_decompression_weight = call_decompression_weight::<B>(payload.call_encoding());
 		let delivery_transaction = BridgedChain::<B>::estimate_delivery_transaction(
 			&payload.encode(),
 			pay_dispatch_fee_at_target_chain,
 			if pay_dispatch_fee_at_target_chain {
-				0.into()
+				call_decompression_weight.into()
 			} else {
-				payload.weight.into()
+				payload.weight.saturating_add(call_decompression_weight).into()
 			},
 		);
 		let delivery_transaction_fee = BridgedChain::<B>::transaction_payment(delivery_transaction);
@@ -372,6 +429,48 @@ pub mod source {
 			.ok_or("Overflow when computing minimal required message delivery and dispatch fee")
 	}
 
+	/// Returns dispatch weight of This -> Bridged chain message at the Bridged chain.
+	///
+	/// If the message call is compressed, the weight includes weight of the call decompression.
+	pub fn message_dispatch_weight<B: MessageBridge>(payload: &FromThisChainMessagePayload<B>) -> Weight {
+		payload
+			.weight
+			.saturating_add(call_decompression_weight::<B>(payload.call_encoding()))
+	}
+
+	/// Returns weight of the message call decompression at the Bridged chain.
+	fn call_decompression_weight<B: MessageBridge>(call_encoding: CallEncoding) -> Weight {
+		match call_encoding {
+			CallEncoding::Plain => 0,
+			CallEncoding::Compressed => BridgedChain::<B>::call_decompression_weight(),
+		}
+	}
+
+	/// Estimate delivery and dispatch fee that must be paid for delivering a message over the lane
+	/// with given state.
+	///
+	/// The fee, computed by `estimate_message_dispatch_and_delivery_fee`, is multiplied by the
+	/// `ThisChainWithMessages::congestion_fee_multiplier_percent` of the lane.
+	pub fn estimate_message_dispatch_and_delivery_fee_at_lane<B: MessageBridge>(
+		lane_outbound_data: &OutboundLaneData,
+		payload: &FromThisChainMessagePayload<B>,
+		relayer_fee_percent: u32,
+	) -> Result<BalanceOf<ThisChain<B>>, &'static str> {
+		let fee = estimate_message_dispatch_and_delivery_fee::<B>(payload, relayer_fee_percent)?;
+		let multiplier_percent =
+			ThisChain::<B>::congestion_fee_multiplier_percent(pending_messages(lane_outbound_data));
+		fee.checked_mul(&multiplier_percent.into())
+			.and_then(|fee| fee.checked_div(&100u32.into()))
+			.ok_or("Overflow when applying congestion multiplier to message delivery and dispatch fee")
+	}
+
+	/// Returns number of pending (undelivered) messages at the outbound lane.
+	fn pending_messages(lane_outbound_data: &OutboundLaneData) -> MessageNonce {
+		lane_outbound_data
+			.latest_generated_nonce
+			.saturating_sub(lane_outbound_data.latest_received_nonce)
+	}
+
 	/// Verify proof of This -> Bridged chain messages delivery.
 	pub fn verify_messages_delivery_proof<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
 		proof: FromBridgedChainMessagesDeliveryProof<HashOf<BridgedChain<B>>>,
@@ -380,13 +479,27 @@ pub mod source {
 		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
 		HashOf<BridgedChain<B>>:
 			Into<bp_runtime::HashOf<<ThisRuntime as pallet_bridge_grandpa::Config<GrandpaInstance>>::BridgedChain>>,
+	{
+		verify_messages_delivery_proof_with_header_chain::<B, pallet_bridge_grandpa::Pallet<ThisRuntime, GrandpaInstance>>(
+			proof,
+		)
+	}
+
+	/// Verify proof of This -> Bridged chain messages delivery, using Bridged chain headers that are
+	/// finalized by the given finality pallet (GRANDPA, BEEFY, ...).
+	pub fn verify_messages_delivery_proof_with_header_chain<B: MessageBridge, BridgedHeaderChain>(
+		proof: FromBridgedChainMessagesDeliveryProof<HashOf<BridgedChain<B>>>,
+	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, &'static str>
+	where
+		BridgedHeaderChain: FinalizedStorageProofParser,
+		HashOf<BridgedChain<B>>: Into<BridgedHeaderChain::Hash>,
 	{
 		let FromBridgedChainMessagesDeliveryProof {
 			bridged_header_hash,
 			storage_proof,
 			lane,
 		} = proof;
-		pallet_bridge_grandpa::Pallet::<ThisRuntime, GrandpaInstance>::parse_finalized_storage_proof(
+		BridgedHeaderChain::parse_finalized_storage_proof(
 			bridged_header_hash.into(),
 			StorageProof::new(storage_proof),
 			|storage| {
@@ -461,7 +574,7 @@ pub mod target {
 	///
 	/// Our Call is opaque (`Vec<u8>`) for Bridged chain. So it is encoded, prefixed with
 	/// vector length. Custom decode implementation here is exactly to deal with this.
-	#[derive(Decode, Encode, RuntimeDebug, PartialEq)]
+	#[derive(Decode, Encode, RuntimeDebug, Clone, PartialEq)]
 	pub struct FromBridgedChainEncodedMessageCall<DecodedCall> {
 		encoded_call: Vec<u8>,
 		_marker: PhantomData<DecodedCall>,
@@ -477,21 +590,103 @@ pub mod target {
 		}
 	}
 
+	impl<DecodedCall> AsRef<[u8]> for FromBridgedChainEncodedMessageCall<DecodedCall> {
+		fn as_ref(&self) -> &[u8] {
+			&self.encoded_call
+		}
+	}
+
 	impl<DecodedCall: Decode> From<FromBridgedChainEncodedMessageCall<DecodedCall>> for Result<DecodedCall, ()> {
 		fn from(encoded_call: FromBridgedChainEncodedMessageCall<DecodedCall>) -> Self {
 			DecodedCall::decode(&mut &encoded_call.encoded_call[..]).map_err(drop)
 		}
 	}
 
+	/// Non-native assets of This chain that may be used to pay dispatch fee of Bridged -> This chain
+	/// messages (see `DispatchFeePayment::AtTargetChainInAsset`).
+	pub trait DispatchFeeAssets<AccountId> {
+		/// Transfer fee for dispatching message with given weight from the dispatch origin account
+		/// to the relayer account. The fee is paid in given asset.
+		fn pay_dispatch_fee(
+			asset_id: AssetId,
+			dispatch_origin: &AccountId,
+			relayer_account: &AccountId,
+			dispatch_weight: Weight,
+		) -> Result<(), ()>;
+	}
+
+	/// Dispatch fee can't be paid in non-native assets of This chain.
+	impl<AccountId> DispatchFeeAssets<AccountId> for () {
+		fn pay_dispatch_fee(
+			_asset_id: AssetId,
+			_dispatch_origin: &AccountId,
+			_relayer_account: &AccountId,
+			_dispatch_weight: Weight,
+		) -> Result<(), ()> {
+			Err(())
+		}
+	}
+
+	/// Converter of the message dispatch weight into the fee, paid in non-native asset of This chain.
+	pub trait WeightToAssetFee<Balance> {
+		/// Returns fee (in given asset) for dispatching message with given weight. Returns `None` if
+		/// dispatch fee can't be paid in this asset.
+		fn weight_to_asset_fee(asset_id: AssetId, dispatch_weight: Weight) -> Option<Balance>;
+	}
+
+	/// `DispatchFeeAssets` implementation that is backed by the `fungibles` implementation.
+	#[derive(RuntimeDebug, Clone, Copy)]
+	pub struct FungiblesDispatchFeeAssets<Assets, AssetFeeConverter> {
+		_marker: PhantomData<(Assets, AssetFeeConverter)>,
+	}
+
+	impl<AccountId, Assets, AssetFeeConverter> DispatchFeeAssets<AccountId>
+		for FungiblesDispatchFeeAssets<Assets, AssetFeeConverter>
+	where
+		Assets: fungibles::Transfer<AccountId, AssetId = AssetId>,
+		AssetFeeConverter: WeightToAssetFee<Assets::Balance>,
+	{
+		fn pay_dispatch_fee(
+			asset_id: AssetId,
+			dispatch_origin: &AccountId,
+			relayer_account: &AccountId,
+			dispatch_weight: Weight,
+		) -> Result<(), ()> {
+			let fee = AssetFeeConverter::weight_to_asset_fee(asset_id, dispatch_weight).ok_or(())?;
+			if !fee.is_zero() {
+				Assets::transfer(asset_id, dispatch_origin, relayer_account, fee, true)
+					.map(drop)
+					.map_err(drop)
+			} else {
+				Ok(())
+			}
+		}
+	}
+
 	/// Dispatching Bridged -> This chain messages.
+	///
+	/// If message sender has chosen to pay dispatch fee at This chain, the fee is paid either in
+	/// `ThisCurrency`, or in one of `ThisDispatchFeeAssets`.
 	#[derive(RuntimeDebug, Clone, Copy)]
-	pub struct FromBridgedChainMessageDispatch<B, ThisRuntime, ThisCurrency, ThisDispatchInstance> {
-		_marker: PhantomData<(B, ThisRuntime, ThisCurrency, ThisDispatchInstance)>,
+	pub struct FromBridgedChainMessageDispatch<
+		B,
+		ThisRuntime,
+		ThisCurrency,
+		ThisDispatchFeeAssets,
+		ThisDispatchInstance,
+	> {
+		_marker: PhantomData<(
+			B,
+			ThisRuntime,
+			ThisCurrency,
+			ThisDispatchFeeAssets,
+			ThisDispatchInstance,
+		)>,
 	}
 
-	impl<B: MessageBridge, ThisRuntime, ThisCurrency, ThisDispatchInstance>
+	impl<B: MessageBridge, ThisRuntime, ThisCurrency, ThisDispatchFeeAssets, ThisDispatchInstance>
 		MessageDispatch<AccountIdOf<ThisChain<B>>, BalanceOf<BridgedChain<B>>>
-		for FromBridgedChainMessageDispatch<B, ThisRuntime, ThisCurrency, ThisDispatchInstance>
+		for FromBridgedChainMessageDispatch<B, ThisRuntime, ThisCurrency, ThisDispatchFeeAssets, ThisDispatchInstance>
 	where
 		BalanceOf<ThisChain<B>>: Saturating + FixedPointOperand,
 		ThisDispatchInstance: 'static,
@@ -500,6 +695,7 @@ pub mod target {
 		<ThisRuntime as pallet_transaction_payment::Config>::OnChargeTransaction:
 			pallet_transaction_payment::OnChargeTransaction<ThisRuntime, Balance = BalanceOf<ThisChain<B>>>,
 		ThisCurrency: Currency<AccountIdOf<ThisChain<B>>, Balance = BalanceOf<ThisChain<B>>>,
+		ThisDispatchFeeAssets: DispatchFeeAssets<AccountIdOf<ThisChain<B>>>,
 		pallet_bridge_dispatch::Pallet<ThisRuntime, ThisDispatchInstance>: bp_message_dispatch::MessageDispatch<
 			AccountIdOf<ThisChain<B>>,
 			(LaneId, MessageNonce),
@@ -511,7 +707,23 @@ pub mod target {
 		fn dispatch_weight(
 			message: &DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
 		) -> frame_support::weights::Weight {
-			message.data.payload.as_ref().map(|payload| payload.weight).unwrap_or(0)
+			message
+				.data
+				.payload
+				.as_ref()
+				.map(pallet_bridge_dispatch::Pallet::<ThisRuntime, ThisDispatchInstance>::dispatch_weight)
+				.unwrap_or(0)
+		}
+
+		fn dispatch_fee_payment(
+			message: &DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
+		) -> DispatchFeePayment {
+			message
+				.data
+				.payload
+				.as_ref()
+				.map(|payload| payload.dispatch_fee_payment)
+				.unwrap_or(DispatchFeePayment::AtSourceChain)
 		}
 
 		fn dispatch(
@@ -519,12 +731,22 @@ pub mod target {
 			message: DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
 		) -> MessageDispatchResult {
 			let message_id = (message.key.lane_id, message.key.nonce);
+			let dispatch_fee_payment = Self::dispatch_fee_payment(&message);
 			pallet_bridge_dispatch::Pallet::<ThisRuntime, ThisDispatchInstance>::dispatch(
 				B::BRIDGED_CHAIN_ID,
 				B::THIS_CHAIN_ID,
 				message_id,
 				message.data.payload.map_err(drop),
 				|dispatch_origin, dispatch_weight| {
+					if let DispatchFeePayment::AtTargetChainInAsset(asset_id) = dispatch_fee_payment {
+						return ThisDispatchFeeAssets::pay_dispatch_fee(
+							asset_id,
+							dispatch_origin,
+							relayer_account,
+							dispatch_weight,
+						);
+					}
+
 					let unadjusted_weight_fee = ThisRuntime::WeightToFee::calc(&dispatch_weight);
 					let fee_multiplier = pallet_transaction_payment::Pallet::<ThisRuntime>::next_fee_multiplier();
 					let adjusted_weight_fee = fee_multiplier.saturating_mul_int(unadjusted_weight_fee);
@@ -567,12 +789,32 @@ pub mod target {
 		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
 		HashOf<BridgedChain<B>>:
 			Into<bp_runtime::HashOf<<ThisRuntime as pallet_bridge_grandpa::Config<GrandpaInstance>>::BridgedChain>>,
+	{
+		verify_messages_proof_with_header_chain::<B, pallet_bridge_grandpa::Pallet<ThisRuntime, GrandpaInstance>>(
+			proof,
+			messages_count,
+		)
+	}
+
+	/// Verify proof of Bridged -> This chain messages, using Bridged chain headers that are
+	/// finalized by the given finality pallet (GRANDPA, BEEFY, ...).
+	///
+	/// The `messages_count` argument verification (sane limits) is supposed to be made
+	/// outside of this function. This function only verifies that the proof declares exactly
+	/// `messages_count` messages.
+	pub fn verify_messages_proof_with_header_chain<B: MessageBridge, BridgedHeaderChain>(
+		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
+		messages_count: u32,
+	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str>
+	where
+		BridgedHeaderChain: FinalizedStorageProofParser,
+		HashOf<BridgedChain<B>>: Into<BridgedHeaderChain::Hash>,
 	{
 		verify_messages_proof_with_parser::<B, _, _>(
 			proof,
 			messages_count,
 			|bridged_header_hash, bridged_storage_proof| {
-				pallet_bridge_grandpa::Pallet::<ThisRuntime, GrandpaInstance>::parse_finalized_storage_proof(
+				BridgedHeaderChain::parse_finalized_storage_proof(
 					bridged_header_hash.into(),
 					StorageProof::new(bridged_storage_proof),
 					|storage_adapter| storage_adapter,
@@ -593,7 +835,9 @@ pub mod target {
 		MessagesCountMismatch,
 		MissingRequiredMessage,
 		FailedToDecodeMessage,
+		MissingRequiredOutboundLaneState,
 		FailedToDecodeOutboundLaneState,
+		MissingRequiredMessageCancellation,
 		Custom(&'static str),
 	}
 
@@ -604,9 +848,15 @@ pub mod target {
 				MessageProofError::MessagesCountMismatch => "Declared messages count doesn't match actual value",
 				MessageProofError::MissingRequiredMessage => "Message is missing from the proof",
 				MessageProofError::FailedToDecodeMessage => "Failed to decode message from the proof",
+				MessageProofError::MissingRequiredOutboundLaneState => {
+					"Outbound lane state is missing from the messages proof"
+				}
 				MessageProofError::FailedToDecodeOutboundLaneState => {
 					"Failed to decode outbound lane data from the proof"
 				}
+				MessageProofError::MissingRequiredMessageCancellation => {
+					"Neither message cancellation entry, nor its absence is proved"
+				}
 				MessageProofError::Custom(err) => err,
 			}
 		}
@@ -615,6 +865,7 @@ pub mod target {
 	pub(crate) trait MessageProofParser {
 		fn read_raw_outbound_lane_data(&self, lane_id: &LaneId) -> Option<Vec<u8>>;
 		fn read_raw_message(&self, message_key: &MessageKey) -> Option<Vec<u8>>;
+		fn read_raw_message_cancellation(&self, message_key: &MessageKey) -> Result<Option<Vec<u8>>, ()>;
 	}
 
 	struct StorageProofCheckerAdapter<H: Hasher, B> {
@@ -643,6 +894,17 @@ pub mod target {
 			);
 			self.storage.read_value(storage_message_key.0.as_ref()).ok()?
 		}
+
+		fn read_raw_message_cancellation(&self, message_key: &MessageKey) -> Result<Option<Vec<u8>>, ()> {
+			let storage_message_cancellation_key = pallet_bridge_messages::storage_keys::message_cancellation_key(
+				B::BRIDGED_MESSAGES_PALLET_NAME,
+				&message_key.lane_id,
+				message_key.nonce,
+			);
+			self.storage
+				.read_value(storage_message_cancellation_key.0.as_ref())
+				.map_err(drop)
+		}
 	}
 
 	/// Verify proof of Bridged -> This chain messages using given message proof parser.
@@ -691,14 +953,23 @@ pub mod target {
 				.ok_or(MessageProofError::MissingRequiredMessage)?;
 			let message_data = MessageData::<BalanceOf<BridgedChain<B>>>::decode(&mut &raw_message_data[..])
 				.map_err(|_| MessageProofError::FailedToDecodeMessage)?;
+			// the proof must show either the cancellation entry, or its absence. Otherwise the relayer
+			// would be able to hide the cancellation and get reward for delivering cancelled message
+			let cancelled = parser
+				.read_raw_message_cancellation(&message_key)
+				.map_err(|_| MessageProofError::MissingRequiredMessageCancellation)?
+				.is_some();
 			messages.push(Message {
 				key: message_key,
-				data: message_data,
+				data: message_data.into(),
+				cancelled,
 			});
 		}
 
-		// Now let's check if proof contains outbound lane state proof. It is optional, so we
-		// simply ignore `read_value` errors and missing value.
+		// Now let's check if proof contains outbound lane state proof. It is optional if the proof
+		// has no messages, so we simply ignore `read_value` errors and missing value. Otherwise, the
+		// lane state is required, because it tells us which messages have expired at the bridged chain
+		// and must not be dispatched.
 		let mut proved_lane_messages = ProvedLaneMessages {
 			lane_state: None,
 			messages,
@@ -709,6 +980,8 @@ pub mod target {
 				OutboundLaneData::decode(&mut &raw_outbound_lane_data[..])
 					.map_err(|_| MessageProofError::FailedToDecodeOutboundLaneState)?,
 			);
+		} else if !proved_lane_messages.messages.is_empty() {
+			return Err(MessageProofError::MissingRequiredOutboundLaneState);
 		}
 
 		// Now we may actually check if the proof is empty or not.
@@ -737,7 +1010,9 @@ mod tests {
 	const BRIDGED_CHAIN_WEIGHT_TO_BALANCE_RATE: Weight = 4;
 	const BRIDGED_CHAIN_TO_THIS_CHAIN_BALANCE_RATE: u32 = 6;
 	const BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT: Weight = 2048;
+	const BRIDGED_CHAIN_CALL_DECOMPRESSION_WEIGHT: Weight = 50;
 	const BRIDGED_CHAIN_MAX_EXTRINSIC_SIZE: u32 = 1024;
+	const BRIDGED_CHAIN_DISPATCH_FEE_ASSET: AssetId = 42;
 
 	/// Bridge that is deployed on ThisChain and allows sending/receiving messages to/from BridgedChain;
 	#[derive(Debug, PartialEq, Eq)]
@@ -884,6 +1159,15 @@ mod tests {
 			MAXIMAL_PENDING_MESSAGES_AT_TEST_LANE
 		}
 
+		fn congestion_fee_multiplier_percent(pending_messages: MessageNonce) -> u32 {
+			linear_congestion_fee_multiplier_percent(
+				pending_messages,
+				FREE_PENDING_MESSAGES_AT_TEST_LANE,
+				CONGESTION_FEE_PERCENT_PER_MESSAGE,
+				MAX_CONGESTION_FEE_MULTIPLIER_PERCENT,
+			)
+		}
+
 		fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>> {
 			MessageTransaction {
 				dispatch_weight: DELIVERY_CONFIRMATION_TRANSACTION_WEIGHT,
@@ -905,6 +1189,14 @@ mod tests {
 			unreachable!()
 		}
 
+		fn call_decompression_weight() -> Weight {
+			unreachable!()
+		}
+
+		fn is_dispatch_fee_asset_supported(_asset_id: AssetId) -> bool {
+			unreachable!()
+		}
+
 		fn estimate_delivery_transaction(
 			_message_payload: &[u8],
 			_include_pay_dispatch_fee_cost: bool,
@@ -940,6 +1232,10 @@ mod tests {
 			unreachable!()
 		}
 
+		fn congestion_fee_multiplier_percent(_pending_messages: MessageNonce) -> u32 {
+			unreachable!()
+		}
+
 		fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>> {
 			unreachable!()
 		}
@@ -959,6 +1255,14 @@ mod tests {
 			begin..=BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT
 		}
 
+		fn call_decompression_weight() -> Weight {
+			BRIDGED_CHAIN_CALL_DECOMPRESSION_WEIGHT
+		}
+
+		fn is_dispatch_fee_asset_supported(asset_id: AssetId) -> bool {
+			asset_id == BRIDGED_CHAIN_DISPATCH_FEE_ASSET
+		}
+
 		fn estimate_delivery_transaction(
 			_message_payload: &[u8],
 			_include_pay_dispatch_fee_cost: bool,
@@ -1012,6 +1316,9 @@ mod tests {
 
 	const TEST_LANE_ID: &LaneId = b"test";
 	const MAXIMAL_PENDING_MESSAGES_AT_TEST_LANE: MessageNonce = 32;
+	const FREE_PENDING_MESSAGES_AT_TEST_LANE: MessageNonce = 16;
+	const CONGESTION_FEE_PERCENT_PER_MESSAGE: u32 = 10;
+	const MAX_CONGESTION_FEE_MULTIPLIER_PERCENT: u32 = 300;
 
 	fn regular_outbound_message_payload() -> source::FromThisChainMessagePayload<OnThisChainBridge> {
 		source::FromThisChainMessagePayload::<OnThisChainBridge> {
@@ -1023,6 +1330,27 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn message_dispatch_weight_is_not_paid_at_source_if_dispatch_fee_is_paid_in_asset() {
+		let payload = regular_outbound_message_payload();
+		let fee_with_prepaid_dispatch = source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
+			&payload,
+			OnThisChainBridge::RELAYER_FEE_PERCENT,
+		)
+		.unwrap();
+
+		let mut payload_with_pay_on_target_in_asset = regular_outbound_message_payload();
+		payload_with_pay_on_target_in_asset.dispatch_fee_payment =
+			DispatchFeePayment::AtTargetChainInAsset(BRIDGED_CHAIN_DISPATCH_FEE_ASSET);
+		let fee_without_prepaid_dispatch = source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
+			&payload_with_pay_on_target_in_asset,
+			OnThisChainBridge::RELAYER_FEE_PERCENT,
+		)
+		.unwrap();
+
+		assert!(fee_without_prepaid_dispatch < fee_with_prepaid_dispatch);
+	}
+
 	#[test]
 	fn message_fee_is_checked_by_verifier() {
 		const EXPECTED_MINIMAL_FEE: u32 = 5500;
@@ -1077,6 +1405,135 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn message_fee_includes_call_decompression_weight() {
+		let compressed_payload = regular_outbound_message_payload().compress();
+		assert_eq!(
+			compressed_payload.call_encoding(),
+			bp_message_dispatch::CallEncoding::Compressed
+		);
+		assert_eq!(
+			source::message_dispatch_weight::<OnThisChainBridge>(&compressed_payload),
+			compressed_payload.weight + BRIDGED_CHAIN_CALL_DECOMPRESSION_WEIGHT,
+		);
+
+		// the fee for compressed message is the same as for the plain message with the weight
+		// increased by the decompression weight
+		let mut heavier_plain_payload = regular_outbound_message_payload();
+		heavier_plain_payload.weight += BRIDGED_CHAIN_CALL_DECOMPRESSION_WEIGHT;
+		assert_eq!(
+			source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
+				&compressed_payload,
+				OnThisChainBridge::RELAYER_FEE_PERCENT,
+			),
+			source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
+				&heavier_plain_payload,
+				OnThisChainBridge::RELAYER_FEE_PERCENT,
+			),
+		);
+
+		// the decompression weight is paid at the source chain, even if dispatch fee is paid at
+		// the target chain
+		let mut compressed_payload_with_pay_on_target = compressed_payload;
+		compressed_payload_with_pay_on_target.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+		let mut plain_payload_with_pay_on_target = regular_outbound_message_payload();
+		plain_payload_with_pay_on_target.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+		assert!(
+			source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
+				&compressed_payload_with_pay_on_target,
+				OnThisChainBridge::RELAYER_FEE_PERCENT,
+			)
+			.unwrap() > source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
+				&plain_payload_with_pay_on_target,
+				OnThisChainBridge::RELAYER_FEE_PERCENT,
+			)
+			.unwrap()
+		);
+	}
+
+	fn congested_lane_outbound_data(pending_messages: MessageNonce) -> OutboundLaneData {
+		OutboundLaneData {
+			latest_received_nonce: 100,
+			latest_generated_nonce: 100 + pending_messages,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn linear_congestion_fee_multiplier_works() {
+		assert_eq!(linear_congestion_fee_multiplier_percent(0, 16, 10, 300), 100);
+		assert_eq!(linear_congestion_fee_multiplier_percent(16, 16, 10, 300), 100);
+		assert_eq!(linear_congestion_fee_multiplier_percent(17, 16, 10, 300), 110);
+		assert_eq!(linear_congestion_fee_multiplier_percent(36, 16, 10, 300), 300);
+		assert_eq!(
+			linear_congestion_fee_multiplier_percent(MessageNonce::MAX, 16, 10, 300),
+			300
+		);
+		assert_eq!(
+			linear_congestion_fee_multiplier_percent(MessageNonce::MAX, 16, 10, 0),
+			100
+		);
+	}
+
+	#[test]
+	fn message_fee_grows_with_lane_congestion() {
+		const EXPECTED_MINIMAL_FEE: u32 = 5500;
+
+		let payload = regular_outbound_message_payload();
+		let estimate_fee = |pending_messages| {
+			source::estimate_message_dispatch_and_delivery_fee_at_lane::<OnThisChainBridge>(
+				&congested_lane_outbound_data(pending_messages),
+				&payload,
+				OnThisChainBridge::RELAYER_FEE_PERCENT,
+			)
+		};
+
+		// fee is not affected while the lane is not congested
+		assert_eq!(estimate_fee(0), Ok(ThisChainBalance(EXPECTED_MINIMAL_FEE)));
+		assert_eq!(
+			estimate_fee(FREE_PENDING_MESSAGES_AT_TEST_LANE),
+			Ok(ThisChainBalance(EXPECTED_MINIMAL_FEE))
+		);
+		// then it grows with every pending message
+		assert_eq!(
+			estimate_fee(FREE_PENDING_MESSAGES_AT_TEST_LANE + 4),
+			Ok(ThisChainBalance(EXPECTED_MINIMAL_FEE * 140 / 100))
+		);
+		// until it reaches the maximal multiplier
+		assert_eq!(
+			estimate_fee(MAXIMAL_PENDING_MESSAGES_AT_TEST_LANE),
+			Ok(ThisChainBalance(EXPECTED_MINIMAL_FEE * 3))
+		);
+	}
+
+	#[test]
+	fn message_fee_is_checked_by_verifier_at_congested_lane() {
+		const EXPECTED_MINIMAL_FEE: u32 = 5500;
+
+		let payload = regular_outbound_message_payload();
+		let lane_outbound_data = congested_lane_outbound_data(FREE_PENDING_MESSAGES_AT_TEST_LANE + 4);
+		assert_eq!(
+			source::FromThisChainMessageVerifier::<OnThisChainBridge>::verify_message(
+				&Sender::Root,
+				&ThisChainBalance(EXPECTED_MINIMAL_FEE),
+				TEST_LANE_ID,
+				&lane_outbound_data,
+				&payload,
+			),
+			Err(source::TOO_LOW_FEE)
+		);
+		assert!(
+			source::FromThisChainMessageVerifier::<OnThisChainBridge>::verify_message(
+				&Sender::Root,
+				&ThisChainBalance(EXPECTED_MINIMAL_FEE * 140 / 100),
+				TEST_LANE_ID,
+				&lane_outbound_data,
+				&payload,
+			)
+			.is_ok(),
+		);
+	}
+
 	#[test]
 	fn should_disallow_root_calls_from_regular_accounts() {
 		// payload of the This -> Bridged chain message
@@ -1169,6 +1626,34 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn message_is_rejected_when_dispatch_fee_asset_is_not_supported_by_target_chain() {
+		let mut payload = regular_outbound_message_payload();
+		payload.dispatch_fee_payment = DispatchFeePayment::AtTargetChainInAsset(BRIDGED_CHAIN_DISPATCH_FEE_ASSET + 1);
+		assert_eq!(
+			source::FromThisChainMessageVerifier::<OnThisChainBridge>::verify_message(
+				&Sender::Root,
+				&ThisChainBalance(1_000_000),
+				TEST_LANE_ID,
+				&test_lane_outbound_data(),
+				&payload,
+			),
+			Err(source::UNSUPPORTED_DISPATCH_FEE_ASSET)
+		);
+
+		payload.dispatch_fee_payment = DispatchFeePayment::AtTargetChainInAsset(BRIDGED_CHAIN_DISPATCH_FEE_ASSET);
+		assert!(
+			source::FromThisChainMessageVerifier::<OnThisChainBridge>::verify_message(
+				&Sender::Root,
+				&ThisChainBalance(1_000_000),
+				TEST_LANE_ID,
+				&test_lane_outbound_data(),
+				&payload,
+			)
+			.is_ok(),
+		);
+	}
+
 	#[test]
 	fn message_is_rejected_when_there_are_too_many_pending_messages_at_outbound_lane() {
 		assert_eq!(
@@ -1255,6 +1740,8 @@ mod tests {
 	struct TestMessageProofParser {
 		failing: bool,
 		messages: RangeInclusive<MessageNonce>,
+		cancelled_messages: Vec<MessageNonce>,
+		unproved_cancellations: Vec<MessageNonce>,
 		outbound_lane_data: Option<OutboundLaneData>,
 	}
 
@@ -1282,6 +1769,16 @@ mod tests {
 				None
 			}
 		}
+
+		fn read_raw_message_cancellation(&self, message_key: &MessageKey) -> Result<Option<Vec<u8>>, ()> {
+			if self.unproved_cancellations.contains(&message_key.nonce) {
+				Err(())
+			} else if self.cancelled_messages.contains(&message_key.nonce) {
+				Ok(Some(1u64.encode()))
+			} else {
+				Ok(None)
+			}
+		}
 	}
 
 	#[allow(clippy::reversed_empty_ranges)]
@@ -1342,6 +1839,8 @@ mod tests {
 				TestMessageProofParser {
 					failing: false,
 					messages: 1..=5,
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
 					outbound_lane_data: None,
 				}
 			),),
@@ -1356,6 +1855,8 @@ mod tests {
 				TestMessageProofParser {
 					failing: true,
 					messages: 1..=10,
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
 					outbound_lane_data: None,
 				}
 			),),
@@ -1363,6 +1864,22 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn message_proof_is_rejected_if_outbound_lane_state_is_missing() {
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(messages_proof(10), 10, |_, _| Ok(
+				TestMessageProofParser {
+					failing: false,
+					messages: 1..=10,
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
+					outbound_lane_data: None,
+				}
+			),),
+			Err(target::MessageProofError::MissingRequiredOutboundLaneState),
+		);
+	}
+
 	#[test]
 	fn message_proof_is_rejected_if_outbound_lane_state_decode_fails() {
 		assert_eq!(
@@ -1370,10 +1887,13 @@ mod tests {
 				TestMessageProofParser {
 					failing: true,
 					messages: no_messages_range(),
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
 					outbound_lane_data: Some(OutboundLaneData {
 						oldest_unpruned_nonce: 1,
 						latest_received_nonce: 1,
 						latest_generated_nonce: 1,
+						latest_expired_nonce: 0,
 					}),
 				}
 			),),
@@ -1388,6 +1908,8 @@ mod tests {
 				TestMessageProofParser {
 					failing: false,
 					messages: no_messages_range(),
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
 					outbound_lane_data: None,
 				}
 			),),
@@ -1402,10 +1924,13 @@ mod tests {
 				TestMessageProofParser {
 					failing: false,
 					messages: no_messages_range(),
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
 					outbound_lane_data: Some(OutboundLaneData {
 						oldest_unpruned_nonce: 1,
 						latest_received_nonce: 1,
 						latest_generated_nonce: 1,
+						latest_expired_nonce: 0,
 					}),
 				}
 			),),
@@ -1416,6 +1941,7 @@ mod tests {
 						oldest_unpruned_nonce: 1,
 						latest_received_nonce: 1,
 						latest_generated_nonce: 1,
+						latest_expired_nonce: 0,
 					}),
 					messages: Vec::new(),
 				},
@@ -1432,10 +1958,13 @@ mod tests {
 				TestMessageProofParser {
 					failing: false,
 					messages: 1..=1,
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
 					outbound_lane_data: Some(OutboundLaneData {
 						oldest_unpruned_nonce: 1,
 						latest_received_nonce: 1,
 						latest_generated_nonce: 1,
+						latest_expired_nonce: 0,
 					}),
 				}
 			),),
@@ -1446,16 +1975,18 @@ mod tests {
 						oldest_unpruned_nonce: 1,
 						latest_received_nonce: 1,
 						latest_generated_nonce: 1,
+						latest_expired_nonce: 0,
 					}),
 					messages: vec![Message {
 						key: MessageKey {
 							lane_id: Default::default(),
 							nonce: 1
 						},
-						data: MessageData {
+						data: bp_messages::MessageData {
 							payload: 1u64.encode(),
 							fee: BridgedChainBalance(0)
 						},
+						cancelled: false,
 					}],
 				},
 			)]
@@ -1464,6 +1995,45 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn message_proof_is_rejected_if_message_cancellation_is_not_proved() {
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(messages_proof(3), 3, |_, _| Ok(
+				TestMessageProofParser {
+					failing: false,
+					messages: 1..=3,
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![2],
+					outbound_lane_data: Some(OutboundLaneData::default()),
+				}
+			),),
+			Err(target::MessageProofError::MissingRequiredMessageCancellation),
+		);
+	}
+
+	#[test]
+	fn cancelled_messages_are_marked_in_message_proof() {
+		let proved_messages =
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(messages_proof(3), 3, |_, _| {
+				Ok(TestMessageProofParser {
+					failing: false,
+					messages: 1..=3,
+					cancelled_messages: vec![2],
+					unproved_cancellations: vec![],
+					outbound_lane_data: Some(OutboundLaneData::default()),
+				})
+			})
+			.unwrap();
+		assert_eq!(
+			proved_messages
+				.values()
+				.flat_map(|lane_messages| lane_messages.messages.iter())
+				.map(|message| (message.key.nonce, message.cancelled))
+				.collect::<Vec<_>>(),
+			vec![(1, false), (2, true), (3, false)],
+		);
+	}
+
 	#[test]
 	fn verify_messages_proof_with_parser_does_not_panic_if_messages_count_mismatches() {
 		assert_eq!(
@@ -1473,10 +2043,13 @@ mod tests {
 				|_, _| Ok(TestMessageProofParser {
 					failing: false,
 					messages: 0..=u64::MAX,
+					cancelled_messages: vec![],
+					unproved_cancellations: vec![],
 					outbound_lane_data: Some(OutboundLaneData {
 						oldest_unpruned_nonce: 1,
 						latest_received_nonce: 1,
 						latest_generated_nonce: 1,
+						latest_expired_nonce: 0,
 					}),
 				}),
 			),
@@ -16,9 +16,13 @@
 
 //! Helpers for implementing various message-related runtime API mthods.
 
-use crate::messages::{source::FromThisChainMessagePayload, MessageBridge};
+use crate::messages::{
+	source::{message_dispatch_weight, FromThisChainMessagePayload},
+	MessageBridge,
+};
 
 use bp_messages::{LaneId, MessageDetails, MessageNonce};
+use bp_runtime::messages::DispatchFeePayment;
 use codec::Decode;
 use sp_std::vec::Vec;
 
@@ -27,7 +31,7 @@ pub fn outbound_message_details<Runtime, MessagesPalletInstance, BridgeConfig>(
 	lane: LaneId,
 	begin: MessageNonce,
 	end: MessageNonce,
-) -> Vec<MessageDetails<Runtime::OutboundMessageFee>>
+) -> Vec<MessageDetails<Runtime::OutboundMessageFee, Runtime::AccountId>>
 where
 	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
 	MessagesPalletInstance: 'static,
@@ -37,14 +41,33 @@ where
 		.filter_map(|nonce| {
 			let message_data =
 				pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_message_data(lane, nonce)?;
+			let sender =
+				pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_message_sender(lane, nonce);
+			// cancelled messages are never dispatched, but relayers still need to deliver them
+			if pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::is_outbound_message_cancelled(
+				lane, nonce,
+			) {
+				return Some(MessageDetails {
+					nonce,
+					dispatch_weight: 0,
+					size: message_data.payload.len() as _,
+					delivery_and_dispatch_fee: message_data.fee,
+					dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+					sender,
+					cancelled: true,
+				});
+			}
+
 			let decoded_payload =
 				FromThisChainMessagePayload::<BridgeConfig>::decode(&mut &message_data.payload[..]).ok()?;
 			Some(MessageDetails {
 				nonce,
-				dispatch_weight: decoded_payload.weight,
+				dispatch_weight: message_dispatch_weight::<BridgeConfig>(&decoded_payload),
 				size: message_data.payload.len() as _,
 				delivery_and_dispatch_fee: message_data.fee,
 				dispatch_fee_payment: decoded_payload.dispatch_fee_payment,
+				sender,
+				cancelled: false,
 			})
 		})
 		.collect()
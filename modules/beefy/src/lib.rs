@@ -0,0 +1,1002 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate BEEFY Pallet
+//!
+//! This pallet is an on-chain BEEFY light client for Substrate based chains. It is an alternative
+//! to the GRANDPA pallet: verifying BEEFY commitments is cheaper than verifying GRANDPA
+//! justifications, because we only need to verify (a supermajority of) ECDSA signatures and a
+//! single MMR leaf proof.
+//!
+//! The relayer submits a commitment, signed by the current BEEFY validator set. The commitment
+//! contains the MMR root of the bridged chain. The relayer also submits the MMR leaf and its
+//! proof. The leaf contains the hash of the bridged chain header, which is also submitted by the
+//! relayer. Once verified, this header is stored in the pallet, thereby creating a sparse header
+//! chain - the same way the GRANDPA pallet does it.
+//!
+//! The pallet is also responsible for tracking BEEFY validator set hand-offs. Every MMR leaf
+//! contains the compact information on the next validator set (its id, length and Merkle root).
+//! When the commitment is signed by the next validator set, the relayer must provide the full
+//! next validator set, which is verified against the stored Merkle root.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// Runtime-generated enums
+#![allow(clippy::large_enum_variant)]
+
+use bp_beefy::{
+	BeefyAuthorityId, BeefyMmrLeaf, BeefyNextAuthoritySet, BeefyValidatorSet, InitializationData, MmrProof,
+	SignedCommitment,
+};
+use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf};
+use codec::Encode;
+use frame_support::{ensure, fail, weights::Weight};
+use frame_system::{ensure_signed, RawOrigin};
+use sp_core::H256;
+use sp_runtime::traits::{BadOrigin, Convert, Hash as HashT, Header as HeaderT};
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+// Re-export in crate namespace for `construct_runtime!`
+pub use pallet::*;
+
+/// Block number of the bridged chain.
+pub type BridgedBlockNumber<T, I> = BlockNumberOf<<T as Config<I>>::BridgedChain>;
+/// Block hash of the bridged chain.
+pub type BridgedBlockHash<T, I> = HashOf<<T as Config<I>>::BridgedChain>;
+/// Hasher of the bridged chain.
+pub type BridgedBlockHasher<T, I> = HasherOf<<T as Config<I>>::BridgedChain>;
+/// Header of the bridged chain.
+pub type BridgedHeader<T, I> = HeaderOf<<T as Config<I>>::BridgedChain>;
+/// MMR leaf of the bridged chain.
+pub type BridgedMmrLeaf<T, I> = BeefyMmrLeaf<BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>>;
+
+/// Approximate weight of single ECDSA public key recovery.
+///
+/// The pallet isn't benchmarked yet, so we're using upper bound of the `secp256k1_ecdsa_recover`
+/// host function weight here.
+const ECDSA_RECOVER_WEIGHT: Weight = 50_000_000;
+
+/// Approximate weight of merging two MMR nodes while verifying MMR proof.
+///
+/// The pallet isn't benchmarked yet, so we're using upper bound of hashing 64 bytes with the
+/// `keccak_256` host function here.
+const MMR_NODES_MERGE_WEIGHT: Weight = 5_000_000;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The chain we are bridging to here.
+		type BridgedChain: Chain;
+
+		/// Hasher that is used to build the MMR and BEEFY validator set Merkle tree of the bridged
+		/// chain.
+		///
+		/// This is `Keccak256` for Polkadot-like chains.
+		type MmrHashing: HashT<Output = H256>;
+
+		/// Conversion of the BEEFY validator public key into the leaf of the validator set Merkle
+		/// tree.
+		///
+		/// For Polkadot-like chains, validators public keys are converted into Ethereum addresses.
+		type BeefyAuthorityToMerkleLeaf: Convert<BeefyAuthorityId, Vec<u8>>;
+
+		/// The upper bound on the number of requests allowed by the pallet.
+		///
+		/// A request refers to an action which writes a header to storage.
+		///
+		/// Once this bound is reached the pallet will not allow any dispatchables to be called
+		/// until the request count has decreased.
+		#[pallet::constant]
+		type MaxRequests: Get<u32>;
+
+		/// Maximal number of finalized headers to keep in the storage.
+		///
+		/// The setting is there to prevent growing the on-chain state indefinitely. Note the setting
+		/// does not relate to block numbers - we will simply keep as much items in the storage, so it
+		/// doesn't guarantee any fixed timeframe for finality headers.
+		#[pallet::constant]
+		type HeadersToKeep: Get<u32>;
+	}
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(_n: T::BlockNumber) -> frame_support::weights::Weight {
+			<RequestCount<T, I>>::mutate(|count| *count = count.saturating_sub(1));
+
+			(0_u64)
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(T::DbWeight::get().writes(1))
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Verify that the header is finalized using the BEEFY commitment and MMR leaf proof.
+		///
+		/// The commitment must be signed either by the current validator set, or by the next
+		/// validator set. In the latter case, the full next validator set must be provided in
+		/// the `next_validators` argument. It is verified against the next validator set Merkle
+		/// root, known to the pallet. Then the next validator set becomes the current set and the
+		/// `mmr_leaf` is used to learn about the new next validator set.
+		///
+		/// The `mmr_leaf` must be the leaf that is referencing the `header` and it must be
+		/// included in the MMR, which root is signed by the commitment.
+		///
+		/// If successful in verification, it will write the header to the storage.
+		#[pallet::weight(submit_commitment_weight::<T, I>(
+			signed_commitment.signatures.len() as u32,
+			next_validators.as_ref().map(|next_validators| next_validators.len() as u32).unwrap_or(0),
+			mmr_proof.items.len() as u32,
+		))]
+		pub fn submit_commitment(
+			origin: OriginFor<T>,
+			signed_commitment: SignedCommitment<BridgedBlockNumber<T, I>>,
+			next_validators: Option<Vec<BeefyAuthorityId>>,
+			mmr_leaf: BridgedMmrLeaf<T, I>,
+			mmr_proof: MmrProof<H256>,
+			header: BridgedHeader<T, I>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(
+				Self::request_count() < T::MaxRequests::get(),
+				<Error<T, I>>::TooManyRequests
+			);
+
+			let (hash, number) = (header.hash(), *header.number());
+			log::trace!(
+				target: "runtime::bridge-beefy",
+				"Going to try and finalize header {:?} using commitment {:?}",
+				hash,
+				signed_commitment.commitment,
+			);
+
+			let best_finalized = match <ImportedHeaders<T, I>>::get(<BestFinalized<T, I>>::get()) {
+				Some(best_finalized) => best_finalized,
+				None => {
+					log::error!(
+						target: "runtime::bridge-beefy",
+						"Cannot finalize header {:?} because pallet is not yet initialized",
+						hash,
+					);
+					fail!(<Error<T, I>>::NotInitialized);
+				}
+			};
+			ensure!(best_finalized.number() < &number, <Error<T, I>>::OldHeader);
+
+			// select validator set that has signed the commitment and verify signatures
+			let (validator_set, is_handoff) =
+				select_validator_set::<T, I>(signed_commitment.commitment.validator_set_id, next_validators)?;
+			bp_beefy::verify_signed_commitment(&signed_commitment, &validator_set).map_err(|e| {
+				log::error!(
+					target: "runtime::bridge-beefy",
+					"Received invalid commitment {:?}: {:?}",
+					signed_commitment.commitment,
+					e,
+				);
+				<Error<T, I>>::InvalidCommitment
+			})?;
+
+			// verify that the leaf is included in the signed MMR and that it references the header
+			ensure!(
+				mmr_leaf.parent_number_and_hash == (number, hash),
+				<Error<T, I>>::MmrLeafHeaderMismatch
+			);
+			bp_beefy::verify_mmr_leaf_proof::<T::MmrHashing>(
+				signed_commitment.commitment.payload,
+				T::MmrHashing::hash(&mmr_leaf.encode()),
+				mmr_proof,
+			)
+			.map_err(|e| {
+				log::error!(
+					target: "runtime::bridge-beefy",
+					"Received invalid MMR proof of leaf {:?}: {:?}",
+					mmr_leaf,
+					e,
+				);
+				<Error<T, I>>::InvalidMmrProof
+			})?;
+
+			// enact validator set handoff
+			if is_handoff {
+				enact_validator_set_handoff::<T, I>(validator_set, mmr_leaf.beefy_next_authority_set)?;
+			}
+
+			insert_header::<T, I>(header, hash);
+			<RequestCount<T, I>>::mutate(|count| *count += 1);
+
+			log::info!(target: "runtime::bridge-beefy", "Successfully imported finalized header {:?}!", hash);
+
+			Ok(().into())
+		}
+
+		/// Bootstrap the bridge pallet with an initial header and BEEFY validator sets from which
+		/// to sync.
+		///
+		/// This function is only allowed to be called from a trusted origin and writes to storage
+		/// with practically no checks in terms of the validity of the data. It is important that
+		/// you ensure that valid data is being passed in.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 6), DispatchClass::Operational))]
+		pub fn initialize(
+			origin: OriginFor<T>,
+			init_data: InitializationData<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+
+			let init_allowed = !<BestFinalized<T, I>>::exists();
+			ensure!(init_allowed, <Error<T, I>>::AlreadyInitialized);
+			initialize_bridge::<T, I>(init_data.clone());
+
+			log::info!(
+				target: "runtime::bridge-beefy",
+				"Pallet has been initialized with the following parameters: {:?}",
+				init_data
+			);
+
+			Ok(().into())
+		}
+
+		/// Change `PalletOwner`.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			match new_owner {
+				Some(new_owner) => {
+					PalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-beefy", "Setting pallet Owner to: {:?}", new_owner);
+				}
+				None => {
+					PalletOwner::<T, I>::kill();
+					log::info!(target: "runtime::bridge-beefy", "Removed Owner of pallet.");
+				}
+			}
+
+			Ok(().into())
+		}
+
+		/// Halt or resume all pallet operations.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_operational(origin: OriginFor<T>, operational: bool) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			<IsHalted<T, I>>::put(!operational);
+
+			if operational {
+				log::info!(target: "runtime::bridge-beefy", "Resuming pallet operations.");
+			} else {
+				log::warn!(target: "runtime::bridge-beefy", "Stopping pallet operations.");
+			}
+
+			Ok(().into())
+		}
+	}
+
+	/// The current number of requests which have written to storage.
+	///
+	/// If the `RequestCount` hits `MaxRequests`, no more calls will be allowed to the pallet until
+	/// the request capacity is increased.
+	///
+	/// The `RequestCount` is decreased by one at the beginning of every block. This is to ensure
+	/// that the pallet can always make progress.
+	#[pallet::storage]
+	#[pallet::getter(fn request_count)]
+	pub(super) type RequestCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Hash of the best finalized header.
+	#[pallet::storage]
+	pub(super) type BestFinalized<T: Config<I>, I: 'static = ()> = StorageValue<_, BridgedBlockHash<T, I>, ValueQuery>;
+
+	/// A ring buffer of imported hashes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedHashes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, u32, BridgedBlockHash<T, I>>;
+
+	/// Current ring buffer position.
+	#[pallet::storage]
+	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Headers which have been imported into the pallet.
+	#[pallet::storage]
+	pub(super) type ImportedHeaders<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, BridgedBlockHash<T, I>, BridgedHeader<T, I>>;
+
+	/// The current BEEFY validator set.
+	#[pallet::storage]
+	pub(super) type CurrentAuthoritySet<T: Config<I>, I: 'static = ()> = StorageValue<_, BeefyValidatorSet, ValueQuery>;
+
+	/// Compact information on the next BEEFY validator set.
+	#[pallet::storage]
+	pub(super) type NextAuthoritySet<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BeefyNextAuthoritySet, ValueQuery>;
+
+	/// Optional pallet owner.
+	///
+	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
+	/// `None`, then there are no direct ways to halt/resume pallet operations, but other
+	/// runtime methods may still be used to do that (i.e. democracy::referendum to update halt
+	/// flag directly or call the `set_operational`).
+	#[pallet::storage]
+	pub(super) type PalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// If true, all pallet transactions are failed immediately.
+	#[pallet::storage]
+	pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Optional module owner account.
+		pub owner: Option<T::AccountId>,
+		/// Optional module initialization data.
+		pub init_data: Option<InitializationData<BridgedHeader<T, I>>>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self {
+				owner: None,
+				init_data: None,
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			if let Some(ref owner) = self.owner {
+				<PalletOwner<T, I>>::put(owner);
+			}
+
+			if let Some(init_data) = self.init_data.clone() {
+				initialize_bridge::<T, I>(init_data);
+			} else {
+				// Since the bridge hasn't been initialized we shouldn't allow anyone to perform
+				// transactions.
+				<IsHalted<T, I>>::put(true);
+			}
+		}
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The commitment is signed by unknown validator set.
+		UnknownValidatorSet,
+		/// The commitment is signed by the next validator set, but it hasn't been provided.
+		MissingNextValidatorSet,
+		/// The provided next validator set doesn't match the next validator set known to the pallet.
+		InvalidNextValidatorSet,
+		/// The signed commitment is invalid.
+		InvalidCommitment,
+		/// The MMR leaf proof is invalid.
+		InvalidMmrProof,
+		/// The MMR leaf doesn't reference the header being imported.
+		MmrLeafHeaderMismatch,
+		/// The MMR leaf doesn't contain the validator set that follows the new validator set.
+		InvalidNextAuthoritySetId,
+		/// There are too many requests for the current window to handle.
+		TooManyRequests,
+		/// The header being imported is older than the best finalized header known to the pallet.
+		OldHeader,
+		/// The header is unknown to the pallet.
+		UnknownHeader,
+		/// The pallet is not yet initialized.
+		NotInitialized,
+		/// The pallet has already been initialized.
+		AlreadyInitialized,
+		/// All pallet operations are halted.
+		Halted,
+		/// The storage proof doesn't contains storage root. So it is invalid for given header.
+		StorageRootMismatch,
+	}
+
+	/// Select validator set that is expected to sign the commitment.
+	///
+	/// Returns the validator set and the flag that tells whether it is the next validator set.
+	pub(crate) fn select_validator_set<T: Config<I>, I: 'static>(
+		validator_set_id: bp_beefy::ValidatorSetId,
+		next_validators: Option<Vec<BeefyAuthorityId>>,
+	) -> Result<(BeefyValidatorSet, bool), sp_runtime::DispatchError> {
+		let current_authority_set = <CurrentAuthoritySet<T, I>>::get();
+		if validator_set_id == current_authority_set.id {
+			return Ok((current_authority_set, false));
+		}
+
+		let next_authority_set = <NextAuthoritySet<T, I>>::get();
+		ensure!(
+			validator_set_id == next_authority_set.id,
+			<Error<T, I>>::UnknownValidatorSet
+		);
+
+		let next_validators = next_validators.ok_or(<Error<T, I>>::MissingNextValidatorSet)?;
+		let next_validators_root = bp_beefy::validators_merkle_root::<T::MmrHashing>(
+			next_validators
+				.iter()
+				.cloned()
+				.map(T::BeefyAuthorityToMerkleLeaf::convert),
+		);
+		ensure!(
+			next_validators.len() == next_authority_set.len as usize && next_validators_root == next_authority_set.root,
+			<Error<T, I>>::InvalidNextValidatorSet
+		);
+
+		Ok((
+			BeefyValidatorSet {
+				id: next_authority_set.id,
+				validators: next_validators,
+			},
+			true,
+		))
+	}
+
+	/// Make the next validator set current and remember the new next validator set.
+	pub(crate) fn enact_validator_set_handoff<T: Config<I>, I: 'static>(
+		new_authority_set: BeefyValidatorSet,
+		new_next_authority_set: BeefyNextAuthoritySet,
+	) -> Result<(), sp_runtime::DispatchError> {
+		// we need to know the next validator set to continue syncing after handoff
+		ensure!(
+			new_next_authority_set.id == new_authority_set.id + 1,
+			<Error<T, I>>::InvalidNextAuthoritySetId
+		);
+
+		log::info!(
+			target: "runtime::bridge-beefy",
+			"Transitioned from validator set {} to {}! New validators are: {:?}",
+			new_authority_set.id - 1,
+			new_authority_set.id,
+			new_authority_set.validators,
+		);
+
+		<CurrentAuthoritySet<T, I>>::put(new_authority_set);
+		<NextAuthoritySet<T, I>>::put(new_next_authority_set);
+
+		Ok(())
+	}
+
+	/// Import a previously verified header to the storage.
+	///
+	/// Note this function solely takes care of updating the storage and pruning old entries,
+	/// but does not verify the validity of such import.
+	pub(crate) fn insert_header<T: Config<I>, I: 'static>(header: BridgedHeader<T, I>, hash: BridgedBlockHash<T, I>) {
+		let index = <ImportedHashesPointer<T, I>>::get();
+		let pruning = <ImportedHashes<T, I>>::try_get(index);
+		<BestFinalized<T, I>>::put(hash);
+		<ImportedHeaders<T, I>>::insert(hash, header);
+		<ImportedHashes<T, I>>::insert(index, hash);
+
+		// Update ring buffer pointer and remove old header.
+		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
+		if let Ok(hash) = pruning {
+			log::debug!(target: "runtime::bridge-beefy", "Pruning old header: {:?}.", hash);
+			<ImportedHeaders<T, I>>::remove(hash);
+		}
+	}
+
+	/// Since this writes to storage with no real checks this should only be used in functions that
+	/// were called by a trusted origin.
+	pub(crate) fn initialize_bridge<T: Config<I>, I: 'static>(init_params: InitializationData<BridgedHeader<T, I>>) {
+		let InitializationData {
+			header,
+			authority_set,
+			next_authority_set,
+			is_halted,
+		} = init_params;
+
+		let initial_hash = header.hash();
+		<ImportedHashesPointer<T, I>>::put(0);
+		insert_header::<T, I>(header, initial_hash);
+
+		<CurrentAuthoritySet<T, I>>::put(authority_set);
+		<NextAuthoritySet<T, I>>::put(next_authority_set);
+
+		<IsHalted<T, I>>::put(is_halted);
+	}
+
+	/// Ensure that the origin is either root, or `PalletOwner`.
+	fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(), BadOrigin> {
+		match origin.into() {
+			Ok(RawOrigin::Root) => Ok(()),
+			Ok(RawOrigin::Signed(ref signer)) if Some(signer) == <PalletOwner<T, I>>::get().as_ref() => Ok(()),
+			_ => Err(BadOrigin),
+		}
+	}
+
+	/// Ensure that the pallet is in operational mode (not halted).
+	fn ensure_operational<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+		if <IsHalted<T, I>>::get() {
+			Err(<Error<T, I>>::Halted)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Get the best finalized header the pallet knows of.
+	///
+	/// Returns a dummy header if there is no best header. This can only happen
+	/// if the pallet has not been initialized yet.
+	pub fn best_finalized() -> BridgedHeader<T, I> {
+		let hash = <BestFinalized<T, I>>::get();
+		<ImportedHeaders<T, I>>::get(hash).unwrap_or_else(|| {
+			<BridgedHeader<T, I>>::new(
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			)
+		})
+	}
+
+	/// Check if a particular header is known to the bridge pallet.
+	pub fn is_known_header(hash: BridgedBlockHash<T, I>) -> bool {
+		<ImportedHeaders<T, I>>::contains_key(hash)
+	}
+
+	/// Get the finalized header with given hash, if it is known to the bridge pallet.
+	pub fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash)
+	}
+
+	/// Verify that the passed storage proof is valid, given it is crafted using
+	/// known finalized header. If the proof is valid, then the `parse` callback
+	/// is called and the function returns its result.
+	pub fn parse_finalized_storage_proof<R>(
+		hash: BridgedBlockHash<T, I>,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(bp_runtime::StorageProofChecker<BridgedBlockHasher<T, I>>) -> R,
+	) -> Result<R, sp_runtime::DispatchError> {
+		let header = <ImportedHeaders<T, I>>::get(hash).ok_or(Error::<T, I>::UnknownHeader)?;
+		let storage_proof_checker = bp_runtime::StorageProofChecker::new(*header.state_root(), storage_proof)
+			.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
+
+		Ok(parse(storage_proof_checker))
+	}
+}
+
+impl<T: Config<I>, I: 'static> bp_runtime::FinalizedStorageProofParser for Pallet<T, I> {
+	type Hash = BridgedBlockHash<T, I>;
+	type Hasher = BridgedBlockHasher<T, I>;
+
+	fn parse_finalized_storage_proof<R>(
+		hash: Self::Hash,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(bp_runtime::StorageProofChecker<Self::Hasher>) -> R,
+	) -> Result<R, sp_runtime::DispatchError> {
+		Pallet::<T, I>::parse_finalized_storage_proof(hash, storage_proof, parse)
+	}
+}
+
+/// Weight of the `submit_commitment` call.
+///
+/// The pallet isn't benchmarked yet, so this accounts db reads and writes and the (approximate)
+/// cost of signatures verification. The next validator set is also converted into Merkle leaves,
+/// which (for Polkadot-like chains) requires public key decompression, so we're assuming the same
+/// cost here. Every item of the MMR proof is merged with the current node while computing the MMR
+/// root, so the cost of MMR proof verification is linear in the number of proof items.
+fn submit_commitment_weight<T: Config<I>, I: 'static>(
+	signatures: u32,
+	next_validators: u32,
+	mmr_proof_items: u32,
+) -> Weight {
+	T::DbWeight::get()
+		.reads_writes(7, 8)
+		.saturating_add(ECDSA_RECOVER_WEIGHT.saturating_mul(signatures.saturating_add(next_validators) as Weight))
+		.saturating_add(MMR_NODES_MERGE_WEIGHT.saturating_mul(mmr_proof_items as Weight))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, test_header, Origin, TestHeader, TestNumber, TestRuntime, TestValidators, MAX_REQUESTS,
+	};
+	use frame_support::{assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo, traits::OnInitialize};
+	use sp_runtime::DispatchError;
+
+	fn initialize(validators: &TestValidators, next_validators: &TestValidators) -> TestHeader {
+		let genesis = test_header(0);
+		assert_ok!(Pallet::<TestRuntime>::initialize(
+			Origin::root(),
+			InitializationData {
+				header: genesis.clone(),
+				authority_set: validators.validator_set(),
+				next_authority_set: next_validators.next_authority_set(),
+				is_halted: false,
+			},
+		));
+		genesis
+	}
+
+	fn submit_commitment(
+		validators: &TestValidators,
+		next_validators: Option<&TestValidators>,
+		leaf_next_validators: &TestValidators,
+		header: TestHeader,
+	) -> DispatchResultWithPostInfo {
+		let (signed_commitment, mmr_leaf, mmr_proof) =
+			validators.finalize_header(&header, leaf_next_validators, validators.count());
+		Pallet::<TestRuntime>::submit_commitment(
+			Origin::signed(1),
+			signed_commitment,
+			next_validators.map(|next_validators| next_validators.validator_set().validators),
+			mmr_leaf,
+			mmr_proof,
+			header,
+		)
+	}
+
+	fn next_block() {
+		let mut number = frame_system::Pallet::<TestRuntime>::block_number();
+		number += 1;
+		frame_system::Pallet::<TestRuntime>::set_block_number(number);
+		let _ = Pallet::<TestRuntime>::on_initialize(number);
+	}
+
+	#[test]
+	fn init_root_or_owner_origin_can_initialize_pallet() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			let init_data = InitializationData {
+				header: test_header(0),
+				authority_set: validators.validator_set(),
+				next_authority_set: next_validators.next_authority_set(),
+				is_halted: false,
+			};
+
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::signed(1), init_data.clone()),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(1)));
+			assert_ok!(Pallet::<TestRuntime>::initialize(Origin::signed(1), init_data.clone()));
+
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), test_header(0));
+			assert_eq!(CurrentAuthoritySet::<TestRuntime>::get(), validators.validator_set());
+			assert_eq!(
+				NextAuthoritySet::<TestRuntime>::get(),
+				next_validators.next_authority_set()
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::root(), init_data),
+				Error::<TestRuntime>::AlreadyInitialized,
+			);
+		});
+	}
+
+	#[test]
+	fn pallet_rejects_transactions_if_halted() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+			assert_noop!(
+				submit_commitment(&validators, None, &next_validators, test_header(1)),
+				Error::<TestRuntime>::Halted,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), true));
+			assert_ok!(submit_commitment(&validators, None, &next_validators, test_header(1)));
+		});
+	}
+
+	#[test]
+	fn pallet_rejects_commitment_if_not_initialized() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			assert_noop!(
+				submit_commitment(&validators, None, &next_validators, test_header(1)),
+				Error::<TestRuntime>::NotInitialized,
+			);
+		});
+	}
+
+	#[test]
+	fn succesfully_imports_header_with_valid_commitment() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			let header = test_header(1);
+			assert_ok!(submit_commitment(&validators, None, &next_validators, header.clone()));
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), header);
+			assert!(Pallet::<TestRuntime>::is_known_header(header.hash()));
+			assert_eq!(Pallet::<TestRuntime>::finalized_header(header.hash()), Some(header));
+		});
+	}
+
+	#[test]
+	fn rejects_commitment_without_enough_signatures() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			let header = test_header(1);
+			let (signed_commitment, mmr_leaf, mmr_proof) = validators.finalize_header(&header, &next_validators, 3);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(
+					Origin::signed(1),
+					signed_commitment,
+					None,
+					mmr_leaf,
+					mmr_proof,
+					header,
+				),
+				Error::<TestRuntime>::InvalidCommitment,
+			);
+		});
+	}
+
+	#[test]
+	fn rejects_commitment_of_unknown_validator_set() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			let unknown_validators = TestValidators::new(2, 5);
+			assert_noop!(
+				submit_commitment(&unknown_validators, None, &next_validators, test_header(1)),
+				Error::<TestRuntime>::UnknownValidatorSet,
+			);
+		});
+	}
+
+	#[test]
+	fn rejects_invalid_mmr_proof() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			let header = test_header(1);
+			let (signed_commitment, mmr_leaf, mut mmr_proof) =
+				validators.finalize_header(&header, &next_validators, validators.count());
+			mmr_proof.items.push(Default::default());
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(
+					Origin::signed(1),
+					signed_commitment,
+					None,
+					mmr_leaf,
+					mmr_proof,
+					header,
+				),
+				Error::<TestRuntime>::InvalidMmrProof,
+			);
+		});
+	}
+
+	#[test]
+	fn submit_commitment_weight_depends_on_mmr_proof_size() {
+		assert_eq!(
+			submit_commitment_weight::<TestRuntime, ()>(5, 0, 10)
+				- submit_commitment_weight::<TestRuntime, ()>(5, 0, 1),
+			9 * MMR_NODES_MERGE_WEIGHT,
+		);
+	}
+
+	#[test]
+	fn rejects_mmr_leaf_of_other_header() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			let (signed_commitment, mmr_leaf, mmr_proof) =
+				validators.finalize_header(&test_header(1), &next_validators, validators.count());
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(
+					Origin::signed(1),
+					signed_commitment,
+					None,
+					mmr_leaf,
+					mmr_proof,
+					test_header(2),
+				),
+				Error::<TestRuntime>::MmrLeafHeaderMismatch,
+			);
+		});
+	}
+
+	#[test]
+	fn rejects_old_header() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			assert_ok!(submit_commitment(&validators, None, &next_validators, test_header(2)));
+			assert_noop!(
+				submit_commitment(&validators, None, &next_validators, test_header(1)),
+				Error::<TestRuntime>::OldHeader,
+			);
+		});
+	}
+
+	#[test]
+	fn enacts_validator_set_handoff() {
+		run_test(|| {
+			let (validators, next_validators, next_next_validators) = (
+				TestValidators::new(0, 5),
+				TestValidators::new(1, 7),
+				TestValidators::new(2, 3),
+			);
+			initialize(&validators, &next_validators);
+
+			// the commitment is signed by the next validator set, which we don't provide
+			assert_noop!(
+				submit_commitment(&next_validators, None, &next_next_validators, test_header(1)),
+				Error::<TestRuntime>::MissingNextValidatorSet,
+			);
+
+			// the commitment is signed by the next validator set, but we provide other set
+			assert_noop!(
+				submit_commitment(
+					&next_validators,
+					Some(&next_next_validators),
+					&next_next_validators,
+					test_header(1)
+				),
+				Error::<TestRuntime>::InvalidNextValidatorSet,
+			);
+
+			// the MMR leaf doesn't contain the next-next validator set
+			assert_noop!(
+				submit_commitment(
+					&next_validators,
+					Some(&next_validators),
+					&next_validators,
+					test_header(1)
+				),
+				Error::<TestRuntime>::InvalidNextAuthoritySetId,
+			);
+
+			// everything is correct
+			assert_ok!(submit_commitment(
+				&next_validators,
+				Some(&next_validators),
+				&next_next_validators,
+				test_header(1)
+			));
+			assert_eq!(
+				CurrentAuthoritySet::<TestRuntime>::get(),
+				next_validators.validator_set()
+			);
+			assert_eq!(
+				NextAuthoritySet::<TestRuntime>::get(),
+				next_next_validators.next_authority_set()
+			);
+
+			// commitments of the old validator set are not accepted anymore
+			assert_noop!(
+				submit_commitment(&validators, None, &next_next_validators, test_header(2)),
+				Error::<TestRuntime>::UnknownValidatorSet,
+			);
+			assert_ok!(submit_commitment(
+				&next_validators,
+				None,
+				&next_next_validators,
+				test_header(2)
+			));
+		});
+	}
+
+	#[test]
+	fn disallows_imports_once_limit_is_hit_in_single_block() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			for number in 1..=MAX_REQUESTS {
+				assert_ok!(submit_commitment(
+					&validators,
+					None,
+					&next_validators,
+					test_header(number as TestNumber)
+				));
+			}
+			assert_noop!(
+				submit_commitment(
+					&validators,
+					None,
+					&next_validators,
+					test_header(MAX_REQUESTS as TestNumber + 1)
+				),
+				Error::<TestRuntime>::TooManyRequests,
+			);
+
+			next_block();
+			assert_ok!(submit_commitment(
+				&validators,
+				None,
+				&next_validators,
+				test_header(MAX_REQUESTS as TestNumber + 1)
+			));
+		});
+	}
+
+	#[test]
+	fn prunes_old_headers() {
+		run_test(|| {
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			let genesis = initialize(&validators, &next_validators);
+
+			let headers_to_keep = crate::mock::HeadersToKeep::get() as TestNumber;
+			for number in 1..=headers_to_keep {
+				next_block();
+				assert_ok!(submit_commitment(
+					&validators,
+					None,
+					&next_validators,
+					test_header(number)
+				));
+			}
+
+			assert!(!Pallet::<TestRuntime>::is_known_header(genesis.hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+		});
+	}
+
+	#[test]
+	fn parse_finalized_storage_proof_rejects_proof_on_unknown_header() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					Default::default(),
+					sp_trie::StorageProof::new(vec![]),
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownHeader,
+			);
+		});
+	}
+
+	#[test]
+	fn parse_finalized_storage_accepts_valid_proof() {
+		run_test(|| {
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+			let (validators, next_validators) = (TestValidators::new(0, 5), TestValidators::new(1, 5));
+			initialize(&validators, &next_validators);
+
+			let mut header = test_header(1);
+			header.set_state_root(state_root);
+			assert_ok!(submit_commitment(&validators, None, &next_validators, header.clone()));
+
+			assert_ok!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(header.hash(), storage_proof, |_| (),),
+				(),
+			);
+		});
+	}
+}
@@ -0,0 +1,230 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use bp_beefy::{
+	BeefyAuthorityId, BeefyMmrLeaf, BeefyNextAuthoritySet, BeefyValidatorSet, Commitment, MmrHasher, MmrProof,
+	SignedCommitment, ValidatorSetId,
+};
+use bp_runtime::Chain;
+use codec::Encode;
+use frame_support::{construct_runtime, parameter_types, weights::Weight};
+use sp_core::{ecdsa, Pair, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Convert, Hash as HashT, Header as HeaderT, IdentityLookup, Keccak256},
+	AnySignature, Perbill,
+};
+
+pub type AccountId = u64;
+pub type TestHeader = crate::BridgedHeader<TestRuntime, ()>;
+pub type TestNumber = crate::BridgedBlockNumber<TestRuntime, ()>;
+pub type TestMmrLeaf = crate::BridgedMmrLeaf<TestRuntime, ()>;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+pub const MAX_REQUESTS: u32 = 2;
+
+use crate as beefy;
+
+construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Beefy: beefy::{Pallet},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type DbWeight = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const MaxRequests: u32 = MAX_REQUESTS;
+	pub const HeadersToKeep: u32 = 5;
+}
+
+impl beefy::Config for TestRuntime {
+	type BridgedChain = TestBridgedChain;
+	type MmrHashing = Keccak256;
+	type BeefyAuthorityToMerkleLeaf = TestBeefyAuthorityToMerkleLeaf;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+}
+
+#[derive(Debug)]
+pub struct TestBridgedChain;
+
+impl Chain for TestBridgedChain {
+	type BlockNumber = <TestRuntime as frame_system::Config>::BlockNumber;
+	type Hash = <TestRuntime as frame_system::Config>::Hash;
+	type Hasher = <TestRuntime as frame_system::Config>::Hashing;
+	type Header = <TestRuntime as frame_system::Config>::Header;
+
+	type AccountId = AccountId;
+	type Balance = u64;
+	type Index = u64;
+	type Signature = AnySignature;
+}
+
+/// Validator set Merkle tree leaf is the raw public key of the validator.
+pub struct TestBeefyAuthorityToMerkleLeaf;
+
+impl Convert<BeefyAuthorityId, Vec<u8>> for TestBeefyAuthorityToMerkleLeaf {
+	fn convert(authority: BeefyAuthorityId) -> Vec<u8> {
+		authority.0.to_vec()
+	}
+}
+
+/// BEEFY validator set of the bridged chain.
+pub struct TestValidators {
+	id: ValidatorSetId,
+	pairs: Vec<ecdsa::Pair>,
+}
+
+impl TestValidators {
+	/// Create validator set with given id and number of validators.
+	pub fn new(id: ValidatorSetId, count: u8) -> Self {
+		TestValidators {
+			id,
+			pairs: (0..count)
+				.map(|index| ecdsa::Pair::from_seed(&[id as u8 * 16 + index + 1; 32]))
+				.collect(),
+		}
+	}
+
+	/// Returns number of validators in the set.
+	pub fn count(&self) -> usize {
+		self.pairs.len()
+	}
+
+	/// Returns the validator set, as it is stored in the pallet.
+	pub fn validator_set(&self) -> BeefyValidatorSet {
+		BeefyValidatorSet {
+			id: self.id,
+			validators: self.pairs.iter().map(|pair| pair.public()).collect(),
+		}
+	}
+
+	/// Returns compact information on this validator set, as it is stored in the MMR leaf.
+	pub fn next_authority_set(&self) -> BeefyNextAuthoritySet {
+		BeefyNextAuthoritySet {
+			id: self.id,
+			len: self.pairs.len() as u32,
+			root: bp_beefy::validators_merkle_root::<Keccak256>(
+				self.pairs
+					.iter()
+					.map(|pair| TestBeefyAuthorityToMerkleLeaf::convert(pair.public())),
+			),
+		}
+	}
+
+	/// Generate commitment, signed by first `signed_by` validators of this set, MMR leaf that is
+	/// referencing given header and the proof of this leaf.
+	pub fn finalize_header(
+		&self,
+		header: &TestHeader,
+		leaf_next_validators: &TestValidators,
+		signed_by: usize,
+	) -> (SignedCommitment<TestNumber>, TestMmrLeaf, MmrProof<H256>) {
+		let mmr_leaf = BeefyMmrLeaf {
+			version: 0,
+			parent_number_and_hash: (*header.number(), header.hash()),
+			beefy_next_authority_set: leaf_next_validators.next_authority_set(),
+			parachain_heads: Default::default(),
+		};
+
+		// surround our leaf with some other leaves
+		let mut mmr = mmr_lib::util::MemMMR::<H256, MmrHasher<Keccak256>>::default();
+		for index in 0u8..3 {
+			mmr.push(Keccak256::hash(&[index])).unwrap();
+		}
+		let leaf_position = mmr.push(Keccak256::hash(&mmr_leaf.encode())).unwrap();
+		for index in 3u8..5 {
+			mmr.push(Keccak256::hash(&[index])).unwrap();
+		}
+		let mmr_proof = MmrProof {
+			leaf_index: 3,
+			leaf_count: 6,
+			items: mmr.gen_proof(vec![leaf_position]).unwrap().proof_items().to_vec(),
+		};
+
+		let commitment = Commitment {
+			payload: mmr.get_root().unwrap(),
+			block_number: *header.number() + 1,
+			validator_set_id: self.id,
+		};
+		let message = sp_io::hashing::keccak_256(&commitment.encode());
+		let signatures = self
+			.pairs
+			.iter()
+			.enumerate()
+			.map(|(index, pair)| {
+				if index < signed_by {
+					Some(pair.sign_prehashed(&message))
+				} else {
+					None
+				}
+			})
+			.collect();
+
+		(SignedCommitment { commitment, signatures }, mmr_leaf, mmr_proof)
+	}
+}
+
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	sp_io::TestExternalities::new(Default::default()).execute_with(test)
+}
+
+pub fn test_header(num: TestNumber) -> TestHeader {
+	// We wrap the call to avoid explicit type annotations in our tests
+	bp_test_utils::test_header(num)
+}
@@ -20,24 +20,30 @@
 //! them and then dispatch as usual. To prevent compatibility issues, the Calls have
 //! to include a `spec_version`. This will be checked before dispatch. In the case of
 //! a successful dispatch an event is emitted.
+//!
+//! The call may be compressed by the message sender (see `CallEncoding::Compressed`). Compressed
+//! calls are prefixed with `bp_message_dispatch::COMPRESSED_CALL_PREFIX` and are decompressed right
+//! before decoding. The size of decompressed call is limited by the
+//! `Config::MaxDecompressedCallSize` and the decompression weight is always added to the dispatch
+//! weight of the message.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 // Generated by `decl_event!`
 #![allow(clippy::unused_unit)]
 
-use bp_message_dispatch::{CallOrigin, MessageDispatch, MessagePayload, SpecVersion};
+use bp_message_dispatch::{CallEncoding, CallOrigin, MessageDispatch, MessagePayload, SpecVersion};
 use bp_runtime::{
 	derive_account_id,
 	messages::{DispatchFeePayment, MessageDispatchResult},
 	ChainId, SourceAccount,
 };
-use codec::Encode;
+use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::Dispatchable,
 	ensure,
 	traits::{Filter, Get},
-	weights::{extract_actual_weight, GetDispatchInfo},
+	weights::{extract_actual_weight, GetDispatchInfo, Weight},
 };
 use frame_system::RawOrigin;
 use sp_runtime::traits::{BadOrigin, Convert, IdentifyAccount, MaybeDisplay, Verify};
@@ -48,7 +54,7 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
 	use frame_system::pallet_prelude::*;
 
 	#[pallet::config]
@@ -84,7 +90,19 @@ pub mod pallet {
 		/// that all other stuff (like `spec_version`) is ok. If we would try to decode
 		/// `Call` which has been encoded using previous `spec_version`, then we might end
 		/// up with decoding error, instead of `MessageVersionSpecMismatch`.
-		type EncodedCall: Decode + Encode + Into<Result<<Self as Config<I>>::Call, ()>>;
+		///
+		/// If the call is compressed, the `AsRef<[u8]>` must return the compressed call bytes.
+		type EncodedCall: Decode + Encode + AsRef<[u8]> + Into<Result<<Self as Config<I>>::Call, ()>>;
+		/// Maximal size of the decompressed call.
+		///
+		/// Compressed calls that are decompressed to larger calls, are rejected.
+		type MaxDecompressedCallSize: Get<u32>;
+		/// Weight of decompressing the call.
+		///
+		/// This must be the upper bound of weight, required to decompress the call of
+		/// `MaxDecompressedCallSize` bytes. It is added to the dispatch weight of every
+		/// message with compressed call.
+		type CallDecompressionWeight: Get<Weight>;
 		/// A type which can be turned into an AccountId from a 256-bit hash.
 		///
 		/// Used when deriving target chain AccountIds from source chain AccountIds.
@@ -93,8 +111,12 @@ pub mod pallet {
 
 	type MessageIdOf<T, I> = <T as Config<I>>::MessageId;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
 	#[pallet::hooks]
@@ -130,6 +152,8 @@ pub mod pallet {
 		),
 		/// Message has been dispatched with given result.
 		MessageDispatched(ChainId, MessageIdOf<T, I>, DispatchResult),
+		/// We have failed to decompress Call from the message.
+		MessageCallDecompressionFailed(ChainId, MessageIdOf<T, I>),
 		/// Phantom member, never used. Needed to handle multiple pallet instances.
 		_Dummy(PhantomData<I>),
 	}
@@ -140,7 +164,9 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::MessageId> for P
 		MessagePayload<T::SourceChainAccountId, T::TargetChainAccountPublic, T::TargetChainSignature, T::EncodedCall>;
 
 	fn dispatch_weight(message: &Self::Message) -> bp_message_dispatch::Weight {
-		message.weight
+		message
+			.weight
+			.saturating_add(call_decompression_weight::<T, I>(message.call_encoding()))
 	}
 
 	fn dispatch<P: FnOnce(&T::AccountId, bp_message_dispatch::Weight) -> Result<(), ()>>(
@@ -173,7 +199,7 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::MessageId> for P
 		// (we want it to be the same, because otherwise we may decode Call improperly)
 		let mut dispatch_result = MessageDispatchResult {
 			dispatch_result: false,
-			unspent_weight: message.weight,
+			unspent_weight: Self::dispatch_weight(&message),
 			dispatch_fee_paid_during_dispatch: false,
 		};
 		let expected_version = <T as frame_system::Config>::Version::get().spec_version;
@@ -194,8 +220,30 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::MessageId> for P
 			return dispatch_result;
 		}
 
-		// now that we have spec version checked, let's decode the call
-		let call = match message.call.into() {
+		// now that we have spec version checked, let's decompress the call (if required)
+		let call = match message.call_encoding() {
+			CallEncoding::Plain => message.call.into(),
+			CallEncoding::Compressed => {
+				// the decompression weight is spent even if we fail to decompress the call
+				dispatch_result.unspent_weight = message.weight;
+				match bp_message_dispatch::decompress_call(message.call.as_ref(), T::MaxDecompressedCallSize::get()) {
+					Ok(decompressed_call) => <T as Config<I>>::Call::decode(&mut &decompressed_call[..]).map_err(drop),
+					Err(_) => {
+						log::trace!(
+							target: "runtime::bridge-dispatch",
+							"Failed to decompress Call from message {:?}/{:?}",
+							source_chain,
+							id,
+						);
+						Self::deposit_event(Event::MessageCallDecompressionFailed(source_chain, id));
+						return dispatch_result;
+					}
+				}
+			}
+		};
+
+		// now that we have the call decompressed, let's decode it
+		let call = match call {
 			Ok(call) => call,
 			Err(_) => {
 				log::trace!(
@@ -288,7 +336,7 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::MessageId> for P
 		}
 
 		// pay dispatch fee right before dispatch
-		let pay_dispatch_fee_at_target_chain = message.dispatch_fee_payment == DispatchFeePayment::AtTargetChain;
+		let pay_dispatch_fee_at_target_chain = message.dispatch_fee_payment.is_paid_at_target_chain();
 		if pay_dispatch_fee_at_target_chain && pay_dispatch_fee(&origin_account, message.weight).is_err() {
 			log::trace!(
 				target: "runtime::bridge-dispatch",
@@ -337,6 +385,14 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::MessageId> for P
 	}
 }
 
+/// Returns weight of decompressing the call with given encoding.
+fn call_decompression_weight<T: Config<I>, I: 'static>(call_encoding: CallEncoding) -> Weight {
+	match call_encoding {
+		CallEncoding::Plain => 0,
+		CallEncoding::Compressed => T::CallDecompressionWeight::get(),
+	}
+}
+
 /// Check if the message is allowed to be dispatched on the target chain given the sender's origin
 /// on the source chain.
 ///
@@ -472,6 +528,8 @@ mod tests {
 		pub const MaximumBlockWeight: Weight = 1024;
 		pub const MaximumBlockLength: u32 = 2 * 1024;
 		pub const AvailableBlockRatio: Perbill = Perbill::one();
+		pub const MaxDecompressedCallSize: u32 = 1024;
+		pub const CallDecompressionWeight: Weight = 1_000;
 	}
 
 	impl frame_system::Config for TestRuntime {
@@ -509,12 +567,20 @@ mod tests {
 		type Call = Call;
 		type CallFilter = TestCallFilter;
 		type EncodedCall = EncodedCall;
+		type MaxDecompressedCallSize = MaxDecompressedCallSize;
+		type CallDecompressionWeight = CallDecompressionWeight;
 		type AccountIdConverter = AccountIdConverter;
 	}
 
 	#[derive(Decode, Encode)]
 	pub struct EncodedCall(Vec<u8>);
 
+	impl AsRef<[u8]> for EncodedCall {
+		fn as_ref(&self) -> &[u8] {
+			&self.0
+		}
+	}
+
 	impl From<EncodedCall> for Result<Call, ()> {
 		fn from(call: EncodedCall) -> Result<Call, ()> {
 			Call::decode(&mut &call.0[..]).map_err(drop)
@@ -720,6 +786,132 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn plain_message_payload_encoding_is_not_changed() {
+		let message = prepare_root_message(Call::System(<frame_system::Call<TestRuntime>>::remark(vec![42; 128])));
+		assert_eq!(message.call_encoding(), CallEncoding::Plain);
+		assert_eq!(
+			message.encode(),
+			(
+				message.spec_version,
+				message.weight,
+				&message.origin,
+				message.dispatch_fee_payment,
+				&message.call.0,
+			)
+				.encode(),
+		);
+	}
+
+	#[test]
+	fn should_dispatch_compressed_call() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let mut message =
+				prepare_root_message(Call::System(<frame_system::Call<TestRuntime>>::remark(vec![42; 128])));
+			let weight = message.weight;
+			message.call.0 = bp_message_dispatch::compress_call(&message.call.0);
+			assert_eq!(
+				Dispatch::dispatch_weight(&message),
+				weight + CallDecompressionWeight::get()
+			);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert!(result.dispatch_result);
+			assert!(result.unspent_weight <= weight);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageDispatched(
+						SOURCE_CHAIN_ID,
+						id,
+						Ok(())
+					)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_decompress_call_on_spec_version_mismatch() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let mut message =
+				prepare_root_message(Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3])));
+			let weight = message.weight;
+			message.spec_version = 99;
+			message.call.0 = bp_message_dispatch::compress_call(&message.call.0);
+
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert_eq!(result.unspent_weight, weight + CallDecompressionWeight::get());
+			assert!(!result.dispatch_result);
+		});
+	}
+
+	#[test]
+	fn should_fail_on_call_decompression() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let mut message =
+				prepare_root_message(Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3])));
+			let weight = message.weight;
+			message.call.0 = bp_message_dispatch::COMPRESSED_CALL_PREFIX.to_vec();
+			message.call.0.extend(vec![0xFF; 16]);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert_eq!(result.unspent_weight, weight);
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageCallDecompressionFailed(
+						SOURCE_CHAIN_ID,
+						id
+					)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_decompressed_call_is_too_large() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let large_remark = vec![42; MaxDecompressedCallSize::get() as usize];
+			let mut message =
+				prepare_root_message(Call::System(<frame_system::Call<TestRuntime>>::remark(large_remark)));
+			message.call.0 = bp_message_dispatch::compress_call(&message.call.0);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageCallDecompressionFailed(
+						SOURCE_CHAIN_ID,
+						id
+					)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
 	#[test]
 	fn should_emit_event_for_rejected_calls() {
 		new_test_ext().execute_with(|| {
@@ -812,6 +1004,27 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn should_dispatch_calls_paid_at_target_chain_in_asset() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let mut message =
+				prepare_root_message(Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3])));
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChainInAsset(42);
+
+			System::set_block_number(1);
+			let mut fee_paid = false;
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| {
+				fee_paid = true;
+				Ok(())
+			});
+			assert!(fee_paid);
+			assert!(result.dispatch_fee_paid_during_dispatch);
+			assert!(result.dispatch_result);
+		});
+	}
+
 	#[test]
 	fn should_return_dispatch_failed_flag_if_dispatch_happened_but_failed() {
 		new_test_ext().execute_with(|| {
@@ -0,0 +1,782 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that forwards messages, delivered over one bridge, to the next chain of the route.
+//!
+//! Let's say we have three chains: A, B and C. A is bridged with B and B is bridged with C, but there's
+//! no direct bridge between A and C. The pallet is deployed at the chain B (where it is used as a
+//! message dispatcher of the A->B lanes) and at the chain A (where it is used as a message dispatcher
+//! of the B->A lanes). Lanes between A and B are using the `bp_message_dispatch::ForwardingPayload`
+//! as a message payload. The message from A to C is then delivered as follows:
+//!
+//! 1) the `ForwardingPayload::Forward` message is sent from A to B;
+//! 2) when the message is dispatched at B, the pallet re-sends its payload over the B->C lane, using
+//!    the `MessagesBridge::send_owned_message`. The delivery and dispatch fee of the forwarded message
+//!    is paid by the sovereign account of the chain A at the chain B. This account is derived using
+//!    `bp_runtime::derive_account_id` and it is the same account that is used to dispatch messages,
+//!    sent by the Root account of the chain A. So it must be funded in advance;
+//! 3) when the forwarded message is delivered to C and the delivery confirmation is received by B,
+//!    the pallet remembers the dispatch result of the forwarded message;
+//! 4) in the `on_idle` hook, the pallet sends the `ForwardingPayload::DispatchReport` message back
+//!    to A. The report is also paid by the sovereign account of the chain A;
+//! 5) when the report is dispatched at A, the pallet emits the `ForwardedMessageDispatched` event.
+//!
+//! All other (`ForwardingPayload::Dispatch`) messages are dispatched at B using the regular dispatcher.
+//!
+//! Anyone who is able to send `ForwardingPayload::Forward` messages to B, is able to spend funds of the
+//! sovereign account. And anyone who is able to send `ForwardingPayload::DispatchReport` messages from B
+//! to A, is able to forge dispatch reports. So both sides of the lane must filter senders of such messages
+//! using the `is_allowed_sender` function: chain A must do that in its `LaneMessageVerifier` and chain B
+//! must do that before dispatching the message (if the message origin is known to the dispatcher).
+//!
+//! Millau and Rialto runtimes are using the pallet to dispatch messages of the dedicated `frwd` lane. The
+//! `ForwardingPayload` is carried by the call of regular call dispatch messages there, so the message origin
+//! is known to both sides of the lane.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_message_dispatch::{ForwardedMessage, ForwardedMessageDispatchReport, ForwardingPayload};
+use bp_messages::{
+	source_chain::{MessageOwner, MessageOwnerId, MessagesBridge, Sender},
+	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
+	LaneId, MessageNonce,
+};
+use bp_runtime::{
+	derive_account_id,
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	ChainId, SourceAccount,
+};
+use codec::{Decode, DecodeAll};
+use frame_support::weights::Weight;
+use sp_core::H256;
+use sp_runtime::traits::Convert;
+
+#[cfg(test)]
+mod mock;
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Id of the bridge with the Source chain (the chain that is sending messages to be forwarded).
+		type SourceChainId: Get<ChainId>;
+		/// Converter from raw hash (derived from Source chain Root account) to This chain account.
+		type SourceChainAccountIdConverter: Convert<H256, Self::AccountId>;
+
+		/// Type of delivery and dispatch fee of inbound messages.
+		type InboundMessageFee: Clone;
+		/// Dispatcher of `ForwardingPayload::Dispatch` messages.
+		type MessageDispatch: MessageDispatch<Self::AccountId, Self::InboundMessageFee>;
+		/// Weight of forwarding single message to the next chain of the route.
+		type ForwardMessageWeight: Get<Weight>;
+
+		/// The identifier of this pallet instance as an owner of forwarded messages.
+		///
+		/// Must be unique among all owners of messages, sent using `NextHopBridge`.
+		type OutboundMessagesOwnerId: Get<MessageOwnerId>;
+		/// Type of delivery and dispatch fee of outbound (forwarded and report) messages.
+		type OutboundMessageFee: Parameter;
+		/// Payload of messages, sent to the next chain of the route.
+		type NextHopPayload: Decode;
+		/// Messages bridge with the next chain of the route.
+		type NextHopBridge: MessagesBridge<Self::AccountId, Self::OutboundMessageFee, Self::NextHopPayload>;
+
+		/// Payload of messages, sent back to the Source chain.
+		type ReportPayload;
+		/// Converter from the dispatch report to the payload of message, sent back to the Source chain.
+		type ReportPayloadConverter: Convert<ForwardedMessageDispatchReport, Self::ReportPayload>;
+		/// Messages bridge with the Source chain.
+		type ReportBridge: MessagesBridge<Self::AccountId, Self::OutboundMessageFee, Self::ReportPayload>;
+		/// Delivery and dispatch fee of single dispatch report message.
+		type ReportDeliveryAndDispatchFee: Get<Self::OutboundMessageFee>;
+		/// Weight of sending single dispatch report message.
+		type SendReportWeight: Get<Weight>;
+	}
+
+	/// Payload of messages, dispatched by the pallet.
+	pub type ForwardingPayloadOf<T, I> = ForwardingPayload<
+		<<T as Config<I>>::MessageDispatch as MessageDispatch<
+			<T as frame_system::Config>::AccountId,
+			<T as Config<I>>::InboundMessageFee,
+		>>::DispatchPayload,
+		<T as Config<I>>::OutboundMessageFee,
+	>;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_idle(_block: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			send_dispatch_reports::<T, I>(remaining_weight)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Message has been forwarded to the next chain of the route.
+		///
+		/// The payload is the lane and nonce of the inbound message and the lane and nonce
+		/// of the forwarded message.
+		MessageForwarded(LaneId, MessageNonce, LaneId, MessageNonce),
+		/// Message has not been forwarded to the next chain of the route.
+		///
+		/// The payload is the lane and nonce of the inbound message.
+		MessageForwardingFailed(LaneId, MessageNonce),
+		/// Dispatch result of the forwarded message has been sent back to the Source chain.
+		///
+		/// The payload is the lane and nonce of the original message and its dispatch result.
+		DispatchReportSent(LaneId, MessageNonce, bool),
+		/// The message that we have sent has been forwarded and dispatched at the next chain
+		/// of the route.
+		///
+		/// The payload is the lane and nonce of the message and its dispatch result.
+		ForwardedMessageDispatched(LaneId, MessageNonce, bool),
+	}
+
+	/// Origins of forwarded messages.
+	///
+	/// Maps lane and nonce of the forwarded message to the lane and nonce of the original message.
+	#[pallet::storage]
+	pub type ForwardedMessages<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, (LaneId, MessageNonce), (LaneId, MessageNonce)>;
+
+	/// Dispatch results of forwarded messages that are not yet reported to the Source chain.
+	///
+	/// Maps lane and nonce of the forwarded message to its dispatch result.
+	#[pallet::storage]
+	pub type PendingDispatchReports<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, (LaneId, MessageNonce), bool>;
+
+	impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::InboundMessageFee> for Pallet<T, I>
+	where
+		<T::MessageDispatch as MessageDispatch<T::AccountId, T::InboundMessageFee>>::DispatchPayload: Clone,
+	{
+		type DispatchPayload = ForwardingPayloadOf<T, I>;
+
+		fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, T::InboundMessageFee>) -> Weight {
+			match message.data.payload {
+				Ok(ForwardingPayload::Dispatch(ref payload)) => T::MessageDispatch::dispatch_weight(&DispatchMessage {
+					key: message.key.clone(),
+					data: DispatchMessageData {
+						payload: Ok(payload.clone()),
+						fee: message.data.fee.clone(),
+					},
+				}),
+				Ok(ForwardingPayload::Forward(_)) => T::ForwardMessageWeight::get(),
+				Ok(ForwardingPayload::DispatchReport(_)) => T::DbWeight::get().writes(1),
+				Err(_) => 0,
+			}
+		}
+
+		fn dispatch_fee_payment(
+			message: &DispatchMessage<Self::DispatchPayload, T::InboundMessageFee>,
+		) -> DispatchFeePayment {
+			match message.data.payload {
+				Ok(ForwardingPayload::Dispatch(ref payload)) => {
+					T::MessageDispatch::dispatch_fee_payment(&DispatchMessage {
+						key: message.key.clone(),
+						data: DispatchMessageData {
+							payload: Ok(payload.clone()),
+							fee: message.data.fee.clone(),
+						},
+					})
+				}
+				_ => DispatchFeePayment::AtSourceChain,
+			}
+		}
+
+		fn dispatch(
+			relayer_account: &T::AccountId,
+			message: DispatchMessage<Self::DispatchPayload, T::InboundMessageFee>,
+		) -> MessageDispatchResult {
+			let DispatchMessage { key, data } = message;
+			match data.payload {
+				Ok(ForwardingPayload::Dispatch(payload)) => T::MessageDispatch::dispatch(
+					relayer_account,
+					DispatchMessage {
+						key,
+						data: DispatchMessageData {
+							payload: Ok(payload),
+							fee: data.fee,
+						},
+					},
+				),
+				Ok(ForwardingPayload::Forward(forwarded_message)) => {
+					let dispatch_result = forward_message::<T, I>(key.lane_id, key.nonce, forwarded_message);
+					MessageDispatchResult {
+						dispatch_result,
+						unspent_weight: if dispatch_result {
+							0
+						} else {
+							T::ForwardMessageWeight::get()
+						},
+						dispatch_fee_paid_during_dispatch: false,
+					}
+				}
+				Ok(ForwardingPayload::DispatchReport(report)) => {
+					Self::deposit_event(Event::ForwardedMessageDispatched(
+						report.lane,
+						report.nonce,
+						report.dispatch_result,
+					));
+					MessageDispatchResult {
+						dispatch_result: true,
+						unspent_weight: 0,
+						dispatch_fee_paid_during_dispatch: false,
+					}
+				}
+				Err(err) => {
+					log::trace!(
+						target: "runtime::bridge-forwarding",
+						"Failed to decode payload of message {:?}/{}: {:?}",
+						key.lane_id,
+						key.nonce,
+						err,
+					);
+
+					MessageDispatchResult {
+						dispatch_result: false,
+						unspent_weight: 0,
+						dispatch_fee_paid_during_dispatch: false,
+					}
+				}
+			}
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> MessageOwner for Pallet<T, I> {
+		fn owner_id() -> MessageOwnerId {
+			T::OutboundMessagesOwnerId::get()
+		}
+
+		fn on_message_dispatched(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) -> Weight {
+			PendingDispatchReports::<T, I>::insert((*lane, nonce), dispatch_result);
+			T::DbWeight::get().writes(1)
+		}
+	}
+
+	/// Returns sovereign account of the Source chain at This chain.
+	///
+	/// The account pays for delivery and dispatch of all messages, sent by the pallet.
+	pub fn source_chain_sovereign_account<T: Config<I>, I: 'static>() -> T::AccountId {
+		T::SourceChainAccountIdConverter::convert(derive_account_id::<T::AccountId>(
+			T::SourceChainId::get(),
+			SourceAccount::Root,
+		))
+	}
+
+	/// Forward message to the next chain of the route.
+	///
+	/// Returns true if the message has been forwarded.
+	fn forward_message<T: Config<I>, I: 'static>(
+		inbound_lane: LaneId,
+		inbound_nonce: MessageNonce,
+		message: ForwardedMessage<T::OutboundMessageFee>,
+	) -> bool {
+		let payload = match T::NextHopPayload::decode_all(&message.payload[..]) {
+			Ok(payload) => payload,
+			Err(err) => {
+				log::trace!(
+					target: "runtime::bridge-forwarding",
+					"Failed to decode payload of forwarded message {:?}/{}: {:?}",
+					inbound_lane,
+					inbound_nonce,
+					err,
+				);
+
+				Pallet::<T, I>::deposit_event(Event::MessageForwardingFailed(inbound_lane, inbound_nonce));
+				return false;
+			}
+		};
+
+		let send_message_result = T::NextHopBridge::send_owned_message(
+			T::OutboundMessagesOwnerId::get(),
+			Sender::Signed(source_chain_sovereign_account::<T, I>()),
+			message.lane,
+			payload,
+			message.delivery_and_dispatch_fee,
+		);
+		let outbound_nonce = match send_message_result {
+			Ok(outbound_nonce) => outbound_nonce,
+			Err(err) => {
+				log::trace!(
+					target: "runtime::bridge-forwarding",
+					"Failed to forward message {:?}/{} to lane {:?}: {:?}",
+					inbound_lane,
+					inbound_nonce,
+					message.lane,
+					err,
+				);
+
+				Pallet::<T, I>::deposit_event(Event::MessageForwardingFailed(inbound_lane, inbound_nonce));
+				return false;
+			}
+		};
+
+		ForwardedMessages::<T, I>::insert((message.lane, outbound_nonce), (inbound_lane, inbound_nonce));
+		Pallet::<T, I>::deposit_event(Event::MessageForwarded(
+			inbound_lane,
+			inbound_nonce,
+			message.lane,
+			outbound_nonce,
+		));
+
+		true
+	}
+
+	/// Send dispatch results of forwarded messages back to the Source chain.
+	///
+	/// The pending report is only removed after it has been sent. If the report can't be sent, we stop
+	/// sending reports and retry in next blocks.
+	///
+	/// Returns weight, consumed by the call.
+	fn send_dispatch_reports<T: Config<I>, I: 'static>(remaining_weight: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		// every report requires: reading and removing pending report + reading and removing
+		// forwarded message origin
+		let single_report_weight = T::SendReportWeight::get().saturating_add(db_weight.reads_writes(2, 2));
+		// we'll always read first entry of the `PendingDispatchReports` map
+		let mut weight_used = db_weight.reads(1);
+		if remaining_weight < weight_used {
+			return 0;
+		}
+
+		while remaining_weight.saturating_sub(weight_used) >= single_report_weight {
+			let ((lane, nonce), dispatch_result) = match PendingDispatchReports::<T, I>::iter().next() {
+				Some(pending_report) => pending_report,
+				None => break,
+			};
+
+			weight_used = weight_used.saturating_add(single_report_weight);
+
+			let (origin_lane, origin_nonce) = match ForwardedMessages::<T, I>::get((lane, nonce)) {
+				Some(origin) => origin,
+				None => {
+					// there's no one to report to
+					PendingDispatchReports::<T, I>::remove((lane, nonce));
+					continue;
+				}
+			};
+
+			let send_message_result = T::ReportBridge::send_message(
+				Sender::Signed(source_chain_sovereign_account::<T, I>()),
+				origin_lane,
+				T::ReportPayloadConverter::convert(ForwardedMessageDispatchReport {
+					lane: origin_lane,
+					nonce: origin_nonce,
+					dispatch_result,
+				}),
+				T::ReportDeliveryAndDispatchFee::get(),
+			);
+			match send_message_result {
+				Ok(_) => {
+					PendingDispatchReports::<T, I>::remove((lane, nonce));
+					ForwardedMessages::<T, I>::remove((lane, nonce));
+					Pallet::<T, I>::deposit_event(Event::DispatchReportSent(
+						origin_lane,
+						origin_nonce,
+						dispatch_result,
+					));
+				}
+				Err(err) => {
+					log::error!(
+						target: "runtime::bridge-forwarding",
+						"Failed to send dispatch report of message {:?}/{} to the Source chain: {:?}. Will retry later",
+						origin_lane,
+						origin_nonce,
+						err,
+					);
+
+					// all reports are sent using the same account and the same bridge, so it is likely
+					// that the next report will fail too
+					break;
+				}
+			}
+		}
+
+		weight_used
+	}
+
+	/// Returns true if `sender` is allowed to send given forwarding payload.
+	///
+	/// The `sovereign_account` is the account that is used by the forwarding pallet of the message
+	/// target chain to forward messages and to send dispatch reports. Only this account and the Root
+	/// account are allowed to send `ForwardingPayload::Forward` messages (the former is used when the
+	/// route has more than one intermediate chain). Only this account is allowed to send
+	/// `ForwardingPayload::DispatchReport` messages. Anyone is allowed to send regular
+	/// `ForwardingPayload::Dispatch` messages.
+	pub fn is_allowed_sender<AccountId: PartialEq, DispatchPayload, Balance>(
+		sender: &Sender<AccountId>,
+		sovereign_account: &AccountId,
+		payload: &ForwardingPayload<DispatchPayload, Balance>,
+	) -> bool {
+		let is_sovereign_account = matches!(*sender, Sender::Signed(ref sender) if sender == sovereign_account);
+		match *payload {
+			ForwardingPayload::Dispatch(_) => true,
+			ForwardingPayload::Forward(_) => is_sovereign_account || matches!(*sender, Sender::Root),
+			ForwardingPayload::DispatchReport(_) => is_sovereign_account,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::*;
+	use codec::Encode;
+	use frame_support::traits::Hooks;
+	use frame_system::{EventRecord, Phase};
+
+	fn forwarded_message(payload: Vec<u8>) -> ForwardingPayloadOf<TestRuntime, ()> {
+		ForwardingPayload::Forward(ForwardedMessage {
+			lane: NEXT_HOP_LANE_ID,
+			payload,
+			delivery_and_dispatch_fee: FORWARDED_MESSAGE_FEE,
+		})
+	}
+
+	fn dispatch_message(
+		nonce: MessageNonce,
+		payload: ForwardingPayloadOf<TestRuntime, ()>,
+	) -> DispatchMessage<ForwardingPayloadOf<TestRuntime, ()>, Balance> {
+		DispatchMessage {
+			key: bp_messages::MessageKey {
+				lane_id: SOURCE_LANE_ID,
+				nonce,
+			},
+			data: DispatchMessageData {
+				payload: Ok(payload),
+				fee: 0,
+			},
+		}
+	}
+
+	fn dispatch(nonce: MessageNonce, payload: ForwardingPayloadOf<TestRuntime, ()>) -> MessageDispatchResult {
+		<Pallet<TestRuntime> as MessageDispatch<AccountId, Balance>>::dispatch(
+			&RELAYER_ACCOUNT,
+			dispatch_message(nonce, payload),
+		)
+	}
+
+	fn last_event() -> Event {
+		frame_system::Pallet::<TestRuntime>::events().pop().unwrap().event
+	}
+
+	#[test]
+	fn message_is_forwarded_from_sovereign_account() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+			frame_system::Pallet::<TestRuntime>::reset_events();
+
+			let result = dispatch(1, forwarded_message(NEXT_HOP_PAYLOAD.encode()));
+			assert!(result.dispatch_result);
+			assert_eq!(result.unspent_weight, 0);
+
+			assert_eq!(
+				TestNextHopBridge::sent_message(),
+				Some((
+					OutboundMessagesOwnerId::get(),
+					Sender::Signed(source_chain_sovereign_account::<TestRuntime, ()>()),
+					NEXT_HOP_LANE_ID,
+					NEXT_HOP_PAYLOAD,
+					FORWARDED_MESSAGE_FEE,
+				)),
+			);
+			assert_eq!(
+				ForwardedMessages::<TestRuntime>::get((NEXT_HOP_LANE_ID, NEXT_HOP_MESSAGE_NONCE)),
+				Some((SOURCE_LANE_ID, 1)),
+			);
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Forwarding(crate::Event::MessageForwarded(
+						SOURCE_LANE_ID,
+						1,
+						NEXT_HOP_LANE_ID,
+						NEXT_HOP_MESSAGE_NONCE,
+					)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn dispatch_fee_of_forwarded_message_is_paid_at_source_chain() {
+		run_test(|| {
+			let message = dispatch_message(1, forwarded_message(NEXT_HOP_PAYLOAD.encode()));
+			assert_eq!(
+				<Pallet<TestRuntime> as MessageDispatch<AccountId, Balance>>::dispatch_fee_payment(&message),
+				DispatchFeePayment::AtSourceChain,
+			);
+		});
+	}
+
+	#[test]
+	fn message_with_invalid_next_hop_payload_is_not_forwarded() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			let result = dispatch(1, forwarded_message(vec![42]));
+			assert!(!result.dispatch_result);
+			assert_eq!(result.unspent_weight, ForwardMessageWeight::get());
+			assert_eq!(TestNextHopBridge::sent_message(), None);
+			assert_eq!(
+				last_event(),
+				Event::Forwarding(crate::Event::MessageForwardingFailed(SOURCE_LANE_ID, 1)),
+			);
+		});
+	}
+
+	#[test]
+	fn message_rejected_by_next_hop_bridge_is_not_forwarded() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			let result = dispatch(1, forwarded_message(REJECTED_NEXT_HOP_PAYLOAD.encode()));
+			assert!(!result.dispatch_result);
+			assert_eq!(result.unspent_weight, ForwardMessageWeight::get());
+			assert_eq!(ForwardedMessages::<TestRuntime>::iter().count(), 0,);
+			assert_eq!(
+				last_event(),
+				Event::Forwarding(crate::Event::MessageForwardingFailed(SOURCE_LANE_ID, 1)),
+			);
+		});
+	}
+
+	#[test]
+	fn regular_message_is_dispatched_by_inner_dispatcher() {
+		run_test(|| {
+			let message = dispatch_message(1, ForwardingPayload::Dispatch(DISPATCH_WEIGHT));
+			assert_eq!(
+				<Pallet<TestRuntime> as MessageDispatch<AccountId, Balance>>::dispatch_weight(&message),
+				DISPATCH_WEIGHT,
+			);
+			assert_eq!(
+				<Pallet<TestRuntime> as MessageDispatch<AccountId, Balance>>::dispatch_fee_payment(&message),
+				DispatchFeePayment::AtTargetChain,
+			);
+
+			let result = dispatch(1, ForwardingPayload::Dispatch(DISPATCH_WEIGHT));
+			assert!(result.dispatch_result);
+			assert_eq!(TestMessageDispatch::dispatched_payload(), Some(DISPATCH_WEIGHT));
+			assert_eq!(TestNextHopBridge::sent_message(), None);
+		});
+	}
+
+	#[test]
+	fn dispatch_result_is_reported_back_to_source_chain() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			dispatch(1, forwarded_message(NEXT_HOP_PAYLOAD.encode()));
+			let weight = <Pallet<TestRuntime> as MessageOwner>::on_message_dispatched(
+				&NEXT_HOP_LANE_ID,
+				NEXT_HOP_MESSAGE_NONCE,
+				false,
+			);
+			assert_eq!(weight, DbWeight::get().writes(1));
+
+			let db_weight = DbWeight::get();
+			let single_report_weight = SendReportWeight::get() + db_weight.reads_writes(2, 2);
+			assert_eq!(
+				Pallet::<TestRuntime>::on_idle(1, Weight::MAX),
+				db_weight.reads(1) + single_report_weight,
+			);
+
+			assert_eq!(
+				TestReportBridge::sent_message(),
+				Some((
+					Sender::Signed(source_chain_sovereign_account::<TestRuntime, ()>()),
+					SOURCE_LANE_ID,
+					ForwardedMessageDispatchReport {
+						lane: SOURCE_LANE_ID,
+						nonce: 1,
+						dispatch_result: false,
+					},
+					REPORT_FEE,
+				)),
+			);
+			assert_eq!(
+				last_event(),
+				Event::Forwarding(crate::Event::DispatchReportSent(SOURCE_LANE_ID, 1, false)),
+			);
+			assert_eq!(PendingDispatchReports::<TestRuntime>::iter().count(), 0);
+			assert_eq!(ForwardedMessages::<TestRuntime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn dispatch_reports_are_not_sent_when_there_is_not_enough_weight() {
+		run_test(|| {
+			dispatch(1, forwarded_message(NEXT_HOP_PAYLOAD.encode()));
+			<Pallet<TestRuntime> as MessageOwner>::on_message_dispatched(
+				&NEXT_HOP_LANE_ID,
+				NEXT_HOP_MESSAGE_NONCE,
+				true,
+			);
+
+			let db_weight = DbWeight::get();
+			let single_report_weight = SendReportWeight::get() + db_weight.reads_writes(2, 2);
+			assert_eq!(
+				Pallet::<TestRuntime>::on_idle(1, db_weight.reads(1) + single_report_weight - 1),
+				db_weight.reads(1),
+			);
+			assert_eq!(TestReportBridge::sent_message(), None);
+			assert_eq!(PendingDispatchReports::<TestRuntime>::iter().count(), 1);
+		});
+	}
+
+	#[test]
+	fn dispatch_report_is_kept_when_it_is_not_sent() {
+		run_test(|| {
+			dispatch(1, forwarded_message(NEXT_HOP_PAYLOAD.encode()));
+			<Pallet<TestRuntime> as MessageOwner>::on_message_dispatched(
+				&NEXT_HOP_LANE_ID,
+				NEXT_HOP_MESSAGE_NONCE,
+				true,
+			);
+
+			TestReportBridge::set_rejects_reports(true);
+			Pallet::<TestRuntime>::on_idle(1, Weight::MAX);
+			assert_eq!(TestReportBridge::sent_message(), None);
+			assert_eq!(
+				PendingDispatchReports::<TestRuntime>::get((NEXT_HOP_LANE_ID, NEXT_HOP_MESSAGE_NONCE)),
+				Some(true),
+			);
+			assert_eq!(
+				ForwardedMessages::<TestRuntime>::get((NEXT_HOP_LANE_ID, NEXT_HOP_MESSAGE_NONCE)),
+				Some((SOURCE_LANE_ID, 1)),
+			);
+
+			TestReportBridge::set_rejects_reports(false);
+			Pallet::<TestRuntime>::on_idle(2, Weight::MAX);
+			assert!(TestReportBridge::sent_message().is_some());
+			assert_eq!(PendingDispatchReports::<TestRuntime>::iter().count(), 0);
+			assert_eq!(ForwardedMessages::<TestRuntime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn dispatch_report_without_origin_is_dropped() {
+		run_test(|| {
+			<Pallet<TestRuntime> as MessageOwner>::on_message_dispatched(
+				&NEXT_HOP_LANE_ID,
+				NEXT_HOP_MESSAGE_NONCE,
+				true,
+			);
+
+			Pallet::<TestRuntime>::on_idle(1, Weight::MAX);
+			assert_eq!(TestReportBridge::sent_message(), None);
+			assert_eq!(PendingDispatchReports::<TestRuntime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn only_privileged_senders_are_allowed_to_send_forwarding_messages() {
+		run_test(|| {
+			let sovereign_account = source_chain_sovereign_account::<TestRuntime, ()>();
+			let regular_account = sovereign_account.wrapping_add(1);
+			let report = ForwardingPayloadOf::<TestRuntime, ()>::DispatchReport(ForwardedMessageDispatchReport {
+				lane: SOURCE_LANE_ID,
+				nonce: 1,
+				dispatch_result: true,
+			});
+			let forward = forwarded_message(NEXT_HOP_PAYLOAD.encode());
+			let regular = ForwardingPayloadOf::<TestRuntime, ()>::Dispatch(DISPATCH_WEIGHT);
+
+			// regular messages may be sent by anyone
+			assert!(is_allowed_sender(&Sender::Root, &sovereign_account, &regular));
+			assert!(is_allowed_sender(
+				&Sender::Signed(regular_account),
+				&sovereign_account,
+				&regular
+			));
+			assert!(is_allowed_sender(&Sender::None, &sovereign_account, &regular));
+
+			// forwarded messages may only be sent by the Root and the sovereign account
+			assert!(is_allowed_sender(&Sender::Root, &sovereign_account, &forward));
+			assert!(is_allowed_sender(
+				&Sender::Signed(sovereign_account),
+				&sovereign_account,
+				&forward
+			));
+			assert!(!is_allowed_sender(
+				&Sender::Signed(regular_account),
+				&sovereign_account,
+				&forward
+			));
+			assert!(!is_allowed_sender(&Sender::None, &sovereign_account, &forward));
+
+			// dispatch reports may only be sent by the sovereign account
+			assert!(is_allowed_sender(
+				&Sender::Signed(sovereign_account),
+				&sovereign_account,
+				&report
+			));
+			assert!(!is_allowed_sender(&Sender::Root, &sovereign_account, &report));
+			assert!(!is_allowed_sender(
+				&Sender::Signed(regular_account),
+				&sovereign_account,
+				&report
+			));
+			assert!(!is_allowed_sender(&Sender::None, &sovereign_account, &report));
+		});
+	}
+
+	#[test]
+	fn dispatch_report_is_received() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			let result = dispatch(
+				1,
+				ForwardingPayload::DispatchReport(ForwardedMessageDispatchReport {
+					lane: NEXT_HOP_LANE_ID,
+					nonce: 42,
+					dispatch_result: true,
+				}),
+			);
+			assert!(result.dispatch_result);
+			assert_eq!(
+				last_event(),
+				Event::Forwarding(crate::Event::ForwardedMessageDispatched(NEXT_HOP_LANE_ID, 42, true)),
+			);
+		});
+	}
+}
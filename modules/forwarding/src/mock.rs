@@ -0,0 +1,285 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate as pallet_bridge_forwarding;
+
+use bp_message_dispatch::ForwardedMessageDispatchReport;
+use bp_messages::{
+	source_chain::{MessageOwnerId, MessagesBridge, Sender},
+	target_chain::{DispatchMessage, MessageDispatch},
+	LaneId, MessageNonce,
+};
+use bp_runtime::{
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	ChainId,
+};
+use frame_support::weights::{RuntimeDbWeight, Weight};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, Identity, IdentityLookup},
+	Perbill,
+};
+use std::ops::RangeInclusive;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type Block = frame_system::mocking::MockBlock<TestRuntime>;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+/// Payload of messages, sent to the next chain of the route.
+pub type NextHopPayload = u64;
+
+pub const RELAYER_ACCOUNT: AccountId = 1;
+
+pub const SOURCE_LANE_ID: LaneId = *b"srce";
+pub const NEXT_HOP_LANE_ID: LaneId = *b"next";
+
+pub const NEXT_HOP_PAYLOAD: NextHopPayload = 1;
+pub const REJECTED_NEXT_HOP_PAYLOAD: NextHopPayload = 2;
+pub const NEXT_HOP_MESSAGE_NONCE: MessageNonce = 3;
+
+pub const FORWARDED_MESSAGE_FEE: Balance = 4;
+pub const REPORT_FEE: Balance = 5;
+pub const DISPATCH_WEIGHT: Weight = 6;
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Forwarding: pallet_bridge_forwarding::{Pallet, Event<T>},
+	}
+}
+
+frame_support::parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 2 };
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = SubstrateHeader;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = DbWeight;
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+frame_support::parameter_types! {
+	pub const SourceChainId: ChainId = *b"srce";
+	pub const ForwardMessageWeight: Weight = 100;
+	pub const OutboundMessagesOwnerId: MessageOwnerId = *b"frwd";
+	pub const ReportDeliveryAndDispatchFee: Balance = REPORT_FEE;
+	pub const SendReportWeight: Weight = 200;
+}
+
+impl pallet_bridge_forwarding::Config for TestRuntime {
+	type Event = Event;
+
+	type SourceChainId = SourceChainId;
+	type SourceChainAccountIdConverter = TestAccountConverter;
+
+	type InboundMessageFee = Balance;
+	type MessageDispatch = TestMessageDispatch;
+	type ForwardMessageWeight = ForwardMessageWeight;
+
+	type OutboundMessagesOwnerId = OutboundMessagesOwnerId;
+	type OutboundMessageFee = Balance;
+	type NextHopPayload = NextHopPayload;
+	type NextHopBridge = TestNextHopBridge;
+
+	type ReportPayload = ForwardedMessageDispatchReport;
+	type ReportPayloadConverter = Identity;
+	type ReportBridge = TestReportBridge;
+	type ReportDeliveryAndDispatchFee = ReportDeliveryAndDispatchFee;
+	type SendReportWeight = SendReportWeight;
+}
+
+/// Dispatcher of regular messages. The dispatch payload is the dispatch weight of the message.
+///
+/// Dispatch fee of all regular messages is paid at the target chain.
+pub struct TestMessageDispatch;
+
+impl TestMessageDispatch {
+	/// Returns payload of the dispatched message.
+	pub fn dispatched_payload() -> Option<Weight> {
+		frame_support::storage::unhashed::get(b":dispatched-payload:")
+	}
+}
+
+impl MessageDispatch<AccountId, Balance> for TestMessageDispatch {
+	type DispatchPayload = Weight;
+
+	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Balance>) -> Weight {
+		message.data.payload.as_ref().map(|payload| *payload).unwrap_or(0)
+	}
+
+	fn dispatch_fee_payment(_message: &DispatchMessage<Self::DispatchPayload, Balance>) -> DispatchFeePayment {
+		DispatchFeePayment::AtTargetChain
+	}
+
+	fn dispatch(
+		_relayer_account: &AccountId,
+		message: DispatchMessage<Self::DispatchPayload, Balance>,
+	) -> MessageDispatchResult {
+		frame_support::storage::unhashed::put(b":dispatched-payload:", &message.data.payload.unwrap());
+		MessageDispatchResult {
+			dispatch_result: true,
+			unspent_weight: 0,
+			dispatch_fee_paid_during_dispatch: false,
+		}
+	}
+}
+
+/// Messages bridge with the next chain of the route.
+pub struct TestNextHopBridge;
+
+impl TestNextHopBridge {
+	/// Returns the message that has been sent over the bridge.
+	pub fn sent_message() -> Option<(MessageOwnerId, Sender<AccountId>, LaneId, NextHopPayload, Balance)> {
+		frame_support::storage::unhashed::get(b":next-hop-message:")
+	}
+}
+
+impl MessagesBridge<AccountId, Balance, NextHopPayload> for TestNextHopBridge {
+	type Error = ();
+
+	fn send_message(
+		_sender: Sender<AccountId>,
+		_lane: LaneId,
+		_message: NextHopPayload,
+		_delivery_and_dispatch_fee: Balance,
+	) -> Result<MessageNonce, Self::Error> {
+		unreachable!("forwarding pallet always forwards owned messages")
+	}
+
+	fn send_messages(
+		_sender: Sender<AccountId>,
+		_lane: LaneId,
+		_messages: Vec<(NextHopPayload, Balance)>,
+	) -> Result<RangeInclusive<MessageNonce>, Self::Error> {
+		unreachable!("forwarding pallet never sends messages in batches")
+	}
+
+	fn send_owned_message(
+		owner: MessageOwnerId,
+		sender: Sender<AccountId>,
+		lane: LaneId,
+		message: NextHopPayload,
+		delivery_and_dispatch_fee: Balance,
+	) -> Result<MessageNonce, Self::Error> {
+		if message == REJECTED_NEXT_HOP_PAYLOAD {
+			return Err(());
+		}
+
+		frame_support::storage::unhashed::put(
+			b":next-hop-message:",
+			&(owner, sender, lane, message, delivery_and_dispatch_fee),
+		);
+		Ok(NEXT_HOP_MESSAGE_NONCE)
+	}
+}
+
+/// Messages bridge with the Source chain.
+pub struct TestReportBridge;
+
+impl TestReportBridge {
+	/// Returns the report message that has been sent over the bridge.
+	pub fn sent_message() -> Option<(Sender<AccountId>, LaneId, ForwardedMessageDispatchReport, Balance)> {
+		frame_support::storage::unhashed::get(b":report-message:")
+	}
+
+	/// Make the bridge reject (or accept) all further reports.
+	pub fn set_rejects_reports(rejects_reports: bool) {
+		frame_support::storage::unhashed::put(b":reject-reports:", &rejects_reports);
+	}
+}
+
+impl MessagesBridge<AccountId, Balance, ForwardedMessageDispatchReport> for TestReportBridge {
+	type Error = ();
+
+	fn send_message(
+		sender: Sender<AccountId>,
+		lane: LaneId,
+		message: ForwardedMessageDispatchReport,
+		delivery_and_dispatch_fee: Balance,
+	) -> Result<MessageNonce, Self::Error> {
+		if frame_support::storage::unhashed::get_or_default(b":reject-reports:") {
+			return Err(());
+		}
+
+		frame_support::storage::unhashed::put(b":report-message:", &(sender, lane, message, delivery_and_dispatch_fee));
+		Ok(1)
+	}
+
+	fn send_messages(
+		_sender: Sender<AccountId>,
+		_lane: LaneId,
+		_messages: Vec<(ForwardedMessageDispatchReport, Balance)>,
+	) -> Result<RangeInclusive<MessageNonce>, Self::Error> {
+		unreachable!("forwarding pallet never sends reports in batches")
+	}
+
+	fn send_owned_message(
+		_owner: MessageOwnerId,
+		_sender: Sender<AccountId>,
+		_lane: LaneId,
+		_message: ForwardedMessageDispatchReport,
+		_delivery_and_dispatch_fee: Balance,
+	) -> Result<MessageNonce, Self::Error> {
+		unreachable!("forwarding pallet never sends owned reports")
+	}
+}
+
+pub struct TestAccountConverter;
+
+impl sp_runtime::traits::Convert<H256, AccountId> for TestAccountConverter {
+	fn convert(hash: H256) -> AccountId {
+		hash.to_low_u64_ne()
+	}
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(test)
+}
@@ -21,6 +21,8 @@
 //! This pallet achieves this by trustlessly verifying GRANDPA finality proofs on-chain. Once
 //! verified, finalized headers are stored in the pallet, thereby creating a sparse header chain.
 //! This sparse header chain can be used as a source of truth for other higher-level applications.
+//! E.g. the pallet implements `InclusionProofVerifier`, so it may be used to verify that the
+//! bridged chain transaction has been included into the finalized block.
 //!
 //! The pallet is responsible for tracking GRANDPA validator set hand-offs. We only import headers
 //! with justifications signed by the current validator set we know of. The header is inspected for
@@ -35,18 +37,21 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 // Runtime-generated enums
 #![allow(clippy::large_enum_variant)]
+// Generated by `#[pallet::event]`
+#![allow(clippy::unused_unit)]
 
 use crate::weights::WeightInfo;
 
 use bp_header_chain::justification::GrandpaJustification;
 use bp_header_chain::InitializationData;
 use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf};
+use codec::{Compact, Encode};
 use finality_grandpa::voter_set::VoterSet;
 use frame_support::{ensure, fail};
 use frame_system::{ensure_signed, RawOrigin};
 use sp_finality_grandpa::{ConsensusLog, GRANDPA_ENGINE_ID};
-use sp_runtime::traits::{BadOrigin, Header as HeaderT, Zero};
-use sp_std::convert::TryInto;
+use sp_runtime::traits::{BadOrigin, Header as HeaderT, Saturating, Zero};
+use sp_std::{convert::TryInto, vec::Vec};
 
 #[cfg(test)]
 mod mock;
@@ -54,6 +59,8 @@ mod mock;
 /// Pallet containing weights for this pallet.
 pub mod weights;
 
+pub mod migration;
+
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 
@@ -69,14 +76,20 @@ pub type BridgedBlockHasher<T, I> = HasherOf<<T as Config<I>>::BridgedChain>;
 /// Header of the bridged chain.
 pub type BridgedHeader<T, I> = HeaderOf<<T as Config<I>>::BridgedChain>;
 
+/// Maximal number of headers that are pruned because of their age when new header is imported.
+pub const MAX_AGED_HEADERS_PRUNED_PER_IMPORT: u32 = 4;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
 	use frame_system::pallet_prelude::*;
 
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
 		/// The chain we are bridging to here.
 		type BridgedChain: Chain;
 
@@ -97,11 +110,24 @@ pub mod pallet {
 		#[pallet::constant]
 		type HeadersToKeep: Get<u32>;
 
+		/// Maximal age of finalized headers to keep in the storage, in bridged chain blocks.
+		///
+		/// If it is `Some(age)`, then headers with numbers that are less than
+		/// `best_finalized_number - age` are pruned, even if there are less than `HeadersToKeep`
+		/// headers in the storage. If it is `None`, headers are only pruned when their count
+		/// exceeds `HeadersToKeep`.
+		#[pallet::constant]
+		type MaxHeaderAge: Get<Option<BlockNumberOf<Self::BridgedChain>>>;
+
 		/// Weights gathered through benchmarking.
 		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
 	#[pallet::hooks]
@@ -127,7 +153,7 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::submit_finality_proof(
 			justification.commit.precommits.len().try_into().unwrap_or(u32::MAX),
 			justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
-		))]
+		).saturating_add(headers_pruning_weight::<T, I>()))]
 		pub fn submit_finality_proof(
 			origin: OriginFor<T>,
 			finality_target: BridgedHeader<T, I>,
@@ -245,6 +271,55 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Forcibly reset the pallet to the new trusted header and authority set.
+		///
+		/// This is the way to recover the bridge when it is stuck - e.g. when the justification
+		/// of the header that enacts the authority set change can't be found. Unlike
+		/// `initialize`, this may be called when the pallet is already initialized. The best
+		/// finalized header, current authority set and initial header hash are replaced. If
+		/// `prune_imported_headers` is true, all previously imported headers are removed from
+		/// the storage. Otherwise they're left there and will be pruned eventually, when new
+		/// headers are imported.
+		///
+		/// May only be called either by root, or by `PalletOwner`. The same considerations as
+		/// for the `initialize` call apply here - the passed data must be valid.
+		#[pallet::weight((
+			force_reinitialize_weight::<T, I>(*prune_imported_headers),
+			DispatchClass::Operational,
+		))]
+		pub fn force_reinitialize(
+			origin: OriginFor<T>,
+			init_data: super::InitializationData<BridgedHeader<T, I>>,
+			prune_imported_headers: bool,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+
+			if prune_imported_headers {
+				prune_all_headers::<T, I>();
+			}
+
+			let (new_hash, new_set_id) = (init_data.header.hash(), init_data.set_id);
+			initialize_bridge::<T, I>(init_data.clone());
+
+			log::warn!(
+				target: "runtime::bridge-grandpa",
+				"Pallet has been forcibly re-initialized with the following parameters: {:?}",
+				init_data
+			);
+			Self::deposit_event(Event::ForceReinitialized(new_hash, new_set_id));
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(BridgedBlockHash<T, I> = "BridgedBlockHash")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// The pallet has been forcibly re-initialized with the new header (hash) and the new
+		/// authority set (id).
+		ForceReinitialized(BridgedBlockHash<T, I>, sp_finality_grandpa::SetId),
 	}
 
 	/// The current number of requests which have written to storage.
@@ -275,6 +350,20 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
 
+	/// Number of occupied ring buffer entries.
+	///
+	/// Occupied entries always precede the `ImportedHashesPointer` position, so the oldest
+	/// imported hash is stored `ImportedHashesCount` entries before the pointer.
+	#[pallet::storage]
+	pub(super) type ImportedHashesCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Hashes of imported headers, indexed by header numbers.
+	///
+	/// Only headers that are still in the `ImportedHeaders` map are referenced here.
+	#[pallet::storage]
+	pub(super) type ImportedHashesByNumber<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>>;
+
 	/// Headers which have been imported into the pallet.
 	#[pallet::storage]
 	pub(super) type ImportedHeaders<T: Config<I>, I: 'static = ()> =
@@ -442,17 +531,126 @@ pub mod pallet {
 	/// Note this function solely takes care of updating the storage and pruning old entries,
 	/// but does not verify the validity of such import.
 	pub(crate) fn insert_header<T: Config<I>, I: 'static>(header: BridgedHeader<T, I>, hash: BridgedBlockHash<T, I>) {
+		let headers_to_keep = T::HeadersToKeep::get();
+		let number = *header.number();
 		let index = <ImportedHashesPointer<T, I>>::get();
 		let pruning = <ImportedHashes<T, I>>::try_get(index);
+
+		// Remove old header before inserting the new one, so that we won't remove the new
+		// header from the number index if both headers have the same number.
+		if let Ok(pruned_hash) = pruning {
+			if pruned_hash != hash {
+				log::debug!(target: "runtime::bridge-grandpa", "Pruning old header: {:?}.", pruned_hash);
+				prune_header::<T, I>(pruned_hash);
+			}
+		}
+
 		<BestFinalized<T, I>>::put(hash);
 		<ImportedHeaders<T, I>>::insert(hash, header);
 		<ImportedHashes<T, I>>::insert(index, hash);
+		<ImportedHashesByNumber<T, I>>::insert(number, hash);
+
+		// Update ring buffer pointer and the number of occupied entries.
+		<ImportedHashesPointer<T, I>>::put((index + 1) % headers_to_keep);
+		<ImportedHashesCount<T, I>>::mutate(|count| {
+			*count = sp_std::cmp::min(count.saturating_add(1), headers_to_keep)
+		});
+
+		prune_aged_headers::<T, I>(number);
+	}
+
+	/// Remove header with given hash from the `ImportedHeaders` map and from the number index.
+	///
+	/// The ring buffer entry that is referencing the header must be updated by the caller.
+	fn prune_header<T: Config<I>, I: 'static>(hash: BridgedBlockHash<T, I>) {
+		if let Some(header) = <ImportedHeaders<T, I>>::take(hash) {
+			<ImportedHashesByNumber<T, I>>::mutate_exists(*header.number(), |indexed_hash| {
+				if *indexed_hash == Some(hash) {
+					*indexed_hash = None;
+				}
+			});
+		}
+	}
+
+	/// Prune oldest headers that are older than `MaxHeaderAge` blocks.
+	///
+	/// At most `MAX_AGED_HEADERS_PRUNED_PER_IMPORT` headers are pruned by this function. Remaining
+	/// aged headers will be pruned when next headers are imported.
+	fn prune_aged_headers<T: Config<I>, I: 'static>(best_finalized_number: BridgedBlockNumber<T, I>) {
+		let max_header_age = match T::MaxHeaderAge::get() {
+			Some(max_header_age) => max_header_age,
+			None => return,
+		};
+		let oldest_number_to_keep = best_finalized_number.saturating_sub(max_header_age);
+
+		let headers_to_keep = T::HeadersToKeep::get();
+		let pointer = <ImportedHashesPointer<T, I>>::get();
+		let mut count = <ImportedHashesCount<T, I>>::get();
+		let mut pruned = 0;
+		// we never prune the most recently imported header
+		while count > 1 && pruned < MAX_AGED_HEADERS_PRUNED_PER_IMPORT {
+			let oldest_index = ring_buffer_index_before(pointer, count, headers_to_keep);
+			let oldest_number = <ImportedHashes<T, I>>::get(oldest_index)
+				.and_then(|hash| <ImportedHeaders<T, I>>::get(hash).map(|header| (hash, *header.number())));
+			match oldest_number {
+				Some((_, number)) if number >= oldest_number_to_keep => break,
+				Some((hash, _)) => {
+					log::debug!(target: "runtime::bridge-grandpa", "Pruning aged header: {:?}.", hash);
+					prune_header::<T, I>(hash);
+				}
+				None => (),
+			}
+
+			<ImportedHashes<T, I>>::remove(oldest_index);
+			count -= 1;
+			pruned += 1;
+		}
+
+		<ImportedHashesCount<T, I>>::put(count);
+	}
 
-		// Update ring buffer pointer and remove old header.
-		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
-		if let Ok(hash) = pruning {
-			log::debug!(target: "runtime::bridge-grandpa", "Pruning old header: {:?}.", hash);
-			<ImportedHeaders<T, I>>::remove(hash);
+	/// Remove all imported headers from the storage and reset the ring buffer.
+	pub(crate) fn prune_all_headers<T: Config<I>, I: 'static>() {
+		for index in 0..T::HeadersToKeep::get() {
+			if let Ok(hash) = <ImportedHashes<T, I>>::try_get(index) {
+				prune_header::<T, I>(hash);
+				<ImportedHashes<T, I>>::remove(index);
+			}
+		}
+		<ImportedHashesPointer<T, I>>::kill();
+		<ImportedHashesCount<T, I>>::kill();
+
+		log::debug!(target: "runtime::bridge-grandpa", "Pruned all imported headers.");
+	}
+
+	/// Additional weight of the `submit_finality_proof` call, that isn't covered by benchmarks.
+	///
+	/// It includes maintenance of the number index and pruning aged headers.
+	pub(crate) fn headers_pruning_weight<T: Config<I>, I: 'static>() -> Weight {
+		let db_weight = T::DbWeight::get();
+		let index_weight = db_weight.reads_writes(2, 3);
+		if T::MaxHeaderAge::get().is_some() {
+			let max_pruned = MAX_AGED_HEADERS_PRUNED_PER_IMPORT as Weight;
+			index_weight.saturating_add(db_weight.reads_writes(3 * max_pruned + 2, 3 * max_pruned + 1))
+		} else {
+			index_weight
+		}
+	}
+
+	/// Weight of the `force_reinitialize` call.
+	///
+	/// If `prune_imported_headers` is true, we're reading every entry of the `ImportedHashes` ring
+	/// buffer and removing it, along with the corresponding header.
+	pub(crate) fn force_reinitialize_weight<T: Config<I>, I: 'static>(prune_imported_headers: bool) -> Weight {
+		let db_weight = T::DbWeight::get();
+		let initialize_weight = db_weight.reads_writes(2, 5);
+		if prune_imported_headers {
+			let headers_to_keep = T::HeadersToKeep::get() as Weight;
+			initialize_weight.saturating_add(
+				db_weight.reads_writes(headers_to_keep.saturating_mul(2), headers_to_keep.saturating_mul(3)),
+			)
+		} else {
+			initialize_weight
 		}
 	}
 
@@ -470,7 +668,6 @@ pub mod pallet {
 
 		let initial_hash = header.hash();
 		<InitialHash<T, I>>::put(initial_hash);
-		<ImportedHashesPointer<T, I>>::put(0);
 		insert_header::<T, I>(header, initial_hash);
 
 		let authority_set = bp_header_chain::AuthoritySet::new(authority_list, set_id);
@@ -544,6 +741,37 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		<ImportedHeaders<T, I>>::contains_key(hash)
 	}
 
+	/// Get the finalized header with given hash, if it is known to the bridge pallet.
+	pub fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash)
+	}
+
+	/// Get hash of the finalized header with given number, if it is known to the bridge pallet.
+	pub fn finalized_header_hash(number: BridgedBlockNumber<T, I>) -> Option<BridgedBlockHash<T, I>> {
+		<ImportedHashesByNumber<T, I>>::get(number)
+	}
+
+	/// Get the finalized header with given number or, if it isn't known to the bridge pallet,
+	/// the most recently imported header with number that is less than the given number.
+	///
+	/// Returns `None` if there are no such headers in the storage (e.g. they have already been
+	/// pruned).
+	pub fn finalized_header_at_or_before(number: BridgedBlockNumber<T, I>) -> Option<BridgedHeader<T, I>> {
+		if let Some(header) = <ImportedHashesByNumber<T, I>>::get(number).and_then(<ImportedHeaders<T, I>>::get) {
+			return Some(header);
+		}
+
+		let headers_to_keep = T::HeadersToKeep::get();
+		let pointer = <ImportedHashesPointer<T, I>>::get();
+		let count = sp_std::cmp::min(<ImportedHashesCount<T, I>>::get(), headers_to_keep);
+		(1..=count)
+			.filter_map(|offset| {
+				<ImportedHashes<T, I>>::get(ring_buffer_index_before(pointer, offset, headers_to_keep))
+			})
+			.filter_map(<ImportedHeaders<T, I>>::get)
+			.find(|header| *header.number() <= number)
+	}
+
 	/// Verify that the passed storage proof is valid, given it is crafted using
 	/// known finalized header. If the proof is valid, then the `parse` callback
 	/// is called and the function returns its result.
@@ -560,6 +788,68 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	}
 }
 
+impl<T: Config<I>, I: 'static> bp_runtime::FinalizedStorageProofParser for Pallet<T, I> {
+	type Hash = BridgedBlockHash<T, I>;
+	type Hasher = BridgedBlockHasher<T, I>;
+
+	fn parse_finalized_storage_proof<R>(
+		hash: Self::Hash,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(bp_runtime::StorageProofChecker<Self::Hasher>) -> R,
+	) -> Result<R, sp_runtime::DispatchError> {
+		Pallet::<T, I>::parse_finalized_storage_proof(hash, storage_proof, parse)
+	}
+}
+
+impl<T: Config<I>, I: 'static> bp_header_chain::InclusionProofVerifier for Pallet<T, I> {
+	type Transaction = Vec<u8>;
+	type TransactionInclusionProof = bp_header_chain::SubstrateTransactionInclusionProof<BridgedBlockHash<T, I>>;
+
+	fn verify_transaction_inclusion_proof(proof: &Self::TransactionInclusionProof) -> Option<Self::Transaction> {
+		let header = match <ImportedHeaders<T, I>>::get(proof.block) {
+			Some(header) => header,
+			None => {
+				log::trace!(
+					target: "runtime::bridge-grandpa",
+					"Tx inclusion check failed: can't find finalized header in the storage: {:?}",
+					proof.block,
+				);
+
+				return None;
+			}
+		};
+
+		// extrinsics trie is built by the `ordered_trie_root` - trie keys are compact-encoded
+		// indices of extrinsics and values are encoded extrinsics
+		let storage_proof = sp_trie::StorageProof::new(proof.proof.clone());
+		let transaction =
+			bp_runtime::StorageProofChecker::<BridgedBlockHasher<T, I>>::new(*header.extrinsics_root(), storage_proof)
+				.and_then(|checker| checker.read_value(&Compact(proof.index).encode()));
+		match transaction {
+			Ok(Some(transaction)) => Some(transaction),
+			Ok(None) | Err(_) => {
+				log::trace!(
+					target: "runtime::bridge-grandpa",
+					"Tx inclusion check failed: transaction {} is missing from the block {:?} or the proof is invalid",
+					proof.index,
+					proof.block,
+				);
+
+				None
+			}
+		}
+	}
+}
+
+/// Returns index of the ring buffer entry that is `offset` entries before the `pointer`.
+fn ring_buffer_index_before(pointer: u32, offset: u32, size: u32) -> u32 {
+	if offset <= pointer {
+		pointer - offset
+	} else {
+		size.saturating_sub(offset - pointer)
+	}
+}
+
 pub(crate) fn find_scheduled_change<H: HeaderT>(header: &H) -> Option<sp_finality_grandpa::ScheduledChange<H::Number>> {
 	use sp_runtime::generic::OpaqueDigestItemId;
 
@@ -609,14 +899,18 @@ pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader
 mod tests {
 	use super::*;
 	use crate::mock::{run_test, test_header, Origin, TestHash, TestHeader, TestNumber, TestRuntime};
+	use bp_header_chain::{InclusionProofVerifier, SubstrateTransactionInclusionProof};
 	use bp_test_utils::{
 		authority_list, make_default_justification, make_justification_for_header, JustificationGeneratorParams, ALICE,
 		BOB,
 	};
-	use codec::Encode;
 	use frame_support::weights::PostDispatchInfo;
 	use frame_support::{assert_err, assert_noop, assert_ok};
-	use sp_runtime::{Digest, DigestItem, DispatchError};
+	use sp_runtime::{
+		traits::{BlakeTwo256, Hash as HashT},
+		Digest, DigestItem, DispatchError,
+	};
+	use sp_state_machine::{backend::Backend, prove_read, InMemoryBackend};
 
 	fn initialize_substrate_bridge() {
 		assert_ok!(init_with_origin(Origin::root()));
@@ -637,6 +931,55 @@ mod tests {
 		Pallet::<TestRuntime>::initialize(origin, init_data.clone()).map(|_| init_data)
 	}
 
+	fn initialize_substrate_bridge_and_get_new_init_data() -> InitializationData<TestHeader> {
+		initialize_substrate_bridge();
+		assert_ok!(submit_finality_proof(1));
+		next_block();
+		assert_ok!(submit_finality_proof(2));
+
+		InitializationData {
+			header: test_header(10),
+			authority_list: vec![(ALICE.into(), 1)],
+			set_id: 2,
+			is_halted: false,
+		}
+	}
+
+	fn prepare_transaction_inclusion_proof(extrinsics: Vec<Vec<u8>>, index: u32) -> (TestHash, Vec<Vec<u8>>) {
+		let backend = InMemoryBackend::<BlakeTwo256>::from(vec![(
+			None,
+			extrinsics
+				.into_iter()
+				.enumerate()
+				.map(|(index, extrinsic)| (Compact(index as u32).encode(), Some(extrinsic)))
+				.collect::<Vec<_>>(),
+		)]);
+		let extrinsics_root = backend.storage_root(std::iter::empty()).0;
+		let proof = prove_read(backend, &[Compact(index).encode()])
+			.unwrap()
+			.iter_nodes()
+			.collect();
+
+		(extrinsics_root, proof)
+	}
+
+	fn initialize_with_extrinsics_root(extrinsics_root: TestHash) -> TestHash {
+		let mut header = test_header(0);
+		header.extrinsics_root = extrinsics_root;
+		let hash = header.hash();
+		assert_ok!(Pallet::<TestRuntime>::initialize(
+			Origin::root(),
+			InitializationData {
+				header,
+				authority_list: authority_list(),
+				set_id: 1,
+				is_halted: false,
+			},
+		));
+
+		hash
+	}
+
 	fn submit_finality_proof(header: u8) -> frame_support::dispatch::DispatchResultWithPostInfo {
 		let header = test_header(header.into());
 		let justification = make_default_justification(&header);
@@ -780,6 +1123,81 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn force_reinitialize_requires_root_or_owner_origin() {
+		run_test(|| {
+			let init_data = initialize_substrate_bridge_and_get_new_init_data();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::force_reinitialize(Origin::signed(1), init_data.clone(), false),
+				DispatchError::BadOrigin,
+			);
+
+			PalletOwner::<TestRuntime>::put(1);
+			assert_ok!(Pallet::<TestRuntime>::force_reinitialize(
+				Origin::signed(1),
+				init_data.clone(),
+				false
+			));
+			assert_ok!(Pallet::<TestRuntime>::force_reinitialize(
+				Origin::root(),
+				init_data,
+				false
+			));
+		});
+	}
+
+	#[test]
+	fn force_reinitialize_replaces_best_finalized_header_and_authority_set() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+			let init_data = initialize_substrate_bridge_and_get_new_init_data();
+			let old_best_finalized = Pallet::<TestRuntime>::best_finalized();
+
+			assert_ok!(Pallet::<TestRuntime>::force_reinitialize(
+				Origin::root(),
+				init_data.clone(),
+				false
+			));
+
+			let new_hash = init_data.header.hash();
+			assert_eq!(BestFinalized::<TestRuntime>::get(), new_hash);
+			assert_eq!(InitialHash::<TestRuntime>::get(), new_hash);
+			assert_eq!(
+				CurrentAuthoritySet::<TestRuntime>::get(),
+				bp_header_chain::AuthoritySet::new(init_data.authority_list, init_data.set_id),
+			);
+			assert!(Pallet::<TestRuntime>::is_known_header(new_hash));
+			assert!(Pallet::<TestRuntime>::is_known_header(old_best_finalized.hash()));
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.last()
+					.map(|record| record.event.clone()),
+				Some(crate::mock::Event::Grandpa(Event::ForceReinitialized(new_hash, 2))),
+			);
+		});
+	}
+
+	#[test]
+	fn force_reinitialize_prunes_imported_headers_if_requested() {
+		run_test(|| {
+			let init_data = initialize_substrate_bridge_and_get_new_init_data();
+			let old_best_finalized = Pallet::<TestRuntime>::best_finalized();
+			let old_initial_hash = InitialHash::<TestRuntime>::get();
+
+			assert_ok!(Pallet::<TestRuntime>::force_reinitialize(
+				Origin::root(),
+				init_data.clone(),
+				true
+			));
+
+			assert!(Pallet::<TestRuntime>::is_known_header(init_data.header.hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(old_best_finalized.hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(old_initial_hash));
+			assert_eq!(ImportedHashesPointer::<TestRuntime>::get(), 1);
+		});
+	}
+
 	#[test]
 	fn pallet_rejects_transactions_if_halted() {
 		run_test(|| {
@@ -1100,4 +1518,229 @@ mod tests {
 			);
 		})
 	}
+
+	#[test]
+	fn imported_headers_are_indexed_by_number() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(2));
+			next_block();
+			assert_ok!(submit_finality_proof(5));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_hash(0),
+				Some(test_header(0).hash())
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_hash(2),
+				Some(test_header(2).hash())
+			);
+			assert_eq!(Pallet::<TestRuntime>::finalized_header_hash(3), None);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_hash(5),
+				Some(test_header(5).hash())
+			);
+		})
+	}
+
+	#[test]
+	fn number_index_is_pruned_along_with_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for header in 1..=5 {
+				next_block();
+				assert_ok!(submit_finality_proof(header));
+			}
+
+			assert_eq!(Pallet::<TestRuntime>::finalized_header_hash(0), None);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_hash(1),
+				Some(test_header(1).hash())
+			);
+		})
+	}
+
+	#[test]
+	fn finalized_header_at_or_before_works() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(2));
+			next_block();
+			assert_ok!(submit_finality_proof(5));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_at_or_before(0),
+				Some(test_header(0))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_at_or_before(1),
+				Some(test_header(0))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_at_or_before(2),
+				Some(test_header(2))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_at_or_before(4),
+				Some(test_header(2))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_at_or_before(5),
+				Some(test_header(5))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_at_or_before(100),
+				Some(test_header(5))
+			);
+		})
+	}
+
+	#[test]
+	fn finalized_header_at_or_before_ignores_pruned_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for header in 1..=5 {
+				next_block();
+				assert_ok!(submit_finality_proof(header * 2));
+			}
+
+			assert_eq!(Pallet::<TestRuntime>::finalized_header_at_or_before(1), None);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_at_or_before(3),
+				Some(test_header(2))
+			);
+		})
+	}
+
+	#[test]
+	fn headers_are_not_pruned_by_age_if_max_header_age_is_not_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			next_block();
+			assert_ok!(submit_finality_proof(100));
+
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert_eq!(ImportedHashesCount::<TestRuntime>::get(), 2);
+		})
+	}
+
+	#[test]
+	fn headers_are_pruned_by_age() {
+		run_test(|| {
+			crate::mock::MaxHeaderAge::set(&Some(3));
+
+			initialize_substrate_bridge();
+			for header in 1..=3 {
+				next_block();
+				assert_ok!(submit_finality_proof(header));
+			}
+			assert_eq!(ImportedHashesCount::<TestRuntime>::get(), 4);
+
+			next_block();
+			assert_ok!(submit_finality_proof(5));
+
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(3).hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(5).hash()));
+			assert_eq!(Pallet::<TestRuntime>::finalized_header_hash(1), None);
+			assert_eq!(ImportedHashesCount::<TestRuntime>::get(), 3);
+		})
+	}
+
+	#[test]
+	fn best_finalized_header_is_never_pruned_by_age() {
+		run_test(|| {
+			crate::mock::MaxHeaderAge::set(&Some(0));
+
+			initialize_substrate_bridge();
+			next_block();
+			assert_ok!(submit_finality_proof(1));
+
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), test_header(1));
+			assert_eq!(ImportedHashesCount::<TestRuntime>::get(), 1);
+		})
+	}
+
+	#[test]
+	fn extrinsics_trie_matches_ordered_trie_root() {
+		let extrinsics = vec![vec![1u8; 8], vec![2u8; 64], vec![3u8; 16]];
+		let (extrinsics_root, _) = prepare_transaction_inclusion_proof(extrinsics.clone(), 0);
+		assert_eq!(extrinsics_root, BlakeTwo256::ordered_trie_root(extrinsics));
+	}
+
+	#[test]
+	fn verify_transaction_inclusion_proof_accepts_valid_proof() {
+		run_test(|| {
+			let (extrinsics_root, proof) =
+				prepare_transaction_inclusion_proof(vec![vec![1u8; 8], vec![2u8; 64], vec![3u8; 16]], 1);
+			let block = initialize_with_extrinsics_root(extrinsics_root);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_transaction_inclusion_proof(&SubstrateTransactionInclusionProof {
+					block,
+					index: 1,
+					proof
+				}),
+				Some(vec![2u8; 64]),
+			);
+		})
+	}
+
+	#[test]
+	fn verify_transaction_inclusion_proof_rejects_proof_at_unknown_block() {
+		run_test(|| {
+			let (extrinsics_root, proof) =
+				prepare_transaction_inclusion_proof(vec![vec![1u8; 8], vec![2u8; 64], vec![3u8; 16]], 1);
+			initialize_with_extrinsics_root(extrinsics_root);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_transaction_inclusion_proof(&SubstrateTransactionInclusionProof {
+					block: Default::default(),
+					index: 1,
+					proof
+				}),
+				None,
+			);
+		})
+	}
+
+	#[test]
+	fn verify_transaction_inclusion_proof_rejects_proof_of_other_transaction() {
+		run_test(|| {
+			let (extrinsics_root, proof) =
+				prepare_transaction_inclusion_proof(vec![vec![1u8; 8], vec![2u8; 64], vec![3u8; 16]], 1);
+			let block = initialize_with_extrinsics_root(extrinsics_root);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_transaction_inclusion_proof(&SubstrateTransactionInclusionProof {
+					block,
+					index: 2,
+					proof
+				}),
+				None,
+			);
+		})
+	}
+
+	#[test]
+	fn verify_transaction_inclusion_proof_rejects_proof_with_other_root() {
+		run_test(|| {
+			let (extrinsics_root, _) =
+				prepare_transaction_inclusion_proof(vec![vec![1u8; 8], vec![2u8; 64], vec![3u8; 16]], 1);
+			let (_, other_proof) = prepare_transaction_inclusion_proof(vec![vec![4u8; 8], vec![5u8; 64]], 1);
+			let block = initialize_with_extrinsics_root(extrinsics_root);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_transaction_inclusion_proof(&SubstrateTransactionInclusionProof {
+					block,
+					index: 1,
+					proof: other_proof,
+				}),
+				None,
+			);
+		})
+	}
 }
@@ -0,0 +1,178 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations of the GRANDPA pallet.
+
+use crate::{Config, ImportedHashes, ImportedHashesByNumber, ImportedHashesCount, ImportedHeaders, Pallet};
+
+use bp_runtime::{StorageMigrationStep, VersionedMigration};
+use frame_support::{traits::Get, weights::Weight};
+use sp_runtime::traits::Header as HeaderT;
+use sp_std::marker::PhantomData;
+
+/// Runtime upgrade that migrates pallet storage from version 0 to version 1.
+pub type MigrateToV1<T, I = ()> =
+	VersionedMigration<Pallet<T, I>, MigrationToV1Step<T, I>, <T as frame_system::Config>::DbWeight>;
+
+/// Migration of the pallet storage from version 0 to version 1.
+///
+/// Version 1 has added the `ImportedHashesByNumber` index and the `ImportedHashesCount` value.
+/// The migration fills them using headers that are referenced by the `ImportedHashes` ring
+/// buffer. Headers are only imported on demand, so there are usually far less stored headers
+/// than `HeadersToKeep`. The `pre_upgrade` check ensures that the migration fits into the
+/// single block.
+pub struct MigrationToV1Step<T, I = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> StorageMigrationStep for MigrationToV1Step<T, I> {
+	const FROM: u16 = 0;
+	const TO: u16 = 1;
+
+	fn migrate() -> Weight {
+		index_imported_headers::<T, I>()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let imported_hashes = ImportedHashes::<T, I>::iter_keys().count() as u64;
+
+		// every ring buffer entry is read twice and written at most once
+		let expected_weight = T::DbWeight::get().reads_writes(2 * imported_hashes, imported_hashes + 1);
+		if expected_weight > <T as frame_system::Config>::BlockWeights::get().max_block {
+			return Err("Indexing of imported headers doesn't fit into the single block");
+		}
+
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		let mut imported_hashes = 0u32;
+		for hash in ImportedHashes::<T, I>::iter_values() {
+			imported_hashes += 1;
+			if let Some(header) = ImportedHeaders::<T, I>::get(hash) {
+				if ImportedHashesByNumber::<T, I>::get(*header.number()).is_none() {
+					return Err("Imported header is missing from the number index");
+				}
+			}
+		}
+		if ImportedHashesCount::<T, I>::get() != imported_hashes {
+			return Err("Number of imported hashes is not equal to the number of ring buffer entries");
+		}
+
+		Ok(())
+	}
+}
+
+/// Fill the `ImportedHashesByNumber` index and `ImportedHashesCount` value using entries of the
+/// `ImportedHashes` ring buffer.
+///
+/// Returns weight, consumed by the migration.
+pub(crate) fn index_imported_headers<T: Config<I>, I: 'static>() -> Weight {
+	let mut imported_hashes = 0u32;
+	let mut writes = 0;
+	for hash in ImportedHashes::<T, I>::iter_values() {
+		imported_hashes += 1;
+		if let Some(header) = ImportedHeaders::<T, I>::get(hash) {
+			ImportedHashesByNumber::<T, I>::insert(*header.number(), hash);
+			writes += 1;
+		}
+	}
+
+	ImportedHashesCount::<T, I>::put(sp_std::cmp::min(imported_hashes, T::HeadersToKeep::get()));
+
+	log::info!(
+		target: "runtime::bridge-grandpa",
+		"Indexed {} imported headers",
+		writes,
+	);
+
+	T::DbWeight::get().reads_writes(2 * imported_hashes as u64, writes + 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, test_header, TestRuntime};
+	use crate::{BestFinalized, ImportedHashesPointer};
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	fn insert_deprecated_headers(numbers: impl IntoIterator<Item = u64>) {
+		let mut pointer = 0;
+		for number in numbers {
+			let header = test_header(number);
+			let hash = header.hash();
+			ImportedHeaders::<TestRuntime, ()>::insert(hash, header);
+			ImportedHashes::<TestRuntime, ()>::insert(pointer, hash);
+			BestFinalized::<TestRuntime, ()>::put(hash);
+			pointer += 1;
+		}
+		ImportedHashesPointer::<TestRuntime, ()>::put(pointer);
+	}
+
+	#[test]
+	fn migration_indexes_imported_headers() {
+		run_test(|| {
+			insert_deprecated_headers(vec![1, 5, 10]);
+
+			index_imported_headers::<TestRuntime, ()>();
+
+			assert_eq!(ImportedHashesCount::<TestRuntime, ()>::get(), 3);
+			for number in vec![1, 5, 10] {
+				assert_eq!(
+					Pallet::<TestRuntime, ()>::finalized_header_hash(number),
+					Some(test_header(number).hash()),
+				);
+			}
+			assert_eq!(
+				Pallet::<TestRuntime, ()>::finalized_header_at_or_before(7),
+				Some(test_header(5)),
+			);
+		});
+	}
+
+	#[test]
+	fn migration_to_v1_migrates_v0_storage() {
+		run_test(|| {
+			StorageVersion::new(0).put::<Pallet<TestRuntime, ()>>();
+			insert_deprecated_headers(vec![1, 2]);
+
+			MigrateToV1::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(
+				Pallet::<TestRuntime, ()>::on_chain_storage_version(),
+				StorageVersion::new(1)
+			);
+			assert_eq!(ImportedHashesCount::<TestRuntime, ()>::get(), 2);
+			assert_eq!(
+				Pallet::<TestRuntime, ()>::finalized_header_hash(2),
+				Some(test_header(2).hash())
+			);
+		});
+	}
+
+	#[test]
+	fn migration_to_v1_is_not_applied_to_v1_storage() {
+		run_test(|| {
+			StorageVersion::new(1).put::<Pallet<TestRuntime, ()>>();
+			insert_deprecated_headers(vec![1, 2]);
+
+			MigrateToV1::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(ImportedHashesCount::<TestRuntime, ()>::get(), 0);
+			assert_eq!(Pallet::<TestRuntime, ()>::finalized_header_hash(2), None);
+		});
+	}
+}
@@ -42,7 +42,7 @@ construct_runtime! {
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Grandpa: grandpa::{Pallet},
+		Grandpa: grandpa::{Pallet, Event<T>},
 	}
 }
 
@@ -63,7 +63,7 @@ impl frame_system::Config for TestRuntime {
 	type AccountId = AccountId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = Event;
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
@@ -82,14 +82,17 @@ impl frame_system::Config for TestRuntime {
 parameter_types! {
 	pub const MaxRequests: u32 = 2;
 	pub const HeadersToKeep: u32 = 5;
+	pub storage MaxHeaderAge: Option<u64> = None;
 	pub const SessionLength: u64 = 5;
 	pub const NumValidators: u32 = 5;
 }
 
 impl grandpa::Config for TestRuntime {
+	type Event = Event;
 	type BridgedChain = TestBridgedChain;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeaderAge = MaxHeaderAge;
 	type WeightInfo = ();
 }
 
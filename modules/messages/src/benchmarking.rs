@@ -24,7 +24,7 @@ use crate::{
 
 use bp_messages::{
 	source_chain::TargetHeaderChain, target_chain::SourceHeaderChain, DeliveredMessages, InboundLaneData, LaneId,
-	MessageData, MessageNonce, OutboundLaneData, UnrewardedRelayer, UnrewardedRelayersState,
+	LaneState, MessageData, MessageNonce, OutboundLaneData, UnrewardedRelayer, UnrewardedRelayersState,
 };
 use bp_runtime::messages::DispatchFeePayment;
 use frame_benchmarking::{account, benchmarks_instance_pallet};
@@ -152,7 +152,7 @@ benchmarks_instance_pallet! {
 			size: 0,
 			sender_account: sender.clone(),
 		});
-	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee)
+	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee, None)
 	verify {
 		assert_eq!(
 			crate::Pallet::<T, I>::outbound_latest_generated_nonce(T::bench_lane_id()),
@@ -189,7 +189,7 @@ benchmarks_instance_pallet! {
 			size,
 			sender_account: sender.clone(),
 		});
-	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee)
+	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee, None)
 	verify {
 		assert_eq!(
 			crate::Pallet::<T, I>::outbound_latest_generated_nonce(T::bench_lane_id()),
@@ -226,7 +226,7 @@ benchmarks_instance_pallet! {
 			size,
 			sender_account: sender.clone(),
 		});
-	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee)
+	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee, None)
 	verify {
 		assert_eq!(
 			crate::Pallet::<T, I>::outbound_latest_generated_nonce(T::bench_lane_id()),
@@ -362,6 +362,7 @@ benchmarks_instance_pallet! {
 				oldest_unpruned_nonce: 21,
 				latest_received_nonce: 20,
 				latest_generated_nonce: 21,
+				latest_expired_nonce: 0,
 			}),
 			size: ProofSize::Minimal(EXPECTED_DEFAULT_MESSAGE_LENGTH),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -631,7 +632,7 @@ benchmarks_instance_pallet! {
 			size: i as _,
 			sender_account: sender.clone(),
 		});
-	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee)
+	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee, None)
 	verify {
 		assert_eq!(
 			crate::Pallet::<T, I>::outbound_latest_generated_nonce(T::bench_lane_id()),
@@ -781,6 +782,7 @@ benchmarks_instance_pallet! {
 				oldest_unpruned_nonce: 21,
 				latest_received_nonce: 20,
 				latest_generated_nonce: 21,
+				latest_expired_nonce: 0,
 			}),
 			size: ProofSize::Minimal(0),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -898,20 +900,32 @@ benchmarks_instance_pallet! {
 	}
 }
 
+fn open_bench_lane<T: Config<I>, I: 'static>() {
+	crate::Lanes::<T, I>::insert(T::bench_lane_id(), LaneState::Open);
+}
+
 fn send_regular_message<T: Config<I>, I: 'static>() {
+	open_bench_lane::<T, I>();
 	let mut outbound_lane = outbound_lane::<T, I>(T::bench_lane_id());
-	outbound_lane.send_message(MessageData {
-		payload: vec![],
-		fee: MESSAGE_FEE.into(),
-	});
+	outbound_lane.send_message(
+		MessageData {
+			payload: vec![],
+			fee: MESSAGE_FEE.into(),
+		},
+		RawOrigin::Root,
+	);
 }
 
 fn send_regular_message_with_payload<T: Config<I>, I: 'static>(payload: Vec<u8>) {
+	open_bench_lane::<T, I>();
 	let mut outbound_lane = outbound_lane::<T, I>(T::bench_lane_id());
-	outbound_lane.send_message(MessageData {
-		payload,
-		fee: MESSAGE_FEE.into(),
-	});
+	outbound_lane.send_message(
+		MessageData {
+			payload,
+			fee: MESSAGE_FEE.into(),
+		},
+		RawOrigin::Root,
+	);
 }
 
 fn confirm_message_delivery<T: Config<I>, I: 'static>(nonce: MessageNonce) {
@@ -931,6 +945,7 @@ fn confirm_message_delivery<T: Config<I>, I: 'static>(nonce: MessageNonce) {
 }
 
 fn receive_messages<T: Config<I>, I: 'static>(nonce: MessageNonce) {
+	open_bench_lane::<T, I>();
 	let mut inbound_lane_storage = inbound_lane_storage::<T, I>(T::bench_lane_id());
 	inbound_lane_storage.set_data(InboundLaneData {
 		relayers: vec![UnrewardedRelayer {
@@ -18,10 +18,11 @@
 
 use bp_messages::{
 	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-	DeliveredMessages, InboundLaneData, LaneId, MessageKey, MessageNonce, OutboundLaneData, UnrewardedRelayer,
+	DeliveredMessages, InboundLaneData, LaneId, MessageData, MessageKey, MessageNonce, OutboundLaneData, QueuedMessage,
+	UnrewardedRelayer,
 };
 use bp_runtime::messages::MessageDispatchResult;
-use frame_support::RuntimeDebug;
+use frame_support::{weights::Weight, RuntimeDebug};
 use sp_std::prelude::PartialEq;
 
 /// Inbound lane storage.
@@ -30,6 +31,8 @@ pub trait InboundLaneStorage {
 	type MessageFee;
 	/// Id of relayer on source chain.
 	type Relayer: Clone + PartialEq;
+	/// Id of relayer on this chain.
+	type AccountId;
 
 	/// Lane id.
 	fn id(&self) -> LaneId;
@@ -41,6 +44,29 @@ pub trait InboundLaneStorage {
 	fn data(&self) -> InboundLaneData<Self::Relayer>;
 	/// Update lane data in the storage.
 	fn set_data(&mut self, data: InboundLaneData<Self::Relayer>);
+	/// Return nonce of the latest message in the dispatch queue. Returns `None` if the queue is empty.
+	fn latest_queued_nonce(&self) -> Option<MessageNonce>;
+	/// Get message from the dispatch queue.
+	fn queued_message(
+		&self,
+		nonce: MessageNonce,
+	) -> Option<QueuedMessage<Self::Relayer, Self::AccountId, Self::MessageFee>>;
+	/// Append message to the dispatch queue.
+	///
+	/// Returns false if the message can't be queued, because the deposit for storing the message
+	/// can't be reserved from the account of the relayer that has delivered it.
+	fn queue_message(
+		&mut self,
+		nonce: MessageNonce,
+		message: QueuedMessage<Self::Relayer, Self::AccountId, Self::MessageFee>,
+	) -> bool;
+	/// Remove message of given size from the dispatch queue and return the deposit to the relayer.
+	fn remove_queued_message(
+		&mut self,
+		nonce: MessageNonce,
+		relayer_at_this_chain: &Self::AccountId,
+		message_size: u32,
+	);
 }
 
 /// Result of single message receival.
@@ -51,6 +77,12 @@ pub enum ReceivalResult {
 	///
 	/// The message dispatch result is also returned.
 	Dispatched(MessageDispatchResult),
+	/// Message has been received and put to the dispatch queue. It'll be dispatched later.
+	Queued,
+	/// Message has been expired or cancelled at the bridged chain. It has been received without dispatch.
+	Skipped,
+	/// Deposit for storing the message in the dispatch queue can't be reserved from the relayer account.
+	FailedToReserveDeposit,
 	/// Message has invalid nonce and lane has rejected to accept this message.
 	InvalidNonce,
 	/// There are too many unrewarded relayer entries at the lane.
@@ -59,6 +91,22 @@ pub enum ReceivalResult {
 	TooManyUnconfirmedMessages,
 }
 
+/// Result of the queued message dispatch.
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub enum QueuedDispatchResult {
+	/// The oldest queued message has been dispatched (or skipped).
+	///
+	/// The message dispatch weight and dispatch result are also returned.
+	Dispatched(Weight, MessageDispatchResult),
+	/// There are no queued messages at the lane.
+	NoQueuedMessages,
+	/// Dispatch weight of the oldest queued message is larger than the given limit.
+	NotEnoughWeight,
+	/// There are too many unrewarded relayer entries at the lane. Queued messages can't be
+	/// dispatched until the bridged chain confirms some messages.
+	TooManyUnrewardedRelayers,
+}
+
 /// Inbound messages lane.
 pub struct InboundLane<S> {
 	storage: S,
@@ -102,6 +150,10 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 					.messages
 					.dispatch_results
 					.split_off((new_confirmed_nonce + 1 - entry.messages.begin) as _);
+				entry.messages.skipped_messages = entry
+					.messages
+					.skipped_messages
+					.split_off((new_confirmed_nonce + 1 - entry.messages.begin) as _);
 				entry.messages.begin = new_confirmed_nonce + 1;
 			}
 			_ => {}
@@ -111,7 +163,155 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 		Some(outbound_lane_data.latest_received_nonce)
 	}
 
+	/// Receive messages that have expired at the bridged chain.
+	///
+	/// Expired messages are never dispatched, but they still occupy their nonces. So they're
+	/// stored as undispatched messages, delivered by the given relayer. Returns number of
+	/// received expired messages.
+	pub fn receive_expired_messages(
+		&mut self,
+		relayer_at_bridged_chain: &S::Relayer,
+		latest_expired_nonce: MessageNonce,
+	) -> MessageNonce {
+		// expired messages are older than queued messages, but we can't mark them as delivered
+		// before queued messages are dispatched. So they'll be received with the next proof
+		if self.has_queued_messages() {
+			return 0;
+		}
+
+		let mut data = self.storage.data();
+		let mut received_messages = 0;
+		let mut nonce = data.last_delivered_nonce() + 1;
+		while nonce <= latest_expired_nonce {
+			if self.ensure_message_may_be_received(&data, nonce).is_err() {
+				break;
+			}
+
+			note_received_message(&mut data, relayer_at_bridged_chain, nonce, None);
+			received_messages += 1;
+			nonce += 1;
+		}
+
+		if received_messages != 0 {
+			self.storage.set_data(data);
+		}
+
+		received_messages
+	}
+
+	/// Returns true if there are queued (received, but not yet dispatched) messages at the lane.
+	///
+	/// Messages are dispatched in order, so while there are queued messages, all new messages
+	/// must also be queued.
+	pub fn has_queued_messages(&self) -> bool {
+		self.storage.latest_queued_nonce().is_some()
+	}
+
+	/// Receive new message and put it to the dispatch queue.
+	///
+	/// Queued message isn't considered delivered until it is dispatched. So the source chain will
+	/// only receive its dispatch result (and reward the relayer) after the message is dispatched.
+	/// Skipped messages are received without dispatch when they leave the queue.
+	pub fn queue_message(
+		&mut self,
+		relayer_at_bridged_chain: &S::Relayer,
+		relayer_at_this_chain: &S::AccountId,
+		nonce: MessageNonce,
+		message_data: MessageData<S::MessageFee>,
+		skipped: bool,
+	) -> ReceivalResult
+	where
+		S::AccountId: Clone,
+	{
+		let data = self.storage.data();
+		if let Err(receival_result) = self.ensure_message_may_be_received(&data, nonce) {
+			return receival_result;
+		}
+
+		let is_queued = self.storage.queue_message(
+			nonce,
+			QueuedMessage {
+				relayer_at_bridged_chain: relayer_at_bridged_chain.clone(),
+				relayer_at_this_chain: relayer_at_this_chain.clone(),
+				data: message_data,
+				skipped,
+			},
+		);
+		if !is_queued {
+			return ReceivalResult::FailedToReserveDeposit;
+		}
+
+		ReceivalResult::Queued
+	}
+
+	/// Dispatch the oldest message from the dispatch queue.
+	///
+	/// The message is only dispatched if its dispatch weight is not larger than the given limit
+	/// and if the lane is able to accept new unrewarded relayer entry.
+	pub fn dispatch_queued_message<P: MessageDispatch<S::AccountId, S::MessageFee>>(
+		&mut self,
+		dispatch_weight_limit: Weight,
+	) -> QueuedDispatchResult {
+		let mut data = self.storage.data();
+		let nonce = data.last_delivered_nonce() + 1;
+		let message = match self.storage.queued_message(nonce) {
+			Some(message) => message,
+			None => return QueuedDispatchResult::NoQueuedMessages,
+		};
+
+		// we need to have a room for new unrewarded relayer entry
+		if data.relayers.len() as MessageNonce >= self.storage.max_unrewarded_relayer_entries() {
+			return QueuedDispatchResult::TooManyUnrewardedRelayers;
+		}
+
+		// skipped messages are received without dispatch
+		let message_size = message.data.payload.len() as u32;
+		if message.skipped {
+			self.storage
+				.remove_queued_message(nonce, &message.relayer_at_this_chain, message_size);
+			note_received_message(&mut data, &message.relayer_at_bridged_chain, nonce, None);
+			self.storage.set_data(data);
+
+			return QueuedDispatchResult::Dispatched(
+				0,
+				MessageDispatchResult {
+					dispatch_result: false,
+					unspent_weight: 0,
+					dispatch_fee_paid_during_dispatch: false,
+				},
+			);
+		}
+
+		let dispatch_message = DispatchMessage {
+			key: MessageKey {
+				lane_id: self.storage.id(),
+				nonce,
+			},
+			data: message.data.into(),
+		};
+		let dispatch_weight = P::dispatch_weight(&dispatch_message);
+		if dispatch_weight > dispatch_weight_limit {
+			return QueuedDispatchResult::NotEnoughWeight;
+		}
+
+		self.storage
+			.remove_queued_message(nonce, &message.relayer_at_this_chain, message_size);
+		let dispatch_result = P::dispatch(&message.relayer_at_this_chain, dispatch_message);
+		note_received_message(
+			&mut data,
+			&message.relayer_at_bridged_chain,
+			nonce,
+			Some(dispatch_result.dispatch_result),
+		);
+		self.storage.set_data(data);
+
+		QueuedDispatchResult::Dispatched(dispatch_weight, dispatch_result)
+	}
+
 	/// Receive new message.
+	///
+	/// The message is dispatched immediately, so this method must not be called if the lane has
+	/// queued messages (see `has_queued_messages`).
 	pub fn receive_message<P: MessageDispatch<AccountId, S::MessageFee>, AccountId>(
 		&mut self,
 		relayer_at_bridged_chain: &S::Relayer,
@@ -120,20 +320,8 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 		message_data: DispatchMessageData<P::DispatchPayload, S::MessageFee>,
 	) -> ReceivalResult {
 		let mut data = self.storage.data();
-		let is_correct_message = nonce == data.last_delivered_nonce() + 1;
-		if !is_correct_message {
-			return ReceivalResult::InvalidNonce;
-		}
-
-		// if there are more unrewarded relayer entries than we may accept, reject this message
-		if data.relayers.len() as MessageNonce >= self.storage.max_unrewarded_relayer_entries() {
-			return ReceivalResult::TooManyUnrewardedRelayers;
-		}
-
-		// if there are more unconfirmed messages than we may accept, reject this message
-		let unconfirmed_messages_count = nonce.saturating_sub(data.last_confirmed_nonce);
-		if unconfirmed_messages_count > self.storage.max_unconfirmed_messages() {
-			return ReceivalResult::TooManyUnconfirmedMessages;
+		if let Err(receival_result) = self.ensure_message_may_be_received(&data, nonce) {
+			return receival_result;
 		}
 
 		// then, dispatch message
@@ -149,23 +337,99 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 		);
 
 		// now let's update inbound lane storage
-		let push_new = match data.relayers.back_mut() {
-			Some(entry) if entry.relayer == *relayer_at_bridged_chain => {
-				entry.messages.note_dispatched_message(dispatch_result.dispatch_result);
-				false
-			}
-			_ => true,
-		};
-		if push_new {
-			data.relayers.push_back(UnrewardedRelayer {
-				relayer: (*relayer_at_bridged_chain).clone(),
-				messages: DeliveredMessages::new(nonce, dispatch_result.dispatch_result),
-			});
-		}
+		note_received_message(
+			&mut data,
+			relayer_at_bridged_chain,
+			nonce,
+			Some(dispatch_result.dispatch_result),
+		);
 		self.storage.set_data(data);
 
 		ReceivalResult::Dispatched(dispatch_result)
 	}
+
+	/// Receive new message that has been expired or cancelled at the bridged chain.
+	///
+	/// Skipped messages are never dispatched, but they still occupy their nonces. So they're
+	/// stored as undispatched messages, delivered by the given relayer. This method must not be
+	/// called if the lane has queued messages (see `has_queued_messages`).
+	pub fn receive_skipped_message(
+		&mut self,
+		relayer_at_bridged_chain: &S::Relayer,
+		nonce: MessageNonce,
+	) -> ReceivalResult {
+		let mut data = self.storage.data();
+		if let Err(receival_result) = self.ensure_message_may_be_received(&data, nonce) {
+			return receival_result;
+		}
+
+		note_received_message(&mut data, relayer_at_bridged_chain, nonce, None);
+		self.storage.set_data(data);
+
+		ReceivalResult::Skipped
+	}
+
+	/// Ensure that the message with given nonce may be received by the lane.
+	fn ensure_message_may_be_received(
+		&self,
+		data: &InboundLaneData<S::Relayer>,
+		nonce: MessageNonce,
+	) -> Result<(), ReceivalResult> {
+		let is_correct_message = nonce == self.latest_received_nonce_with(data) + 1;
+		if !is_correct_message {
+			return Err(ReceivalResult::InvalidNonce);
+		}
+
+		// if there are more unrewarded relayer entries than we may accept, reject this message
+		if data.relayers.len() as MessageNonce >= self.storage.max_unrewarded_relayer_entries() {
+			return Err(ReceivalResult::TooManyUnrewardedRelayers);
+		}
+
+		// if there are more unconfirmed messages than we may accept, reject this message
+		let unconfirmed_messages_count = nonce.saturating_sub(data.last_confirmed_nonce);
+		if unconfirmed_messages_count > self.storage.max_unconfirmed_messages() {
+			return Err(ReceivalResult::TooManyUnconfirmedMessages);
+		}
+
+		Ok(())
+	}
+
+	/// Returns nonce of the latest received (dispatched or queued) message.
+	fn latest_received_nonce_with(&self, data: &InboundLaneData<S::Relayer>) -> MessageNonce {
+		self.storage
+			.latest_queued_nonce()
+			.unwrap_or_else(|| data.last_delivered_nonce())
+	}
+}
+
+/// Remember that the message has been delivered by given relayer.
+///
+/// The `dispatch_result` is `None` if the message has been skipped (received without dispatch).
+fn note_received_message<Relayer: Clone + PartialEq>(
+	data: &mut InboundLaneData<Relayer>,
+	relayer_at_bridged_chain: &Relayer,
+	nonce: MessageNonce,
+	dispatch_result: Option<bool>,
+) {
+	let push_new = match data.relayers.back_mut() {
+		Some(entry) if entry.relayer == *relayer_at_bridged_chain => {
+			match dispatch_result {
+				Some(dispatch_result) => entry.messages.note_dispatched_message(dispatch_result),
+				None => entry.messages.note_skipped_message(),
+			}
+			false
+		}
+		_ => true,
+	};
+	if push_new {
+		data.relayers.push_back(UnrewardedRelayer {
+			relayer: (*relayer_at_bridged_chain).clone(),
+			messages: match dispatch_result {
+				Some(dispatch_result) => DeliveredMessages::new(nonce, dispatch_result),
+				None => DeliveredMessages::new_skipped(nonce),
+			},
+		});
+	}
 }
 
 #[cfg(test)]
@@ -179,6 +443,7 @@ mod tests {
 		},
 		RuntimeInboundLaneStorage,
 	};
+	use bitvec::prelude::*;
 
 	fn receive_regular_message(
 		lane: &mut InboundLane<RuntimeInboundLaneStorage<TestRuntime, ()>>,
@@ -476,6 +741,43 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn expired_messages_are_received_without_dispatch() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			receive_regular_message(&mut lane, 1);
+			assert_eq!(lane.receive_expired_messages(&TEST_RELAYER_A, 3), 2);
+			assert_eq!(lane.receive_expired_messages(&TEST_RELAYER_A, 3), 0);
+			receive_regular_message(&mut lane, 4);
+
+			assert_eq!(
+				lane.storage.data().relayers,
+				vec![UnrewardedRelayer {
+					relayer: TEST_RELAYER_A,
+					messages: DeliveredMessages {
+						begin: 1,
+						end: 4,
+						dispatch_results: bitvec![Msb0, u8; 1, 0, 0, 1],
+						skipped_messages: bitvec![Msb0, u8; 0, 1, 1, 0],
+					},
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn expired_messages_are_not_received_above_unconfirmed_messages_limit_per_lane() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			let max_nonce = <TestRuntime as crate::Config>::MaxUnconfirmedMessagesAtInboundLane::get();
+			assert_eq!(
+				lane.receive_expired_messages(&TEST_RELAYER_A, max_nonce + 10),
+				max_nonce
+			);
+			assert_eq!(lane.storage.data().last_delivered_nonce(), max_nonce);
+		});
+	}
+
 	#[test]
 	fn correct_message_is_processed_instantly() {
 		run_test(|| {
@@ -502,4 +804,166 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn queued_message_is_not_dispatched_when_received() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			assert_eq!(
+				lane.queue_message(
+					&TEST_RELAYER_A,
+					&TEST_RELAYER_A,
+					1,
+					message_data(REGULAR_PAYLOAD),
+					false
+				),
+				ReceivalResult::Queued
+			);
+			assert!(lane.has_queued_messages());
+			assert_eq!(lane.storage.latest_queued_nonce(), Some(1));
+			assert_eq!(lane.storage.data().last_delivered_nonce(), 0);
+
+			// the next message must have next nonce
+			assert_eq!(
+				lane.queue_message(
+					&TEST_RELAYER_A,
+					&TEST_RELAYER_A,
+					1,
+					message_data(REGULAR_PAYLOAD),
+					false
+				),
+				ReceivalResult::InvalidNonce
+			);
+		});
+	}
+
+	#[test]
+	fn queued_messages_are_dispatched_in_order() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			lane.queue_message(
+				&TEST_RELAYER_A,
+				&TEST_RELAYER_A,
+				1,
+				message_data(REGULAR_PAYLOAD),
+				false,
+			);
+			lane.queue_message(
+				&TEST_RELAYER_B,
+				&TEST_RELAYER_B,
+				2,
+				message_data(REGULAR_PAYLOAD),
+				false,
+			);
+
+			// not enough weight to dispatch the message
+			assert_eq!(
+				lane.dispatch_queued_message::<TestMessageDispatch>(REGULAR_PAYLOAD.declared_weight - 1),
+				QueuedDispatchResult::NotEnoughWeight
+			);
+
+			assert_eq!(
+				lane.dispatch_queued_message::<TestMessageDispatch>(REGULAR_PAYLOAD.declared_weight),
+				QueuedDispatchResult::Dispatched(REGULAR_PAYLOAD.declared_weight, dispatch_result(0))
+			);
+			assert_eq!(lane.storage.data().last_delivered_nonce(), 1);
+			assert!(lane.has_queued_messages());
+
+			assert_eq!(
+				lane.dispatch_queued_message::<TestMessageDispatch>(REGULAR_PAYLOAD.declared_weight),
+				QueuedDispatchResult::Dispatched(REGULAR_PAYLOAD.declared_weight, dispatch_result(0))
+			);
+			assert_eq!(
+				lane.storage.data().relayers,
+				vec![
+					unrewarded_relayer(1, 1, TEST_RELAYER_A),
+					unrewarded_relayer(2, 2, TEST_RELAYER_B)
+				]
+			);
+			assert!(!lane.has_queued_messages());
+			assert_eq!(
+				lane.dispatch_queued_message::<TestMessageDispatch>(REGULAR_PAYLOAD.declared_weight),
+				QueuedDispatchResult::NoQueuedMessages
+			);
+		});
+	}
+
+	#[test]
+	fn skipped_message_is_received_without_dispatch() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			receive_regular_message(&mut lane, 1);
+			assert_eq!(
+				lane.receive_skipped_message(&TEST_RELAYER_A, 2),
+				ReceivalResult::Skipped
+			);
+			assert_eq!(
+				lane.receive_skipped_message(&TEST_RELAYER_A, 2),
+				ReceivalResult::InvalidNonce
+			);
+			receive_regular_message(&mut lane, 3);
+
+			assert_eq!(
+				lane.storage.data().relayers,
+				vec![UnrewardedRelayer {
+					relayer: TEST_RELAYER_A,
+					messages: DeliveredMessages {
+						begin: 1,
+						end: 3,
+						dispatch_results: bitvec![Msb0, u8; 1, 0, 1],
+						skipped_messages: bitvec![Msb0, u8; 0, 1, 0],
+					},
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn queued_skipped_message_is_received_without_dispatch() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			lane.queue_message(&TEST_RELAYER_A, &TEST_RELAYER_A, 1, message_data(REGULAR_PAYLOAD), true);
+
+			assert_eq!(
+				lane.dispatch_queued_message::<TestMessageDispatch>(1),
+				QueuedDispatchResult::Dispatched(
+					0,
+					MessageDispatchResult {
+						dispatch_result: false,
+						unspent_weight: 0,
+						dispatch_fee_paid_during_dispatch: false,
+					}
+				)
+			);
+			assert!(!lane.has_queued_messages());
+			assert_eq!(
+				lane.storage.data().relayers,
+				vec![UnrewardedRelayer {
+					relayer: TEST_RELAYER_A,
+					messages: DeliveredMessages {
+						begin: 1,
+						end: 1,
+						dispatch_results: bitvec![Msb0, u8; 0],
+						skipped_messages: bitvec![Msb0, u8; 1],
+					},
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn expired_messages_are_not_received_while_lane_has_queued_messages() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			lane.queue_message(
+				&TEST_RELAYER_A,
+				&TEST_RELAYER_A,
+				1,
+				message_data(REGULAR_PAYLOAD),
+				false,
+			);
+			assert_eq!(lane.receive_expired_messages(&TEST_RELAYER_A, 10), 0);
+			assert_eq!(lane.storage.data().last_delivered_nonce(), 0);
+		});
+	}
 }
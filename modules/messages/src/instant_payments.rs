@@ -21,7 +21,7 @@
 
 use bp_messages::{
 	source_chain::{MessageDeliveryAndDispatchPayment, RelayersRewards, Sender},
-	MessageNonce,
+	LaneId, MessageNonce,
 };
 use codec::Encode;
 use frame_support::traits::{Currency as CurrencyT, ExistenceRequirement, Get};
@@ -67,12 +67,7 @@ where
 		}
 
 		let root_account = RootAccount::get();
-		let account = match submitter {
-			Sender::Signed(submitter) => submitter,
-			Sender::Root | Sender::None => root_account
-				.as_ref()
-				.ok_or("Sending messages using Root or None origin is disallowed.")?,
-		};
+		let account = submitter_account(submitter, &root_account)?;
 
 		Currency::transfer(
 			account,
@@ -84,7 +79,26 @@ where
 		.map_err(Into::into)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &Currency::Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		let root_account = RootAccount::get();
+		let account = submitter_account(submitter, &root_account)?;
+
+		Currency::transfer(
+			relayer_fund_account,
+			account,
+			*fee,
+			// the relayers fund account must always exist
+			ExistenceRequirement::KeepAlive,
+		)
+		.map_err(Into::into)
+	}
+
 	fn pay_relayers_rewards(
+		_lane_id: LaneId,
 		confirmation_relayer: &T::AccountId,
 		relayers_rewards: RelayersRewards<T::AccountId, Currency::Balance>,
 		relayer_fund_account: &T::AccountId,
@@ -96,6 +110,23 @@ where
 			GetConfirmationFee::get(),
 		);
 	}
+
+	fn relayer_fund_balance(relayer_fund_account: &T::AccountId) -> Currency::Balance {
+		Currency::free_balance(relayer_fund_account)
+	}
+}
+
+/// Returns account that pays (and is refunded) the message fee.
+fn submitter_account<'a, AccountId>(
+	submitter: &'a Sender<AccountId>,
+	root_account: &'a Option<AccountId>,
+) -> Result<&'a AccountId, &'static str> {
+	match submitter {
+		Sender::Signed(submitter) => Ok(submitter),
+		Sender::Root | Sender::None => root_account
+			.as_ref()
+			.ok_or("Sending messages using Root or None origin is disallowed."),
+	}
 }
 
 /// Pay rewards to given relayers, optionally rewarding confirmation relayer.
@@ -108,9 +139,31 @@ fn pay_relayers_rewards<Currency, AccountId>(
 	AccountId: Debug + Default + Encode + PartialEq,
 	Currency: CurrencyT<AccountId>,
 	Currency::Balance: From<u64>,
+{
+	distribute_relayers_rewards(
+		confirmation_relayer,
+		relayers_rewards,
+		confirmation_fee,
+		|relayer, reward| pay_relayer_reward::<Currency, _>(relayer_fund_account, relayer, reward),
+	);
+}
+
+/// Split rewards between relayers that have delivered messages and the relayer that has confirmed
+/// delivery, and give every relayer its share using the `reward_relayer` callback.
+///
+/// Every relayer except `confirmation_relayer` pays `confirmation_fee` for every delivered message
+/// to the `confirmation_relayer`.
+pub fn distribute_relayers_rewards<AccountId, Balance>(
+	confirmation_relayer: &AccountId,
+	relayers_rewards: RelayersRewards<AccountId, Balance>,
+	confirmation_fee: Balance,
+	mut reward_relayer: impl FnMut(&AccountId, Balance),
+) where
+	AccountId: PartialEq,
+	Balance: Copy + From<MessageNonce> + PartialOrd + Saturating + Zero,
 {
 	// reward every relayer except `confirmation_relayer`
-	let mut confirmation_relayer_reward = Currency::Balance::zero();
+	let mut confirmation_relayer_reward = Balance::zero();
 	for (relayer, reward) in relayers_rewards {
 		let mut relayer_reward = reward.reward;
 
@@ -133,11 +186,11 @@ fn pay_relayers_rewards<Currency, AccountId>(
 			continue;
 		}
 
-		pay_relayer_reward::<Currency, _>(relayer_fund_account, &relayer, relayer_reward);
+		reward_relayer(&relayer, relayer_reward);
 	}
 
-	// finally - pay reward to confirmation relayer
-	pay_relayer_reward::<Currency, _>(relayer_fund_account, confirmation_relayer, confirmation_relayer_reward);
+	// finally - reward confirmation relayer
+	reward_relayer(confirmation_relayer, confirmation_relayer_reward);
 }
 
 /// Transfer funds from relayers fund account to given relayer.
@@ -212,6 +265,25 @@ mod tests {
 		.collect()
 	}
 
+	#[test]
+	fn expired_message_fee_is_refunded_from_relayers_fund() {
+		run_test(|| {
+			type Payments = InstantCurrencyPayments<TestRuntime, Balances, (), ()>;
+
+			let relayers_fund_balance = Balances::free_balance(&RELAYERS_FUND_ACCOUNT);
+			assert_eq!(
+				Payments::refund_delivery_and_dispatch_fee(&Sender::Signed(RELAYER_1), &100, &RELAYERS_FUND_ACCOUNT),
+				Ok(()),
+			);
+
+			assert_eq!(Balances::free_balance(&RELAYER_1), 100);
+			assert_eq!(
+				Balances::free_balance(&RELAYERS_FUND_ACCOUNT),
+				relayers_fund_balance - 100
+			);
+		});
+	}
+
 	#[test]
 	fn confirmation_relayer_is_rewarded_if_it_has_also_delivered_messages() {
 		run_test(|| {
@@ -42,36 +42,45 @@ pub use crate::weights_ext::{
 	EXPECTED_DEFAULT_MESSAGE_LENGTH,
 };
 
-use crate::inbound_lane::{InboundLane, InboundLaneStorage, ReceivalResult};
+use crate::inbound_lane::{InboundLane, InboundLaneStorage, QueuedDispatchResult, ReceivalResult};
 use crate::outbound_lane::{OutboundLane, OutboundLaneStorage, ReceivalConfirmationResult};
 use crate::weights::WeightInfo;
 
 use bp_messages::{
 	source_chain::{
-		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed, OnMessageAccepted,
-		RelayersRewards, TargetHeaderChain,
+		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, MessageOwnerId, MessageOwners, MessagesSenders,
+		OnDeliveryConfirmed, OnMessageAccepted, RelayersRewards, TargetHeaderChain,
 	},
-	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain},
-	total_unrewarded_messages, DeliveredMessages, InboundLaneData, LaneId, MessageData, MessageKey, MessageNonce,
-	OperatingMode, OutboundLaneData, Parameter as MessagesParameter, UnrewardedRelayersState,
+	target_chain::{
+		DispatchMessage, DispatchMessageData, MessageDispatch, ProvedMessages, QueuedMessagesDeposit, RelayersRegistry,
+		SourceHeaderChain,
+	},
+	total_unrewarded_messages, DecommissioningProgress, DeliveredMessages, InboundLaneData, LaneId, LaneState, Message,
+	MessageData, MessageKey, MessageNonce, MessagePayloadDetails, OperatingMode, OutboundLaneData,
+	Parameter as MessagesParameter, QueuedMessage, UnrewardedRelayersState,
 };
-use bp_runtime::{ChainId, Size};
+use bp_runtime::{messages::DispatchFeePayment, ChainId, Size};
 use codec::{Decode, Encode};
 use frame_support::{
-	fail,
+	ensure, fail,
 	traits::Get,
-	weights::{Pays, PostDispatchInfo},
+	weights::{Pays, PostDispatchInfo, Weight},
 };
 use frame_system::RawOrigin;
-use num_traits::{SaturatingAdd, Zero};
+use num_traits::{CheckedAdd, SaturatingAdd, Zero};
 use sp_runtime::traits::BadOrigin;
-use sp_std::{cell::RefCell, cmp::PartialOrd, marker::PhantomData, prelude::*};
+use sp_std::{
+	cell::RefCell, cmp::PartialOrd, collections::btree_map::BTreeMap, convert::TryFrom, marker::PhantomData,
+	ops::RangeInclusive, prelude::*,
+};
 
 mod inbound_lane;
 mod outbound_lane;
 mod weights_ext;
 
 pub mod instant_payments;
+pub mod migration;
+pub mod queued_messages_deposit;
 pub mod weights;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -85,7 +94,7 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
 	use frame_system::pallet_prelude::*;
 
 	#[pallet::config]
@@ -132,16 +141,36 @@ pub mod pallet {
 		/// that is declaring more messages than this value, will be rejected. Even if these messages are
 		/// from different lanes.
 		type MaxUnconfirmedMessagesAtInboundLane: Get<MessageNonce>;
+		/// Weight that is reserved for dispatching queued inbound messages at the beginning of every
+		/// block. It guarantees that the dispatch queue is processed even if blocks are full and
+		/// there's no weight left for `on_idle`.
+		type QueuedMessagesDispatchWeight: Get<Weight>;
+		/// Length (in blocks) of the period, during which number of messages that may be sent by
+		/// single account is limited by the `MaxMessagesPerAccountPerPeriod`.
+		type AccountRateLimitPeriod: Get<Self::BlockNumber>;
+		/// Maximal number of messages that single account may send (over all lanes) during
+		/// `AccountRateLimitPeriod` blocks. Messages that are sent by root or without origin are
+		/// not limited.
+		///
+		/// The value may be backed by runtime storage, so that it could be tuned using the
+		/// `update_pallet_parameter` call.
+		type MaxMessagesPerAccountPerPeriod: Get<MessageNonce>;
+		/// Maximal total size of message payloads that may be sent over single lane in a single
+		/// block.
+		///
+		/// The value may be backed by runtime storage, so that it could be tuned using the
+		/// `update_pallet_parameter` call.
+		type MaxLaneBytesPerBlock: Get<u32>;
 
 		/// Payload type of outbound messages. This payload is dispatched on the bridged chain.
 		type OutboundPayload: Parameter + Size;
 		/// Message fee type of outbound messages. This fee is paid on this chain.
-		type OutboundMessageFee: Default + From<u64> + PartialOrd + Parameter + SaturatingAdd + Zero;
+		type OutboundMessageFee: CheckedAdd + Default + From<u64> + PartialOrd + Parameter + SaturatingAdd + Zero;
 
 		/// Payload type of inbound messages. This payload is dispatched on this chain.
 		type InboundPayload: Decode;
 		/// Message fee type of inbound messages. This fee is paid on the bridged chain.
-		type InboundMessageFee: Decode;
+		type InboundMessageFee: Parameter;
 		/// Identifier of relayer that deliver messages to this chain. Relayer reward is paid on the bridged chain.
 		type InboundRelayer: Parameter;
 
@@ -162,9 +191,12 @@ pub mod pallet {
 			Self::OutboundMessageFee,
 		>;
 		/// Handler for accepted messages.
-		type OnMessageAccepted: OnMessageAccepted;
+		type OnMessageAccepted: OnMessageAccepted<Self::AccountId>;
 		/// Handler for delivered messages.
-		type OnDeliveryConfirmed: OnDeliveryConfirmed;
+		type OnDeliveryConfirmed: OnDeliveryConfirmed<Self::AccountId>;
+		/// Owners of outbound messages (see `MessagesBridge::send_owned_message`), that are
+		/// receiving dispatch results of their messages.
+		type MessageOwners: MessageOwners;
 
 		// Types that are used by inbound_lane (on target chain).
 
@@ -176,6 +208,10 @@ pub mod pallet {
 			Self::InboundMessageFee,
 			DispatchPayload = Self::InboundPayload,
 		>;
+		/// Registry of relayers that are delivering messages to this chain.
+		type RelayersRegistry: RelayersRegistry<Self::AccountId>;
+		/// Deposit that the relayer pays for every inbound message that is put to the dispatch queue.
+		type QueuedMessagesDeposit: QueuedMessagesDeposit<Self::AccountId>;
 	}
 
 	/// Shortcut to messages proof type for Config.
@@ -187,10 +223,41 @@ pub mod pallet {
 		<T as frame_system::Config>::AccountId,
 	>>::MessagesDeliveryProof;
 
+	/// The current storage version.
+	///
+	/// Version 1 has added the `latest_expired_nonce` field to outbound lanes data, the lanes
+	/// registry and the `OutboundMessagesToPrune` value.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(_block: BlockNumberFor<T>) -> Weight {
+			// queued messages must be dispatched even if there's no weight left for `on_idle`
+			dispatch_queued_messages::<T, I>(T::QueuedMessagesDispatchWeight::get())
+		}
+
+		fn on_idle(_block: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let dispatch_weight = dispatch_queued_messages::<T, I>(remaining_weight);
+			let prune_weight = prune_confirmed_messages::<T, I>(remaining_weight.saturating_sub(dispatch_weight));
+			let used_weight = dispatch_weight.saturating_add(prune_weight);
+			let prune_rates_weight =
+				prune_expired_account_messages_rates::<T, I>(remaining_weight.saturating_sub(used_weight));
+			let used_weight = used_weight.saturating_add(prune_rates_weight);
+			let decommission_weight = decommission_lanes::<T, I>(remaining_weight.saturating_sub(used_weight));
+			used_weight.saturating_add(decommission_weight)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		/// Change `PalletOwner`.
@@ -214,10 +281,33 @@ pub mod pallet {
 
 		/// Halt or resume all/some pallet operations.
 		///
-		/// May only be called either by root, or by `PalletOwner`.
-		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		/// May only be called either by root, or by `PalletOwner`. Only root may switch the pallet
+		/// to or from the `Decommissioning` mode.
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 4), DispatchClass::Operational))]
 		pub fn set_operating_mode(origin: OriginFor<T>, operating_mode: OperatingMode) -> DispatchResult {
-			ensure_owner_or_root::<T, I>(origin)?;
+			let current_operating_mode = PalletOperatingMode::<T, I>::get();
+			let is_decommissioning = current_operating_mode == OperatingMode::Decommissioning;
+			let start_decommissioning = operating_mode == OperatingMode::Decommissioning;
+			if is_decommissioning || start_decommissioning {
+				ensure_root(origin)?;
+			} else {
+				ensure_owner_or_root::<T, I>(origin)?;
+			}
+
+			if start_decommissioning && !is_decommissioning {
+				// proofs of undelivered messages are only valid during single decommissioning
+				ensure!(
+					DecommissioningLanes::<T, I>::iter_keys().next().is_none(),
+					Error::<T, I>::DecommissioningLanesNotCleared
+				);
+				Decommissioning::<T, I>::put(DecommissioningProgress::default());
+				Self::deposit_event(Event::DecommissioningStarted);
+			} else if !start_decommissioning && is_decommissioning {
+				// proofs of undelivered messages are removed later, in the `on_idle` hook
+				Decommissioning::<T, I>::kill();
+				DecommissioningLanesCursor::<T, I>::kill();
+			}
+
 			PalletOperatingMode::<T, I>::put(operating_mode);
 			log::info!(
 				target: "runtime::bridge-messages",
@@ -241,18 +331,25 @@ pub mod pallet {
 		}
 
 		/// Send message over lane.
-		#[pallet::weight(T::WeightInfo::send_message_weight(payload, T::DbWeight::get()))]
+		///
+		/// If `ttl` is specified, the message expires after `ttl` blocks. If the expired message
+		/// is still not delivered, its submitter may call `expire_message` to get the
+		/// `delivery_and_dispatch_fee` back once the bridged chain skips the message.
+		#[pallet::weight(send_message_weight::<T, I>(payload, ttl))]
 		pub fn send_message(
 			origin: OriginFor<T>,
 			lane_id: LaneId,
 			payload: T::OutboundPayload,
 			delivery_and_dispatch_fee: T::OutboundMessageFee,
+			ttl: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			crate::send_message::<T, I>(
 				origin.into().map_err(|_| BadOrigin)?,
 				lane_id,
 				payload,
 				delivery_and_dispatch_fee,
+				ttl,
+				None,
 			)
 			.map(|sent_message| sent_message.post_dispatch_info)
 		}
@@ -266,6 +363,7 @@ pub mod pallet {
 			additional_fee: T::OutboundMessageFee,
 		) -> DispatchResultWithPostInfo {
 			ensure_not_halted::<T, I>()?;
+			ensure_lane_not_halted::<T, I>(&lane_id)?;
 			// if someone tries to pay for already-delivered message, we're rejecting this intention
 			// (otherwise this additional fee will be locked forever in relayers fund)
 			//
@@ -276,11 +374,23 @@ pub mod pallet {
 				nonce > lane.data().latest_received_nonce,
 				Error::<T, I>::MessageIsAlreadyDelivered
 			);
+			ensure!(
+				nonce > lane.data().latest_expired_nonce,
+				Error::<T, I>::MessageIsExpired
+			);
 			ensure!(
 				nonce <= lane.data().latest_generated_nonce,
 				Error::<T, I>::MessageIsNotYetSent
 			);
 
+			// if someone tries to pay for cancelled message, we're rejecting this intention (the
+			// message is never dispatched, so there's no reason to pay more for it)
+			let message_key = MessageKey { lane_id, nonce };
+			ensure!(
+				!OutboundMessagesCancellations::<T, I>::contains_key(&message_key),
+				Error::<T, I>::MessageIsCancelled
+			);
+
 			// withdraw additional fee from submitter
 			let submitter = origin.into().map_err(|_| BadOrigin)?;
 			T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
@@ -303,7 +413,6 @@ pub mod pallet {
 			})?;
 
 			// and finally update fee in the storage
-			let message_key = MessageKey { lane_id, nonce };
 			let message_size = OutboundMessages::<T, I>::mutate(message_key, |message_data| {
 				// saturating_add is fine here - overflow here means that someone controls all
 				// chain funds, which shouldn't ever happen + `pay_delivery_and_dispatch_fee`
@@ -332,7 +441,10 @@ pub mod pallet {
 		/// The weight of the call assumes that the transaction always brings outbound lane
 		/// state update. Because of that, the submitter (relayer) has no benefit of not including
 		/// this data in the transaction, so reward confirmations lags should be minimal.
-		#[pallet::weight(T::WeightInfo::receive_messages_proof_weight(proof, *messages_count, *dispatch_weight))]
+		#[pallet::weight(
+			T::WeightInfo::receive_messages_proof_weight(proof, *messages_count, *dispatch_weight)
+				.saturating_add(T::DbWeight::get().reads(1))
+		)]
 		pub fn receive_messages_proof(
 			origin: OriginFor<T>,
 			relayer_id_at_bridged_chain: T::InboundRelayer,
@@ -359,33 +471,52 @@ pub mod pallet {
 			//
 			// The DeclaredWeight is exactly what's computed here. Unfortunately it is impossible
 			// to get pre-computed value (and it has been already computed by the executive).
-			let declared_weight = T::WeightInfo::receive_messages_proof_weight(&proof, messages_count, dispatch_weight);
+			//
+			// The lane operating mode read isn't covered by the weight formula, so it is added here.
+			let declared_weight = T::WeightInfo::receive_messages_proof_weight(&proof, messages_count, dispatch_weight)
+				.saturating_add(T::DbWeight::get().reads(1));
 			let mut actual_weight = declared_weight;
 
 			// verify messages proof && convert proof into messages
-			let messages = verify_and_decode_messages_proof::<
-				T::SourceHeaderChain,
-				T::InboundMessageFee,
-				T::InboundPayload,
-			>(proof, messages_count)
-			.map_err(|err| {
-				log::trace!(
-					target: "runtime::bridge-messages",
-					"Rejecting invalid messages proof: {:?}",
-					err,
-				);
+			let messages =
+				match verify_messages_proof::<T::SourceHeaderChain, T::InboundMessageFee>(proof, messages_count) {
+					Ok(messages) => messages,
+					Err(err) => {
+						log::trace!(
+							target: "runtime::bridge-messages",
+							"Rejecting invalid messages proof: {:?}",
+							err,
+						);
 
-				Error::<T, I>::InvalidMessagesProof
-			})?;
+						// registered relayers are punished for submitting invalid proofs
+						T::RelayersRegistry::on_invalid_messages_proof(&relayer_id_at_this_chain);
+
+						fail!(Error::<T, I>::InvalidMessagesProof);
+					}
+				};
+
+			// reject the whole proof if it brings messages to the unknown or halted lane
+			for lane_id in messages.keys() {
+				ensure_lane_registered::<T, I>(lane_id)?;
+				ensure_lane_not_halted::<T, I>(lane_id)?;
+			}
+
+			// registered relayers are not paying for delivering new messages
+			let is_registered_relayer = messages
+				.keys()
+				.all(|lane_id| T::RelayersRegistry::is_registered(&relayer_id_at_this_chain, lane_id));
 
 			// dispatch messages and (optionally) update lane(s) state(s)
 			let mut total_messages = 0;
 			let mut valid_messages = 0;
 			let mut dispatch_weight_left = dispatch_weight;
 			for (lane_id, lane_data) in messages {
+				note_lane_proof_received::<T, I>(lane_id);
 				let mut lane = inbound_lane::<T, I>(lane_id);
 
+				let mut latest_expired_nonce = 0;
 				if let Some(lane_state) = lane_data.lane_state {
+					latest_expired_nonce = lane_state.latest_expired_nonce;
 					let updated_latest_confirmed_nonce = lane.receive_state_update(lane_state);
 					if let Some(updated_latest_confirmed_nonce) = updated_latest_confirmed_nonce {
 						log::trace!(
@@ -395,33 +526,89 @@ pub mod pallet {
 							updated_latest_confirmed_nonce,
 						);
 					}
+
+					// messages that have expired at the bridged chain are never dispatched
+					let expired_messages =
+						lane.receive_expired_messages(&relayer_id_at_bridged_chain, latest_expired_nonce);
+					if expired_messages != 0 {
+						log::trace!(
+							target: "runtime::bridge-messages",
+							"Received {} expired messages at lane {:?}",
+							expired_messages,
+							lane_id,
+						);
+					}
 				}
 
 				for message in lane_data.messages {
 					debug_assert_eq!(message.key.lane_id, lane_id);
+					total_messages += 1;
 
-					// ensure that relayer has declared enough weight for dispatching next message on
-					// this lane. We can't dispatch lane messages out-of-order, so if declared weight
-					// is not enough, let's move to next lane
-					let dispatch_weight = T::MessageDispatch::dispatch_weight(&message);
-					if dispatch_weight > dispatch_weight_left {
+					// we can't dispatch lane messages out-of-order. So if there are already queued
+					// messages at the lane, or if relayer has not declared enough weight for dispatching
+					// next message, the message is put to the dispatch queue. Queued messages are
+					// dispatched later, in `on_idle`. Storing message in the queue is covered by the
+					// message delivery weight, because the inbound lane data isn't updated until the
+					// message is dispatched
+					//
+					// messages that have been expired or cancelled at the bridged chain are never dispatched
+					let is_skipped = message.cancelled || message.key.nonce <= latest_expired_nonce;
+					let dispatch_message = DispatchMessage {
+						key: message.key.clone(),
+						data: DispatchMessageData {
+							payload: T::InboundPayload::decode(&mut &message.data.payload[..]),
+							fee: message.data.fee.clone(),
+						},
+					};
+					let dispatch_weight = if is_skipped {
+						0
+					} else {
+						T::MessageDispatch::dispatch_weight(&dispatch_message)
+					};
+					if lane.has_queued_messages() || dispatch_weight > dispatch_weight_left {
 						log::trace!(
 							target: "runtime::bridge-messages",
-							"Cannot dispatch any more messages on lane {:?}. Weight: declared={}, left={}",
-							lane_id,
+							"Queueing message {:?}. Weight: declared={}, left={}",
+							message.key,
 							dispatch_weight,
 							dispatch_weight_left,
 						);
-						break;
+
+						let receival_result = lane.queue_message(
+							&relayer_id_at_bridged_chain,
+							&relayer_id_at_this_chain,
+							message.key.nonce,
+							message.data,
+							is_skipped,
+						);
+						if receival_result == ReceivalResult::Queued {
+							valid_messages += 1;
+						}
+
+						// the dispatch fee of messages that are paying it at this chain is paid when the
+						// message is dispatched, so let's refund relayer with this extra cost. For other
+						// messages, the overhead covers reservation of the queued message deposit
+						let is_fee_paid_at_this_chain = matches!(
+							T::MessageDispatch::dispatch_fee_payment(&dispatch_message),
+							DispatchFeePayment::AtTargetChain | DispatchFeePayment::AtTargetChainInAsset(_)
+						);
+						if is_fee_paid_at_this_chain {
+							actual_weight =
+								actual_weight.saturating_sub(T::WeightInfo::pay_inbound_dispatch_fee_overhead());
+						}
+						continue;
 					}
-					total_messages += 1;
 
-					let receival_result = lane.receive_message::<T::MessageDispatch, T::AccountId>(
-						&relayer_id_at_bridged_chain,
-						&relayer_id_at_this_chain,
-						message.key.nonce,
-						message.data,
-					);
+					let receival_result = if is_skipped {
+						lane.receive_skipped_message(&relayer_id_at_bridged_chain, message.key.nonce)
+					} else {
+						lane.receive_message::<T::MessageDispatch, T::AccountId>(
+							&relayer_id_at_bridged_chain,
+							&relayer_id_at_this_chain,
+							message.key.nonce,
+							dispatch_message.data,
+						)
+					};
 
 					// note that we're returning unspent weight to relayer even if message has been
 					// rejected by the lane. This allows relayers to submit spam transactions with
@@ -437,7 +624,13 @@ pub mod pallet {
 								!dispatch_result.dispatch_fee_paid_during_dispatch,
 							)
 						}
-						ReceivalResult::InvalidNonce
+						ReceivalResult::Skipped => {
+							valid_messages += 1;
+							(dispatch_weight, true)
+						}
+						ReceivalResult::Queued
+						| ReceivalResult::FailedToReserveDeposit
+						| ReceivalResult::InvalidNonce
 						| ReceivalResult::TooManyUnrewardedRelayers
 						| ReceivalResult::TooManyUnconfirmedMessages => (dispatch_weight, true),
 					};
@@ -468,22 +661,30 @@ pub mod pallet {
 
 			Ok(PostDispatchInfo {
 				actual_weight: Some(actual_weight),
-				pays_fee: Pays::Yes,
+				pays_fee: if is_registered_relayer && valid_messages != 0 {
+					Pays::No
+				} else {
+					Pays::Yes
+				},
 			})
 		}
 
 		/// Receive messages delivery proof from bridged chain.
-		#[pallet::weight(T::WeightInfo::receive_messages_delivery_proof_weight(
-			proof,
-			relayers_state,
-			T::DbWeight::get(),
-		))]
+		#[pallet::weight(
+			T::WeightInfo::receive_messages_delivery_proof_weight(proof, relayers_state, T::DbWeight::get())
+				.saturating_add(T::DbWeight::get().reads(1))
+		)]
 		pub fn receive_messages_delivery_proof(
 			origin: OriginFor<T>,
 			proof: MessagesDeliveryProofOf<T, I>,
 			relayers_state: UnrewardedRelayersState,
 		) -> DispatchResultWithPostInfo {
-			ensure_not_halted::<T, I>()?;
+			// delivery confirmations are accepted while the bridge is being decommissioned, because
+			// relayers still need to be rewarded for delivering messages
+			ensure!(
+				PalletOperatingMode::<T, I>::get() != OperatingMode::Halted,
+				Error::<T, I>::Halted
+			);
 
 			// why do we need to know the weight of this (`receive_messages_delivery_proof`) call? Because
 			// we may want to return some funds for messages that are not processed by the delivery callback,
@@ -494,9 +695,12 @@ pub mod pallet {
 			//
 			// The DeclaredWeight is exactly what's computed here. Unfortunately it is impossible
 			// to get pre-computed value (and it has been already computed by the executive).
+			//
+			// The lane operating mode read isn't covered by the weight formula, so it is added here.
 			let single_message_callback_overhead = T::WeightInfo::single_message_callback_overhead(T::DbWeight::get());
 			let declared_weight =
-				T::WeightInfo::receive_messages_delivery_proof_weight(&proof, &relayers_state, T::DbWeight::get());
+				T::WeightInfo::receive_messages_delivery_proof_weight(&proof, &relayers_state, T::DbWeight::get())
+					.saturating_add(T::DbWeight::get().reads(1));
 			let mut actual_weight = declared_weight;
 
 			let confirmation_relayer = ensure_signed(origin)?;
@@ -509,6 +713,8 @@ pub mod pallet {
 
 				Error::<T, I>::InvalidMessagesDeliveryProof
 			})?;
+			ensure_lane_registered::<T, I>(&lane_id)?;
+			ensure_lane_not_halted::<T, I>(&lane_id)?;
 
 			// verify that the relayer has declared correct `lane_data::relayers` state
 			// (we only care about total number of entries and messages, because this affects call weight)
@@ -549,12 +755,51 @@ pub mod pallet {
 				};
 
 			if let Some(confirmed_messages) = confirmed_messages {
+				// read fees and senders of all confirmed messages. We never prune unconfirmed messages,
+				// so the message may only be missing if the pallet is decommissioned
+				//
+				// expired and cancelled messages are received without dispatch by the bridged chain, so
+				// their fees are refunded to senders. If such message has been dispatched anyway (it may
+				// happen if it has been delivered before the bridged chain has learned about its
+				// expiration or cancellation), the relayer is rewarded for its delivery
+				// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
+				let mut confirmed_messages_fees = BTreeMap::new();
+				let mut confirmed_messages_senders = MessagesSenders::new();
+				let mut skipped_messages = Vec::new();
+				for nonce in confirmed_messages.begin..=confirmed_messages.end {
+					let message_key = MessageKey { lane_id, nonce };
+					let sender = outbound_message_sender::<T, I>(&message_key);
+					if let Some(message_data) = OutboundMessages::<T, I>::get(&message_key) {
+						if confirmed_messages.message_skipped(nonce) {
+							skipped_messages.push((nonce, sender.clone(), message_data.fee));
+						} else {
+							confirmed_messages_fees.insert(nonce, message_data.fee);
+						}
+					}
+					if let Some(sender) = sender {
+						confirmed_messages_senders.insert(nonce, sender);
+					}
+				}
+
+				// refund fees of skipped messages
+				let preliminary_refunds_overhead = relayers_state
+					.total_messages
+					.saturating_mul(T::WeightInfo::single_message_refund_overhead(T::DbWeight::get()));
+				let actual_refunds_overhead = (skipped_messages.len() as MessageNonce)
+					.saturating_mul(T::WeightInfo::single_message_refund_overhead(T::DbWeight::get()));
+				refund_skipped_messages::<T, I>(&lane_id, skipped_messages);
+				actual_weight =
+					actual_weight.saturating_sub(preliminary_refunds_overhead.saturating_sub(actual_refunds_overhead));
+
 				// handle messages delivery confirmation
 				let preliminary_callback_overhead = relayers_state
 					.total_messages
 					.saturating_mul(single_message_callback_overhead);
-				let actual_callback_weight =
-					T::OnDeliveryConfirmed::on_messages_delivered(&lane_id, &confirmed_messages);
+				let actual_callback_weight = T::OnDeliveryConfirmed::on_messages_delivered(
+					&lane_id,
+					&confirmed_messages,
+					&confirmed_messages_senders,
+				);
 				match preliminary_callback_overhead.checked_sub(actual_callback_weight) {
 					Some(difference) if difference == 0 => (),
 					Some(difference) => {
@@ -580,6 +825,21 @@ pub mod pallet {
 					}
 				}
 
+				// pass dispatch results to owners of confirmed messages
+				let preliminary_owners_overhead = relayers_state
+					.total_messages
+					.saturating_mul(T::WeightInfo::single_message_owner_callback_overhead(T::DbWeight::get()));
+				actual_weight = actual_weight.saturating_sub(on_messages_dispatched::<T, I>(
+					&lane_id,
+					&confirmed_messages,
+					preliminary_owners_overhead,
+				));
+
+				// confirmed messages may now be pruned
+				OutboundMessagesToPrune::<T, I>::mutate(|messages_to_prune| {
+					*messages_to_prune = messages_to_prune.saturating_add(confirmed_messages.total_messages())
+				});
+
 				// emit 'delivered' event
 				let received_range = confirmed_messages.begin..=confirmed_messages.end;
 				Self::deposit_event(Event::MessagesDelivered(lane_id, confirmed_messages));
@@ -594,18 +854,28 @@ pub mod pallet {
 					// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
 					let mut relayer_reward = relayers_rewards.entry(entry.relayer).or_default();
 					for nonce in nonce_begin..nonce_end + 1 {
-						let message_data = OutboundMessages::<T, I>::get(MessageKey { lane_id, nonce })
-							.expect("message was just confirmed; we never prune unconfirmed messages; qed");
-						relayer_reward.reward = relayer_reward.reward.saturating_add(&message_data.fee);
+						let message_fee = match confirmed_messages_fees.get(&nonce) {
+							Some(message_fee) => message_fee,
+							None => continue,
+						};
+						relayer_reward.reward = relayer_reward.reward.saturating_add(message_fee);
 						relayer_reward.messages += 1;
 					}
 				}
 			}
 
+			// update lane state in the registry: the lane is either opened at the bridged chain, or
+			// it is closing and all messages are delivered
+			note_lane_proof_received::<T, I>(lane_id);
+			if Lanes::<T, I>::get(&lane_id) == Some(LaneState::Closing) && is_outbound_lane_drained::<T, I>(&lane_id) {
+				set_lane_state::<T, I>(lane_id, LaneState::Closed);
+			}
+
 			// if some new messages have been confirmed, reward relayers
 			if !relayers_rewards.is_empty() {
 				let relayer_fund_account = Self::relayer_fund_account_id();
 				<T as Config<I>>::MessageDeliveryAndDispatchPayment::pay_relayers_rewards(
+					lane_id,
 					&confirmation_relayer,
 					relayers_rewards,
 					&relayer_fund_account,
@@ -624,6 +894,292 @@ pub mod pallet {
 				pays_fee: Pays::Yes,
 			})
 		}
+
+		/// Halt or resume all/some operations at given lane.
+		///
+		/// The pallet-wide operating mode (see `set_operating_mode`) always has priority over the
+		/// lane operating mode. So e.g. if the pallet is halted, nothing will work at the lane, even
+		/// if the lane itself is in `Normal` mode.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		///
+		/// The weight is: single read for permissions check + 2 writes for lane mode and event.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn set_lane_operating_mode(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			operating_mode: OperatingMode,
+		) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			ensure!(
+				operating_mode != OperatingMode::Decommissioning,
+				Error::<T, I>::UnsupportedLaneOperatingMode
+			);
+			if operating_mode == OperatingMode::Normal {
+				LaneOperatingModes::<T, I>::remove(&lane_id);
+			} else {
+				LaneOperatingModes::<T, I>::insert(&lane_id, operating_mode);
+			}
+			log::info!(
+				target: "runtime::bridge-messages",
+				"Setting lane {:?} operating mode to {:?}.",
+				lane_id,
+				operating_mode,
+			);
+			Self::deposit_event(Event::LaneOperatingModeChanged(lane_id, operating_mode));
+			Ok(())
+		}
+
+		/// Register new lane.
+		///
+		/// The lane is registered in the `Opening` state and it switches to the `Open` state once the
+		/// first proof from the bridged chain is received at this lane. Keep in mind that the lane
+		/// must also be opened at the bridged chain.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn open_lane(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			ensure!(
+				!Lanes::<T, I>::contains_key(&lane_id),
+				Error::<T, I>::LaneAlreadyRegistered
+			);
+			set_lane_state::<T, I>(lane_id, LaneState::Opening);
+			Ok(())
+		}
+
+		/// Close the lane.
+		///
+		/// The closing lane rejects all new outbound messages, but still accepts delivery proofs and
+		/// confirmations. Once all messages that have been sent over this lane are delivered, the lane
+		/// switches to the `Closed` state.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 2), DispatchClass::Operational))]
+		pub fn close_lane(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			let lane_state = Lanes::<T, I>::get(&lane_id).ok_or(Error::<T, I>::UnknownLane)?;
+			ensure!(
+				lane_state.is_accepting_outbound_messages(),
+				Error::<T, I>::InvalidLaneStateTransition
+			);
+			let new_lane_state = if is_outbound_lane_drained::<T, I>(&lane_id) {
+				LaneState::Closed
+			} else {
+				LaneState::Closing
+			};
+			set_lane_state::<T, I>(lane_id, new_lane_state);
+			Ok(())
+		}
+
+		/// Reopen closing or closed lane.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn reopen_lane(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			let lane_state = Lanes::<T, I>::get(&lane_id).ok_or(Error::<T, I>::UnknownLane)?;
+			ensure!(
+				!lane_state.is_accepting_outbound_messages(),
+				Error::<T, I>::InvalidLaneStateTransition
+			);
+			set_lane_state::<T, I>(lane_id, LaneState::Open);
+			Ok(())
+		}
+
+		/// Expire the undelivered message and schedule refund of its fee.
+		///
+		/// Messages may only be expired in order, i.e. the message must be the oldest message at
+		/// the lane that is neither delivered, nor expired. The `proof` is the proof of the inbound
+		/// lane state at the bridged chain. It must be at least as fresh as the latest delivery
+		/// confirmation, received by this chain, and it must show that the message has not been
+		/// received by the bridged chain.
+		///
+		/// The bridged chain learns about expired messages from the outbound lane state that is
+		/// included in the messages proof, so it never dispatches them. Expired messages are kept
+		/// in the storage until their delivery is confirmed, so relayers are still able to prove them.
+		/// The fee is returned from the relayers fund to the message submitter when the bridged chain
+		/// confirms that the message has been received without dispatch. If the message has been
+		/// delivered and dispatched before the bridged chain has learned about its expiration, the
+		/// relayer is rewarded instead. The owner of the message (if any) is notified about the
+		/// actual dispatch result at the same time, because the message may still be dispatched.
+		///
+		/// May only be called by the message submitter.
+		#[pallet::weight(T::WeightInfo::expire_message_weight(proof, T::DbWeight::get()))]
+		pub fn expire_message(
+			origin: OriginFor<T>,
+			proof: MessagesDeliveryProofOf<T, I>,
+			lane_id: LaneId,
+			nonce: MessageNonce,
+		) -> DispatchResult {
+			ensure_not_halted::<T, I>()?;
+			ensure_lane_not_halted::<T, I>(&lane_id)?;
+
+			let mut lane_data = OutboundLanes::<T, I>::get(&lane_id);
+			ensure!(
+				nonce > lane_data.latest_received_nonce,
+				Error::<T, I>::MessageIsAlreadyDelivered
+			);
+			ensure!(nonce > lane_data.latest_expired_nonce, Error::<T, I>::MessageIsExpired);
+			ensure!(
+				nonce <= lane_data.latest_generated_nonce,
+				Error::<T, I>::MessageIsNotYetSent
+			);
+			ensure!(
+				nonce == sp_std::cmp::max(lane_data.latest_received_nonce, lane_data.latest_expired_nonce) + 1,
+				Error::<T, I>::MessageIsNotOldestPending
+			);
+
+			let message_key = MessageKey { lane_id, nonce };
+			let (expires_at, message_submitter) =
+				OutboundMessagesExpiry::<T, I>::get(&message_key).ok_or(Error::<T, I>::MessageHasNoTtl)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > expires_at,
+				Error::<T, I>::MessageIsNotYetExpired
+			);
+			let submitter = origin.into().map_err(|_| BadOrigin)?;
+			ensure!(submitter == message_submitter, BadOrigin);
+
+			// the message must not be received by the bridged chain
+			ensure_message_is_not_received::<T, I>(proof, &lane_id, &lane_data, nonce)?;
+
+			lane_data.latest_expired_nonce = nonce;
+			OutboundLanes::<T, I>::insert(&lane_id, lane_data);
+			OutboundMessagesExpiry::<T, I>::remove(&message_key);
+			// senders of messages that have been sent before the storage version 1, are only stored
+			// in the expiration entry, but we need the sender to refund the fee
+			OutboundMessagesSenders::<T, I>::insert(&message_key, submitter);
+
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Message {:?}/{:?} has expired. Its fee will be refunded once delivery is confirmed",
+				lane_id,
+				nonce,
+			);
+
+			Self::deposit_event(Event::MessageExpired(lane_id, nonce));
+			Ok(())
+		}
+
+		/// Send batch of messages over lane.
+		///
+		/// Messages are either all accepted, or all rejected. Every message is verified as if it
+		/// has been sent using `send_message` call, but the combined fee of all messages is paid
+		/// in a single transfer. Messages get consecutive nonces.
+		#[pallet::weight(send_messages_batch_weight::<T, I>(messages))]
+		pub fn send_messages_batch(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			messages: Vec<(T::OutboundPayload, T::OutboundMessageFee)>,
+		) -> DispatchResultWithPostInfo {
+			crate::send_messages::<T, I>(origin.into().map_err(|_| BadOrigin)?, lane_id, messages)
+				.map(|sent_messages| sent_messages.post_dispatch_info)
+		}
+
+		/// Cancel undelivered message and schedule refund of its fee.
+		///
+		/// The `proof` is the proof of the inbound lane state at the bridged chain. It must be at
+		/// least as fresh as the latest delivery confirmation, received by this chain, and it must
+		/// show that the message has not been received by the bridged chain.
+		///
+		/// The cancelled message still needs to be delivered to the bridged chain to unblock the
+		/// lane. The bridged chain learns about cancellation from the cancellation entry that is
+		/// proved along with the message, so it never dispatches it. The fee is returned from the
+		/// relayers fund to the message submitter when the bridged chain confirms that the message
+		/// has been received without dispatch. If the message has been delivered and dispatched
+		/// before the bridged chain has learned about its cancellation, the relayer is rewarded
+		/// instead. The owner of the message (if any) is notified about the actual dispatch result
+		/// at the same time.
+		///
+		/// May only be called by the message submitter.
+		#[pallet::weight(T::WeightInfo::cancel_message_weight(proof, T::DbWeight::get()))]
+		pub fn cancel_message(
+			origin: OriginFor<T>,
+			proof: MessagesDeliveryProofOf<T, I>,
+			lane_id: LaneId,
+			nonce: MessageNonce,
+		) -> DispatchResult {
+			ensure_not_halted::<T, I>()?;
+			ensure_lane_not_halted::<T, I>(&lane_id)?;
+
+			let lane_data = OutboundLanes::<T, I>::get(&lane_id);
+			ensure!(
+				nonce > lane_data.latest_received_nonce,
+				Error::<T, I>::MessageIsAlreadyDelivered
+			);
+			ensure!(nonce > lane_data.latest_expired_nonce, Error::<T, I>::MessageIsExpired);
+			ensure!(
+				nonce <= lane_data.latest_generated_nonce,
+				Error::<T, I>::MessageIsNotYetSent
+			);
+
+			let message_key = MessageKey { lane_id, nonce };
+			ensure!(
+				!OutboundMessagesCancellations::<T, I>::contains_key(&message_key),
+				Error::<T, I>::MessageIsCancelled
+			);
+			let submitter = origin.into().map_err(|_| BadOrigin)?;
+			ensure!(
+				outbound_message_sender::<T, I>(&message_key) == Some(submitter),
+				BadOrigin
+			);
+
+			// the message must not be received by the bridged chain
+			ensure_message_is_not_received::<T, I>(proof, &lane_id, &lane_data, nonce)?;
+
+			OutboundMessagesCancellations::<T, I>::insert(&message_key, frame_system::Pallet::<T>::block_number());
+
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Message {:?}/{:?} has been cancelled. Its fee will be refunded once delivery is confirmed",
+				lane_id,
+				nonce,
+			);
+
+			Self::deposit_event(Event::MessageCancelled(lane_id, nonce));
+			Ok(())
+		}
+
+		/// Prove that messages of the lane have not been received by the bridged chain, so their
+		/// fees may be refunded while the bridge is being decommissioned.
+		///
+		/// The `proof` is the proof of the inbound lane state at the bridged chain. It must be at
+		/// least as fresh as the latest delivery confirmation, received by this chain. Fees of all
+		/// messages that have not been received by the bridged chain (according to the proof) are
+		/// refunded in the `on_idle` hook. Messages that have been received, but their delivery is
+		/// not yet confirmed, are never refunded - refunds at the lane start once their delivery
+		/// is confirmed and relayers are rewarded.
+		///
+		/// May only be called when the pallet is in the `Decommissioning` mode.
+		#[pallet::weight(T::WeightInfo::prove_undelivered_messages_weight(proof, T::DbWeight::get()))]
+		pub fn prove_undelivered_messages(
+			origin: OriginFor<T>,
+			proof: MessagesDeliveryProofOf<T, I>,
+			lane_id: LaneId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(
+				PalletOperatingMode::<T, I>::get() == OperatingMode::Decommissioning,
+				Error::<T, I>::NotDecommissioning
+			);
+
+			ensure_lane_registered::<T, I>(&lane_id)?;
+
+			let lane_data = OutboundLanes::<T, I>::get(&lane_id);
+			let last_delivered_nonce = verify_inbound_lane_state_proof::<T, I>(proof, &lane_id, &lane_data)?;
+			DecommissioningLanes::<T, I>::mutate(&lane_id, |proved_nonce| {
+				*proved_nonce = Some(sp_std::cmp::max(proved_nonce.unwrap_or(0), last_delivered_nonce));
+			});
+
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Messages after {:?}/{} are proved to be undelivered",
+				lane_id,
+				last_delivered_nonce,
+			);
+
+			Ok(())
+		}
 	}
 
 	#[pallet::event]
@@ -636,12 +1192,46 @@ pub mod pallet {
 		MessageAccepted(LaneId, MessageNonce),
 		/// Messages in the inclusive range have been delivered to the bridged chain.
 		MessagesDelivered(LaneId, DeliveredMessages),
+		/// Lane operating mode has been changed.
+		LaneOperatingModeChanged(LaneId, OperatingMode),
+		/// Lane state in the lanes registry has been changed.
+		LaneStateChanged(LaneId, LaneState),
+		/// Message has expired. Its fee is refunded to the submitter once the bridged chain confirms
+		/// that the message has been received without dispatch.
+		MessageExpired(LaneId, MessageNonce),
+		/// Fee of the message that has been received without dispatch by the bridged chain has been
+		/// refunded to the submitter.
+		MessageFeeRefunded(LaneId, MessageNonce),
+		/// Queued messages at the lane can't be dispatched, because there are too many unrewarded
+		/// relayer entries at the lane. Dispatch resumes when the bridged chain confirms delivery
+		/// of some messages.
+		QueuedMessagesDispatchStalled(LaneId),
+		/// Batch of messages with nonces in the inclusive range has been accepted and is waiting
+		/// to be delivered.
+		MessagesAccepted(LaneId, MessageNonce, MessageNonce),
+		/// The bridge decommissioning has been started.
+		DecommissioningStarted,
+		/// All lanes have been drained and the bridge decommissioning is completed.
+		DecommissioningCompleted(DecommissioningProgress<T::OutboundMessageFee>),
+		/// Message has been cancelled. Its fee is refunded to the submitter once the bridged chain
+		/// confirms that the message has been received without dispatch.
+		MessageCancelled(LaneId, MessageNonce),
 	}
 
 	#[pallet::error]
 	pub enum Error<T, I = ()> {
 		/// All pallet operations are halted.
 		Halted,
+		/// All (or some) operations at the lane are halted.
+		LaneHalted,
+		/// The lane is not registered in the lanes registry.
+		UnknownLane,
+		/// The lane is already registered in the lanes registry.
+		LaneAlreadyRegistered,
+		/// The lane is closing or closed and it doesn't accept new outbound messages.
+		LaneIsClosed,
+		/// The lane can't switch from its current state to the requested state.
+		InvalidLaneStateTransition,
 		/// Message has been treated as invalid by chain verifier.
 		MessageRejectedByChainVerifier,
 		/// Message has been treated as invalid by lane verifier.
@@ -662,9 +1252,38 @@ pub mod pallet {
 		MessageIsAlreadyDelivered,
 		/// The message someone is trying to work with (i.e. increase fee) is not yet sent.
 		MessageIsNotYetSent,
+		/// The message someone is trying to work with (i.e. increase fee) has expired.
+		MessageIsExpired,
+		/// The message has been sent without time-to-live, so it never expires.
+		MessageHasNoTtl,
+		/// The message time-to-live has not yet passed.
+		MessageIsNotYetExpired,
+		/// There are older messages at the lane that are neither delivered, nor expired.
+		MessageIsNotOldestPending,
+		/// The batch of messages is empty.
+		EmptyMessagesBatch,
 		/// The number of actually confirmed messages is going to be larger than the number of messages in the proof.
 		/// This may mean that this or bridged chain storage is corrupted.
 		TryingToConfirmMoreMessagesThanExpected,
+		/// The messages delivery proof is older than the latest delivery confirmation.
+		StaleMessagesDeliveryProof,
+		/// The submitter has already sent `MaxMessagesPerAccountPerPeriod` messages during
+		/// current rate limit period.
+		TooManyMessagesFromAccount,
+		/// Total size of messages that are sent over the lane in the current block would exceed
+		/// `MaxLaneBytesPerBlock`.
+		TooManyBytesAtLane,
+		/// The bridge is being decommissioned.
+		Decommissioning,
+		/// The operating mode can't be used at lanes.
+		UnsupportedLaneOperatingMode,
+		/// The bridge is not being decommissioned.
+		NotDecommissioning,
+		/// Proofs of undelivered messages that have been submitted during previous decommissioning
+		/// are not yet removed. They're removed in the `on_idle` hook.
+		DecommissioningLanesNotCleared,
+		/// The message someone is trying to work with (i.e. increase fee) has been cancelled.
+		MessageIsCancelled,
 	}
 
 	/// Optional pallet owner.
@@ -684,6 +1303,20 @@ pub mod pallet {
 	#[pallet::getter(fn operating_mode)]
 	pub type PalletOperatingMode<T: Config<I>, I: 'static = ()> = StorageValue<_, OperatingMode, ValueQuery>;
 
+	/// Map of lane id => lane operating mode.
+	///
+	/// Only lanes with non-`Normal` operating mode are stored here. The pallet-wide operating mode
+	/// (`PalletOperatingMode`) has priority over the lane operating mode.
+	#[pallet::storage]
+	pub type LaneOperatingModes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, OperatingMode, ValueQuery>;
+
+	/// Map of lane id => lane state.
+	///
+	/// Only lanes that are registered here may be used to send and receive messages.
+	#[pallet::storage]
+	pub type Lanes<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, LaneId, LaneState>;
+
 	/// Map of lane id => inbound lane data.
 	#[pallet::storage]
 	pub type InboundLanes<T: Config<I>, I: 'static = ()> =
@@ -699,26 +1332,155 @@ pub mod pallet {
 	pub type OutboundMessages<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, MessageKey, MessageData<T::OutboundMessageFee>>;
 
-	#[pallet::genesis_config]
-	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
-		/// Initial pallet operating mode.
-		pub operating_mode: OperatingMode,
-		/// Initial pallet owner.
-		pub owner: Option<T::AccountId>,
-		/// Dummy marker.
-		pub phantom: sp_std::marker::PhantomData<I>,
-	}
+	/// Map of message key => message submitter.
+	///
+	/// Senders are stored separately from messages, because messages are delivered to the bridged
+	/// chain as they are stored. Messages that have been sent before senders have been recorded
+	/// have no entries in this map. Fees of such messages can't be refunded.
+	#[pallet::storage]
+	pub type OutboundMessagesSenders<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, bp_messages::source_chain::Sender<T::AccountId>>;
 
-	#[cfg(feature = "std")]
-	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
-		fn default() -> Self {
-			Self {
-				operating_mode: Default::default(),
-				owner: Default::default(),
-				phantom: Default::default(),
-			}
-		}
-	}
+	/// Map of message key => block at which the message has been cancelled by its submitter.
+	///
+	/// Cancellation entries are proved to the bridged chain along with messages, so cancelled
+	/// messages are received there without dispatch. Entries are removed along with messages.
+	#[pallet::storage]
+	pub type OutboundMessagesCancellations<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, T::BlockNumber>;
+
+	/// Map of lane id => nonce of the latest message in the inbound lane dispatch queue.
+	///
+	/// The queue contains messages in the `last_delivered_nonce+1..=latest_queued_nonce` range. There's
+	/// no entry for the lane if its dispatch queue is empty.
+	#[pallet::storage]
+	pub type InboundLatestQueuedNonces<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, MessageNonce>;
+
+	/// Raw storage key of the `InboundLatestQueuedNonces` entry, where the next queued messages
+	/// dispatch round starts.
+	///
+	/// If it is `None`, dispatch starts from the first lane.
+	#[pallet::storage]
+	pub type InboundQueuedMessagesDispatchCursor<T: Config<I>, I: 'static = ()> = StorageValue<_, Vec<u8>>;
+
+	/// All inbound messages that are received, but not yet dispatched.
+	#[pallet::storage]
+	pub type InboundQueuedMessages<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		MessageKey,
+		QueuedMessage<T::InboundRelayer, T::AccountId, T::InboundMessageFee>,
+	>;
+
+	/// Map of message key => identifier of the message owner.
+	///
+	/// Only messages that have been sent using `MessagesBridge::send_owned_message` are stored
+	/// here. The entry is removed when message delivery is confirmed.
+	#[pallet::storage]
+	pub type OutboundMessagesOwners<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, MessageOwnerId>;
+
+	/// Map of message key => (block at which message expires, message submitter).
+	///
+	/// Only messages that have been sent with time-to-live are stored here.
+	#[pallet::storage]
+	pub type OutboundMessagesExpiry<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, (T::BlockNumber, bp_messages::source_chain::Sender<T::AccountId>)>;
+
+	/// Progress of the bridge decommissioning.
+	///
+	/// It is only stored while the pallet is in the `Decommissioning` operating mode.
+	#[pallet::storage]
+	#[pallet::getter(fn decommissioning_progress)]
+	pub type Decommissioning<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, DecommissioningProgress<T::OutboundMessageFee>>;
+
+	/// Map of lane id => nonce of the latest message that has been received by the bridged chain,
+	/// according to the inbound lane state proof that has been submitted during decommissioning.
+	///
+	/// Messages with larger nonces are proved to be undelivered, so their fees are refunded once
+	/// all received messages are confirmed.
+	#[pallet::storage]
+	pub type DecommissioningLanes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, MessageNonce>;
+
+	/// Raw storage key of the `OutboundLanes` entry, where the next decommissioning round starts,
+	/// and the flag that is `true` if all lanes, processed during the current pass over lanes, are
+	/// drained.
+	///
+	/// If it is `None`, the next round starts new pass from the first outbound lane.
+	#[pallet::storage]
+	pub type DecommissioningLanesCursor<T: Config<I>, I: 'static = ()> = StorageValue<_, (Vec<u8>, bool)>;
+
+	/// Map of message sender => total fee that has been refunded to the sender while the bridge
+	/// has been decommissioned.
+	///
+	/// Together with the `DecommissioningCompleted` event, it forms the final decommissioning
+	/// report, so entries are never removed.
+	#[pallet::storage]
+	#[pallet::getter(fn decommissioning_refunds)]
+	pub type DecommissioningRefunds<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		bp_messages::source_chain::Sender<T::AccountId>,
+		T::OutboundMessageFee,
+		ValueQuery,
+	>;
+
+	/// Map of account => (first block of the current rate limit period, number of messages that
+	/// the account has sent during this period).
+	#[pallet::storage]
+	pub type AccountMessagesRate<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, MessageNonce)>;
+
+	/// Raw storage key of the `AccountMessagesRate` entry, where the next `on_idle` pruning round
+	/// starts.
+	///
+	/// If it is `None`, pruning starts from the first entry.
+	#[pallet::storage]
+	pub type AccountMessagesRatePruningCursor<T: Config<I>, I: 'static = ()> = StorageValue<_, Vec<u8>>;
+
+	/// Map of lane id => (block number, total size of message payloads that have been sent over
+	/// the lane at this block).
+	#[pallet::storage]
+	pub type LaneBytesRate<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, (T::BlockNumber, u32)>;
+
+	/// Number of confirmed outbound messages (across all lanes) that are not yet pruned.
+	#[pallet::storage]
+	#[pallet::getter(fn outbound_messages_to_prune)]
+	pub type OutboundMessagesToPrune<T: Config<I>, I: 'static = ()> = StorageValue<_, MessageNonce, ValueQuery>;
+
+	/// Raw storage key of the `OutboundLanes` entry, where the next `on_idle` pruning round starts.
+	///
+	/// If it is `None`, pruning starts from the first outbound lane.
+	#[pallet::storage]
+	pub type OutboundPruningLaneCursor<T: Config<I>, I: 'static = ()> = StorageValue<_, Vec<u8>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Initial pallet operating mode.
+		pub operating_mode: OperatingMode,
+		/// Initial pallet owner.
+		pub owner: Option<T::AccountId>,
+		/// Lanes that are opened at genesis.
+		pub opened_lanes: Vec<LaneId>,
+		/// Dummy marker.
+		pub phantom: sp_std::marker::PhantomData<I>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self {
+				operating_mode: Default::default(),
+				owner: Default::default(),
+				opened_lanes: Default::default(),
+				phantom: Default::default(),
+			}
+		}
+	}
 
 	#[pallet::genesis_build]
 	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
@@ -727,15 +1489,78 @@ pub mod pallet {
 			if let Some(ref owner) = self.owner {
 				PalletOwner::<T, I>::put(owner);
 			}
+			for lane_id in &self.opened_lanes {
+				Lanes::<T, I>::insert(lane_id, LaneState::Open);
+			}
 		}
 	}
 
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Get operating mode of given lane.
+		pub fn lane_operating_mode(lane: LaneId) -> OperatingMode {
+			LaneOperatingModes::<T, I>::get(&lane)
+		}
+
+		/// Get state of given lane in the lanes registry.
+		pub fn lane_state(lane: LaneId) -> Option<LaneState> {
+			Lanes::<T, I>::get(&lane)
+		}
+
+		/// Get all lanes from the lanes registry with their states.
+		pub fn lanes() -> Vec<(LaneId, LaneState)> {
+			Lanes::<T, I>::iter().collect()
+		}
+
 		/// Get stored data of the outbound message with given nonce.
 		pub fn outbound_message_data(lane: LaneId, nonce: MessageNonce) -> Option<MessageData<T::OutboundMessageFee>> {
 			OutboundMessages::<T, I>::get(MessageKey { lane_id: lane, nonce })
 		}
 
+		/// Get sender of the outbound message with given nonce.
+		///
+		/// Returns `None` if the message is unknown or if it has been sent before senders of
+		/// outbound messages have been recorded.
+		pub fn outbound_message_sender(
+			lane: LaneId,
+			nonce: MessageNonce,
+		) -> Option<bp_messages::source_chain::Sender<T::AccountId>> {
+			outbound_message_sender::<T, I>(&MessageKey { lane_id: lane, nonce })
+		}
+
+		/// Returns true if the outbound message with given nonce has been cancelled by its submitter.
+		pub fn is_outbound_message_cancelled(lane: LaneId, nonce: MessageNonce) -> bool {
+			OutboundMessagesCancellations::<T, I>::contains_key(MessageKey { lane_id: lane, nonce })
+		}
+
+		/// Get encoded payloads and fees of all outbound messages in given inclusive range.
+		///
+		/// The range is clamped to the range of messages that may be stored at the lane (i.e. to
+		/// `[oldest_unpruned_nonce; latest_generated_nonce]`) and no more than
+		/// `MaxUnconfirmedMessagesAtInboundLane` messages are returned. Messages that are missing
+		/// from the storage are also missing from the resulting vector.
+		pub fn outbound_message_payloads(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessagePayloadDetails<T::OutboundMessageFee>> {
+			let lane_data = OutboundLanes::<T, I>::get(&lane);
+			let begin = sp_std::cmp::max(begin, lane_data.oldest_unpruned_nonce);
+			let end = sp_std::cmp::min(
+				sp_std::cmp::min(end, lane_data.latest_generated_nonce),
+				begin.saturating_add(T::MaxUnconfirmedMessagesAtInboundLane::get().saturating_sub(1)),
+			);
+			(begin..=end)
+				.filter_map(|nonce| {
+					let message_data = OutboundMessages::<T, I>::get(MessageKey { lane_id: lane, nonce })?;
+					Some(MessagePayloadDetails {
+						nonce,
+						payload: message_data.payload,
+						delivery_and_dispatch_fee: message_data.fee,
+					})
+				})
+				.collect()
+		}
+
 		/// Get nonce of the latest generated message at given outbound lane.
 		pub fn outbound_latest_generated_nonce(lane: LaneId) -> MessageNonce {
 			OutboundLanes::<T, I>::get(&lane).latest_generated_nonce
@@ -747,15 +1572,33 @@ pub mod pallet {
 		}
 
 		/// Get nonce of the latest received message at given inbound lane.
+		///
+		/// Messages that are received, but not yet dispatched, are not included, because they're
+		/// not considered delivered. So the result matches the inbound lane state that is proved
+		/// to the bridged chain.
 		pub fn inbound_latest_received_nonce(lane: LaneId) -> MessageNonce {
 			InboundLanes::<T, I>::get(&lane).last_delivered_nonce()
 		}
 
+		/// Get nonce of the latest queued message at given inbound lane.
+		///
+		/// Queued messages are received, but not yet dispatched. They must not be delivered again,
+		/// so relayers should deliver messages that follow this nonce. Returns `None` if the lane
+		/// has no queued messages.
+		pub fn inbound_latest_queued_nonce(lane: LaneId) -> Option<MessageNonce> {
+			InboundLatestQueuedNonces::<T, I>::get(&lane)
+		}
+
 		/// Get nonce of the latest confirmed message at given inbound lane.
 		pub fn inbound_latest_confirmed_nonce(lane: LaneId) -> MessageNonce {
 			InboundLanes::<T, I>::get(&lane).last_confirmed_nonce
 		}
 
+		/// Get data of given inbound lane, including all unrewarded relayers entries.
+		pub fn inbound_lane_data(lane: LaneId) -> InboundLaneData<T::InboundRelayer> {
+			InboundLanes::<T, I>::get(&lane)
+		}
+
 		/// Get state of unrewarded relayers set.
 		pub fn inbound_unrewarded_relayers_state(lane: bp_messages::LaneId) -> bp_messages::UnrewardedRelayersState {
 			let relayers = InboundLanes::<T, I>::get(&lane).relayers;
@@ -779,6 +1622,19 @@ pub mod pallet {
 			let encoded_id = bp_runtime::derive_relayer_fund_account_id(bp_runtime::NO_INSTANCE_ID);
 			T::AccountIdConverter::convert(encoded_id)
 		}
+
+		/// Check invariants of all outbound lanes, inbound lanes, outbound messages and inbound
+		/// dispatch queues.
+		///
+		/// The check reads the whole pallet storage, so it must never be called from the runtime
+		/// code, other than `try-runtime` hooks and runtime APIs. Details of the broken invariant
+		/// are logged.
+		pub fn do_try_state() -> Result<(), &'static str> {
+			try_state_outbound_lanes::<T, I>()?;
+			try_state_outbound_messages::<T, I>()?;
+			try_state_inbound_lanes::<T, I>()?;
+			try_state_inbound_queues::<T, I>()
+		}
 	}
 }
 
@@ -798,6 +1654,15 @@ pub mod storage_keys {
 		)
 	}
 
+	/// Storage key of the outbound message cancellation entry in the runtime storage.
+	pub fn message_cancellation_key(pallet_prefix: &str, lane: &LaneId, nonce: MessageNonce) -> StorageKey {
+		storage_map_final_key(
+			pallet_prefix,
+			"OutboundMessagesCancellations",
+			&MessageKey { lane_id: *lane, nonce }.encode(),
+		)
+	}
+
 	/// Storage key of the outbound message lane state in the runtime storage.
 	pub fn outbound_lane_data_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey {
 		storage_map_final_key(pallet_prefix, "OutboundLanes", lane)
@@ -839,7 +1704,26 @@ where
 		message: T::OutboundPayload,
 		delivery_and_dispatch_fee: T::OutboundMessageFee,
 	) -> Result<MessageNonce, Self::Error> {
-		crate::send_message::<T, I>(sender, lane, message, delivery_and_dispatch_fee)
+		crate::send_message::<T, I>(sender, lane, message, delivery_and_dispatch_fee, None, None)
+			.map(|sent_message| sent_message.nonce)
+	}
+
+	fn send_messages(
+		sender: bp_messages::source_chain::Sender<T::AccountId>,
+		lane: LaneId,
+		messages: Vec<(T::OutboundPayload, T::OutboundMessageFee)>,
+	) -> Result<RangeInclusive<MessageNonce>, Self::Error> {
+		crate::send_messages::<T, I>(sender, lane, messages).map(|sent_messages| sent_messages.nonces)
+	}
+
+	fn send_owned_message(
+		owner: MessageOwnerId,
+		sender: bp_messages::source_chain::Sender<T::AccountId>,
+		lane: LaneId,
+		message: T::OutboundPayload,
+		delivery_and_dispatch_fee: T::OutboundMessageFee,
+	) -> Result<MessageNonce, Self::Error> {
+		crate::send_message::<T, I>(sender, lane, message, delivery_and_dispatch_fee, None, Some(owner))
 			.map(|sent_message| sent_message.nonce)
 	}
 }
@@ -852,20 +1736,205 @@ struct SentMessage {
 	pub post_dispatch_info: PostDispatchInfo,
 }
 
+/// Messages that have been sent.
+struct SentMessages {
+	/// Nonces of sent messages.
+	pub nonces: RangeInclusive<MessageNonce>,
+	/// Post-dispatch call info.
+	pub post_dispatch_info: PostDispatchInfo,
+}
+
+/// Weight of the `send_messages_batch` call.
+///
+/// Every message of the batch is paying for itself, as if it has been sent using `send_message` call.
+fn send_messages_batch_weight<T: Config<I>, I: 'static>(
+	messages: &[(T::OutboundPayload, T::OutboundMessageFee)],
+) -> Weight {
+	messages.iter().fold(0, |weight, (payload, _)| {
+		weight.saturating_add(send_message_weight::<T, I>(payload, &None))
+	})
+}
+
+/// Weight of the `send_message` call.
+fn send_message_weight<T: Config<I>, I: 'static>(payload: &T::OutboundPayload, ttl: &Option<T::BlockNumber>) -> Weight {
+	let sender_weight = T::DbWeight::get().writes(1);
+	let expiry_weight = if ttl.is_some() { T::DbWeight::get().writes(1) } else { 0 };
+	T::WeightInfo::send_message_weight(payload, T::DbWeight::get())
+		.saturating_add(sender_weight)
+		.saturating_add(expiry_weight)
+		.saturating_add(rate_limits_weight::<T, I>())
+}
+
+/// Weight of checking and updating rate limits of the submitter account and the lane.
+fn rate_limits_weight<T: Config<I>, I: 'static>() -> Weight {
+	T::DbWeight::get().reads_writes(2, 2)
+}
+
 /// Function that actually sends message.
 fn send_message<T: Config<I>, I: 'static>(
 	submitter: bp_messages::source_chain::Sender<T::AccountId>,
 	lane_id: LaneId,
 	payload: T::OutboundPayload,
 	delivery_and_dispatch_fee: T::OutboundMessageFee,
+	ttl: Option<T::BlockNumber>,
+	owner: Option<MessageOwnerId>,
 ) -> sp_std::result::Result<SentMessage, sp_runtime::DispatchErrorWithPostInfo<PostDispatchInfo>> {
 	ensure_normal_operating_mode::<T, I>()?;
+	ensure_lane_normal_operating_mode::<T, I>(&lane_id)?;
+	ensure_lane_accepts_outbound_messages::<T, I>(&lane_id)?;
+
+	// initially, actual (post-dispatch) weight is equal to pre-dispatch weight
+	let mut actual_weight = send_message_weight::<T, I>(&payload, &ttl);
+
+	// let's first check if message can be delivered to target chain and enforce any additional lane rules
+	let mut lane = outbound_lane::<T, I>(lane_id);
+	verify_outbound_message::<T, I>(&submitter, &lane_id, &lane.data(), &payload, &delivery_and_dispatch_fee)?;
+	let rate_limits_update = check_rate_limits::<T, I>(&submitter, &lane_id, 1, payload.encoded_size())?;
+
+	// let's withdraw delivery and dispatch fee from submitter
+	pay_delivery_and_dispatch_fee::<T, I>(&submitter, &lane_id, &delivery_and_dispatch_fee)?;
+
+	// finally, save message in outbound storage and emit event
+	rate_limits_update.apply(&lane_id);
+	let encoded_payload = payload.encode();
+	let encoded_payload_len = encoded_payload.len();
+	let nonce = lane.send_message(
+		MessageData {
+			payload: encoded_payload,
+			fee: delivery_and_dispatch_fee,
+		},
+		submitter.clone(),
+	);
+	if let Some(ttl) = ttl {
+		use sp_runtime::traits::Saturating;
+		let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(ttl);
+		OutboundMessagesExpiry::<T, I>::insert(MessageKey { lane_id, nonce }, (expires_at, submitter.clone()));
+	}
+	if let Some(owner) = owner {
+		OutboundMessagesOwners::<T, I>::insert(MessageKey { lane_id, nonce }, owner);
+	}
+	actual_weight = actual_weight.saturating_sub(on_message_accepted::<T, I>(&lane_id, &nonce, &submitter));
+
+	// message sender pays for pruning at most `MaxMessagesToPruneAtOnce` messages
+	// the cost of pruning every message is roughly single db write
+	// => lets refund sender if less than `MaxMessagesToPruneAtOnce` messages pruned
+	let max_messages_to_prune = T::MaxMessagesToPruneAtOnce::get();
+	let pruned_messages = prune_lane_messages::<T, I>(&mut lane, max_messages_to_prune);
+	if let Some(extra_messages) = max_messages_to_prune.checked_sub(pruned_messages) {
+		actual_weight = actual_weight.saturating_sub(T::DbWeight::get().writes(extra_messages));
+	}
+
+	log::trace!(
+		target: "runtime::bridge-messages",
+		"Accepted message {} to lane {:?}. Message size: {:?}",
+		nonce,
+		lane_id,
+		encoded_payload_len,
+	);
+
+	Pallet::<T, I>::deposit_event(Event::MessageAccepted(lane_id, nonce));
+
+	Ok(SentMessage {
+		nonce,
+		post_dispatch_info: PostDispatchInfo {
+			actual_weight: Some(actual_weight),
+			pays_fee: Pays::Yes,
+		},
+	})
+}
+
+/// Function that actually sends batch of messages.
+///
+/// Either all messages are accepted, or all messages are rejected.
+fn send_messages<T: Config<I>, I: 'static>(
+	submitter: bp_messages::source_chain::Sender<T::AccountId>,
+	lane_id: LaneId,
+	messages: Vec<(T::OutboundPayload, T::OutboundMessageFee)>,
+) -> sp_std::result::Result<SentMessages, sp_runtime::DispatchErrorWithPostInfo<PostDispatchInfo>> {
+	ensure_normal_operating_mode::<T, I>()?;
+	ensure_lane_normal_operating_mode::<T, I>(&lane_id)?;
+	ensure_lane_accepts_outbound_messages::<T, I>(&lane_id)?;
+	ensure!(!messages.is_empty(), Error::<T, I>::EmptyMessagesBatch);
 
 	// initially, actual (post-dispatch) weight is equal to pre-dispatch weight
-	let mut actual_weight = T::WeightInfo::send_message_weight(&payload, T::DbWeight::get());
+	let mut actual_weight = send_messages_batch_weight::<T, I>(&messages);
+
+	// verify all messages. Lane verifier sees every message as if all previous messages of the
+	// batch have been already accepted
+	let mut lane = outbound_lane::<T, I>(lane_id);
+	let mut lane_data = lane.data();
+	let mut total_fee = T::OutboundMessageFee::zero();
+	let mut total_size: usize = 0;
+	for (payload, delivery_and_dispatch_fee) in &messages {
+		verify_outbound_message::<T, I>(&submitter, &lane_id, &lane_data, payload, delivery_and_dispatch_fee)?;
+		lane_data.latest_generated_nonce += 1;
+		total_fee = total_fee
+			.checked_add(delivery_and_dispatch_fee)
+			.ok_or(Error::<T, I>::FailedToWithdrawMessageFee)?;
+		total_size = total_size.saturating_add(payload.encoded_size());
+	}
+	let rate_limits_update =
+		check_rate_limits::<T, I>(&submitter, &lane_id, messages.len() as MessageNonce, total_size)?;
+
+	// let's withdraw combined delivery and dispatch fee of all messages from submitter
+	pay_delivery_and_dispatch_fee::<T, I>(&submitter, &lane_id, &total_fee)?;
+
+	// finally, save messages in outbound storage and emit event
+	rate_limits_update.apply(&lane_id);
+	let messages_count = messages.len() as MessageNonce;
+	let mut end = 0;
+	for (payload, delivery_and_dispatch_fee) in messages {
+		end = lane.send_message(
+			MessageData {
+				payload: payload.encode(),
+				fee: delivery_and_dispatch_fee,
+			},
+			submitter.clone(),
+		);
+		actual_weight = actual_weight.saturating_sub(on_message_accepted::<T, I>(&lane_id, &end, &submitter));
+	}
+	let begin = end + 1 - messages_count;
+
+	// message sender pays for pruning at most `MaxMessagesToPruneAtOnce` messages for every
+	// message in the batch, but pruning only happens once
+	let max_messages_to_prune = T::MaxMessagesToPruneAtOnce::get();
+	let pruned_messages = prune_lane_messages::<T, I>(&mut lane, max_messages_to_prune);
+	if let Some(extra_messages) = max_messages_to_prune
+		.saturating_mul(messages_count)
+		.checked_sub(pruned_messages)
+	{
+		actual_weight = actual_weight.saturating_sub(T::DbWeight::get().writes(extra_messages));
+	}
+
+	log::trace!(
+		target: "runtime::bridge-messages",
+		"Accepted messages {}..={} to lane {:?}",
+		begin,
+		end,
+		lane_id,
+	);
+
+	Pallet::<T, I>::deposit_event(Event::MessagesAccepted(lane_id, begin, end));
+
+	Ok(SentMessages {
+		nonces: begin..=end,
+		post_dispatch_info: PostDispatchInfo {
+			actual_weight: Some(actual_weight),
+			pays_fee: Pays::Yes,
+		},
+	})
+}
 
+/// Verify that the message may be sent over the lane.
+fn verify_outbound_message<T: Config<I>, I: 'static>(
+	submitter: &bp_messages::source_chain::Sender<T::AccountId>,
+	lane_id: &LaneId,
+	lane_data: &OutboundLaneData,
+	payload: &T::OutboundPayload,
+	delivery_and_dispatch_fee: &T::OutboundMessageFee,
+) -> Result<(), Error<T, I>> {
 	// let's first check if message can be delivered to target chain
-	T::TargetHeaderChain::verify_message(&payload).map_err(|err| {
+	T::TargetHeaderChain::verify_message(payload).map_err(|err| {
 		log::trace!(
 			target: "runtime::bridge-messages",
 			"Message to lane {:?} is rejected by target chain: {:?}",
@@ -877,9 +1946,8 @@ fn send_message<T: Config<I>, I: 'static>(
 	})?;
 
 	// now let's enforce any additional lane rules
-	let mut lane = outbound_lane::<T, I>(lane_id);
-	T::LaneMessageVerifier::verify_message(&submitter, &delivery_and_dispatch_fee, &lane_id, &lane.data(), &payload)
-		.map_err(|err| {
+	T::LaneMessageVerifier::verify_message(submitter, delivery_and_dispatch_fee, lane_id, lane_data, payload).map_err(
+		|err| {
 			log::trace!(
 				target: "runtime::bridge-messages",
 				"Message to lane {:?} is rejected by lane verifier: {:?}",
@@ -888,12 +1956,162 @@ fn send_message<T: Config<I>, I: 'static>(
 			);
 
 			Error::<T, I>::MessageRejectedByLaneVerifier
-		})?;
+		},
+	)
+}
 
-	// let's withdraw delivery and dispatch fee from submitter
+/// Rate limits state that needs to be saved when messages are accepted.
+struct RateLimitsUpdate<T: Config<I>, I: 'static> {
+	/// Updated rate limit period of the submitter account, if it is limited.
+	account: Option<(T::AccountId, (T::BlockNumber, MessageNonce))>,
+	/// Updated number of bytes, sent over the lane at the current block.
+	lane_bytes: (T::BlockNumber, u32),
+	_phantom: PhantomData<I>,
+}
+
+impl<T: Config<I>, I: 'static> RateLimitsUpdate<T, I> {
+	/// Save updated rate limits state to the runtime storage.
+	fn apply(self, lane_id: &LaneId) {
+		if let Some((account_id, account_rate)) = self.account {
+			AccountMessagesRate::<T, I>::insert(account_id, account_rate);
+		}
+		LaneBytesRate::<T, I>::insert(lane_id, self.lane_bytes);
+	}
+}
+
+/// Ensure that sending `messages_count` messages with `messages_size` total payload size won't
+/// exceed submitter and lane rate limits.
+///
+/// Returns updated rate limits state that must be applied once messages are accepted.
+fn check_rate_limits<T: Config<I>, I: 'static>(
+	submitter: &bp_messages::source_chain::Sender<T::AccountId>,
+	lane_id: &LaneId,
+	messages_count: MessageNonce,
+	messages_size: usize,
+) -> Result<RateLimitsUpdate<T, I>, Error<T, I>> {
+	use sp_runtime::traits::Saturating;
+
+	let current_block = frame_system::Pallet::<T>::block_number();
+
+	// only messages that are sent by regular accounts are limited
+	let account = match *submitter {
+		bp_messages::source_chain::Sender::Signed(ref account_id) => {
+			let period_length = T::AccountRateLimitPeriod::get();
+			let (period_start, sent_messages) = AccountMessagesRate::<T, I>::get(account_id)
+				.filter(|(period_start, _)| current_block < period_start.saturating_add(period_length))
+				.unwrap_or((current_block, 0));
+			let sent_messages = sent_messages.saturating_add(messages_count);
+			if sent_messages > T::MaxMessagesPerAccountPerPeriod::get() {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Messages to lane {:?} are rejected: account {:?} has exceeded its rate limit",
+					lane_id,
+					account_id,
+				);
+
+				return Err(Error::<T, I>::TooManyMessagesFromAccount);
+			}
+
+			Some((account_id.clone(), (period_start, sent_messages)))
+		}
+		_ => None,
+	};
+
+	let lane_bytes = LaneBytesRate::<T, I>::get(lane_id)
+		.filter(|(block, _)| *block == current_block)
+		.map(|(_, lane_bytes)| lane_bytes)
+		.unwrap_or(0)
+		.saturating_add(u32::try_from(messages_size).unwrap_or(u32::MAX));
+	if lane_bytes > T::MaxLaneBytesPerBlock::get() {
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Messages to lane {:?} are rejected: lane has exceeded its rate limit",
+			lane_id,
+		);
+
+		return Err(Error::<T, I>::TooManyBytesAtLane);
+	}
+
+	Ok(RateLimitsUpdate {
+		account,
+		lane_bytes: (current_block, lane_bytes),
+		_phantom: Default::default(),
+	})
+}
+
+/// Remove expired entries of the `AccountMessagesRate` map, spending no more than `remaining_weight`.
+///
+/// Entries are processed in order, starting from the entry that follows the
+/// `AccountMessagesRatePruningCursor`. Returns the used weight.
+fn prune_expired_account_messages_rates<T: Config<I>, I: 'static>(remaining_weight: Weight) -> Weight {
+	use sp_runtime::traits::Saturating;
+
+	// read and update the cursor
+	let db_weight = T::DbWeight::get();
+	let mut used_weight = db_weight.reads_writes(1, 1);
+	if used_weight > remaining_weight {
+		return 0;
+	}
+
+	// every entry is read and (optionally) removed
+	let entry_weight = db_weight.reads_writes(1, 1);
+	let current_block = frame_system::Pallet::<T>::block_number();
+	let period_length = T::AccountRateLimitPeriod::get();
+	let cursor = AccountMessagesRatePruningCursor::<T, I>::get();
+	let mut entries = match cursor {
+		Some(ref cursor) => AccountMessagesRate::<T, I>::iter_from(cursor.clone()),
+		None => AccountMessagesRate::<T, I>::iter(),
+	};
+	let mut next_cursor = cursor.clone();
+	let mut pruned_entries = 0;
+	loop {
+		if used_weight.saturating_add(entry_weight) > remaining_weight {
+			break;
+		}
+
+		let (account_id, (period_start, _)) = match entries.next() {
+			Some(entry) => entry,
+			None => {
+				next_cursor = None;
+				break;
+			}
+		};
+
+		used_weight = used_weight.saturating_add(entry_weight);
+		if current_block >= period_start.saturating_add(period_length) {
+			AccountMessagesRate::<T, I>::remove(&account_id);
+			pruned_entries += 1;
+		}
+		next_cursor = Some(AccountMessagesRate::<T, I>::hashed_key_for(&account_id));
+	}
+
+	if next_cursor != cursor {
+		match next_cursor {
+			Some(next_cursor) => AccountMessagesRatePruningCursor::<T, I>::put(next_cursor),
+			None => AccountMessagesRatePruningCursor::<T, I>::kill(),
+		}
+	}
+
+	if pruned_entries != 0 {
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Pruned {} expired account rate limit entries",
+			pruned_entries,
+		);
+	}
+
+	used_weight
+}
+
+/// Withdraw delivery and dispatch fee from the submitter.
+fn pay_delivery_and_dispatch_fee<T: Config<I>, I: 'static>(
+	submitter: &bp_messages::source_chain::Sender<T::AccountId>,
+	lane_id: &LaneId,
+	delivery_and_dispatch_fee: &T::OutboundMessageFee,
+) -> Result<(), Error<T, I>> {
 	T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
-		&submitter,
-		&delivery_and_dispatch_fee,
+		submitter,
+		delivery_and_dispatch_fee,
 		&Pallet::<T, I>::relayer_fund_account_id(),
 	)
 	.map_err(|err| {
@@ -907,23 +2125,25 @@ fn send_message<T: Config<I>, I: 'static>(
 		);
 
 		Error::<T, I>::FailedToWithdrawMessageFee
-	})?;
+	})
+}
 
-	// finally, save message in outbound storage and emit event
-	let encoded_payload = payload.encode();
-	let encoded_payload_len = encoded_payload.len();
-	let nonce = lane.send_message(MessageData {
-		payload: encoded_payload,
-		fee: delivery_and_dispatch_fee,
-	});
+/// Call `OnMessageAccepted` callback for the accepted message.
+///
+/// Returns weight that has been reserved for the callback, but has not been spent.
+fn on_message_accepted<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	nonce: &MessageNonce,
+	submitter: &bp_messages::source_chain::Sender<T::AccountId>,
+) -> Weight {
 	// Guaranteed to be called outside only when the message is accepted.
 	// We assume that the maximum weight call back used is `single_message_callback_overhead`, so do not perform
 	// complex db operation in callback. If you want to, put these magic logic in outside pallet and control
 	// the weight there.
 	let single_message_callback_overhead = T::WeightInfo::single_message_callback_overhead(T::DbWeight::get());
-	let actual_callback_weight = T::OnMessageAccepted::on_messages_accepted(&lane_id, &nonce);
+	let actual_callback_weight = T::OnMessageAccepted::on_messages_accepted(lane_id, nonce, submitter);
 	match single_message_callback_overhead.checked_sub(actual_callback_weight) {
-		Some(difference) if difference == 0 => (),
+		Some(difference) if difference == 0 => 0,
 		Some(difference) => {
 			log::trace!(
 				target: "runtime::bridge-messages",
@@ -933,7 +2153,7 @@ fn send_message<T: Config<I>, I: 'static>(
 				actual_callback_weight,
 				difference,
 			);
-			actual_weight = actual_weight.saturating_sub(difference);
+			difference
 		}
 		None => {
 			debug_assert!(false, "T::OnMessageAccepted callback consumed too much weight.");
@@ -944,35 +2164,123 @@ fn send_message<T: Config<I>, I: 'static>(
 				single_message_callback_overhead,
 				actual_callback_weight,
 			);
+			0
 		}
 	}
+}
 
-	// message sender pays for pruning at most `MaxMessagesToPruneAtOnce` messages
-	// the cost of pruning every message is roughly single db write
-	// => lets refund sender if less than `MaxMessagesToPruneAtOnce` messages pruned
-	let max_messages_to_prune = T::MaxMessagesToPruneAtOnce::get();
-	let pruned_messages = lane.prune_messages(max_messages_to_prune);
-	if let Some(extra_messages) = max_messages_to_prune.checked_sub(pruned_messages) {
-		actual_weight = actual_weight.saturating_sub(T::DbWeight::get().writes(extra_messages));
+/// Refund fees of confirmed messages that have been received without dispatch by the bridged chain.
+///
+/// Fees of messages with unknown senders are not refunded.
+fn refund_skipped_messages<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	skipped_messages: Vec<(
+		MessageNonce,
+		Option<bp_messages::source_chain::Sender<T::AccountId>>,
+		T::OutboundMessageFee,
+	)>,
+) {
+	if skipped_messages.is_empty() {
+		return;
 	}
 
-	log::trace!(
-		target: "runtime::bridge-messages",
-		"Accepted message {} to lane {:?}. Message size: {:?}",
-		nonce,
-		lane_id,
-		encoded_payload_len,
-	);
+	// refunds that are made while the bridge is being decommissioned are added to the report
+	let mut decommissioning_progress =
+		Decommissioning::<T, I>::get().filter(|progress| progress.final_relayer_fund_balance.is_none());
+	let relayer_fund_account = Pallet::<T, I>::relayer_fund_account_id();
+	for (nonce, sender, fee) in skipped_messages {
+		let sender = match sender {
+			Some(sender) => sender,
+			None => {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Not refunding fee {:?} of the skipped message {:?}/{}: sender is unknown",
+					fee,
+					lane_id,
+					nonce,
+				);
+				continue;
+			}
+		};
 
-	Pallet::<T, I>::deposit_event(Event::MessageAccepted(lane_id, nonce));
+		match T::MessageDeliveryAndDispatchPayment::refund_delivery_and_dispatch_fee(
+			&sender,
+			&fee,
+			&relayer_fund_account,
+		) {
+			Ok(()) => {
+				if let Some(ref mut progress) = decommissioning_progress {
+					note_decommissioning_refund::<T, I>(progress, &sender, &fee);
+				}
+				Pallet::<T, I>::deposit_event(Event::MessageFeeRefunded(*lane_id, nonce));
+			}
+			Err(err) => log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to refund fee {:?} of the skipped message {:?}/{} to {:?}: {:?}",
+				fee,
+				lane_id,
+				nonce,
+				sender,
+				err,
+			),
+		}
+	}
 
-	Ok(SentMessage {
-		nonce,
-		post_dispatch_info: PostDispatchInfo {
-			actual_weight: Some(actual_weight),
-			pays_fee: Pays::Yes,
-		},
-	})
+	if let Some(progress) = decommissioning_progress {
+		Decommissioning::<T, I>::put(progress);
+	}
+}
+
+/// Pass dispatch results of confirmed messages to their owners.
+///
+/// Returns weight that has been reserved for passing dispatch results, but has not been spent.
+fn on_messages_dispatched<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	confirmed_messages: &DeliveredMessages,
+	reserved_weight: Weight,
+) -> Weight {
+	let db_weight = T::DbWeight::get();
+	let mut actual_weight: Weight = 0;
+	// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
+	for nonce in confirmed_messages.begin..=confirmed_messages.end {
+		actual_weight = actual_weight.saturating_add(db_weight.reads(1));
+		let message_key = MessageKey {
+			lane_id: *lane_id,
+			nonce,
+		};
+		let owner = match OutboundMessagesOwners::<T, I>::take(&message_key) {
+			Some(owner) => owner,
+			None => continue,
+		};
+
+		actual_weight = actual_weight.saturating_add(db_weight.writes(1));
+		let dispatch_result = confirmed_messages.message_dispatch_result(nonce);
+		match T::MessageOwners::on_message_dispatched(&owner, lane_id, nonce, dispatch_result) {
+			Some(callback_weight) => actual_weight = actual_weight.saturating_add(callback_weight),
+			None => log::trace!(
+				target: "runtime::bridge-messages",
+				"Unknown owner {:?} of the message {:?}/{}. Dispatch result is dropped",
+				owner,
+				lane_id,
+				nonce,
+			),
+		}
+	}
+
+	match reserved_weight.checked_sub(actual_weight) {
+		Some(difference) => difference,
+		None => {
+			debug_assert!(false, "T::MessageOwners callbacks consumed too much weight.");
+			log::error!(
+				target: "runtime::bridge-messages",
+				"T::MessageOwners callbacks have spent more weight that they are allowed to: \
+				{} vs {}",
+				reserved_weight,
+				actual_weight,
+			);
+			0
+		}
+	}
 }
 
 /// Ensure that the origin is either root, or `PalletOwner`.
@@ -986,230 +2294,3141 @@ fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(
 
 /// Ensure that the pallet is in normal operational mode.
 fn ensure_normal_operating_mode<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
-	if PalletOperatingMode::<T, I>::get() != OperatingMode::Normal {
-		Err(Error::<T, I>::Halted)
+	match PalletOperatingMode::<T, I>::get() {
+		OperatingMode::Normal => Ok(()),
+		OperatingMode::Decommissioning => Err(Error::<T, I>::Decommissioning),
+		_ => Err(Error::<T, I>::Halted),
+	}
+}
+
+/// Ensure that the pallet is neither halted, nor decommissioning.
+fn ensure_not_halted<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+	match PalletOperatingMode::<T, I>::get() {
+		OperatingMode::Halted => Err(Error::<T, I>::Halted),
+		OperatingMode::Decommissioning => Err(Error::<T, I>::Decommissioning),
+		_ => Ok(()),
+	}
+}
+
+/// Ensure that the lane is in normal operational mode.
+fn ensure_lane_normal_operating_mode<T: Config<I>, I: 'static>(lane_id: &LaneId) -> Result<(), Error<T, I>> {
+	if LaneOperatingModes::<T, I>::get(lane_id) != OperatingMode::Normal {
+		Err(Error::<T, I>::LaneHalted)
 	} else {
 		Ok(())
 	}
 }
 
-/// Ensure that the pallet is not halted.
-fn ensure_not_halted<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
-	if PalletOperatingMode::<T, I>::get() == OperatingMode::Halted {
-		Err(Error::<T, I>::Halted)
+/// Ensure that the lane is not halted.
+fn ensure_lane_not_halted<T: Config<I>, I: 'static>(lane_id: &LaneId) -> Result<(), Error<T, I>> {
+	if LaneOperatingModes::<T, I>::get(lane_id) == OperatingMode::Halted {
+		Err(Error::<T, I>::LaneHalted)
 	} else {
 		Ok(())
 	}
 }
 
-/// Creates new inbound lane object, backed by runtime storage.
-fn inbound_lane<T: Config<I>, I: 'static>(lane_id: LaneId) -> InboundLane<RuntimeInboundLaneStorage<T, I>> {
-	InboundLane::new(inbound_lane_storage::<T, I>(lane_id))
+/// Ensure that the lane is registered in the lanes registry.
+fn ensure_lane_registered<T: Config<I>, I: 'static>(lane_id: &LaneId) -> Result<(), Error<T, I>> {
+	if Lanes::<T, I>::contains_key(lane_id) {
+		Ok(())
+	} else {
+		Err(Error::<T, I>::UnknownLane)
+	}
 }
 
-/// Creates new runtime inbound lane storage.
-fn inbound_lane_storage<T: Config<I>, I: 'static>(lane_id: LaneId) -> RuntimeInboundLaneStorage<T, I> {
-	RuntimeInboundLaneStorage {
-		lane_id,
-		cached_data: RefCell::new(None),
-		_phantom: Default::default(),
+/// Ensure that the lane is registered in the lanes registry and accepts new outbound messages.
+fn ensure_lane_accepts_outbound_messages<T: Config<I>, I: 'static>(lane_id: &LaneId) -> Result<(), Error<T, I>> {
+	match Lanes::<T, I>::get(lane_id) {
+		Some(lane_state) if lane_state.is_accepting_outbound_messages() => Ok(()),
+		Some(_) => Err(Error::<T, I>::LaneIsClosed),
+		None => Err(Error::<T, I>::UnknownLane),
 	}
 }
 
-/// Creates new outbound lane object, backed by runtime storage.
-fn outbound_lane<T: Config<I>, I: 'static>(lane_id: LaneId) -> OutboundLane<RuntimeOutboundLaneStorage<T, I>> {
-	OutboundLane::new(RuntimeOutboundLaneStorage {
-		lane_id,
-		_phantom: Default::default(),
-	})
+/// Ensure that the message has not been received by the bridged chain.
+///
+/// The `proof` is the proof of the inbound lane state at the bridged chain. It must not be older
+/// than the latest delivery confirmation that we have received. Keep in mind that the message may
+/// still be received by the bridged chain after the proof has been generated.
+fn ensure_message_is_not_received<T: Config<I>, I: 'static>(
+	proof: MessagesDeliveryProofOf<T, I>,
+	lane_id: &LaneId,
+	lane_data: &OutboundLaneData,
+	nonce: MessageNonce,
+) -> Result<(), Error<T, I>> {
+	let last_delivered_nonce = verify_inbound_lane_state_proof::<T, I>(proof, lane_id, lane_data)?;
+	ensure!(nonce > last_delivered_nonce, Error::<T, I>::MessageIsAlreadyDelivered);
+
+	Ok(())
 }
 
-/// Runtime inbound lane storage.
-struct RuntimeInboundLaneStorage<T: Config<I>, I: 'static = ()> {
-	lane_id: LaneId,
-	cached_data: RefCell<Option<InboundLaneData<T::InboundRelayer>>>,
-	_phantom: PhantomData<I>,
+/// Verify proof of the inbound lane state at the bridged chain.
+///
+/// The proof must be at least as fresh as the latest delivery confirmation. Returns nonce of the
+/// latest message that has been received by the bridged chain.
+fn verify_inbound_lane_state_proof<T: Config<I>, I: 'static>(
+	proof: MessagesDeliveryProofOf<T, I>,
+	lane_id: &LaneId,
+	lane_data: &OutboundLaneData,
+) -> Result<MessageNonce, Error<T, I>> {
+	let (proved_lane_id, inbound_lane_data) =
+		T::TargetHeaderChain::verify_messages_delivery_proof(proof).map_err(|err| {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Rejecting invalid message non-delivery proof: {:?}",
+				err,
+			);
+
+			Error::<T, I>::InvalidMessagesDeliveryProof
+		})?;
+	ensure!(proved_lane_id == *lane_id, Error::<T, I>::InvalidMessagesDeliveryProof);
+
+	let last_delivered_nonce = inbound_lane_data.last_delivered_nonce();
+	ensure!(
+		last_delivered_nonce >= lane_data.latest_received_nonce,
+		Error::<T, I>::StaleMessagesDeliveryProof
+	);
+
+	Ok(last_delivered_nonce)
 }
 
-impl<T: Config<I>, I: 'static> InboundLaneStorage for RuntimeInboundLaneStorage<T, I> {
-	type MessageFee = T::InboundMessageFee;
-	type Relayer = T::InboundRelayer;
+/// Returns sender of the outbound message.
+///
+/// Senders of messages with TTL that have been sent before the storage version 1, are only stored
+/// in the `OutboundMessagesExpiry` map.
+fn outbound_message_sender<T: Config<I>, I: 'static>(
+	message_key: &MessageKey,
+) -> Option<bp_messages::source_chain::Sender<T::AccountId>> {
+	OutboundMessagesSenders::<T, I>::get(message_key)
+		.or_else(|| OutboundMessagesExpiry::<T, I>::get(message_key).map(|(_, sender)| sender))
+}
 
-	fn id(&self) -> LaneId {
-		self.lane_id
-	}
+/// Returns true if all messages that have been sent over the lane are delivered.
+fn is_outbound_lane_drained<T: Config<I>, I: 'static>(lane_id: &LaneId) -> bool {
+	let data = OutboundLanes::<T, I>::get(lane_id);
+	data.latest_received_nonce == data.latest_generated_nonce
+}
 
-	fn max_unrewarded_relayer_entries(&self) -> MessageNonce {
-		T::MaxUnrewardedRelayerEntriesAtInboundLane::get()
+/// Switch the lane from `Opening` to `Open` state when we receive the first proof from the bridged chain.
+fn note_lane_proof_received<T: Config<I>, I: 'static>(lane_id: LaneId) {
+	if Lanes::<T, I>::get(&lane_id) == Some(LaneState::Opening) {
+		set_lane_state::<T, I>(lane_id, LaneState::Open);
 	}
+}
 
-	fn max_unconfirmed_messages(&self) -> MessageNonce {
-		T::MaxUnconfirmedMessagesAtInboundLane::get()
+/// Update lane state in the lanes registry and emit the corresponding event.
+fn set_lane_state<T: Config<I>, I: 'static>(lane_id: LaneId, lane_state: LaneState) {
+	Lanes::<T, I>::insert(&lane_id, lane_state);
+	log::info!(
+		target: "runtime::bridge-messages",
+		"Lane {:?} has switched to {:?} state.",
+		lane_id,
+		lane_state,
+	);
+	Pallet::<T, I>::deposit_event(Event::LaneStateChanged(lane_id, lane_state));
+}
+
+/// Dispatch queued inbound messages, spending no more than `remaining_weight`.
+///
+/// Lanes are processed one by one, starting from the lane that follows the
+/// `InboundQueuedMessagesDispatchCursor`. Messages of the lane are dispatched in order, until
+/// either the lane queue is empty, or the next message can't be dispatched. Returns the used weight.
+fn dispatch_queued_messages<T: Config<I>, I: 'static>(remaining_weight: Weight) -> Weight {
+	// read the pallet operating mode, read and update the cursor
+	let db_weight = T::DbWeight::get();
+	let mut used_weight = db_weight.reads_writes(2, 1);
+	if used_weight > remaining_weight {
+		return 0;
+	}
+	if ensure_not_halted::<T, I>().is_err() {
+		return used_weight;
 	}
 
-	fn data(&self) -> InboundLaneData<T::InboundRelayer> {
-		match self.cached_data.clone().into_inner() {
-			Some(data) => data,
+	// every lane requires reading the lane queue and the lane operating mode
+	let lane_overhead = db_weight.reads(2);
+	// every dispatch attempt requires reading the inbound lane data and the queued message
+	let attempt_overhead = db_weight.reads(2);
+	// every dispatched message requires reading and removing the queued message, returning the
+	// queued message deposit, reading and updating the inbound lane data and (optionally) paying
+	// the dispatch fee at this chain
+	let message_overhead = db_weight.reads_writes(3, 4);
+	let cursor = InboundQueuedMessagesDispatchCursor::<T, I>::get();
+	let mut lanes = match cursor {
+		Some(ref cursor) => InboundLatestQueuedNonces::<T, I>::iter_from(cursor.clone()),
+		None => InboundLatestQueuedNonces::<T, I>::iter(),
+	};
+	let mut next_cursor = cursor.clone();
+	'lanes: loop {
+		if used_weight
+			.saturating_add(lane_overhead)
+			.saturating_add(message_overhead)
+			> remaining_weight
+		{
+			break;
+		}
+
+		let lane_id = match lanes.next() {
+			Some((lane_id, _)) => lane_id,
 			None => {
-				let data = InboundLanes::<T, I>::get(&self.lane_id);
-				*self.cached_data.try_borrow_mut().expect(
-					"we're in the single-threaded environment;\
-						we have no recursive borrows; qed",
-				) = Some(data.clone());
-				data
+				next_cursor = None;
+				break;
+			}
+		};
+
+		used_weight = used_weight.saturating_add(lane_overhead);
+		if ensure_lane_not_halted::<T, I>(&lane_id).is_ok() {
+			let mut lane = inbound_lane::<T, I>(lane_id);
+			loop {
+				// if there's no weight left, the next round starts from this lane
+				let dispatch_weight_limit = remaining_weight
+					.saturating_sub(used_weight)
+					.saturating_sub(message_overhead)
+					.saturating_sub(T::WeightInfo::pay_inbound_dispatch_fee_overhead());
+				if dispatch_weight_limit == 0 {
+					break 'lanes;
+				}
+
+				let (dispatch_weight, dispatch_result) =
+					match lane.dispatch_queued_message::<T::MessageDispatch>(dispatch_weight_limit) {
+						QueuedDispatchResult::Dispatched(dispatch_weight, dispatch_result) => {
+							(dispatch_weight, dispatch_result)
+						}
+						QueuedDispatchResult::NoQueuedMessages | QueuedDispatchResult::NotEnoughWeight => {
+							used_weight = used_weight.saturating_add(attempt_overhead);
+							break;
+						}
+						QueuedDispatchResult::TooManyUnrewardedRelayers => {
+							log::trace!(
+								target: "runtime::bridge-messages",
+								"Dispatch of queued messages at lane {:?} is stalled: too many unrewarded relayers",
+								lane_id,
+							);
+
+							used_weight = used_weight
+								.saturating_add(attempt_overhead)
+								.saturating_add(db_weight.writes(1));
+							Pallet::<T, I>::deposit_event(Event::QueuedMessagesDispatchStalled(lane_id));
+							break;
+						}
+					};
+
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Dispatched queued message at lane {:?}: {:?}",
+					lane_id,
+					dispatch_result,
+				);
+
+				let unspent_weight = sp_std::cmp::min(dispatch_result.unspent_weight, dispatch_weight);
+				used_weight = used_weight
+					.saturating_add(message_overhead)
+					.saturating_add(dispatch_weight - unspent_weight)
+					.saturating_add(if dispatch_result.dispatch_fee_paid_during_dispatch {
+						T::WeightInfo::pay_inbound_dispatch_fee_overhead()
+					} else {
+						0
+					});
 			}
 		}
+		next_cursor = Some(InboundLatestQueuedNonces::<T, I>::hashed_key_for(&lane_id));
 	}
 
-	fn set_data(&mut self, data: InboundLaneData<T::InboundRelayer>) {
-		*self.cached_data.try_borrow_mut().expect(
-			"we're in the single-threaded environment;\
-				we have no recursive borrows; qed",
-		) = Some(data.clone());
-		InboundLanes::<T, I>::insert(&self.lane_id, data)
+	if next_cursor != cursor {
+		match next_cursor {
+			Some(next_cursor) => InboundQueuedMessagesDispatchCursor::<T, I>::put(next_cursor),
+			None => InboundQueuedMessagesDispatchCursor::<T, I>::kill(),
+		}
 	}
-}
 
-/// Runtime outbound lane storage.
-struct RuntimeOutboundLaneStorage<T, I = ()> {
-	lane_id: LaneId,
-	_phantom: PhantomData<(T, I)>,
+	used_weight
 }
 
-impl<T: Config<I>, I: 'static> OutboundLaneStorage for RuntimeOutboundLaneStorage<T, I> {
-	type MessageFee = T::OutboundMessageFee;
-
-	fn id(&self) -> LaneId {
-		self.lane_id
+/// Prune at most `max_messages_to_prune` confirmed messages of the given lane.
+///
+/// Returns number of pruned messages.
+fn prune_lane_messages<T: Config<I>, I: 'static>(
+	lane: &mut OutboundLane<RuntimeOutboundLaneStorage<T, I>>,
+	max_messages_to_prune: MessageNonce,
+) -> MessageNonce {
+	let pruned_messages = lane.prune_messages(max_messages_to_prune);
+	if pruned_messages != 0 {
+		OutboundMessagesToPrune::<T, I>::mutate(|messages_to_prune| {
+			*messages_to_prune = messages_to_prune.saturating_sub(pruned_messages)
+		});
 	}
+	pruned_messages
+}
 
-	fn data(&self) -> OutboundLaneData {
-		OutboundLanes::<T, I>::get(&self.lane_id)
+/// Prune confirmed outbound messages, spending no more than `remaining_weight`.
+///
+/// Lanes are processed in round-robin order, starting from the lane that follows the
+/// `OutboundPruningLaneCursor`. The next round starts from the lane that follows the last processed
+/// lane, so that lanes with large backlog can't starve other lanes. Returns the used weight.
+fn prune_confirmed_messages<T: Config<I>, I: 'static>(remaining_weight: Weight) -> Weight {
+	let db_weight = T::DbWeight::get();
+	let mut used_weight = db_weight.reads(1);
+	if used_weight > remaining_weight {
+		return 0;
+	}
+	// confirmed messages are also pruned when the bridge is being decommissioned
+	if PalletOperatingMode::<T, I>::get() == OperatingMode::Halted {
+		return used_weight;
 	}
 
-	fn set_data(&mut self, data: OutboundLaneData) {
-		OutboundLanes::<T, I>::insert(&self.lane_id, data)
+	// there's nothing to prune, so we don't need to iterate lanes
+	if used_weight.saturating_add(db_weight.reads(1)) > remaining_weight {
+		return used_weight;
+	}
+	used_weight = used_weight.saturating_add(db_weight.reads(1));
+	if OutboundMessagesToPrune::<T, I>::get() == 0 {
+		return used_weight;
 	}
 
-	#[cfg(test)]
-	fn message(&self, nonce: &MessageNonce) -> Option<MessageData<T::OutboundMessageFee>> {
-		OutboundMessages::<T, I>::get(MessageKey {
-			lane_id: self.lane_id,
-			nonce: *nonce,
-		})
+	// read and update the cursor and update the number of messages to prune
+	if used_weight.saturating_add(db_weight.reads_writes(1, 2)) > remaining_weight {
+		return used_weight;
 	}
+	used_weight = used_weight.saturating_add(db_weight.reads_writes(1, 2));
+
+	// every lane requires reading the lane entry, reading and (optionally) updating the lane data.
+	// Pruning every message removes the message itself, its sender, its expiry and its cancellation
+	// entry
+	let lane_overhead = db_weight.reads_writes(2, 1);
+	let message_weight = db_weight.writes(4);
+	let cursor = OutboundPruningLaneCursor::<T, I>::get();
+	let mut lanes = match cursor {
+		Some(ref cursor) => OutboundLanes::<T, I>::iter_from(cursor.clone()),
+		None => OutboundLanes::<T, I>::iter(),
+	};
+	let mut next_cursor = cursor.clone();
+	let mut pruned_messages = 0;
+	loop {
+		// we need to be able to prune at least one message of the lane
+		if used_weight.saturating_add(lane_overhead).saturating_add(message_weight) > remaining_weight {
+			break;
+		}
 
-	fn save_message(&mut self, nonce: MessageNonce, mesage_data: MessageData<T::OutboundMessageFee>) {
-		OutboundMessages::<T, I>::insert(
-			MessageKey {
-				lane_id: self.lane_id,
-				nonce,
-			},
-			mesage_data,
-		);
+		let lane_id = match lanes.next() {
+			Some((lane_id, _)) => lane_id,
+			None => {
+				next_cursor = None;
+				break;
+			}
+		};
+
+		used_weight = used_weight.saturating_add(lane_overhead);
+		let max_messages_to_prune = remaining_weight
+			.saturating_sub(used_weight)
+			.checked_div(message_weight)
+			.unwrap_or(MessageNonce::MAX);
+		let lane_pruned_messages = outbound_lane::<T, I>(lane_id).prune_messages(max_messages_to_prune);
+		used_weight = used_weight.saturating_add(message_weight.saturating_mul(lane_pruned_messages));
+		pruned_messages += lane_pruned_messages;
+		next_cursor = Some(OutboundLanes::<T, I>::hashed_key_for(&lane_id));
+
+		if lane_pruned_messages != 0 {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Pruned {} confirmed messages at lane {:?}",
+				lane_pruned_messages,
+				lane_id,
+			);
+		}
 	}
 
-	fn remove_message(&mut self, nonce: &MessageNonce) {
-		OutboundMessages::<T, I>::remove(MessageKey {
-			lane_id: self.lane_id,
-			nonce: *nonce,
+	if next_cursor != cursor {
+		match next_cursor {
+			Some(next_cursor) => OutboundPruningLaneCursor::<T, I>::put(next_cursor),
+			None => OutboundPruningLaneCursor::<T, I>::kill(),
+		}
+	}
+	if pruned_messages != 0 {
+		OutboundMessagesToPrune::<T, I>::mutate(|messages_to_prune| {
+			*messages_to_prune = messages_to_prune.saturating_sub(pruned_messages)
 		});
 	}
+
+	used_weight
 }
 
-/// Verify messages proof and return proved messages with decoded payload.
-fn verify_and_decode_messages_proof<Chain: SourceHeaderChain<Fee>, Fee, DispatchPayload: Decode>(
-	proof: Chain::MessagesProof,
-	messages_count: u32,
-) -> Result<ProvedMessages<DispatchMessage<DispatchPayload, Fee>>, Chain::Error> {
-	// `receive_messages_proof` weight formula and `MaxUnconfirmedMessagesAtInboundLane` check
-	// guarantees that the `message_count` is sane and Vec<Message> may be allocated.
-	// (tx with too many messages will either be rejected from the pool, or will fail earlier)
-	Chain::verify_messages_proof(proof, messages_count).map(|messages_by_lane| {
-		messages_by_lane
-			.into_iter()
-			.map(|(lane, lane_data)| {
-				(
-					lane,
-					ProvedLaneMessages {
-						lane_state: lane_data.lane_state,
-						messages: lane_data.messages.into_iter().map(Into::into).collect(),
-					},
-				)
-			})
-			.collect()
-	})
+/// Log broken lane invariant and return error.
+fn broken_lane_invariant(lane_id: &LaneId, reason: &'static str) -> Result<(), &'static str> {
+	log::error!(
+		target: "runtime::bridge-messages",
+		"Broken invariant of lane {:?}: {}",
+		lane_id,
+		reason,
+	);
+	Err(reason)
 }
 
-#[cfg(test)]
-mod tests {
-	use super::*;
-	use crate::mock::{
-		message, message_payload, run_test, unrewarded_relayer, Event as TestEvent, Origin,
-		TestMessageDeliveryAndDispatchPayment, TestMessagesDeliveryProof, TestMessagesParameter, TestMessagesProof,
-		TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2, TestOnMessageAccepted, TestRuntime, TokenConversionRate,
-		PAYLOAD_REJECTED_BY_TARGET_CHAIN, REGULAR_PAYLOAD, TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B,
-	};
-	use bp_messages::{UnrewardedRelayer, UnrewardedRelayersState};
-	use frame_support::{assert_noop, assert_ok, weights::Weight};
-	use frame_system::{EventRecord, Pallet as System, Phase};
-	use hex_literal::hex;
-	use sp_runtime::DispatchError;
+/// Check invariants of all outbound lanes.
+fn try_state_outbound_lanes<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+	let mut messages_to_prune: MessageNonce = 0;
+	for (lane_id, lane_data) in OutboundLanes::<T, I>::iter() {
+		if lane_data.oldest_unpruned_nonce > lane_data.latest_received_nonce.saturating_add(1) {
+			return broken_lane_invariant(&lane_id, "Oldest unpruned nonce is ahead of the latest received nonce");
+		}
+		if lane_data.latest_received_nonce > lane_data.latest_generated_nonce {
+			return broken_lane_invariant(&lane_id, "Latest received nonce is ahead of the latest generated nonce");
+		}
+		if lane_data.latest_expired_nonce > lane_data.latest_generated_nonce {
+			return broken_lane_invariant(&lane_id, "Latest expired nonce is ahead of the latest generated nonce");
+		}
 
-	fn get_ready_for_events() {
-		System::<TestRuntime>::set_block_number(1);
-		System::<TestRuntime>::reset_events();
+		messages_to_prune = messages_to_prune
+			.saturating_add((lane_data.latest_received_nonce + 1).saturating_sub(lane_data.oldest_unpruned_nonce));
 	}
 
-	fn send_regular_message() -> Weight {
-		get_ready_for_events();
-
-		let message_nonce = outbound_lane::<TestRuntime, ()>(TEST_LANE_ID)
-			.data()
-			.latest_generated_nonce
-			+ 1;
-		let weight = Pallet::<TestRuntime>::send_message(
-			Origin::signed(1),
-			TEST_LANE_ID,
-			REGULAR_PAYLOAD,
-			REGULAR_PAYLOAD.declared_weight,
-		)
-		.expect("send_message has failed")
-		.actual_weight
-		.expect("send_message always returns Some");
-
-		// check event with assigned nonce
-		assert_eq!(
-			System::<TestRuntime>::events(),
-			vec![EventRecord {
-				phase: Phase::Initialization,
-				event: TestEvent::Messages(Event::MessageAccepted(TEST_LANE_ID, message_nonce)),
-				topics: vec![],
-			}],
+	if messages_to_prune != OutboundMessagesToPrune::<T, I>::get() {
+		log::error!(
+			target: "runtime::bridge-messages",
+			"Broken invariant: {} messages are waiting to be pruned, but counter value is {}",
+			messages_to_prune,
+			OutboundMessagesToPrune::<T, I>::get(),
 		);
+		return Err("Number of messages to prune doesn't match the counter value");
+	}
 
-		// check that fee has been withdrawn from submitter
-		assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(
-			1,
-			REGULAR_PAYLOAD.declared_weight
-		));
+	Ok(())
+}
 
-		weight
+/// Check that every stored outbound message is within its lane bounds.
+fn try_state_outbound_messages<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+	for message_key in OutboundMessages::<T, I>::iter_keys() {
+		let lane_data = OutboundLanes::<T, I>::get(&message_key.lane_id);
+		if message_key.nonce < lane_data.oldest_unpruned_nonce {
+			return broken_lane_invariant(
+				&message_key.lane_id,
+				"Stored message is below the oldest unpruned nonce",
+			);
+		}
+		if message_key.nonce > lane_data.latest_generated_nonce {
+			return broken_lane_invariant(
+				&message_key.lane_id,
+				"Stored message is above the latest generated nonce",
+			);
+		}
 	}
 
-	fn receive_messages_delivery_proof() {
-		System::<TestRuntime>::set_block_number(1);
-		System::<TestRuntime>::reset_events();
+	Ok(())
+}
 
+/// Check invariants of all inbound lanes.
+fn try_state_inbound_lanes<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+	for (lane_id, lane_data) in InboundLanes::<T, I>::iter() {
+		if lane_data.relayers.len() as MessageNonce > T::MaxUnrewardedRelayerEntriesAtInboundLane::get() {
+			return broken_lane_invariant(&lane_id, "Too many unrewarded relayer entries");
+		}
+
+		let mut expected_begin = match lane_data.relayers.front() {
+			Some(entry) if entry.messages.begin > lane_data.last_confirmed_nonce + 1 => {
+				return broken_lane_invariant(&lane_id, "Gap between confirmed and unrewarded messages");
+			}
+			Some(entry) if entry.messages.end <= lane_data.last_confirmed_nonce => {
+				return broken_lane_invariant(&lane_id, "Unrewarded relayer entry with confirmed messages");
+			}
+			Some(entry) => entry.messages.begin,
+			None => continue,
+		};
+		for entry in &lane_data.relayers {
+			if entry.messages.begin != expected_begin {
+				return broken_lane_invariant(&lane_id, "Unrewarded relayer entries are not contiguous");
+			}
+			if entry.messages.begin > entry.messages.end {
+				return broken_lane_invariant(&lane_id, "Unrewarded relayer entry with empty messages range");
+			}
+			if entry.messages.dispatch_results.len() as MessageNonce != entry.messages.end - entry.messages.begin + 1 {
+				return broken_lane_invariant(&lane_id, "Dispatch results don't match the messages range");
+			}
+			expected_begin = entry.messages.end + 1;
+		}
+
+		let unconfirmed_messages = total_unrewarded_messages(&lane_data.relayers).unwrap_or(MessageNonce::MAX);
+		if unconfirmed_messages > T::MaxUnconfirmedMessagesAtInboundLane::get() {
+			return broken_lane_invariant(&lane_id, "Too many unconfirmed messages");
+		}
+	}
+
+	Ok(())
+}
+
+/// Check that inbound dispatch queues contain all messages in the
+/// `last_delivered_nonce+1..=latest_queued_nonce` range and nothing else.
+fn try_state_inbound_queues<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+	for (lane_id, latest_queued_nonce) in InboundLatestQueuedNonces::<T, I>::iter() {
+		let last_delivered_nonce = InboundLanes::<T, I>::get(&lane_id).last_delivered_nonce();
+		if latest_queued_nonce <= last_delivered_nonce {
+			return broken_lane_invariant(
+				&lane_id,
+				"Latest queued nonce is not ahead of the latest delivered nonce",
+			);
+		}
+		for nonce in last_delivered_nonce + 1..=latest_queued_nonce {
+			if !InboundQueuedMessages::<T, I>::contains_key(MessageKey { lane_id, nonce }) {
+				return broken_lane_invariant(&lane_id, "Queued message is missing from the dispatch queue");
+			}
+		}
+	}
+
+	for message_key in InboundQueuedMessages::<T, I>::iter_keys() {
+		let latest_queued_nonce = match InboundLatestQueuedNonces::<T, I>::get(&message_key.lane_id) {
+			Some(latest_queued_nonce) => latest_queued_nonce,
+			None => return broken_lane_invariant(&message_key.lane_id, "Queued message at the lane without queue"),
+		};
+		let last_delivered_nonce = InboundLanes::<T, I>::get(&message_key.lane_id).last_delivered_nonce();
+		if message_key.nonce <= last_delivered_nonce || message_key.nonce > latest_queued_nonce {
+			return broken_lane_invariant(&message_key.lane_id, "Queued message is outside of the dispatch queue");
+		}
+	}
+
+	Ok(())
+}
+
+/// Refund fees of undelivered outbound messages to their senders, spending no more than
+/// `remaining_weight`.
+///
+/// Only works when the pallet is in the `Decommissioning` mode. Only messages that are proved to be
+/// undelivered (see `prove_undelivered_messages`) are refunded. Refunds at the lane start once all
+/// messages that have been received by the bridged chain are confirmed. Undelivered messages are
+/// refunded in order, as if they have expired, so the bridged chain never dispatches them. Once all
+/// lanes are drained and pruned, the `DecommissioningCompleted` event is emitted. Returns the used
+/// weight.
+fn decommission_lanes<T: Config<I>, I: 'static>(remaining_weight: Weight) -> Weight {
+	let db_weight = T::DbWeight::get();
+	// read the operating mode, the decommissioning progress and the cursor, reserve weight for
+	// reading the relayers fund balance and the end of the lanes map. Then update the progress and
+	// the cursor
+	let mut used_weight = db_weight.reads_writes(5, 2);
+	if used_weight > remaining_weight {
+		return 0;
+	}
+	if PalletOperatingMode::<T, I>::get() != OperatingMode::Decommissioning {
+		let used_weight = db_weight.reads(1);
+		return used_weight.saturating_add(clear_decommissioning_lanes::<T, I>(
+			remaining_weight.saturating_sub(used_weight),
+		));
+	}
+	let mut progress = match Decommissioning::<T, I>::get() {
+		Some(progress) if progress.final_relayer_fund_balance.is_none() => progress,
+		_ => return db_weight.reads(2),
+	};
+
+	// every refunded message requires reading and removing the message and its sender, removing
+	// its expiration and cancellation entries, refunding the fee, updating the refunds report and
+	// notifying the message owner
+	let message_overhead = db_weight
+		.reads_writes(6, 7)
+		.saturating_add(T::WeightInfo::single_message_owner_callback_overhead(db_weight));
+	// every lane requires reading the lane entry and the proved nonce and (optionally) updating
+	// the lane data
+	let lane_overhead = db_weight.reads_writes(2, 1);
+	let relayer_fund_account = Pallet::<T, I>::relayer_fund_account_id();
+	let cursor = DecommissioningLanesCursor::<T, I>::get();
+	let (mut lanes, mut all_lanes_drained) = match cursor {
+		Some((ref cursor, all_lanes_drained)) => (OutboundLanes::<T, I>::iter_from(cursor.clone()), all_lanes_drained),
+		None => (OutboundLanes::<T, I>::iter(), true),
+	};
+	let mut next_cursor = cursor.clone();
+	let mut pass_completed = false;
+	'lanes: loop {
+		if remaining_weight.saturating_sub(used_weight) < lane_overhead {
+			break;
+		}
+
+		let (lane_id, mut lane_data) = match lanes.next() {
+			Some(lane) => lane,
+			None => {
+				pass_completed = true;
+				break;
+			}
+		};
+
+		used_weight = used_weight.saturating_add(lane_overhead);
+		let latest_refunded_nonce = lane_data.latest_expired_nonce;
+		// messages may only be refunded if they're proved to be undelivered and all messages that
+		// have been received by the bridged chain are confirmed
+		let can_refund = DecommissioningLanes::<T, I>::get(&lane_id)
+			.map(|proved_nonce| proved_nonce <= lane_data.latest_received_nonce)
+			.unwrap_or(false);
+		let mut is_lane_drained = true;
+		let mut out_of_weight = false;
+		loop {
+			let nonce = sp_std::cmp::max(lane_data.latest_received_nonce, lane_data.latest_expired_nonce) + 1;
+			if nonce > lane_data.latest_generated_nonce {
+				break;
+			}
+			if !can_refund {
+				is_lane_drained = false;
+				break;
+			}
+			if remaining_weight.saturating_sub(used_weight) < message_overhead {
+				out_of_weight = true;
+				break;
+			}
+
+			used_weight = used_weight.saturating_add(message_overhead);
+			refund_undelivered_message::<T, I>(&lane_id, nonce, &relayer_fund_account, &mut progress);
+			lane_data.latest_expired_nonce = nonce;
+		}
+
+		if lane_data.latest_expired_nonce != latest_refunded_nonce {
+			OutboundLanes::<T, I>::insert(&lane_id, &lane_data);
+		}
+		// the next round starts from this lane, because it may have more messages to refund
+		if out_of_weight {
+			break 'lanes;
+		}
+
+		if lane_data.oldest_unpruned_nonce <= lane_data.latest_received_nonce {
+			// confirmed messages are pruned by `prune_confirmed_messages`
+			is_lane_drained = false;
+		}
+		all_lanes_drained = all_lanes_drained && is_lane_drained;
+		next_cursor = Some((OutboundLanes::<T, I>::hashed_key_for(&lane_id), all_lanes_drained));
+	}
+
+	if pass_completed {
+		next_cursor = None;
+		if all_lanes_drained {
+			let relayer_fund_balance =
+				T::MessageDeliveryAndDispatchPayment::relayer_fund_balance(&relayer_fund_account);
+			progress.final_relayer_fund_balance = Some(relayer_fund_balance);
+
+			log::info!(
+				target: "runtime::bridge-messages",
+				"Decommissioning is completed: {:?}",
+				progress,
+			);
+
+			Pallet::<T, I>::deposit_event(Event::DecommissioningCompleted(progress.clone()));
+		}
+	}
+	if next_cursor != cursor {
+		match next_cursor {
+			Some(next_cursor) => DecommissioningLanesCursor::<T, I>::put(next_cursor),
+			None => DecommissioningLanesCursor::<T, I>::kill(),
+		}
+	}
+	Decommissioning::<T, I>::put(progress);
+
+	used_weight
+}
+
+/// Remove proofs of undelivered messages, that have been submitted during previous decommissioning,
+/// spending no more than `remaining_weight`. Returns the used weight.
+fn clear_decommissioning_lanes<T: Config<I>, I: 'static>(remaining_weight: Weight) -> Weight {
+	// we always read the entry that follows the last removed entry (or the end of the map)
+	let db_weight = T::DbWeight::get();
+	let used_weight = db_weight.reads(1);
+	if used_weight > remaining_weight {
+		return 0;
+	}
+
+	// every entry is read and removed
+	let entry_weight = db_weight.reads_writes(1, 1);
+	let max_entries = remaining_weight
+		.saturating_sub(used_weight)
+		.checked_div(entry_weight)
+		.unwrap_or(Weight::MAX);
+	let removed_entries = DecommissioningLanes::<T, I>::drain()
+		.take(usize::try_from(max_entries).unwrap_or(usize::MAX))
+		.count() as Weight;
+	if removed_entries != 0 {
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Removed {} proofs of undelivered messages",
+			removed_entries,
+		);
+	}
+
+	used_weight.saturating_add(entry_weight.saturating_mul(removed_entries))
+}
+
+/// Refund fee of the undelivered message to its sender and remove the message from the storage.
+fn refund_undelivered_message<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	nonce: MessageNonce,
+	relayer_fund_account: &T::AccountId,
+	progress: &mut DecommissioningProgress<T::OutboundMessageFee>,
+) {
+	let message_key = MessageKey {
+		lane_id: *lane_id,
+		nonce,
+	};
+	let sender = outbound_message_sender::<T, I>(&message_key);
+	OutboundMessagesSenders::<T, I>::remove(&message_key);
+	OutboundMessagesExpiry::<T, I>::remove(&message_key);
+	OutboundMessagesCancellations::<T, I>::remove(&message_key);
+
+	// the message is never dispatched, so let its owner know about that
+	on_message_not_dispatched::<T, I>(lane_id, nonce);
+
+	let message_data = match OutboundMessages::<T, I>::take(&message_key) {
+		Some(message_data) => message_data,
+		None => {
+			log::error!(
+				target: "runtime::bridge-messages",
+				"Undelivered message {:?}/{} is missing from the storage",
+				lane_id,
+				nonce,
+			);
+			return;
+		}
+	};
+
+	// fee of the message with unknown sender can't be refunded
+	let sender = match sender {
+		Some(sender) => sender,
+		None => {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Not refunding fee {:?} of the undelivered message {:?}/{}: sender is unknown",
+				message_data.fee,
+				lane_id,
+				nonce,
+			);
+			progress.failed_refunds += 1;
+			return;
+		}
+	};
+
+	match T::MessageDeliveryAndDispatchPayment::refund_delivery_and_dispatch_fee(
+		&sender,
+		&message_data.fee,
+		relayer_fund_account,
+	) {
+		Ok(()) => {
+			note_decommissioning_refund::<T, I>(progress, &sender, &message_data.fee);
+			Pallet::<T, I>::deposit_event(Event::MessageFeeRefunded(*lane_id, nonce));
+		}
+		Err(err) => {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to refund fee {:?} of the undelivered message {:?}/{} to {:?}: {:?}",
+				message_data.fee,
+				lane_id,
+				nonce,
+				sender,
+				err,
+			);
+			progress.failed_refunds += 1;
+		}
+	}
+}
+
+/// Add refund, made while the bridge is being decommissioned, to the decommissioning report.
+fn note_decommissioning_refund<T: Config<I>, I: 'static>(
+	progress: &mut DecommissioningProgress<T::OutboundMessageFee>,
+	sender: &bp_messages::source_chain::Sender<T::AccountId>,
+	fee: &T::OutboundMessageFee,
+) {
+	progress.refunded_messages += 1;
+	progress.refunded_fee = progress.refunded_fee.saturating_add(fee);
+	DecommissioningRefunds::<T, I>::mutate(sender, |refunded_fee| *refunded_fee = refunded_fee.saturating_add(fee));
+}
+
+/// Let the owner of the message know that the message is never dispatched.
+fn on_message_not_dispatched<T: Config<I>, I: 'static>(lane_id: &LaneId, nonce: MessageNonce) {
+	let message_key = MessageKey {
+		lane_id: *lane_id,
+		nonce,
+	};
+	if let Some(owner) = OutboundMessagesOwners::<T, I>::take(&message_key) {
+		let callback_weight = T::MessageOwners::on_message_dispatched(&owner, lane_id, nonce, false).unwrap_or(0);
+		let max_callback_weight = T::WeightInfo::single_message_callback_overhead(T::DbWeight::get());
+		if callback_weight > max_callback_weight {
+			debug_assert!(false, "T::MessageOwners callback consumed too much weight.");
+			log::error!(
+				target: "runtime::bridge-messages",
+				"T::MessageOwners callback has spent more weight that it is allowed to: {} vs {}",
+				callback_weight,
+				max_callback_weight,
+			);
+		}
+	}
+}
+
+/// Creates new inbound lane object, backed by runtime storage.
+fn inbound_lane<T: Config<I>, I: 'static>(lane_id: LaneId) -> InboundLane<RuntimeInboundLaneStorage<T, I>> {
+	InboundLane::new(inbound_lane_storage::<T, I>(lane_id))
+}
+
+/// Creates new runtime inbound lane storage.
+fn inbound_lane_storage<T: Config<I>, I: 'static>(lane_id: LaneId) -> RuntimeInboundLaneStorage<T, I> {
+	RuntimeInboundLaneStorage {
+		lane_id,
+		cached_data: RefCell::new(None),
+		_phantom: Default::default(),
+	}
+}
+
+/// Creates new outbound lane object, backed by runtime storage.
+fn outbound_lane<T: Config<I>, I: 'static>(lane_id: LaneId) -> OutboundLane<RuntimeOutboundLaneStorage<T, I>> {
+	OutboundLane::new(RuntimeOutboundLaneStorage {
+		lane_id,
+		_phantom: Default::default(),
+	})
+}
+
+/// Runtime inbound lane storage.
+struct RuntimeInboundLaneStorage<T: Config<I>, I: 'static = ()> {
+	lane_id: LaneId,
+	cached_data: RefCell<Option<InboundLaneData<T::InboundRelayer>>>,
+	_phantom: PhantomData<I>,
+}
+
+impl<T: Config<I>, I: 'static> InboundLaneStorage for RuntimeInboundLaneStorage<T, I> {
+	type MessageFee = T::InboundMessageFee;
+	type Relayer = T::InboundRelayer;
+	type AccountId = T::AccountId;
+
+	fn id(&self) -> LaneId {
+		self.lane_id
+	}
+
+	fn max_unrewarded_relayer_entries(&self) -> MessageNonce {
+		T::MaxUnrewardedRelayerEntriesAtInboundLane::get()
+	}
+
+	fn max_unconfirmed_messages(&self) -> MessageNonce {
+		T::MaxUnconfirmedMessagesAtInboundLane::get()
+	}
+
+	fn data(&self) -> InboundLaneData<T::InboundRelayer> {
+		match self.cached_data.clone().into_inner() {
+			Some(data) => data,
+			None => {
+				let data = InboundLanes::<T, I>::get(&self.lane_id);
+				*self.cached_data.try_borrow_mut().expect(
+					"we're in the single-threaded environment;\
+						we have no recursive borrows; qed",
+				) = Some(data.clone());
+				data
+			}
+		}
+	}
+
+	fn set_data(&mut self, data: InboundLaneData<T::InboundRelayer>) {
+		*self.cached_data.try_borrow_mut().expect(
+			"we're in the single-threaded environment;\
+				we have no recursive borrows; qed",
+		) = Some(data.clone());
+		InboundLanes::<T, I>::insert(&self.lane_id, data)
+	}
+
+	fn latest_queued_nonce(&self) -> Option<MessageNonce> {
+		InboundLatestQueuedNonces::<T, I>::get(&self.lane_id)
+	}
+
+	fn queued_message(
+		&self,
+		nonce: MessageNonce,
+	) -> Option<QueuedMessage<T::InboundRelayer, T::AccountId, T::InboundMessageFee>> {
+		InboundQueuedMessages::<T, I>::get(MessageKey {
+			lane_id: self.lane_id,
+			nonce,
+		})
+	}
+
+	fn queue_message(
+		&mut self,
+		nonce: MessageNonce,
+		message: QueuedMessage<T::InboundRelayer, T::AccountId, T::InboundMessageFee>,
+	) -> bool {
+		let message_size = message.data.payload.len() as u32;
+		if let Err(err) = T::QueuedMessagesDeposit::reserve_deposit(&message.relayer_at_this_chain, message_size) {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to reserve deposit for queued message {:?}/{}: {:?}",
+				self.lane_id,
+				nonce,
+				err,
+			);
+			return false;
+		}
+
+		InboundQueuedMessages::<T, I>::insert(
+			MessageKey {
+				lane_id: self.lane_id,
+				nonce,
+			},
+			message,
+		);
+		InboundLatestQueuedNonces::<T, I>::insert(&self.lane_id, nonce);
+		true
+	}
+
+	fn remove_queued_message(&mut self, nonce: MessageNonce, relayer_at_this_chain: &T::AccountId, message_size: u32) {
+		T::QueuedMessagesDeposit::unreserve_deposit(relayer_at_this_chain, message_size);
+		InboundQueuedMessages::<T, I>::remove(MessageKey {
+			lane_id: self.lane_id,
+			nonce,
+		});
+		if InboundLatestQueuedNonces::<T, I>::get(&self.lane_id) == Some(nonce) {
+			InboundLatestQueuedNonces::<T, I>::remove(&self.lane_id);
+		}
+	}
+}
+
+/// Runtime outbound lane storage.
+struct RuntimeOutboundLaneStorage<T, I = ()> {
+	lane_id: LaneId,
+	_phantom: PhantomData<(T, I)>,
+}
+
+impl<T: Config<I>, I: 'static> OutboundLaneStorage for RuntimeOutboundLaneStorage<T, I> {
+	type MessageFee = T::OutboundMessageFee;
+	type AccountId = T::AccountId;
+
+	fn id(&self) -> LaneId {
+		self.lane_id
+	}
+
+	fn data(&self) -> OutboundLaneData {
+		OutboundLanes::<T, I>::get(&self.lane_id)
+	}
+
+	fn set_data(&mut self, data: OutboundLaneData) {
+		OutboundLanes::<T, I>::insert(&self.lane_id, data)
+	}
+
+	#[cfg(test)]
+	fn message(&self, nonce: &MessageNonce) -> Option<MessageData<T::OutboundMessageFee>> {
+		OutboundMessages::<T, I>::get(MessageKey {
+			lane_id: self.lane_id,
+			nonce: *nonce,
+		})
+	}
+
+	fn save_message(
+		&mut self,
+		nonce: MessageNonce,
+		mesage_data: MessageData<T::OutboundMessageFee>,
+		sender: bp_messages::source_chain::Sender<T::AccountId>,
+	) {
+		let message_key = MessageKey {
+			lane_id: self.lane_id,
+			nonce,
+		};
+		OutboundMessages::<T, I>::insert(&message_key, mesage_data);
+		OutboundMessagesSenders::<T, I>::insert(&message_key, sender);
+	}
+
+	fn remove_message(&mut self, nonce: &MessageNonce) {
+		let message_key = MessageKey {
+			lane_id: self.lane_id,
+			nonce: *nonce,
+		};
+		OutboundMessages::<T, I>::remove(&message_key);
+		OutboundMessagesSenders::<T, I>::remove(&message_key);
+		OutboundMessagesExpiry::<T, I>::remove(&message_key);
+		OutboundMessagesCancellations::<T, I>::remove(&message_key);
+	}
+}
+
+/// Verify messages proof and return proved messages.
+///
+/// Message payloads are decoded later, right before dispatch.
+fn verify_messages_proof<Chain: SourceHeaderChain<Fee>, Fee>(
+	proof: Chain::MessagesProof,
+	messages_count: u32,
+) -> Result<ProvedMessages<Message<Fee>>, Chain::Error> {
+	// `receive_messages_proof` weight formula and `MaxUnconfirmedMessagesAtInboundLane` check
+	// guarantees that the `message_count` is sane and Vec<Message> may be allocated.
+	// (tx with too many messages will either be rejected from the pool, or will fail earlier)
+	Chain::verify_messages_proof(proof, messages_count)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		message, message_data, message_payload, run_test, unrewarded_relayer, AccountRateLimitPeriod, Balance,
+		Event as TestEvent, Origin, QueuedMessagesDispatchWeight, TestMessageDeliveryAndDispatchPayment,
+		TestMessageOwner1, TestMessageOwner2, TestMessagesDeliveryProof, TestMessagesParameter, TestMessagesProof,
+		TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2, TestOnMessageAccepted, TestQueuedMessagesDeposit,
+		TestRelayersRegistry, TestRuntime, TokenConversionRate, PAYLOAD_REJECTED_BY_TARGET_CHAIN, REGULAR_PAYLOAD,
+		TEST_LANE_ID, TEST_LANE_ID_2, TEST_MESSAGE_OWNER_1, TEST_MESSAGE_OWNER_2, TEST_REGISTERED_RELAYER,
+		TEST_RELAYER_A, TEST_RELAYER_B, TEST_RELAYER_WITHOUT_DEPOSIT, UNKNOWN_LANE_ID,
+	};
+	use bp_messages::{UnrewardedRelayer, UnrewardedRelayersState};
+	use frame_support::{
+		assert_err, assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo, traits::Hooks, weights::Weight,
+	};
+	use frame_system::{EventRecord, Pallet as System, Phase};
+	use hex_literal::hex;
+	use sp_runtime::DispatchError;
+
+	fn get_ready_for_events() {
+		System::<TestRuntime>::set_block_number(1);
+		System::<TestRuntime>::reset_events();
+	}
+
+	fn send_regular_message() -> Weight {
+		get_ready_for_events();
+
+		let message_nonce = outbound_lane::<TestRuntime, ()>(TEST_LANE_ID)
+			.data()
+			.latest_generated_nonce
+			+ 1;
+		let weight = Pallet::<TestRuntime>::send_message(
+			Origin::signed(1),
+			TEST_LANE_ID,
+			REGULAR_PAYLOAD,
+			REGULAR_PAYLOAD.declared_weight,
+			None,
+		)
+		.expect("send_message has failed")
+		.actual_weight
+		.expect("send_message always returns Some");
+
+		// check event with assigned nonce
+		assert_eq!(
+			System::<TestRuntime>::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(Event::MessageAccepted(TEST_LANE_ID, message_nonce)),
+				topics: vec![],
+			}],
+		);
+
+		// check that fee has been withdrawn from submitter
+		assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(
+			1,
+			REGULAR_PAYLOAD.declared_weight
+		));
+
+		weight
+	}
+
+	fn send_owned_regular_message(owner: MessageOwnerId) -> MessageNonce {
+		<Pallet<TestRuntime> as bp_messages::source_chain::MessagesBridge<_, _, _>>::send_owned_message(
+			owner,
+			bp_messages::source_chain::Sender::Signed(1),
+			TEST_LANE_ID,
+			REGULAR_PAYLOAD,
+			REGULAR_PAYLOAD.declared_weight,
+		)
+		.expect("send_owned_message has failed")
+	}
+
+	fn receive_messages_delivery_proof() {
+		System::<TestRuntime>::set_block_number(1);
+		System::<TestRuntime>::reset_events();
+
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+			Origin::signed(1),
+			TestMessagesDeliveryProof(Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 1,
+					relayers: vec![UnrewardedRelayer {
+						relayer: 0,
+						messages: DeliveredMessages::new(1, true),
+					}]
+					.into_iter()
+					.collect(),
+				},
+			))),
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				total_messages: 1,
+				..Default::default()
+			},
+		));
+
+		assert_eq!(
+			System::<TestRuntime>::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(Event::MessagesDelivered(TEST_LANE_ID, DeliveredMessages::new(1, true),)),
+				topics: vec![],
+			}],
+		);
+	}
+
+	#[test]
+	fn pallet_owner_may_change_owner() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(1)));
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(2), OperatingMode::Halted),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Halted
+			));
+
+			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::signed(1), None));
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Normal),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(2), OperatingMode::Normal),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Normal
+			));
+		});
+	}
+
+	#[test]
+	fn pallet_may_be_halted_by_root() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Halted
+			));
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Normal
+			));
+		});
+	}
+
+	#[test]
+	fn pallet_may_be_halted_by_owner() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::signed(2),
+				OperatingMode::Halted
+			));
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::signed(2),
+				OperatingMode::Normal
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Halted),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Normal),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::signed(2),
+				OperatingMode::Halted
+			));
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Normal),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn pallet_parameter_may_be_updated_by_root() {
+		run_test(|| {
+			get_ready_for_events();
+
+			let parameter = TestMessagesParameter::TokenConversionRate(10.into());
+			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
+				Origin::root(),
+				parameter.clone(),
+			));
+
+			assert_eq!(TokenConversionRate::get(), 10.into());
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::ParameterUpdated(parameter)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn pallet_parameter_may_be_updated_by_owner() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+			get_ready_for_events();
+
+			let parameter = TestMessagesParameter::TokenConversionRate(10.into());
+			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
+				Origin::signed(2),
+				parameter.clone(),
+			));
+
+			assert_eq!(TokenConversionRate::get(), 10.into());
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::ParameterUpdated(parameter)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn pallet_parameter_cant_be_updated_by_arbitrary_submitter() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::update_pallet_parameter(
+					Origin::signed(2),
+					TestMessagesParameter::TokenConversionRate(10.into()),
+				),
+				DispatchError::BadOrigin,
+			);
+
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::update_pallet_parameter(
+					Origin::signed(1),
+					TestMessagesParameter::TokenConversionRate(10.into()),
+				),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn fixed_u128_works_as_i_think() {
+		// this test is here just to be sure that conversion rate may be represented with FixedU128
+		run_test(|| {
+			use sp_runtime::{FixedPointNumber, FixedU128};
+
+			// 1:1 conversion that we use by default for testnets
+			let rialto_token = 1u64;
+			let rialto_token_in_millau_tokens = TokenConversionRate::get().saturating_mul_int(rialto_token);
+			assert_eq!(rialto_token_in_millau_tokens, 1);
+
+			// let's say conversion rate is 1:1.7
+			let conversion_rate = FixedU128::saturating_from_rational(170, 100);
+			let rialto_tokens = 100u64;
+			let rialto_tokens_in_millau_tokens = conversion_rate.saturating_mul_int(rialto_tokens);
+			assert_eq!(rialto_tokens_in_millau_tokens, 170);
+
+			// let's say conversion rate is 1:0.25
+			let conversion_rate = FixedU128::saturating_from_rational(25, 100);
+			let rialto_tokens = 100u64;
+			let rialto_tokens_in_millau_tokens = conversion_rate.saturating_mul_int(rialto_tokens);
+			assert_eq!(rialto_tokens_in_millau_tokens, 25);
+		});
+	}
+
+	#[test]
+	fn pallet_rejects_transactions_if_halted() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
+			send_regular_message();
+
+			PalletOperatingMode::<TestRuntime, ()>::put(OperatingMode::Halted);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None,
+				),
+				Error::<TestRuntime, ()>::Halted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 1,),
+				Error::<TestRuntime, ()>::Halted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(2, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::Halted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							last_confirmed_nonce: 1,
+							relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+						},
+					))),
+					UnrewardedRelayersState {
+						unrewarded_relayer_entries: 1,
+						messages_in_oldest_entry: 1,
+						total_messages: 1,
+					},
+				),
+				Error::<TestRuntime, ()>::Halted,
+			);
+		});
+	}
+
+	#[test]
+	fn pallet_rejects_new_messages_in_rejecting_outbound_messages_operating_mode() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
+			send_regular_message();
+
+			PalletOperatingMode::<TestRuntime, ()>::put(OperatingMode::RejectingOutboundMessages);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None,
+				),
+				Error::<TestRuntime, ()>::Halted,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::increase_message_fee(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+				1,
+			));
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			),);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 1,
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					messages_in_oldest_entry: 1,
+					total_messages: 1,
+				},
+			));
+		});
+	}
+
+	#[test]
+	fn lane_operating_mode_may_be_changed_by_root_or_owner() {
+		run_test(|| {
+			get_ready_for_events();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_operating_mode(Origin::signed(2), TEST_LANE_ID, OperatingMode::Halted),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::root(),
+				TEST_LANE_ID,
+				OperatingMode::Halted
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_operating_mode(TEST_LANE_ID),
+				OperatingMode::Halted
+			);
+
+			PalletOwner::<TestRuntime>::put(2);
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::signed(2),
+				TEST_LANE_ID,
+				OperatingMode::Normal
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_operating_mode(TEST_LANE_ID),
+				OperatingMode::Normal
+			);
+			assert!(!LaneOperatingModes::<TestRuntime>::contains_key(&TEST_LANE_ID));
+
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::LaneOperatingModeChanged(
+							TEST_LANE_ID,
+							OperatingMode::Halted
+						)),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::LaneOperatingModeChanged(
+							TEST_LANE_ID,
+							OperatingMode::Normal
+						)),
+						topics: vec![],
+					},
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn pallet_rejects_transactions_if_lane_is_halted() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
+			send_regular_message();
+
+			LaneOperatingModes::<TestRuntime, ()>::insert(TEST_LANE_ID, OperatingMode::Halted);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None,
+				),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 1,),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(2, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							last_confirmed_nonce: 1,
+							relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+						},
+					))),
+					UnrewardedRelayersState {
+						unrewarded_relayer_entries: 1,
+						messages_in_oldest_entry: 1,
+						total_messages: 1,
+					},
+				),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			// other lanes are still working
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID_2,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+				None,
+			));
+		});
+	}
+
+	#[test]
+	fn pallet_rejects_new_messages_if_lane_is_rejecting_outbound_messages() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
+			send_regular_message();
+
+			LaneOperatingModes::<TestRuntime, ()>::insert(TEST_LANE_ID, OperatingMode::RejectingOutboundMessages);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None,
+				),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			),);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 1,
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					messages_in_oldest_entry: 1,
+					total_messages: 1,
+				},
+			));
+		});
+	}
+
+	#[test]
+	fn pallet_operating_mode_overrides_lane_operating_mode() {
+		run_test(|| {
+			PalletOperatingMode::<TestRuntime, ()>::put(OperatingMode::Halted);
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::root(),
+				TEST_LANE_ID,
+				OperatingMode::Normal
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None,
+				),
+				Error::<TestRuntime, ()>::Halted,
+			);
+		});
+	}
+
+	#[test]
+	fn lane_may_be_opened_by_root_or_owner() {
+		run_test(|| {
+			get_ready_for_events();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::open_lane(Origin::signed(2), UNKNOWN_LANE_ID),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::open_lane(Origin::root(), UNKNOWN_LANE_ID));
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_state(UNKNOWN_LANE_ID),
+				Some(LaneState::Opening)
+			);
+
+			PalletOwner::<TestRuntime>::put(2);
+			assert_ok!(Pallet::<TestRuntime>::open_lane(Origin::signed(2), [0, 0, 0, 4]));
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_state([0, 0, 0, 4]),
+				Some(LaneState::Opening)
+			);
+
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::LaneStateChanged(UNKNOWN_LANE_ID, LaneState::Opening)),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::LaneStateChanged([0, 0, 0, 4], LaneState::Opening)),
+						topics: vec![],
+					},
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn lane_cant_be_opened_twice() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::open_lane(Origin::root(), TEST_LANE_ID),
+				Error::<TestRuntime, ()>::LaneAlreadyRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn opening_lane_becomes_open_when_proof_is_received() {
+		run_test(|| {
+			Lanes::<TestRuntime>::insert(TEST_LANE_ID, LaneState::Opening);
+
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+				None,
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_state(TEST_LANE_ID),
+				Some(LaneState::Opening)
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(Pallet::<TestRuntime>::lane_state(TEST_LANE_ID), Some(LaneState::Open));
+		});
+	}
+
+	#[test]
+	fn messages_to_unknown_lane_are_rejected() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					UNKNOWN_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None,
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+
+			let mut unknown_lane_message = message(1, REGULAR_PAYLOAD);
+			unknown_lane_message.key.lane_id = UNKNOWN_LANE_ID;
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![unknown_lane_message]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((UNKNOWN_LANE_ID, InboundLaneData::default()))),
+					UnrewardedRelayersState::default(),
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+		});
+	}
+
+	#[test]
+	fn closing_lane_rejects_new_messages_and_is_closed_when_drained() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime>::close_lane(Origin::root(), TEST_LANE_ID));
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_state(TEST_LANE_ID),
+				Some(LaneState::Closing)
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None,
+				),
+				Error::<TestRuntime, ()>::LaneIsClosed,
+			);
+
+			// inbound messages are still accepted
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			// confirmation drains the lane
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 1,
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					messages_in_oldest_entry: 1,
+					total_messages: 1,
+				},
+			));
+			assert_eq!(Pallet::<TestRuntime>::lane_state(TEST_LANE_ID), Some(LaneState::Closed));
+		});
+	}
+
+	#[test]
+	fn drained_lane_is_closed_immediately() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::close_lane(Origin::root(), TEST_LANE_ID));
+			assert_eq!(Pallet::<TestRuntime>::lane_state(TEST_LANE_ID), Some(LaneState::Closed));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::close_lane(Origin::root(), TEST_LANE_ID),
+				Error::<TestRuntime, ()>::InvalidLaneStateTransition,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::close_lane(Origin::root(), UNKNOWN_LANE_ID),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+		});
+	}
+
+	#[test]
+	fn closed_lane_may_be_reopened() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::reopen_lane(Origin::root(), TEST_LANE_ID),
+				Error::<TestRuntime, ()>::InvalidLaneStateTransition,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::close_lane(Origin::root(), TEST_LANE_ID));
+			assert_noop!(
+				Pallet::<TestRuntime>::reopen_lane(Origin::signed(2), TEST_LANE_ID),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::reopen_lane(Origin::root(), TEST_LANE_ID));
+			assert_eq!(Pallet::<TestRuntime>::lane_state(TEST_LANE_ID), Some(LaneState::Open));
+
+			send_regular_message();
+		});
+	}
+
+	#[test]
+	fn send_message_works() {
+		run_test(|| {
+			send_regular_message();
+		});
+	}
+
+	#[test]
+	fn send_message_saves_message_sender() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_sender(TEST_LANE_ID, 1),
+				Some(bp_messages::source_chain::Sender::Signed(1)),
+			);
+		});
+	}
+
+	#[test]
+	fn send_messages_batch_works() {
+		run_test(|| {
+			send_regular_message();
+
+			let fee = REGULAR_PAYLOAD.declared_weight;
+			assert_ok!(Pallet::<TestRuntime>::send_messages_batch(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				vec![(REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, fee)],
+			));
+
+			// messages get consecutive nonces and the combined fee is paid at once
+			assert_eq!(
+				OutboundLanes::<TestRuntime>::get(&TEST_LANE_ID).latest_generated_nonce,
+				4
+			);
+			for nonce in 2..=4 {
+				assert!(Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, nonce).is_some());
+			}
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 3 * fee));
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessagesAccepted(TEST_LANE_ID, 2, 4)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn send_messages_batch_is_sent_using_messages_bridge() {
+		run_test(|| {
+			let fee = REGULAR_PAYLOAD.declared_weight;
+			assert_eq!(
+				<Pallet<TestRuntime> as bp_messages::source_chain::MessagesBridge<_, _, _>>::send_messages(
+					bp_messages::source_chain::Sender::Signed(1),
+					TEST_LANE_ID,
+					vec![(REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, fee)],
+				),
+				Ok(1..=2),
+			);
+		});
+	}
+
+	#[test]
+	fn send_messages_batch_rejects_empty_batch() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages_batch(Origin::signed(1), TEST_LANE_ID, vec![]),
+				Error::<TestRuntime, ()>::EmptyMessagesBatch,
+			);
+		});
+	}
+
+	#[test]
+	fn send_messages_batch_rejects_all_messages_if_one_is_invalid() {
+		run_test(|| {
+			let fee = REGULAR_PAYLOAD.declared_weight;
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages_batch(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					vec![(REGULAR_PAYLOAD, fee), (PAYLOAD_REJECTED_BY_TARGET_CHAIN, fee)],
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages_batch(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					vec![(REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, 0)],
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+			);
+		});
+	}
+
+	#[test]
+	fn send_messages_batch_rejects_all_messages_if_submitter_cant_pay_fee() {
+		run_test(|| {
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
+			let fee = REGULAR_PAYLOAD.declared_weight;
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages_batch(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					vec![(REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, fee)],
+				),
+				Error::<TestRuntime, ()>::FailedToWithdrawMessageFee,
+			);
+		});
+	}
+
+	#[test]
+	fn chain_verifier_rejects_invalid_message_in_send_message() {
+		run_test(|| {
+			// messages with this payload are rejected by target chain verifier
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					PAYLOAD_REJECTED_BY_TARGET_CHAIN,
+					PAYLOAD_REJECTED_BY_TARGET_CHAIN.declared_weight,
+					None
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
+			);
+		});
+	}
+
+	fn send_message_to_lane(lane_id: LaneId) -> DispatchResultWithPostInfo {
+		Pallet::<TestRuntime>::send_message(
+			Origin::signed(1),
+			lane_id,
+			REGULAR_PAYLOAD,
+			REGULAR_PAYLOAD.declared_weight,
+			None,
+		)
+	}
+
+	#[test]
+	fn send_message_rejects_messages_over_account_rate_limit() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
+				Origin::root(),
+				TestMessagesParameter::MaxMessagesPerAccountPerPeriod(2),
+			));
+
+			// the account may send 2 messages during the rate limit period, over any lanes
+			assert_ok!(send_message_to_lane(TEST_LANE_ID));
+			assert_ok!(send_message_to_lane(TEST_LANE_ID_2));
+			assert_noop!(
+				send_message_to_lane(TEST_LANE_ID),
+				Error::<TestRuntime, ()>::TooManyMessagesFromAccount,
+			);
+
+			// messages from root are not limited
+			assert_ok!(<Pallet<TestRuntime> as bp_messages::source_chain::MessagesBridge<
+				_,
+				_,
+				_,
+			>>::send_message(
+				bp_messages::source_chain::Sender::Root,
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			// when next period starts, the account may send messages again
+			System::<TestRuntime>::set_block_number(AccountRateLimitPeriod::get());
+			assert_ok!(send_message_to_lane(TEST_LANE_ID));
+		});
+	}
+
+	#[test]
+	fn send_message_rejects_messages_over_lane_rate_limit() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
+				Origin::root(),
+				TestMessagesParameter::MaxLaneBytesPerBlock(2 * REGULAR_PAYLOAD.encoded_size() as u32),
+			));
+
+			// only 2 messages may be sent over every lane in a single block
+			assert_ok!(send_message_to_lane(TEST_LANE_ID));
+			assert_ok!(send_message_to_lane(TEST_LANE_ID));
+			assert_noop!(
+				send_message_to_lane(TEST_LANE_ID),
+				Error::<TestRuntime, ()>::TooManyBytesAtLane,
+			);
+			assert_ok!(send_message_to_lane(TEST_LANE_ID_2));
+
+			// when next block starts, the lane accepts messages again
+			System::<TestRuntime>::set_block_number(1);
+			assert_ok!(send_message_to_lane(TEST_LANE_ID));
+		});
+	}
+
+	#[test]
+	fn send_messages_batch_respects_rate_limits() {
+		run_test(|| {
+			let fee = REGULAR_PAYLOAD.declared_weight;
+			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
+				Origin::root(),
+				TestMessagesParameter::MaxMessagesPerAccountPerPeriod(2),
+			));
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages_batch(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					vec![(REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, fee)],
+				),
+				Error::<TestRuntime, ()>::TooManyMessagesFromAccount,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
+				Origin::root(),
+				TestMessagesParameter::MaxLaneBytesPerBlock(REGULAR_PAYLOAD.encoded_size() as u32),
+			));
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages_batch(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					vec![(REGULAR_PAYLOAD, fee), (REGULAR_PAYLOAD, fee)],
+				),
+				Error::<TestRuntime, ()>::TooManyBytesAtLane,
+			);
+		});
+	}
+
+	#[test]
+	fn expired_account_messages_rates_are_pruned_in_on_idle() {
+		run_test(|| {
+			AccountMessagesRate::<TestRuntime>::insert(1, (0, 1));
+			AccountMessagesRate::<TestRuntime>::insert(2, (5, 1));
+			AccountMessagesRate::<TestRuntime>::insert(3, (0, 1));
+			System::<TestRuntime>::set_block_number(AccountRateLimitPeriod::get());
+
+			// the weight is only enough to read and update the cursor and process single entry
+			let db_weight = crate::mock::DbWeight::get();
+			let remaining_weight = db_weight.reads_writes(2, 2);
+			assert_eq!(
+				prune_expired_account_messages_rates::<TestRuntime, ()>(remaining_weight),
+				remaining_weight,
+			);
+			assert!(AccountMessagesRatePruningCursor::<TestRuntime>::get().is_some());
+
+			// the next round starts from the next entry
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(
+				AccountMessagesRate::<TestRuntime>::iter_keys().collect::<Vec<_>>(),
+				vec![2],
+			);
+			assert_eq!(AccountMessagesRatePruningCursor::<TestRuntime>::get(), None);
+		});
+	}
+
+	#[test]
+	fn only_root_may_start_and_stop_decommissioning() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(2), OperatingMode::Decommissioning),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_progress(),
+				Some(DecommissioningProgress::default()),
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(2), OperatingMode::Normal),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Normal
+			));
+			assert_eq!(Pallet::<TestRuntime>::decommissioning_progress(), None);
+		});
+	}
+
+	#[test]
+	fn proofs_of_undelivered_messages_are_removed_after_decommissioning_is_stopped() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+			assert_ok!(Pallet::<TestRuntime>::prove_undelivered_messages(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID
+			));
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Normal
+			));
+			assert_eq!(DecommissioningLanes::<TestRuntime>::get(TEST_LANE_ID), Some(0));
+
+			// stale proofs must be removed before the next decommissioning is started
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operating_mode(Origin::root(), OperatingMode::Decommissioning),
+				Error::<TestRuntime, ()>::DecommissioningLanesNotCleared,
+			);
+
+			// read the mode, read and remove the proof, then read the end of proofs map
+			assert_eq!(
+				decommission_lanes::<TestRuntime, ()>(Weight::MAX),
+				crate::mock::DbWeight::get().reads_writes(3, 1),
+			);
+			assert_eq!(DecommissioningLanes::<TestRuntime>::get(TEST_LANE_ID), None);
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+		});
+	}
+
+	#[test]
+	fn lanes_can_not_be_decommissioned_individually() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_operating_mode(
+					Origin::root(),
+					TEST_LANE_ID,
+					OperatingMode::Decommissioning
+				),
+				Error::<TestRuntime, ()>::UnsupportedLaneOperatingMode,
+			);
+		});
+	}
+
+	#[test]
+	fn bridge_operations_are_rejected_when_decommissioning() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+
+			assert_noop!(
+				send_message_to_lane(TEST_LANE_ID),
+				Error::<TestRuntime, ()>::Decommissioning
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::Decommissioning,
+			);
+		});
+	}
+
+	#[test]
+	fn delivery_confirmations_are_accepted_when_decommissioning() {
+		run_test(|| {
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+
+			receive_messages_delivery_proof();
+			assert_eq!(OutboundLanes::<TestRuntime>::get(TEST_LANE_ID).latest_received_nonce, 1);
+		});
+	}
+
+	#[test]
+	fn undelivered_messages_may_only_be_proved_when_decommissioning() {
+		run_test(|| {
+			send_regular_message();
+			assert_noop!(
+				Pallet::<TestRuntime>::prove_undelivered_messages(
+					Origin::signed(1),
+					non_delivery_proof(0),
+					TEST_LANE_ID
+				),
+				Error::<TestRuntime, ()>::NotDecommissioning,
+			);
+		});
+	}
+
+	#[test]
+	fn stale_undelivered_messages_proof_is_rejected() {
+		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::prove_undelivered_messages(
+					Origin::signed(1),
+					non_delivery_proof(0),
+					TEST_LANE_ID
+				),
+				Error::<TestRuntime, ()>::StaleMessagesDeliveryProof,
+			);
+		});
+	}
+
+	#[test]
+	fn decommissioning_refunds_undelivered_messages() {
+		run_test(|| {
+			// message 1 is delivered, messages 2 and 3 are not
+			send_regular_message();
+			send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+			send_regular_message();
+			receive_messages_delivery_proof();
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+			assert_ok!(Pallet::<TestRuntime>::prove_undelivered_messages(
+				Origin::signed(1),
+				non_delivery_proof(1),
+				TEST_LANE_ID
+			));
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+
+			// undelivered messages are refunded and the delivered message is pruned
+			let expected_progress = DecommissioningProgress {
+				refunded_messages: 2,
+				refunded_fee: 2 * REGULAR_PAYLOAD.declared_weight,
+				failed_refunds: 0,
+				final_relayer_fund_balance: Some(0),
+			};
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_progress(),
+				Some(expected_progress.clone()),
+			);
+			assert_eq!(OutboundLanes::<TestRuntime>::get(TEST_LANE_ID).latest_expired_nonce, 3);
+			assert_eq!(OutboundMessages::<TestRuntime>::iter().count(), 0);
+			assert_eq!(OutboundMessagesOwners::<TestRuntime>::iter().count(), 0);
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_refunds(bp_messages::source_chain::Sender::Signed(1)),
+				2 * REGULAR_PAYLOAD.declared_weight,
+			);
+			TestMessageOwner1::ensure_called(&TEST_LANE_ID, 2, false);
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::DecommissioningCompleted(expected_progress)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn decommissioning_refunds_messages_in_batches() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+			assert_ok!(Pallet::<TestRuntime>::prove_undelivered_messages(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID
+			));
+
+			// the weight is only enough to refund single message: read mode, progress, cursor, relayers
+			// fund balance and the end of lanes map, update progress and cursor, read both lanes entries
+			// and proved nonces, update both lanes data and refund the message
+			let db_weight = crate::mock::DbWeight::get();
+			let remaining_weight =
+				db_weight
+					.reads_writes(9, 4)
+					.saturating_add(db_weight.reads_writes(6, 7).saturating_add(
+						<TestRuntime as Config>::WeightInfo::single_message_owner_callback_overhead(db_weight),
+					));
+			for refunded_messages in 1..=3 {
+				assert_eq!(
+					decommission_lanes::<TestRuntime, ()>(remaining_weight),
+					remaining_weight
+				);
+				assert_eq!(
+					Pallet::<TestRuntime>::decommissioning_progress().map(|progress| progress.refunded_messages),
+					Some(refunded_messages),
+				);
+			}
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_progress()
+					.and_then(|progress| progress.final_relayer_fund_balance),
+				None,
+			);
+
+			// decommissioning is completed when all lanes are checked and there are no undelivered messages left
+			decommission_lanes::<TestRuntime, ()>(remaining_weight);
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_progress()
+					.and_then(|progress| progress.final_relayer_fund_balance),
+				Some(0),
+			);
+		});
+	}
+
+	#[test]
+	fn decommissioning_does_not_refund_messages_without_proof() {
+		run_test(|| {
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_progress(),
+				Some(DecommissioningProgress::default()),
+			);
+			assert_eq!(OutboundMessages::<TestRuntime>::iter().count(), 1);
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_refunds(bp_messages::source_chain::Sender::Signed(1)),
+				0
+			);
+		});
+	}
+
+	#[test]
+	fn decommissioning_does_not_refund_delivered_messages_until_delivery_is_confirmed() {
+		run_test(|| {
+			// message 1 is delivered, but its delivery is not confirmed. Message 2 is not delivered
+			send_regular_message();
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				OperatingMode::Decommissioning
+			));
+			assert_ok!(Pallet::<TestRuntime>::prove_undelivered_messages(
+				Origin::signed(1),
+				non_delivery_proof(1),
+				TEST_LANE_ID
+			));
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_progress(),
+				Some(DecommissioningProgress::default()),
+			);
+			assert_eq!(OutboundMessages::<TestRuntime>::iter().count(), 2);
+
+			// when delivery of message 1 is confirmed, relayer is rewarded and message 2 is refunded
+			receive_messages_delivery_proof();
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_progress(),
+				Some(DecommissioningProgress {
+					refunded_messages: 1,
+					refunded_fee: REGULAR_PAYLOAD.declared_weight,
+					failed_refunds: 0,
+					final_relayer_fund_balance: Some(0),
+				}),
+			);
+			assert_eq!(OutboundLanes::<TestRuntime>::get(TEST_LANE_ID).latest_expired_nonce, 2);
+			assert_eq!(
+				Pallet::<TestRuntime>::decommissioning_refunds(bp_messages::source_chain::Sender::Signed(1)),
+				REGULAR_PAYLOAD.declared_weight,
+			);
+		});
+	}
+
+	#[test]
+	fn lane_verifier_rejects_invalid_message_in_send_message() {
+		run_test(|| {
+			// messages with zero fee are rejected by lane verifier
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(Origin::signed(1), TEST_LANE_ID, REGULAR_PAYLOAD, 0, None),
+				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+			);
+		});
+	}
+
+	#[test]
+	fn message_send_fails_if_submitter_cant_pay_message_fee() {
+		run_test(|| {
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+					None
+				),
+				Error::<TestRuntime, ()>::FailedToWithdrawMessageFee,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_works() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_updates_confirmed_message_nonce() {
+		run_test(|| {
+			// say we have received 10 messages && last confirmed message is 8
+			InboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 8,
+					relayers: vec![
+						unrewarded_relayer(9, 9, TEST_RELAYER_A),
+						unrewarded_relayer(10, 10, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_unrewarded_relayers_state(TEST_LANE_ID),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 2,
+					messages_in_oldest_entry: 1,
+					total_messages: 2,
+				},
+			);
+
+			// message proof includes outbound lane state with latest confirmed message updated to 9
+			let mut message_proof: TestMessagesProof = Ok(vec![message(11, REGULAR_PAYLOAD)]).into();
+			message_proof.result.as_mut().unwrap()[0].1.lane_state = Some(OutboundLaneData {
+				latest_received_nonce: 9,
+				..Default::default()
+			});
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				message_proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID),
+				InboundLaneData {
+					last_confirmed_nonce: 9,
+					relayers: vec![
+						unrewarded_relayer(10, 10, TEST_RELAYER_B),
+						unrewarded_relayer(11, 11, TEST_RELAYER_A)
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_unrewarded_relayers_state(TEST_LANE_ID),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 2,
+					messages_in_oldest_entry: 1,
+					total_messages: 2,
+				},
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_does_not_accept_message_if_dispatch_weight_is_not_enough() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight - 1,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_queues_message_if_dispatch_weight_is_not_enough() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight - 1,
+			));
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), Some(1));
+			assert_eq!(Pallet::<TestRuntime>::inbound_latest_received_nonce(TEST_LANE_ID), 0);
+			assert_eq!(
+				TestQueuedMessagesDeposit::reserved_deposit(1),
+				REGULAR_PAYLOAD.encode().len() as Balance,
+			);
+
+			// the same message can't be delivered again
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_B,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			// the message is dispatched when there's enough weight in the block
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), None);
+			assert_eq!(
+				InboundQueuedMessages::<TestRuntime>::get(MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce: 1
+				}),
+				None,
+			);
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers,
+				vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)],
+			);
+			assert_eq!(Pallet::<TestRuntime>::inbound_latest_received_nonce(TEST_LANE_ID), 1);
+			assert_eq!(TestQueuedMessagesDeposit::reserved_deposit(1), 0);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_does_not_queue_message_if_deposit_is_not_reserved() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(TEST_RELAYER_WITHOUT_DEPOSIT),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				0,
+			));
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), None);
+			assert_eq!(
+				InboundQueuedMessages::<TestRuntime>::get(MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce: 1
+				}),
+				None,
+			);
+		});
+	}
+
+	#[test]
+	fn queued_messages_are_dispatched_in_on_initialize() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				0,
+			));
+
+			assert!(Pallet::<TestRuntime>::on_initialize(0) <= QueuedMessagesDispatchWeight::get());
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), None);
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+		});
+	}
+
+	#[test]
+	fn dispatch_fee_overhead_is_refunded_only_for_queued_messages_that_are_paying_fee_at_this_chain() {
+		run_test(|| {
+			fn queue_message(nonce: MessageNonce, is_prepaid: bool) -> (Weight, Weight) {
+				let mut payload = REGULAR_PAYLOAD;
+				payload.dispatch_result.dispatch_fee_paid_during_dispatch = !is_prepaid;
+				let proof = Ok(vec![message(nonce, payload)]).into();
+				let pre_dispatch_weight =
+					<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(&proof, 1, 0)
+						+ crate::mock::DbWeight::get().reads(1);
+				let post_dispatch_weight =
+					Pallet::<TestRuntime>::receive_messages_proof(Origin::signed(1), TEST_RELAYER_A, proof, 1, 0)
+						.expect("delivery has failed")
+						.actual_weight
+						.expect("receive_messages_proof always returns Some");
+
+				(pre_dispatch_weight, post_dispatch_weight)
+			}
+
+			let (pre, post) = queue_message(1, false);
+			assert_eq!(
+				post,
+				pre - <TestRuntime as Config>::WeightInfo::pay_inbound_dispatch_fee_overhead()
+			);
+
+			let (pre, post) = queue_message(2, true);
+			assert_eq!(post, pre);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_queues_messages_if_lane_has_queued_messages() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				0,
+			));
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_B,
+				Ok(vec![message(2, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), Some(2));
+
+			// both messages are dispatched in order
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), None);
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers,
+				vec![
+					unrewarded_relayer(1, 1, TEST_RELAYER_A),
+					unrewarded_relayer(2, 2, TEST_RELAYER_B),
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn queued_messages_are_not_dispatched_if_block_weight_is_not_enough() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				0,
+			));
+
+			let remaining_weight = REGULAR_PAYLOAD.declared_weight - 1;
+			assert!(Pallet::<TestRuntime>::on_idle(0, remaining_weight) <= remaining_weight);
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), Some(1));
+		});
+	}
+
+	#[test]
+	fn queued_messages_are_not_dispatched_at_halted_lane() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				0,
+			));
+
+			LaneOperatingModes::<TestRuntime>::insert(TEST_LANE_ID, OperatingMode::Halted);
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
+
+			LaneOperatingModes::<TestRuntime>::insert(TEST_LANE_ID, OperatingMode::Normal);
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+		});
+	}
+
+	#[test]
+	fn queued_messages_dispatch_starts_from_the_lane_that_follows_the_cursor() {
+		run_test(|| {
+			for lane_id in &[TEST_LANE_ID, TEST_LANE_ID_2] {
+				inbound_lane::<TestRuntime, ()>(*lane_id).queue_message(
+					&TEST_RELAYER_A,
+					&TEST_RELAYER_A,
+					1,
+					message_data(REGULAR_PAYLOAD),
+					false,
+				);
+			}
+
+			// the round starts after the first lane, so only the second lane is processed
+			let first_lane = InboundLatestQueuedNonces::<TestRuntime>::iter_keys().next().unwrap();
+			let second_lane = if first_lane == TEST_LANE_ID {
+				TEST_LANE_ID_2
+			} else {
+				TEST_LANE_ID
+			};
+			InboundQueuedMessagesDispatchCursor::<TestRuntime>::put(
+				InboundLatestQueuedNonces::<TestRuntime>::hashed_key_for(&first_lane),
+			);
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(InboundLanes::<TestRuntime>::get(first_lane).last_delivered_nonce(), 0);
+			assert_eq!(InboundLanes::<TestRuntime>::get(second_lane).last_delivered_nonce(), 1);
+			assert_eq!(InboundQueuedMessagesDispatchCursor::<TestRuntime>::get(), None);
+
+			// the next round starts from the first lane
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(InboundLanes::<TestRuntime>::get(first_lane).last_delivered_nonce(), 1);
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::iter_keys().next(), None);
+		});
+	}
+
+	#[test]
+	fn queued_messages_dispatch_is_stalled_if_there_are_too_many_unrewarded_relayers() {
+		run_test(|| {
+			let max_entries = crate::mock::MaxUnrewardedRelayerEntriesAtInboundLane::get();
+			InboundLanes::<TestRuntime>::mutate(TEST_LANE_ID, |data| {
+				for nonce in 1..=max_entries {
+					data.relayers
+						.push_back(unrewarded_relayer(nonce, nonce, TEST_RELAYER_A + nonce));
+				}
+			});
+			let nonce = max_entries + 1;
+			InboundQueuedMessages::<TestRuntime>::insert(
+				MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce,
+				},
+				QueuedMessage {
+					relayer_at_bridged_chain: TEST_RELAYER_A,
+					relayer_at_this_chain: TEST_RELAYER_A,
+					data: message_data(REGULAR_PAYLOAD),
+					skipped: false,
+				},
+			);
+			InboundLatestQueuedNonces::<TestRuntime>::insert(TEST_LANE_ID, nonce);
+
+			get_ready_for_events();
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(),
+				max_entries
+			);
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), Some(nonce));
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::QueuedMessagesDispatchStalled(TEST_LANE_ID)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+	#[test]
+	fn receive_messages_proof_rejects_invalid_proof() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Err(()).into(),
+					1,
+					0,
+				),
+				Error::<TestRuntime, ()>::InvalidMessagesProof,
+			);
+		});
+	}
+
+	#[test]
+	fn registered_relayer_is_slashed_for_invalid_messages_proof() {
+		run_test(|| {
+			assert_err!(
+				Pallet::<TestRuntime, ()>::receive_messages_proof(
+					Origin::signed(TEST_REGISTERED_RELAYER),
+					TEST_RELAYER_A,
+					Err(()).into(),
+					1,
+					0,
+				),
+				Error::<TestRuntime, ()>::InvalidMessagesProof,
+			);
+			assert!(TestRelayersRegistry::is_relayer_slashed(TEST_REGISTERED_RELAYER));
+		});
+	}
+
+	#[test]
+	fn registered_relayer_does_not_pay_for_delivering_new_messages() {
+		run_test(|| {
+			let receive_messages_proof = |relayer| {
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(relayer),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				)
+				.expect("proof is valid")
+				.pays_fee
+			};
+
+			// new message is delivered by registered relayer => no fee
+			assert_eq!(receive_messages_proof(TEST_REGISTERED_RELAYER), Pays::No);
+			// the same message is delivered again => relayer pays fee
+			assert_eq!(receive_messages_proof(TEST_REGISTERED_RELAYER), Pays::Yes);
+		});
+	}
+
+	#[test]
+	fn unregistered_relayer_pays_for_delivering_new_messages() {
+		run_test(|| {
+			let post_dispatch_info = Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			)
+			.expect("proof is valid");
+			assert_eq!(post_dispatch_info.pays_fee, Pays::Yes);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_rejects_proof_with_too_many_messages() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					u32::MAX,
+					0,
+				),
+				Error::<TestRuntime, ()>::TooManyMessagesInTheProof,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_works() {
+		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(&TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_rewards_relayers() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				1000,
+				None,
+			));
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				2000,
+				None,
+			));
+
+			// this reports delivery of message 1 => reward is paid to TEST_RELAYER_A
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 1,
+					..Default::default()
+				},
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000
+			));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_B,
+				2000
+			));
+
+			// this reports delivery of both message 1 and message 2 => reward is paid only to TEST_RELAYER_B
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![
+							unrewarded_relayer(1, 1, TEST_RELAYER_A),
+							unrewarded_relayer(2, 2, TEST_RELAYER_B)
+						]
+						.into_iter()
+						.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 2,
+					total_messages: 2,
+					..Default::default()
+				},
+			));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_B,
+				2000
+			));
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_rejects_invalid_proof() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Err(())),
+					Default::default(),
+				),
+				Error::<TestRuntime, ()>::InvalidMessagesDeliveryProof,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_rejects_proof_if_declared_relayers_state_is_invalid() {
+		run_test(|| {
+			// when number of relayers entries is invalid
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							relayers: vec![
+								unrewarded_relayer(1, 1, TEST_RELAYER_A),
+								unrewarded_relayer(2, 2, TEST_RELAYER_B)
+							]
+							.into_iter()
+							.collect(),
+							..Default::default()
+						}
+					))),
+					UnrewardedRelayersState {
+						unrewarded_relayer_entries: 1,
+						total_messages: 2,
+						..Default::default()
+					},
+				),
+				Error::<TestRuntime, ()>::InvalidUnrewardedRelayersState,
+			);
+
+			// when number of messages is invalid
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							relayers: vec![
+								unrewarded_relayer(1, 1, TEST_RELAYER_A),
+								unrewarded_relayer(2, 2, TEST_RELAYER_B)
+							]
+							.into_iter()
+							.collect(),
+							..Default::default()
+						}
+					))),
+					UnrewardedRelayersState {
+						unrewarded_relayer_entries: 2,
+						total_messages: 1,
+						..Default::default()
+					},
+				),
+				Error::<TestRuntime, ()>::InvalidUnrewardedRelayersState,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_accepts_single_message_with_invalid_payload() {
+		run_test(|| {
+			let mut invalid_message = message(1, REGULAR_PAYLOAD);
+			invalid_message.data.payload = Vec::new();
+
+			assert_ok!(Pallet::<TestRuntime, ()>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![invalid_message]).into(),
+				1,
+				0, // weight may be zero in this case (all messages are improperly encoded)
+			),);
+
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
+				1,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_accepts_batch_with_message_with_invalid_payload() {
+		run_test(|| {
+			let mut invalid_message = message(2, REGULAR_PAYLOAD);
+			invalid_message.data.payload = Vec::new();
+
+			assert_ok!(Pallet::<TestRuntime, ()>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![
+					message(1, REGULAR_PAYLOAD),
+					invalid_message,
+					message(3, REGULAR_PAYLOAD),
+				])
+				.into(),
+				3,
+				REGULAR_PAYLOAD.declared_weight + REGULAR_PAYLOAD.declared_weight,
+			),);
+
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
+				3,
+			);
+		});
+	}
+
+	#[test]
+	fn storage_message_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking all
+		// previously crafted messages proofs.
+		let storage_key = storage_keys::message_key("BridgeMessages", &*b"test", 42).0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed018a395e6242c6813b196ca31ed0547ea79446af0e09063bd4a7874aef8a997cec746573742a00000000000000").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn message_cancellation_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking all
+		// previously crafted messages proofs.
+		let storage_key = storage_keys::message_cancellation_key("BridgeMessages", &*b"test", 42).0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed019f9da409b2350863963581233a5137f79446af0e09063bd4a7874aef8a997cec746573742a00000000000000").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn outbound_lane_data_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking all
+		// previously crafted outbound lane state proofs.
+		let storage_key = storage_keys::outbound_lane_data_key("BridgeMessages", &*b"test").0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed0196c246acb9b55077390e3ca723a0ca1f44a8995dd50b6657a037a7839304535b74657374").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn inbound_lane_data_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking all
+		// previously crafted inbound lane state proofs.
+		let storage_key = storage_keys::inbound_lane_data_key("BridgeMessages", &*b"test").0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed01e5f83cf83f2127eb47afdc35d6e43fab44a8995dd50b6657a037a7839304535b74657374").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn actual_dispatch_weight_does_not_overlow() {
+		run_test(|| {
+			let message1 = message(1, message_payload(0, Weight::MAX / 2));
+			let message2 = message(2, message_payload(0, Weight::MAX / 2));
+			let message3 = message(3, message_payload(0, Weight::MAX / 2));
+
+			assert_ok!(Pallet::<TestRuntime, ()>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				// this may cause overflow if source chain storage is invalid
+				Ok(vec![message1, message2, message3]).into(),
+				3,
+				Weight::MAX,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 2);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_message_is_already_delivered() {
+		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
+				Error::<TestRuntime, ()>::MessageIsAlreadyDelivered,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_message_is_not_yet_sent() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
+				Error::<TestRuntime, ()>::MessageIsNotYetSent,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_submitter_cant_pay_additional_fee() {
+		run_test(|| {
+			send_regular_message();
+
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
+				Error::<TestRuntime, ()>::FailedToWithdrawMessageFee,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_succeeds() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime, ()>::increase_message_fee(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+				100,
+			),);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 100));
+		});
+	}
+
+	fn send_message_with_ttl(ttl: u64) {
+		assert_ok!(Pallet::<TestRuntime>::send_message(
+			Origin::signed(1),
+			TEST_LANE_ID,
+			REGULAR_PAYLOAD,
+			REGULAR_PAYLOAD.declared_weight,
+			Some(ttl),
+		));
+	}
+
+	fn non_delivery_proof(last_delivered_nonce: MessageNonce) -> TestMessagesDeliveryProof {
+		TestMessagesDeliveryProof(Ok((
+			TEST_LANE_ID,
+			InboundLaneData {
+				last_confirmed_nonce: last_delivered_nonce,
+				..Default::default()
+			},
+		)))
+	}
+
+	fn confirm_expired_message_delivery(messages: DeliveredMessages) {
 		assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
 			Origin::signed(1),
 			TestMessagesDeliveryProof(Ok((
 				TEST_LANE_ID,
 				InboundLaneData {
-					last_confirmed_nonce: 1,
+					last_confirmed_nonce: messages.begin - 1,
 					relayers: vec![UnrewardedRelayer {
-						relayer: 0,
-						messages: DeliveredMessages::new(1, true),
+						relayer: TEST_RELAYER_A,
+						messages,
 					}]
 					.into_iter()
 					.collect(),
@@ -1221,1111 +5440,1497 @@ mod tests {
 				..Default::default()
 			},
 		));
+	}
 
-		assert_eq!(
-			System::<TestRuntime>::events(),
-			vec![EventRecord {
+	#[test]
+	fn expired_message_fee_is_refunded_when_delivery_is_confirmed() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+
+			System::<TestRuntime>::set_block_number(12);
+			System::<TestRuntime>::reset_events();
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
+			));
+
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert_eq!(OutboundLanes::<TestRuntime>::get(&TEST_LANE_ID).latest_expired_nonce, 1);
+			// the message is kept in the storage, so relayers are able to prove it
+			assert!(Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1).is_some());
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessageExpired(TEST_LANE_ID, 1)),
+					topics: vec![],
+				}],
+			);
+
+			// the fee is refunded when the bridged chain confirms that the message is not dispatched
+			System::<TestRuntime>::reset_events();
+			confirm_expired_message_delivery(DeliveredMessages::new_skipped(1));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(System::<TestRuntime>::events().contains(&EventRecord {
 				phase: Phase::Initialization,
-				event: TestEvent::Messages(Event::MessagesDelivered(TEST_LANE_ID, DeliveredMessages::new(1, true),)),
+				event: TestEvent::Messages(Event::MessageFeeRefunded(TEST_LANE_ID, 1)),
 				topics: vec![],
-			}],
-		);
+			}));
+		});
 	}
 
 	#[test]
-	fn pallet_owner_may_change_owner() {
+	fn relayer_is_rewarded_for_expired_message_that_has_been_dispatched() {
 		run_test(|| {
-			PalletOwner::<TestRuntime>::put(2);
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
 
-			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(1)));
-			assert_noop!(
-				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(2), OperatingMode::Halted),
-				DispatchError::BadOrigin,
+			System::<TestRuntime>::set_block_number(12);
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
+			));
+
+			// the message has been delivered and dispatched before the bridged chain has learned
+			// about its expiration
+			confirm_expired_message_delivery(DeliveredMessages::new(1, true));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn relayer_is_rewarded_for_expired_message_that_has_failed_to_dispatch() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+
+			System::<TestRuntime>::set_block_number(12);
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
+			));
+
+			// the message has been delivered and dispatched (with error) before the bridged chain has
+			// learned about its expiration
+			confirm_expired_message_delivery(DeliveredMessages::new(1, false));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn owner_of_expired_message_is_notified_when_delivery_is_confirmed() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+			OutboundMessagesExpiry::<TestRuntime>::insert(
+				MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce: 1,
+				},
+				(10, bp_messages::source_chain::Sender::Signed(1)),
 			);
-			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
-				Origin::root(),
-				OperatingMode::Halted
+
+			System::<TestRuntime>::set_block_number(12);
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
 			));
 
-			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::signed(1), None));
+			// the message may still be dispatched, so the owner is not notified yet
+			TestMessageOwner1::ensure_not_called(&TEST_LANE_ID, 1);
+
+			confirm_expired_message_delivery(DeliveredMessages::new_skipped(1));
+			TestMessageOwner1::ensure_called(&TEST_LANE_ID, 1, false);
+			assert_eq!(OutboundMessagesOwners::<TestRuntime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn expire_message_fails_if_message_is_not_expired() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+
+			System::<TestRuntime>::set_block_number(11);
 			assert_noop!(
-				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Normal),
-				DispatchError::BadOrigin,
+				Pallet::<TestRuntime>::expire_message(Origin::signed(1), non_delivery_proof(0), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsNotYetExpired,
+			);
+		});
+	}
+
+	#[test]
+	fn expire_message_fails_if_message_has_no_ttl() {
+		run_test(|| {
+			send_regular_message();
+
+			System::<TestRuntime>::set_block_number(100);
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(Origin::signed(1), non_delivery_proof(0), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageHasNoTtl,
 			);
+		});
+	}
+
+	#[test]
+	fn expire_message_fails_if_called_by_other_account() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+
+			System::<TestRuntime>::set_block_number(12);
 			assert_noop!(
-				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(2), OperatingMode::Normal),
+				Pallet::<TestRuntime>::expire_message(Origin::signed(2), non_delivery_proof(0), TEST_LANE_ID, 1),
 				DispatchError::BadOrigin,
 			);
-			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
-				Origin::root(),
-				OperatingMode::Normal
-			));
 		});
 	}
 
 	#[test]
-	fn pallet_may_be_halted_by_root() {
+	fn expired_messages_are_refunded_in_order() {
 		run_test(|| {
-			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
-				Origin::root(),
-				OperatingMode::Halted
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+			send_message_with_ttl(10);
+
+			System::<TestRuntime>::set_block_number(12);
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(Origin::signed(1), non_delivery_proof(0), TEST_LANE_ID, 2),
+				Error::<TestRuntime, ()>::MessageIsNotOldestPending,
+			);
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
 			));
-			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
-				Origin::root(),
-				OperatingMode::Normal
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(Origin::signed(1), non_delivery_proof(0), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsExpired,
+			);
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				2,
 			));
 		});
 	}
 
 	#[test]
-	fn pallet_may_be_halted_by_owner() {
+	fn expire_message_fails_if_message_is_already_delivered() {
 		run_test(|| {
-			PalletOwner::<TestRuntime>::put(2);
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+			receive_messages_delivery_proof();
 
-			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
-				Origin::signed(2),
-				OperatingMode::Halted
+			System::<TestRuntime>::set_block_number(12);
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(Origin::signed(1), non_delivery_proof(1), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsAlreadyDelivered,
+			);
+		});
+	}
+
+	#[test]
+	fn expire_message_fails_if_message_is_received_by_bridged_chain() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+
+			System::<TestRuntime>::set_block_number(12);
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(Origin::signed(1), non_delivery_proof(1), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsAlreadyDelivered,
+			);
+		});
+	}
+
+	#[test]
+	fn expire_message_fails_if_proof_is_stale() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+			send_message_with_ttl(10);
+			receive_messages_delivery_proof();
+
+			System::<TestRuntime>::set_block_number(12);
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(Origin::signed(1), non_delivery_proof(0), TEST_LANE_ID, 2),
+				Error::<TestRuntime, ()>::StaleMessagesDeliveryProof,
+			);
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(1),
+				TEST_LANE_ID,
+				2,
 			));
-			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
-				Origin::signed(2),
-				OperatingMode::Normal
+		});
+	}
+
+	#[test]
+	fn expire_message_fails_if_proof_is_invalid() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+
+			System::<TestRuntime>::set_block_number(12);
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Err(())),
+					TEST_LANE_ID,
+					1
+				),
+				Error::<TestRuntime, ()>::InvalidMessagesDeliveryProof,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::expire_message(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((TEST_LANE_ID_2, Default::default()))),
+					TEST_LANE_ID,
+					1
+				),
+				Error::<TestRuntime, ()>::InvalidMessagesDeliveryProof,
+			);
+		});
+	}
+
+	#[test]
+	fn relayers_are_not_rewarded_for_expired_messages() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			send_message_with_ttl(10);
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				1000,
+				None,
 			));
 
-			assert_noop!(
-				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Halted),
-				DispatchError::BadOrigin,
-			);
-			assert_noop!(
-				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Normal),
-				DispatchError::BadOrigin,
-			);
+			System::<TestRuntime>::set_block_number(12);
+			assert_ok!(Pallet::<TestRuntime>::expire_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
+			));
 
-			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
-				Origin::signed(2),
-				OperatingMode::Halted
+			let mut delivered_messages = DeliveredMessages::new_skipped(1);
+			delivered_messages.note_dispatched_message(true);
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![UnrewardedRelayer {
+							relayer: TEST_RELAYER_A,
+							messages: delivered_messages,
+						}]
+						.into_iter()
+						.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 2,
+					..Default::default()
+				},
 			));
-			assert_noop!(
-				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Normal),
-				DispatchError::BadOrigin,
+
+			assert_eq!(
+				OutboundLanes::<TestRuntime>::get(&TEST_LANE_ID).latest_received_nonce,
+				2
 			);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000
+			));
 		});
 	}
 
 	#[test]
-	fn pallet_parameter_may_be_updated_by_root() {
+	fn receive_messages_proof_does_not_dispatch_expired_messages() {
 		run_test(|| {
-			get_ready_for_events();
+			// message proof includes outbound lane state with two expired messages
+			let mut message_proof: TestMessagesProof = Ok(vec![message(3, REGULAR_PAYLOAD)]).into();
+			message_proof.result.as_mut().unwrap()[0].1.lane_state = Some(OutboundLaneData {
+				latest_expired_nonce: 2,
+				..Default::default()
+			});
 
-			let parameter = TestMessagesParameter::TokenConversionRate(10.into());
-			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
-				Origin::root(),
-				parameter.clone(),
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				message_proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
 			));
 
-			assert_eq!(TokenConversionRate::get(), 10.into());
+			let mut delivered_messages = DeliveredMessages::new_skipped(1);
+			delivered_messages.note_skipped_message();
+			delivered_messages.note_dispatched_message(true);
 			assert_eq!(
-				System::<TestRuntime>::events(),
-				vec![EventRecord {
-					phase: Phase::Initialization,
-					event: TestEvent::Messages(Event::ParameterUpdated(parameter)),
-					topics: vec![],
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers,
+				vec![UnrewardedRelayer {
+					relayer: TEST_RELAYER_A,
+					messages: delivered_messages,
 				}],
 			);
 		});
 	}
 
 	#[test]
-	fn pallet_parameter_may_be_updated_by_owner() {
+	fn receive_messages_proof_does_not_dispatch_queued_expired_messages() {
 		run_test(|| {
-			PalletOwner::<TestRuntime>::put(2);
-			get_ready_for_events();
+			// the first message is queued
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				0,
+			));
 
-			let parameter = TestMessagesParameter::TokenConversionRate(10.into());
-			assert_ok!(Pallet::<TestRuntime>::update_pallet_parameter(
-				Origin::signed(2),
-				parameter.clone(),
+			// the second message has expired at the source chain, but it is queued behind the first one
+			let mut message_proof: TestMessagesProof = Ok(vec![message(2, REGULAR_PAYLOAD)]).into();
+			message_proof.result.as_mut().unwrap()[0].1.lane_state = Some(OutboundLaneData {
+				latest_expired_nonce: 2,
+				..Default::default()
+			});
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				message_proof,
+				1,
+				0,
 			));
+			assert_eq!(InboundLatestQueuedNonces::<TestRuntime>::get(TEST_LANE_ID), Some(2));
 
-			assert_eq!(TokenConversionRate::get(), 10.into());
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			let mut delivered_messages = DeliveredMessages::new(1, true);
+			delivered_messages.note_dispatched_message(false);
 			assert_eq!(
-				System::<TestRuntime>::events(),
-				vec![EventRecord {
-					phase: Phase::Initialization,
-					event: TestEvent::Messages(Event::ParameterUpdated(parameter)),
-					topics: vec![],
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers,
+				vec![UnrewardedRelayer {
+					relayer: TEST_RELAYER_A,
+					messages: delivered_messages,
 				}],
 			);
 		});
 	}
 
 	#[test]
-	fn pallet_parameter_cant_be_updated_by_arbitrary_submitter() {
+	fn weight_refund_from_receive_messages_proof_works() {
 		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime>::update_pallet_parameter(
-					Origin::signed(2),
-					TestMessagesParameter::TokenConversionRate(10.into()),
-				),
-				DispatchError::BadOrigin,
-			);
+			fn submit_with_unspent_weight(
+				nonce: MessageNonce,
+				unspent_weight: Weight,
+				is_prepaid: bool,
+			) -> (Weight, Weight) {
+				let mut payload = REGULAR_PAYLOAD;
+				payload.dispatch_result.unspent_weight = unspent_weight;
+				payload.dispatch_result.dispatch_fee_paid_during_dispatch = !is_prepaid;
+				let proof = Ok(vec![message(nonce, payload)]).into();
+				let messages_count = 1;
+				let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
+					&proof,
+					messages_count,
+					REGULAR_PAYLOAD.declared_weight,
+				) + crate::mock::DbWeight::get().reads(1);
+				let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					proof,
+					messages_count,
+					REGULAR_PAYLOAD.declared_weight,
+				)
+				.expect("delivery has failed")
+				.actual_weight
+				.expect("receive_messages_proof always returns Some");
 
-			PalletOwner::<TestRuntime>::put(2);
+				(pre_dispatch_weight, post_dispatch_weight)
+			}
 
-			assert_noop!(
-				Pallet::<TestRuntime>::update_pallet_parameter(
-					Origin::signed(1),
-					TestMessagesParameter::TokenConversionRate(10.into()),
-				),
-				DispatchError::BadOrigin,
+			// when dispatch is returning `unspent_weight < declared_weight`
+			let (pre, post) = submit_with_unspent_weight(1, 1, false);
+			assert_eq!(post, pre - 1);
+
+			// when dispatch is returning `unspent_weight = declared_weight`
+			let (pre, post) = submit_with_unspent_weight(2, REGULAR_PAYLOAD.declared_weight, false);
+			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
+
+			// when dispatch is returning `unspent_weight > declared_weight`
+			let (pre, post) = submit_with_unspent_weight(3, REGULAR_PAYLOAD.declared_weight + 1, false);
+			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
+
+			// when there's no unspent weight
+			let (pre, post) = submit_with_unspent_weight(4, 0, false);
+			assert_eq!(post, pre);
+
+			// when dispatch is returning `unspent_weight < declared_weight` AND message is prepaid
+			let (pre, post) = submit_with_unspent_weight(5, 1, true);
+			assert_eq!(
+				post,
+				pre - 1 - <TestRuntime as Config>::WeightInfo::pay_inbound_dispatch_fee_overhead()
 			);
 		});
 	}
 
 	#[test]
-	fn fixed_u128_works_as_i_think() {
-		// this test is here just to be sure that conversion rate may be represented with FixedU128
+	fn messages_delivered_callbacks_are_called() {
 		run_test(|| {
-			use sp_runtime::{FixedPointNumber, FixedU128};
+			send_regular_message();
+			send_regular_message();
+			send_regular_message();
 
-			// 1:1 conversion that we use by default for testnets
-			let rialto_token = 1u64;
-			let rialto_token_in_millau_tokens = TokenConversionRate::get().saturating_mul_int(rialto_token);
-			assert_eq!(rialto_token_in_millau_tokens, 1);
+			// messages 1+2 are confirmed in 1 tx, message 3 in a separate tx
+			// dispatch of message 2 has failed
+			let mut delivered_messages_1_and_2 = DeliveredMessages::new(1, true);
+			delivered_messages_1_and_2.note_dispatched_message(false);
+			let messages_1_and_2_proof = Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![UnrewardedRelayer {
+						relayer: 0,
+						messages: delivered_messages_1_and_2.clone(),
+					}]
+					.into_iter()
+					.collect(),
+				},
+			));
+			let delivered_message_3 = DeliveredMessages::new(3, true);
+			let messages_3_proof = Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![UnrewardedRelayer {
+						relayer: 0,
+						messages: delivered_message_3.clone(),
+					}]
+					.into_iter()
+					.collect(),
+				},
+			));
 
-			// let's say conversion rate is 1:1.7
-			let conversion_rate = FixedU128::saturating_from_rational(170, 100);
-			let rialto_tokens = 100u64;
-			let rialto_tokens_in_millau_tokens = conversion_rate.saturating_mul_int(rialto_tokens);
-			assert_eq!(rialto_tokens_in_millau_tokens, 170);
+			// first tx with messages 1+2
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(messages_1_and_2_proof),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 2,
+					..Default::default()
+				},
+			));
+			// second tx with message 3
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(messages_3_proof),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 1,
+					..Default::default()
+				},
+			));
 
-			// let's say conversion rate is 1:0.25
-			let conversion_rate = FixedU128::saturating_from_rational(25, 100);
-			let rialto_tokens = 100u64;
-			let rialto_tokens_in_millau_tokens = conversion_rate.saturating_mul_int(rialto_tokens);
-			assert_eq!(rialto_tokens_in_millau_tokens, 25);
+			// ensure that both callbacks have been called twice: for 1+2, then for 3
+			let sender = bp_messages::source_chain::Sender::Signed(1);
+			TestOnDeliveryConfirmed1::ensure_called(
+				&TEST_LANE_ID,
+				&delivered_messages_1_and_2,
+				&vec![(1, sender.clone()), (2, sender.clone())].into_iter().collect(),
+			);
+			TestOnDeliveryConfirmed1::ensure_called(
+				&TEST_LANE_ID,
+				&delivered_message_3,
+				&vec![(3, sender)].into_iter().collect(),
+			);
+			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_messages_1_and_2);
+			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_message_3);
 		});
 	}
 
+	fn confirm_3_messages_delivery() -> (Weight, Weight) {
+		send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+		send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+		send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+
+		let proof = TestMessagesDeliveryProof(Ok((
+			TEST_LANE_ID,
+			InboundLaneData {
+				last_confirmed_nonce: 0,
+				relayers: vec![unrewarded_relayer(1, 3, TEST_RELAYER_A)].into_iter().collect(),
+			},
+		)));
+		let relayers_state = UnrewardedRelayersState {
+			unrewarded_relayer_entries: 1,
+			total_messages: 3,
+			..Default::default()
+		};
+		// none of confirmed messages is skipped by the bridged chain, so refund overhead is never spent
+		let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::receive_messages_delivery_proof_weight(
+			&proof,
+			&relayers_state,
+			crate::mock::DbWeight::get(),
+		) + crate::mock::DbWeight::get().reads(1)
+			- <TestRuntime as Config>::WeightInfo::single_message_refund_overhead(crate::mock::DbWeight::get()) * 3;
+		let post_dispatch_weight =
+			Pallet::<TestRuntime>::receive_messages_delivery_proof(Origin::signed(1), proof, relayers_state)
+				.expect("confirmation has failed")
+				.actual_weight
+				.expect("receive_messages_delivery_proof always returns Some");
+		(pre_dispatch_weight, post_dispatch_weight)
+	}
+
 	#[test]
-	fn pallet_rejects_transactions_if_halted() {
+	fn receive_messages_delivery_proof_refunds_zero_weight() {
 		run_test(|| {
-			// send message first to be able to check that delivery_proof fails later
-			send_regular_message();
-
-			PalletOperatingMode::<TestRuntime, ()>::put(OperatingMode::Halted);
-
-			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
-					Origin::signed(1),
-					TEST_LANE_ID,
-					REGULAR_PAYLOAD,
-					REGULAR_PAYLOAD.declared_weight,
-				),
-				Error::<TestRuntime, ()>::Halted,
-			);
+			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
+			assert_eq!(pre_dispatch_weight, post_dispatch_weight);
+		});
+	}
 
-			assert_noop!(
-				Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 1,),
-				Error::<TestRuntime, ()>::Halted,
-			);
+	#[test]
+	fn receive_messages_delivery_proof_refunds_non_zero_weight() {
+		run_test(|| {
+			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().writes(1));
 
-			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_proof(
-					Origin::signed(1),
-					TEST_RELAYER_A,
-					Ok(vec![message(2, REGULAR_PAYLOAD)]).into(),
-					1,
-					REGULAR_PAYLOAD.declared_weight,
-				),
-				Error::<TestRuntime, ()>::Halted,
+			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
+			assert_eq!(
+				pre_dispatch_weight.saturating_sub(post_dispatch_weight),
+				crate::mock::DbWeight::get().reads(1) * 3
 			);
+		});
+	}
 
-			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
-					Origin::signed(1),
-					TestMessagesDeliveryProof(Ok((
-						TEST_LANE_ID,
-						InboundLaneData {
-							last_confirmed_nonce: 1,
-							relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
-						},
-					))),
-					UnrewardedRelayersState {
-						unrewarded_relayer_entries: 1,
-						messages_in_oldest_entry: 1,
-						total_messages: 1,
-					},
-				),
-				Error::<TestRuntime, ()>::Halted,
-			);
+	#[test]
+	#[should_panic]
+	fn receive_messages_panics_in_debug_mode_if_callback_is_wrong() {
+		run_test(|| {
+			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().reads_writes(2, 2));
+			confirm_3_messages_delivery()
 		});
 	}
 
 	#[test]
-	fn pallet_rejects_new_messages_in_rejecting_outbound_messages_operating_mode() {
+	fn receive_messages_delivery_proof_refunds_weight_of_unowned_messages() {
 		run_test(|| {
-			// send message first to be able to check that delivery_proof fails later
-			send_regular_message();
+			let (pre_dispatch_weight, _) = confirm_3_messages_delivery();
 
-			PalletOperatingMode::<TestRuntime, ()>::put(OperatingMode::RejectingOutboundMessages);
+			send_regular_message();
+			send_regular_message();
+			send_regular_message();
+			let proof = TestMessagesDeliveryProof(Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![unrewarded_relayer(4, 6, TEST_RELAYER_A)].into_iter().collect(),
+				},
+			)));
+			let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 3,
+					..Default::default()
+				},
+			)
+			.expect("confirmation has failed")
+			.actual_weight
+			.expect("receive_messages_delivery_proof always returns Some");
 
-			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
-					Origin::signed(1),
-					TEST_LANE_ID,
-					REGULAR_PAYLOAD,
-					REGULAR_PAYLOAD.declared_weight,
-				),
-				Error::<TestRuntime, ()>::Halted,
+			// we only need to read owners of unowned messages
+			assert_eq!(
+				pre_dispatch_weight.saturating_sub(post_dispatch_weight),
+				crate::mock::DbWeight::get().reads_writes(1, 2) * 3
 			);
+		});
+	}
 
-			assert_ok!(Pallet::<TestRuntime>::increase_message_fee(
-				Origin::signed(1),
-				TEST_LANE_ID,
-				1,
-				1,
-			));
+	#[test]
+	#[should_panic]
+	fn receive_messages_panics_in_debug_mode_if_owner_callback_is_wrong() {
+		run_test(|| {
+			TestMessageOwner1::set_consumed_weight_per_message(crate::mock::DbWeight::get().reads_writes(2, 2));
+			confirm_3_messages_delivery()
+		});
+	}
 
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
-				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
-				1,
-				REGULAR_PAYLOAD.declared_weight,
-			),);
+	#[test]
+	fn dispatch_results_are_routed_to_message_owners() {
+		run_test(|| {
+			// messages 1 and 3 are owned by the first owner, message 2 is owned by the second owner
+			// and message 4 has no owner
+			send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+			send_owned_regular_message(TEST_MESSAGE_OWNER_2);
+			send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+			send_regular_message();
 
+			// dispatch of messages 2 and 3 has failed
+			let mut delivered_messages = DeliveredMessages::new(1, true);
+			delivered_messages.note_dispatched_message(false);
+			delivered_messages.note_dispatched_message(false);
+			delivered_messages.note_dispatched_message(true);
 			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
 				Origin::signed(1),
 				TestMessagesDeliveryProof(Ok((
 					TEST_LANE_ID,
 					InboundLaneData {
-						last_confirmed_nonce: 1,
-						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+						last_confirmed_nonce: 0,
+						relayers: vec![UnrewardedRelayer {
+							relayer: 0,
+							messages: delivered_messages,
+						}]
+						.into_iter()
+						.collect(),
 					},
 				))),
 				UnrewardedRelayersState {
 					unrewarded_relayer_entries: 1,
-					messages_in_oldest_entry: 1,
-					total_messages: 1,
+					total_messages: 4,
+					..Default::default()
 				},
 			));
-		});
-	}
 
-	#[test]
-	fn send_message_works() {
-		run_test(|| {
-			send_regular_message();
+			// every owner only sees results of its own messages
+			TestMessageOwner1::ensure_called(&TEST_LANE_ID, 1, true);
+			TestMessageOwner1::ensure_not_called(&TEST_LANE_ID, 2);
+			TestMessageOwner1::ensure_called(&TEST_LANE_ID, 3, false);
+			TestMessageOwner1::ensure_not_called(&TEST_LANE_ID, 4);
+			TestMessageOwner2::ensure_not_called(&TEST_LANE_ID, 1);
+			TestMessageOwner2::ensure_called(&TEST_LANE_ID, 2, false);
+			TestMessageOwner2::ensure_not_called(&TEST_LANE_ID, 3);
+			TestMessageOwner2::ensure_not_called(&TEST_LANE_ID, 4);
+
+			// and owners are removed once delivery is confirmed
+			assert_eq!(OutboundMessagesOwners::<TestRuntime, ()>::iter().count(), 0);
 		});
 	}
 
-	#[test]
-	fn chain_verifier_rejects_invalid_message_in_send_message() {
-		run_test(|| {
-			// messages with this payload are rejected by target chain verifier
-			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
-					Origin::signed(1),
-					TEST_LANE_ID,
-					PAYLOAD_REJECTED_BY_TARGET_CHAIN,
-					PAYLOAD_REJECTED_BY_TARGET_CHAIN.declared_weight
-				),
-				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
-			);
-		});
+	fn send_and_confirm_2_messages(lane_id: LaneId) {
+		for _ in 0..2 {
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				lane_id,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+				None,
+			));
+		}
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+			Origin::signed(1),
+			TestMessagesDeliveryProof(Ok((
+				lane_id,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![unrewarded_relayer(1, 2, TEST_RELAYER_A)].into_iter().collect(),
+				},
+			))),
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				total_messages: 2,
+				..Default::default()
+			},
+		));
 	}
 
 	#[test]
-	fn lane_verifier_rejects_invalid_message_in_send_message() {
+	fn confirmed_messages_are_pruned_in_on_idle() {
 		run_test(|| {
-			// messages with zero fee are rejected by lane verifier
-			assert_noop!(
-				Pallet::<TestRuntime>::send_message(Origin::signed(1), TEST_LANE_ID, REGULAR_PAYLOAD, 0),
-				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
-			);
+			confirm_3_messages_delivery();
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 3);
+
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 0);
+			assert_eq!(OutboundLanes::<TestRuntime>::get(TEST_LANE_ID).oldest_unpruned_nonce, 4);
+			assert_eq!(OutboundMessages::<TestRuntime>::iter().count(), 0);
 		});
 	}
 
 	#[test]
-	fn message_send_fails_if_submitter_cant_pay_message_fee() {
+	fn confirmed_messages_pruned_when_sending_message_are_not_counted() {
 		run_test(|| {
-			TestMessageDeliveryAndDispatchPayment::reject_payments();
-			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
-					Origin::signed(1),
-					TEST_LANE_ID,
-					REGULAR_PAYLOAD,
-					REGULAR_PAYLOAD.declared_weight
-				),
-				Error::<TestRuntime, ()>::FailedToWithdrawMessageFee,
-			);
+			confirm_3_messages_delivery();
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 3);
+
+			send_regular_message();
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 0);
+			assert_eq!(OutboundLanes::<TestRuntime>::get(TEST_LANE_ID).oldest_unpruned_nonce, 4);
 		});
 	}
 
 	#[test]
-	fn receive_messages_proof_works() {
+	fn confirmed_messages_are_not_pruned_in_on_idle_if_pallet_is_halted() {
 		run_test(|| {
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
-				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
-				1,
-				REGULAR_PAYLOAD.declared_weight,
-			));
+			confirm_3_messages_delivery();
+			PalletOperatingMode::<TestRuntime, ()>::put(OperatingMode::Halted);
 
-			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 3);
+			assert_eq!(OutboundLanes::<TestRuntime>::get(TEST_LANE_ID).oldest_unpruned_nonce, 1);
 		});
 	}
 
 	#[test]
-	fn receive_messages_proof_updates_confirmed_message_nonce() {
+	fn outbound_lanes_are_not_read_if_there_are_no_messages_to_prune() {
 		run_test(|| {
-			// say we have received 10 messages && last confirmed message is 8
-			InboundLanes::<TestRuntime, ()>::insert(
-				TEST_LANE_ID,
-				InboundLaneData {
-					last_confirmed_nonce: 8,
-					relayers: vec![
-						unrewarded_relayer(9, 9, TEST_RELAYER_A),
-						unrewarded_relayer(10, 10, TEST_RELAYER_B),
-					]
-					.into_iter()
-					.collect(),
-				},
-			);
-			assert_eq!(
-				Pallet::<TestRuntime>::inbound_unrewarded_relayers_state(TEST_LANE_ID),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 2,
-					messages_in_oldest_entry: 1,
-					total_messages: 2,
-				},
-			);
-
-			// message proof includes outbound lane state with latest confirmed message updated to 9
-			let mut message_proof: TestMessagesProof = Ok(vec![message(11, REGULAR_PAYLOAD)]).into();
-			message_proof.result.as_mut().unwrap()[0].1.lane_state = Some(OutboundLaneData {
-				latest_received_nonce: 9,
-				..Default::default()
-			});
-
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
-				Origin::signed(1),
-				TEST_RELAYER_A,
-				message_proof,
-				1,
-				REGULAR_PAYLOAD.declared_weight,
-			));
-
-			assert_eq!(
-				InboundLanes::<TestRuntime>::get(TEST_LANE_ID),
-				InboundLaneData {
-					last_confirmed_nonce: 9,
-					relayers: vec![
-						unrewarded_relayer(10, 10, TEST_RELAYER_B),
-						unrewarded_relayer(11, 11, TEST_RELAYER_A)
-					]
-					.into_iter()
-					.collect(),
-				},
-			);
-			assert_eq!(
-				Pallet::<TestRuntime>::inbound_unrewarded_relayers_state(TEST_LANE_ID),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 2,
-					messages_in_oldest_entry: 1,
-					total_messages: 2,
-				},
+			send_regular_message();
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 0);
+
+			// only operating mode and the number of messages to prune are read
+			assert_eq!(
+				prune_confirmed_messages::<TestRuntime, ()>(Weight::MAX),
+				crate::mock::DbWeight::get().reads(2),
 			);
+			assert_eq!(OutboundPruningLaneCursor::<TestRuntime, ()>::get(), None);
 		});
 	}
 
 	#[test]
-	fn receive_messages_proof_does_not_accept_message_if_dispatch_weight_is_not_enough() {
+	fn confirmed_messages_pruning_never_uses_more_than_remaining_weight() {
 		run_test(|| {
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
-				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
-				1,
-				REGULAR_PAYLOAD.declared_weight - 1,
-			));
-			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
+			send_and_confirm_2_messages(TEST_LANE_ID);
+			send_and_confirm_2_messages(TEST_LANE_ID_2);
+
+			let db_weight = crate::mock::DbWeight::get();
+			for remaining_weight in 0..db_weight.reads_writes(7, 12) {
+				assert!(prune_confirmed_messages::<TestRuntime, ()>(remaining_weight) <= remaining_weight);
+			}
 		});
 	}
 
 	#[test]
-	fn receive_messages_proof_rejects_invalid_proof() {
+	fn confirmed_messages_pruning_in_on_idle_is_round_robin() {
 		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime, ()>::receive_messages_proof(
-					Origin::signed(1),
-					TEST_RELAYER_A,
-					Err(()).into(),
-					1,
-					0,
-				),
-				Error::<TestRuntime, ()>::InvalidMessagesProof,
+			send_and_confirm_2_messages(TEST_LANE_ID);
+			send_and_confirm_2_messages(TEST_LANE_ID_2);
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 4);
+
+			// the weight is only enough to prune single message: read mode, counter and cursor, update
+			// cursor and counter, read lane entry, read and update lane data and prune single message
+			let db_weight = crate::mock::DbWeight::get();
+			let remaining_weight = db_weight.reads_writes(5, 7);
+			assert_eq!(
+				prune_confirmed_messages::<TestRuntime, ()>(remaining_weight),
+				remaining_weight
 			);
-		});
-	}
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 3);
+			assert_eq!(
+				prune_confirmed_messages::<TestRuntime, ()>(remaining_weight),
+				remaining_weight
+			);
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_to_prune(), 2);
 
-	#[test]
-	fn receive_messages_proof_rejects_proof_with_too_many_messages() {
-		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime, ()>::receive_messages_proof(
-					Origin::signed(1),
-					TEST_RELAYER_A,
-					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
-					u32::MAX,
-					0,
-				),
-				Error::<TestRuntime, ()>::TooManyMessagesInTheProof,
+			// every lane has lost one message
+			assert_eq!(OutboundLanes::<TestRuntime>::get(TEST_LANE_ID).oldest_unpruned_nonce, 2);
+			assert_eq!(
+				OutboundLanes::<TestRuntime>::get(TEST_LANE_ID_2).oldest_unpruned_nonce,
+				2
 			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_works() {
+	fn receive_messages_delivery_proof_rejects_proof_if_trying_to_confirm_more_messages_than_expected() {
 		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
 			send_regular_message();
-			receive_messages_delivery_proof();
 
-			assert_eq!(
-				OutboundLanes::<TestRuntime, ()>::get(&TEST_LANE_ID).latest_received_nonce,
-				1,
+			// 1) InboundLaneData declares that the `last_confirmed_nonce` is 1;
+			// 2) InboundLaneData has no entries => `InboundLaneData::last_delivered_nonce()`
+			//    returns `last_confirmed_nonce`;
+			// 3) it means that we're going to confirm delivery of messages 1..=1;
+			// 4) so the number of declared messages (see `UnrewardedRelayersState`) is `0` and
+			//    numer of actually confirmed messages is `1`.
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							last_confirmed_nonce: 1,
+							relayers: Default::default(),
+						},
+					))),
+					UnrewardedRelayersState::default(),
+				),
+				Error::<TestRuntime, ()>::TryingToConfirmMoreMessagesThanExpected,
 			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rewards_relayers() {
+	fn increase_message_fee_weight_depends_on_message_size() {
 		run_test(|| {
+			let mut small_payload = message_payload(0, 100);
+			let mut large_payload = message_payload(1, 100);
+			small_payload.extra = vec![1; 100];
+			large_payload.extra = vec![2; 16_384];
+
 			assert_ok!(Pallet::<TestRuntime>::send_message(
 				Origin::signed(1),
 				TEST_LANE_ID,
-				REGULAR_PAYLOAD,
-				1000,
+				small_payload,
+				100,
+				None,
 			));
 			assert_ok!(Pallet::<TestRuntime>::send_message(
 				Origin::signed(1),
 				TEST_LANE_ID,
-				REGULAR_PAYLOAD,
-				2000,
+				large_payload,
+				100,
+				None,
 			));
 
-			// this reports delivery of message 1 => reward is paid to TEST_RELAYER_A
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
-				Origin::signed(1),
-				TestMessagesDeliveryProof(Ok((
-					TEST_LANE_ID,
-					InboundLaneData {
-						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
-						..Default::default()
-					}
-				))),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 1,
-					total_messages: 1,
-					..Default::default()
-				},
-			));
-			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
-				TEST_RELAYER_A,
-				1000
-			));
-			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
-				TEST_RELAYER_B,
-				2000
-			));
+			let small_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 1)
+				.expect("increase_message_fee has failed")
+				.actual_weight
+				.expect("increase_message_fee always returns Some");
 
-			// this reports delivery of both message 1 and message 2 => reward is paid only to TEST_RELAYER_B
+			let large_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 2, 1)
+				.expect("increase_message_fee has failed")
+				.actual_weight
+				.expect("increase_message_fee always returns Some");
+
+			assert!(
+				large_weight > small_weight,
+				"Actual post-dispatch weigth for larger message {} must be larger than {} for small message",
+				large_weight,
+				small_weight,
+			);
+		});
+	}
+
+	#[test]
+	fn weight_is_refunded_for_messages_that_are_not_pruned() {
+		run_test(|| {
+			// send first MAX messages - no messages are pruned
+			let max_messages_to_prune = crate::mock::MaxMessagesToPruneAtOnce::get();
+			let when_zero_messages_are_pruned = send_regular_message();
+			let mut delivered_messages = DeliveredMessages::new(1, true);
+			for _ in 1..max_messages_to_prune {
+				assert_eq!(send_regular_message(), when_zero_messages_are_pruned);
+				delivered_messages.note_dispatched_message(true);
+			}
+
+			// confirm delivery of all sent messages
 			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
 				Origin::signed(1),
 				TestMessagesDeliveryProof(Ok((
 					TEST_LANE_ID,
 					InboundLaneData {
-						relayers: vec![
-							unrewarded_relayer(1, 1, TEST_RELAYER_A),
-							unrewarded_relayer(2, 2, TEST_RELAYER_B)
-						]
+						last_confirmed_nonce: 1,
+						relayers: vec![UnrewardedRelayer {
+							relayer: 0,
+							messages: delivered_messages,
+						}]
 						.into_iter()
 						.collect(),
-						..Default::default()
-					}
+					},
 				))),
 				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 2,
-					total_messages: 2,
+					unrewarded_relayer_entries: 1,
+					total_messages: max_messages_to_prune,
 					..Default::default()
 				},
 			));
-			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
-				TEST_RELAYER_A,
-				1000
-			));
-			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
-				TEST_RELAYER_B,
-				2000
-			));
+
+			// when next message is sent, MAX messages are pruned
+			let weight_when_max_messages_are_pruned = send_regular_message();
+			assert_eq!(
+				weight_when_max_messages_are_pruned,
+				when_zero_messages_are_pruned + crate::mock::DbWeight::get().writes(max_messages_to_prune),
+			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rejects_invalid_proof() {
+	fn message_accepted_callbacks_are_called() {
 		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
-					Origin::signed(1),
-					TestMessagesDeliveryProof(Err(())),
-					Default::default(),
-				),
-				Error::<TestRuntime, ()>::InvalidMessagesDeliveryProof,
-			);
+			send_regular_message();
+			TestOnMessageAccepted::ensure_called(&TEST_LANE_ID, &1, &bp_messages::source_chain::Sender::Signed(1));
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rejects_proof_if_declared_relayers_state_is_invalid() {
+	#[should_panic]
+	fn message_accepted_panics_in_debug_mode_if_callback_is_wrong() {
 		run_test(|| {
-			// when number of relayers entries is invalid
-			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
-					Origin::signed(1),
-					TestMessagesDeliveryProof(Ok((
-						TEST_LANE_ID,
-						InboundLaneData {
-							relayers: vec![
-								unrewarded_relayer(1, 1, TEST_RELAYER_A),
-								unrewarded_relayer(2, 2, TEST_RELAYER_B)
-							]
-							.into_iter()
-							.collect(),
-							..Default::default()
-						}
-					))),
-					UnrewardedRelayersState {
-						unrewarded_relayer_entries: 1,
-						total_messages: 2,
-						..Default::default()
-					},
-				),
-				Error::<TestRuntime, ()>::InvalidUnrewardedRelayersState,
-			);
-
-			// when number of messages is invalid
-			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
-					Origin::signed(1),
-					TestMessagesDeliveryProof(Ok((
-						TEST_LANE_ID,
-						InboundLaneData {
-							relayers: vec![
-								unrewarded_relayer(1, 1, TEST_RELAYER_A),
-								unrewarded_relayer(2, 2, TEST_RELAYER_B)
-							]
-							.into_iter()
-							.collect(),
-							..Default::default()
-						}
-					))),
-					UnrewardedRelayersState {
-						unrewarded_relayer_entries: 2,
-						total_messages: 1,
-						..Default::default()
-					},
-				),
-				Error::<TestRuntime, ()>::InvalidUnrewardedRelayersState,
-			);
+			TestOnMessageAccepted::set_consumed_weight_per_message(crate::mock::DbWeight::get().reads_writes(2, 2));
+			send_regular_message();
 		});
 	}
 
 	#[test]
-	fn receive_messages_accepts_single_message_with_invalid_payload() {
+	fn message_accepted_refunds_non_zero_weight() {
 		run_test(|| {
-			let mut invalid_message = message(1, REGULAR_PAYLOAD);
-			invalid_message.data.payload = Vec::new();
-
-			assert_ok!(Pallet::<TestRuntime, ()>::receive_messages_proof(
-				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![invalid_message]).into(),
-				1,
-				0, // weight may be zero in this case (all messages are improperly encoded)
-			),);
+			TestOnMessageAccepted::set_consumed_weight_per_message(crate::mock::DbWeight::get().writes(1));
+			let actual_callback_weight = send_regular_message();
+			let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::send_message_weight(
+				&REGULAR_PAYLOAD,
+				crate::mock::DbWeight::get(),
+			);
+			let prune_weight =
+				crate::mock::DbWeight::get().writes(<TestRuntime as Config>::MaxMessagesToPruneAtOnce::get());
 
 			assert_eq!(
-				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
-				1,
+				pre_dispatch_weight.saturating_sub(actual_callback_weight),
+				crate::mock::DbWeight::get().reads(1).saturating_add(prune_weight)
 			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_accepts_batch_with_message_with_invalid_payload() {
+	fn outbound_message_payloads_returns_stored_messages() {
 		run_test(|| {
-			let mut invalid_message = message(2, REGULAR_PAYLOAD);
-			invalid_message.data.payload = Vec::new();
-
-			assert_ok!(Pallet::<TestRuntime, ()>::receive_messages_proof(
-				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![
-					message(1, REGULAR_PAYLOAD),
-					invalid_message,
-					message(3, REGULAR_PAYLOAD),
-				])
-				.into(),
-				3,
-				REGULAR_PAYLOAD.declared_weight + REGULAR_PAYLOAD.declared_weight,
-			),);
+			send_regular_message();
+			send_regular_message();
 
 			assert_eq!(
-				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
-				3,
+				Pallet::<TestRuntime>::outbound_message_payloads(TEST_LANE_ID, 2, 3),
+				vec![MessagePayloadDetails {
+					nonce: 2,
+					payload: REGULAR_PAYLOAD.encode(),
+					delivery_and_dispatch_fee: REGULAR_PAYLOAD.declared_weight,
+				}],
 			);
 		});
 	}
 
 	#[test]
-	fn storage_message_key_computed_properly() {
-		// If this test fails, then something has been changed in module storage that is breaking all
-		// previously crafted messages proofs.
-		let storage_key = storage_keys::message_key("BridgeMessages", &*b"test", 42).0;
-		assert_eq!(
-			storage_key,
-			hex!("dd16c784ebd3390a9bc0357c7511ed018a395e6242c6813b196ca31ed0547ea79446af0e09063bd4a7874aef8a997cec746573742a00000000000000").to_vec(),
-			"Unexpected storage key: {}",
-			hex::encode(&storage_key),
-		);
+	fn outbound_message_payloads_range_is_clamped() {
+		run_test(|| {
+			for _ in 0..3 {
+				send_regular_message();
+			}
+			OutboundLanes::<TestRuntime>::mutate(TEST_LANE_ID, |lane_data| lane_data.oldest_unpruned_nonce = 2);
+
+			let nonces = |begin, end| {
+				Pallet::<TestRuntime>::outbound_message_payloads(TEST_LANE_ID, begin, end)
+					.into_iter()
+					.map(|details| details.nonce)
+					.collect::<Vec<_>>()
+			};
+			assert_eq!(nonces(0, MessageNonce::MAX), vec![2, 3]);
+			assert_eq!(nonces(3, 2), Vec::<MessageNonce>::new());
+		});
 	}
 
 	#[test]
-	fn outbound_lane_data_key_computed_properly() {
-		// If this test fails, then something has been changed in module storage that is breaking all
-		// previously crafted outbound lane state proofs.
-		let storage_key = storage_keys::outbound_lane_data_key("BridgeMessages", &*b"test").0;
-		assert_eq!(
-			storage_key,
-			hex!("dd16c784ebd3390a9bc0357c7511ed0196c246acb9b55077390e3ca723a0ca1f44a8995dd50b6657a037a7839304535b74657374").to_vec(),
-			"Unexpected storage key: {}",
-			hex::encode(&storage_key),
-		);
+	fn outbound_message_payloads_count_is_limited() {
+		run_test(|| {
+			let max_messages = <TestRuntime as Config>::MaxUnconfirmedMessagesAtInboundLane::get();
+			for _ in 0..max_messages + 1 {
+				send_regular_message();
+			}
+
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_payloads(TEST_LANE_ID, 1, max_messages + 1).len()
+					as MessageNonce,
+				max_messages,
+			);
+		});
 	}
 
 	#[test]
-	fn inbound_lane_data_key_computed_properly() {
-		// If this test fails, then something has been changed in module storage that is breaking all
-		// previously crafted inbound lane state proofs.
-		let storage_key = storage_keys::inbound_lane_data_key("BridgeMessages", &*b"test").0;
-		assert_eq!(
-			storage_key,
-			hex!("dd16c784ebd3390a9bc0357c7511ed01e5f83cf83f2127eb47afdc35d6e43fab44a8995dd50b6657a037a7839304535b74657374").to_vec(),
-			"Unexpected storage key: {}",
-			hex::encode(&storage_key),
-		);
+	fn inbound_lane_data_returns_unrewarded_relayers_entries() {
+		run_test(|| {
+			let lane_data = InboundLaneData {
+				last_confirmed_nonce: 8,
+				relayers: vec![
+					unrewarded_relayer(9, 9, TEST_RELAYER_A),
+					unrewarded_relayer(10, 10, TEST_RELAYER_B),
+				]
+				.into_iter()
+				.collect(),
+			};
+			InboundLanes::<TestRuntime, ()>::insert(TEST_LANE_ID, lane_data.clone());
+
+			assert_eq!(Pallet::<TestRuntime>::inbound_lane_data(TEST_LANE_ID), lane_data);
+		});
 	}
 
 	#[test]
-	fn actual_dispatch_weight_does_not_overlow() {
+	fn do_try_state_accepts_consistent_state() {
 		run_test(|| {
-			let message1 = message(1, message_payload(0, Weight::MAX / 2));
-			let message2 = message(2, message_payload(0, Weight::MAX / 2));
-			let message3 = message(3, message_payload(0, Weight::MAX / 2));
-
-			assert_ok!(Pallet::<TestRuntime, ()>::receive_messages_proof(
+			send_regular_message();
+			send_regular_message();
+			receive_messages_delivery_proof();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
 				Origin::signed(1),
 				TEST_RELAYER_A,
-				// this may cause overflow if source chain storage is invalid
-				Ok(vec![message1, message2, message3]).into(),
-				3,
-				Weight::MAX,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
 			));
-			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 2);
+
+			assert_ok!(Pallet::<TestRuntime>::do_try_state());
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_fails_if_message_is_already_delivered() {
+	fn do_try_state_rejects_inconsistent_outbound_lane() {
 		run_test(|| {
-			send_regular_message();
-			receive_messages_delivery_proof();
+			OutboundLanes::<TestRuntime>::insert(
+				TEST_LANE_ID,
+				OutboundLaneData {
+					oldest_unpruned_nonce: 3,
+					latest_received_nonce: 1,
+					latest_generated_nonce: 5,
+					latest_expired_nonce: 0,
+				},
+			);
 
-			assert_noop!(
-				Pallet::<TestRuntime, ()>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
-				Error::<TestRuntime, ()>::MessageIsAlreadyDelivered,
+			assert_err!(
+				Pallet::<TestRuntime>::do_try_state(),
+				"Oldest unpruned nonce is ahead of the latest received nonce",
 			);
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_fails_if_message_is_not_yet_sent() {
+	fn do_try_state_rejects_messages_below_oldest_unpruned_nonce() {
 		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime, ()>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
-				Error::<TestRuntime, ()>::MessageIsNotYetSent,
+			send_regular_message();
+			send_regular_message();
+			receive_messages_delivery_proof();
+			OutboundLanes::<TestRuntime>::mutate(TEST_LANE_ID, |lane_data| lane_data.oldest_unpruned_nonce = 2);
+			OutboundMessagesToPrune::<TestRuntime>::put(0);
+
+			assert_err!(
+				Pallet::<TestRuntime>::do_try_state(),
+				"Stored message is below the oldest unpruned nonce",
 			);
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_fails_if_submitter_cant_pay_additional_fee() {
+	fn do_try_state_rejects_non_contiguous_unrewarded_relayers_entries() {
 		run_test(|| {
-			send_regular_message();
-
-			TestMessageDeliveryAndDispatchPayment::reject_payments();
+			InboundLanes::<TestRuntime>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![
+						unrewarded_relayer(1, 2, TEST_RELAYER_A),
+						unrewarded_relayer(4, 4, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
 
-			assert_noop!(
-				Pallet::<TestRuntime, ()>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
-				Error::<TestRuntime, ()>::FailedToWithdrawMessageFee,
+			assert_err!(
+				Pallet::<TestRuntime>::do_try_state(),
+				"Unrewarded relayer entries are not contiguous",
 			);
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_succeeds() {
+	fn do_try_state_rejects_too_many_unconfirmed_messages() {
 		run_test(|| {
-			send_regular_message();
-
-			assert_ok!(Pallet::<TestRuntime, ()>::increase_message_fee(
-				Origin::signed(1),
+			let max_unconfirmed_messages = <TestRuntime as Config>::MaxUnconfirmedMessagesAtInboundLane::get();
+			InboundLanes::<TestRuntime>::insert(
 				TEST_LANE_ID,
-				1,
-				100,
-			),);
-			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 100));
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![unrewarded_relayer(1, max_unconfirmed_messages + 1, TEST_RELAYER_A)]
+						.into_iter()
+						.collect(),
+				},
+			);
+
+			assert_err!(Pallet::<TestRuntime>::do_try_state(), "Too many unconfirmed messages");
 		});
 	}
 
 	#[test]
-	fn weight_refund_from_receive_messages_proof_works() {
+	fn do_try_state_accepts_queued_messages() {
 		run_test(|| {
-			fn submit_with_unspent_weight(
-				nonce: MessageNonce,
-				unspent_weight: Weight,
-				is_prepaid: bool,
-			) -> (Weight, Weight) {
-				let mut payload = REGULAR_PAYLOAD;
-				payload.dispatch_result.unspent_weight = unspent_weight;
-				payload.dispatch_result.dispatch_fee_paid_during_dispatch = !is_prepaid;
-				let proof = Ok(vec![message(nonce, payload)]).into();
-				let messages_count = 1;
-				let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
-					&proof,
-					messages_count,
-					REGULAR_PAYLOAD.declared_weight,
-				);
-				let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
-					Origin::signed(1),
-					TEST_RELAYER_A,
-					proof,
-					messages_count,
-					REGULAR_PAYLOAD.declared_weight,
-				)
-				.expect("delivery has failed")
-				.actual_weight
-				.expect("receive_messages_proof always returns Some");
-
-				(pre_dispatch_weight, post_dispatch_weight)
-			}
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight - 1,
+			));
 
-			// when dispatch is returning `unspent_weight < declared_weight`
-			let (pre, post) = submit_with_unspent_weight(1, 1, false);
-			assert_eq!(post, pre - 1);
+			assert_ok!(Pallet::<TestRuntime>::do_try_state());
+		});
+	}
 
-			// when dispatch is returning `unspent_weight = declared_weight`
-			let (pre, post) = submit_with_unspent_weight(2, REGULAR_PAYLOAD.declared_weight, false);
-			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
+	#[test]
+	fn do_try_state_rejects_missing_queued_message() {
+		run_test(|| {
+			InboundLatestQueuedNonces::<TestRuntime>::insert(TEST_LANE_ID, 1);
 
-			// when dispatch is returning `unspent_weight > declared_weight`
-			let (pre, post) = submit_with_unspent_weight(3, REGULAR_PAYLOAD.declared_weight + 1, false);
-			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
+			assert_err!(
+				Pallet::<TestRuntime>::do_try_state(),
+				"Queued message is missing from the dispatch queue",
+			);
+		});
+	}
 
-			// when there's no unspent weight
-			let (pre, post) = submit_with_unspent_weight(4, 0, false);
-			assert_eq!(post, pre);
+	#[test]
+	fn do_try_state_rejects_queued_message_outside_of_queue() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight - 1,
+			));
+			let queued_message = InboundQueuedMessages::<TestRuntime>::get(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			})
+			.unwrap();
+			InboundQueuedMessages::<TestRuntime>::insert(
+				MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce: 2,
+				},
+				queued_message,
+			);
 
-			// when dispatch is returning `unspent_weight < declared_weight` AND message is prepaid
-			let (pre, post) = submit_with_unspent_weight(5, 1, true);
-			assert_eq!(
-				post,
-				pre - 1 - <TestRuntime as Config>::WeightInfo::pay_inbound_dispatch_fee_overhead()
+			assert_err!(
+				Pallet::<TestRuntime>::do_try_state(),
+				"Queued message is outside of the dispatch queue",
 			);
 		});
 	}
 
 	#[test]
-	fn messages_delivered_callbacks_are_called() {
+	fn cancelled_message_fee_is_refunded_when_delivery_is_confirmed() {
 		run_test(|| {
 			send_regular_message();
-			send_regular_message();
-			send_regular_message();
 
-			// messages 1+2 are confirmed in 1 tx, message 3 in a separate tx
-			// dispatch of message 2 has failed
-			let mut delivered_messages_1_and_2 = DeliveredMessages::new(1, true);
-			delivered_messages_1_and_2.note_dispatched_message(false);
-			let messages_1_and_2_proof = Ok((
-				TEST_LANE_ID,
-				InboundLaneData {
-					last_confirmed_nonce: 0,
-					relayers: vec![UnrewardedRelayer {
-						relayer: 0,
-						messages: delivered_messages_1_and_2.clone(),
-					}]
-					.into_iter()
-					.collect(),
-				},
-			));
-			let delivered_message_3 = DeliveredMessages::new(3, true);
-			let messages_3_proof = Ok((
+			System::<TestRuntime>::reset_events();
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
 				TEST_LANE_ID,
-				InboundLaneData {
-					last_confirmed_nonce: 0,
-					relayers: vec![UnrewardedRelayer {
-						relayer: 0,
-						messages: delivered_message_3.clone(),
-					}]
-					.into_iter()
-					.collect(),
-				},
+				1,
 			));
 
-			// first tx with messages 1+2
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
-				Origin::signed(1),
-				TestMessagesDeliveryProof(messages_1_and_2_proof),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 1,
-					total_messages: 2,
-					..Default::default()
-				},
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(Pallet::<TestRuntime>::is_outbound_message_cancelled(TEST_LANE_ID, 1));
+			// the message is kept in the storage, so relayers are able to prove it
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1),
+				Some(MessageData {
+					payload: REGULAR_PAYLOAD.encode(),
+					fee: REGULAR_PAYLOAD.declared_weight,
+				}),
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessageCancelled(TEST_LANE_ID, 1)),
+					topics: vec![],
+				}],
+			);
+
+			// the fee is refunded when the bridged chain confirms that the message is not dispatched
+			System::<TestRuntime>::reset_events();
+			confirm_expired_message_delivery(DeliveredMessages::new_skipped(1));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
 			));
-			// second tx with message 3
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(System::<TestRuntime>::events().contains(&EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(Event::MessageFeeRefunded(TEST_LANE_ID, 1)),
+				topics: vec![],
+			}));
+		});
+	}
+
+	#[test]
+	fn relayer_is_rewarded_for_cancelled_message_that_has_been_dispatched() {
+		run_test(|| {
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(
 				Origin::signed(1),
-				TestMessagesDeliveryProof(messages_3_proof),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 1,
-					total_messages: 1,
-					..Default::default()
-				},
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
 			));
 
-			// ensure that both callbacks have been called twice: for 1+2, then for 3
-			TestOnDeliveryConfirmed1::ensure_called(&TEST_LANE_ID, &delivered_messages_1_and_2);
-			TestOnDeliveryConfirmed1::ensure_called(&TEST_LANE_ID, &delivered_message_3);
-			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_messages_1_and_2);
-			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_message_3);
+			// the message has been delivered and dispatched before the bridged chain has learned
+			// about its cancellation
+			confirm_expired_message_delivery(DeliveredMessages::new(1, true));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
 		});
 	}
 
-	fn confirm_3_messages_delivery() -> (Weight, Weight) {
-		send_regular_message();
-		send_regular_message();
-		send_regular_message();
+	#[test]
+	fn cancel_message_fails_if_proof_is_stale() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+			receive_messages_delivery_proof();
 
-		let proof = TestMessagesDeliveryProof(Ok((
-			TEST_LANE_ID,
-			InboundLaneData {
-				last_confirmed_nonce: 0,
-				relayers: vec![unrewarded_relayer(1, 3, TEST_RELAYER_A)].into_iter().collect(),
-			},
-		)));
-		let relayers_state = UnrewardedRelayersState {
-			unrewarded_relayer_entries: 1,
-			total_messages: 3,
-			..Default::default()
-		};
-		let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::receive_messages_delivery_proof_weight(
-			&proof,
-			&relayers_state,
-			crate::mock::DbWeight::get(),
-		);
-		let post_dispatch_weight =
-			Pallet::<TestRuntime>::receive_messages_delivery_proof(Origin::signed(1), proof, relayers_state)
-				.expect("confirmation has failed")
-				.actual_weight
-				.expect("receive_messages_delivery_proof always returns Some");
-		(pre_dispatch_weight, post_dispatch_weight)
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(1), non_delivery_proof(0), TEST_LANE_ID, 2),
+				Error::<TestRuntime, ()>::StaleMessagesDeliveryProof,
+			);
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(
+				Origin::signed(1),
+				non_delivery_proof(1),
+				TEST_LANE_ID,
+				2,
+			));
+		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_refunds_zero_weight() {
+	fn cancel_message_fails_if_message_is_received_by_bridged_chain() {
 		run_test(|| {
-			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
-			assert_eq!(pre_dispatch_weight, post_dispatch_weight);
+			send_regular_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(1), non_delivery_proof(1), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsAlreadyDelivered,
+			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_refunds_non_zero_weight() {
+	fn cancel_message_fails_if_proof_is_invalid() {
 		run_test(|| {
-			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().writes(1));
+			send_regular_message();
 
-			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
-			assert_eq!(
-				pre_dispatch_weight.saturating_sub(post_dispatch_weight),
-				crate::mock::DbWeight::get().reads(1) * 3
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Err(())),
+					TEST_LANE_ID,
+					1
+				),
+				Error::<TestRuntime, ()>::InvalidMessagesDeliveryProof,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((TEST_LANE_ID_2, Default::default()))),
+					TEST_LANE_ID,
+					1
+				),
+				Error::<TestRuntime, ()>::InvalidMessagesDeliveryProof,
 			);
 		});
 	}
 
 	#[test]
-	#[should_panic]
-	fn receive_messages_panics_in_debug_mode_if_callback_is_wrong() {
+	fn cancel_message_fails_if_called_by_other_account() {
 		run_test(|| {
-			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().reads_writes(2, 2));
-			confirm_3_messages_delivery()
+			send_regular_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(2), non_delivery_proof(0), TEST_LANE_ID, 1),
+				DispatchError::BadOrigin,
+			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rejects_proof_if_trying_to_confirm_more_messages_than_expected() {
+	fn cancel_message_fails_if_message_is_already_cancelled() {
 		run_test(|| {
-			// send message first to be able to check that delivery_proof fails later
 			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
+			));
 
-			// 1) InboundLaneData declares that the `last_confirmed_nonce` is 1;
-			// 2) InboundLaneData has no entries => `InboundLaneData::last_delivered_nonce()`
-			//    returns `last_confirmed_nonce`;
-			// 3) it means that we're going to confirm delivery of messages 1..=1;
-			// 4) so the number of declared messages (see `UnrewardedRelayersState`) is `0` and
-			//    numer of actually confirmed messages is `1`.
 			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
-					Origin::signed(1),
-					TestMessagesDeliveryProof(Ok((
-						TEST_LANE_ID,
-						InboundLaneData {
-							last_confirmed_nonce: 1,
-							relayers: Default::default(),
-						},
-					))),
-					UnrewardedRelayersState::default(),
-				),
-				Error::<TestRuntime, ()>::TryingToConfirmMoreMessagesThanExpected,
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(1), non_delivery_proof(0), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsCancelled,
 			);
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_weight_depends_on_message_size() {
+	fn owner_of_cancelled_message_is_notified_when_delivery_is_confirmed() {
 		run_test(|| {
-			let mut small_payload = message_payload(0, 100);
-			let mut large_payload = message_payload(1, 100);
-			small_payload.extra = vec![1; 100];
-			large_payload.extra = vec![2; 16_384];
-
-			assert_ok!(Pallet::<TestRuntime>::send_message(
+			send_owned_regular_message(TEST_MESSAGE_OWNER_1);
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(
 				Origin::signed(1),
+				non_delivery_proof(0),
 				TEST_LANE_ID,
-				small_payload,
-				100,
+				1,
 			));
-			assert_ok!(Pallet::<TestRuntime>::send_message(
+
+			// the message may still be dispatched, so the owner is not notified yet
+			TestMessageOwner1::ensure_not_called(&TEST_LANE_ID, 1);
+
+			confirm_expired_message_delivery(DeliveredMessages::new_skipped(1));
+			TestMessageOwner1::ensure_called(&TEST_LANE_ID, 1, false);
+			assert_eq!(OutboundMessagesOwners::<TestRuntime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_message_is_cancelled() {
+		run_test(|| {
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(
 				Origin::signed(1),
+				non_delivery_proof(0),
 				TEST_LANE_ID,
-				large_payload,
-				100,
+				1,
 			));
 
-			let small_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 1)
-				.expect("increase_message_fee has failed")
-				.actual_weight
-				.expect("increase_message_fee always returns Some");
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100),
+				Error::<TestRuntime, ()>::MessageIsCancelled,
+			);
+		});
+	}
 
-			let large_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 2, 1)
-				.expect("increase_message_fee has failed")
-				.actual_weight
-				.expect("increase_message_fee always returns Some");
+	#[test]
+	fn receive_messages_proof_does_not_dispatch_cancelled_messages() {
+		run_test(|| {
+			let cancelled_message = Message {
+				cancelled: true,
+				..message(2, REGULAR_PAYLOAD)
+			};
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![
+					message(1, REGULAR_PAYLOAD),
+					cancelled_message,
+					message(3, REGULAR_PAYLOAD)
+				])
+				.into(),
+				3,
+				2 * REGULAR_PAYLOAD.declared_weight,
+			));
 
-			assert!(
-				large_weight > small_weight,
-				"Actual post-dispatch weigth for larger message {} must be larger than {} for small message",
-				large_weight,
-				small_weight,
+			let mut delivered_messages = DeliveredMessages::new(1, true);
+			delivered_messages.note_dispatched_message(false);
+			delivered_messages.note_dispatched_message(true);
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers,
+				vec![UnrewardedRelayer {
+					relayer: TEST_RELAYER_A,
+					messages: delivered_messages,
+				}],
 			);
 		});
 	}
 
 	#[test]
-	fn weight_is_refunded_for_messages_that_are_not_pruned() {
+	fn relayers_are_not_rewarded_for_cancelled_messages() {
 		run_test(|| {
-			// send first MAX messages - no messages are pruned
-			let max_messages_to_prune = crate::mock::MaxMessagesToPruneAtOnce::get();
-			let when_zero_messages_are_pruned = send_regular_message();
-			let mut delivered_messages = DeliveredMessages::new(1, true);
-			for _ in 1..max_messages_to_prune {
-				assert_eq!(send_regular_message(), when_zero_messages_are_pruned);
-				delivered_messages.note_dispatched_message(true);
-			}
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				1000,
+				None,
+			));
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(
+				Origin::signed(1),
+				non_delivery_proof(0),
+				TEST_LANE_ID,
+				1,
+			));
 
-			// confirm delivery of all sent messages
+			let mut delivered_messages = DeliveredMessages::new(1, false);
+			delivered_messages.note_dispatched_message(true);
 			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
 				Origin::signed(1),
 				TestMessagesDeliveryProof(Ok((
 					TEST_LANE_ID,
 					InboundLaneData {
-						last_confirmed_nonce: 1,
 						relayers: vec![UnrewardedRelayer {
-							relayer: 0,
+							relayer: TEST_RELAYER_A,
 							messages: delivered_messages,
 						}]
 						.into_iter()
 						.collect(),
-					},
+						..Default::default()
+					}
 				))),
 				UnrewardedRelayersState {
 					unrewarded_relayer_entries: 1,
-					total_messages: max_messages_to_prune,
+					total_messages: 2,
 					..Default::default()
 				},
 			));
 
-			// when next message is sent, MAX messages are pruned
-			let weight_when_max_messages_are_pruned = send_regular_message();
-			assert_eq!(
-				weight_when_max_messages_are_pruned,
-				when_zero_messages_are_pruned + crate::mock::DbWeight::get().writes(max_messages_to_prune),
-			);
-		});
-	}
-
-	#[test]
-	fn message_accepted_callbacks_are_called() {
-		run_test(|| {
-			send_regular_message();
-			TestOnMessageAccepted::ensure_called(&TEST_LANE_ID, &1);
-		});
-	}
-
-	#[test]
-	#[should_panic]
-	fn message_accepted_panics_in_debug_mode_if_callback_is_wrong() {
-		run_test(|| {
-			TestOnMessageAccepted::set_consumed_weight_per_message(crate::mock::DbWeight::get().reads_writes(2, 2));
-			send_regular_message();
-		});
-	}
-
-	#[test]
-	fn message_accepted_refunds_non_zero_weight() {
-		run_test(|| {
-			TestOnMessageAccepted::set_consumed_weight_per_message(crate::mock::DbWeight::get().writes(1));
-			let actual_callback_weight = send_regular_message();
-			let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::send_message_weight(
-				&REGULAR_PAYLOAD,
-				crate::mock::DbWeight::get(),
-			);
-			let prune_weight =
-				crate::mock::DbWeight::get().writes(<TestRuntime as Config>::MaxMessagesToPruneAtOnce::get());
-
-			assert_eq!(
-				pre_dispatch_weight.saturating_sub(actual_callback_weight),
-				crate::mock::DbWeight::get().reads(1).saturating_add(prune_weight)
-			);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
 		});
 	}
 }
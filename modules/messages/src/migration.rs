@@ -0,0 +1,610 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations of the messages pallet.
+
+use crate::{
+	Config, InboundLanes, Lanes, OutboundLanes, OutboundMessages, OutboundMessagesOwners, OutboundMessagesToPrune,
+	Pallet,
+};
+
+use bp_messages::{
+	source_chain::{MessageOwner, MessageOwnerId},
+	DeliveredMessages, DispatchResultsBitVec, InboundLaneData, LaneId, LaneState, MessageKey, MessageNonce,
+	OutboundLaneData, UnrewardedRelayer,
+};
+use bp_runtime::{StorageMigrationStep, VersionedMigration};
+use codec::{Decode, DecodeAll, Encode};
+use frame_support::{
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+use sp_std::{collections::vec_deque::VecDeque, marker::PhantomData, prelude::*};
+
+/// Runtime upgrade that migrates pallet storage from version 0 to version 1.
+pub type MigrateToV1<T, I = ()> =
+	VersionedMigration<Pallet<T, I>, MigrationToV1Step<T, I>, <T as frame_system::Config>::DbWeight>;
+
+/// Migration of the pallet storage from version 0 to version 1.
+///
+/// The migration adds `latest_expired_nonce` field to all outbound lanes, adds skip flags to
+/// unrewarded relayers entries of all inbound lanes, registers all existing lanes in the lanes
+/// registry and initializes the `OutboundMessagesToPrune` value. It only touches
+/// lanes, so it fits into the single block. Outbound messages are left intact: senders of messages
+/// with TTL, that have been sent before version 1, are read from the `OutboundMessagesExpiry` map.
+pub struct MigrationToV1Step<T, I = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> StorageMigrationStep for MigrationToV1Step<T, I> {
+	const FROM: u16 = 0;
+	const TO: u16 = 1;
+
+	fn migrate() -> Weight {
+		// lanes must be migrated before `OutboundMessagesToPrune` is computed, because it reads
+		// lanes data
+		add_outbound_lanes_expired_nonces::<T, I>()
+			.saturating_add(add_inbound_lanes_skipped_messages::<T, I>())
+			.saturating_add(register_existing_lanes::<T, I>())
+			.saturating_add(initialize_outbound_messages_to_prune::<T, I>())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let mut lanes: u64 = 0;
+		for lane_id in OutboundLanes::<T, I>::iter_keys() {
+			if !is_decodable::<OutboundLaneData>(&OutboundLanes::<T, I>::hashed_key_for(&lane_id)) {
+				return Err("Failed to decode outbound lane data");
+			}
+			lanes += 1;
+		}
+		lanes += InboundLanes::<T, I>::iter_keys().count() as u64;
+
+		// every lane is read at most 4 times and written at most 2 times
+		let expected_weight = T::DbWeight::get().reads_writes(4 * lanes, 2 * lanes + 1);
+		if expected_weight > <T as frame_system::Config>::BlockWeights::get().max_block {
+			return Err("Migration of outbound lanes doesn't fit into the single block");
+		}
+
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		for lane_id in OutboundLanes::<T, I>::iter_keys() {
+			if !is_decodable::<OutboundLaneData>(&OutboundLanes::<T, I>::hashed_key_for(&lane_id)) {
+				return Err("Failed to decode migrated outbound lane data");
+			}
+		}
+		for lane_id in InboundLanes::<T, I>::iter_keys() {
+			if !is_decodable::<InboundLaneData<T::InboundRelayer>>(&InboundLanes::<T, I>::hashed_key_for(&lane_id)) {
+				return Err("Failed to decode migrated inbound lane data");
+			}
+		}
+		for message_key in OutboundMessages::<T, I>::iter_keys() {
+			if !is_decodable::<bp_messages::MessageData<T::OutboundMessageFee>>(
+				&OutboundMessages::<T, I>::hashed_key_for(&message_key),
+			) {
+				return Err("Failed to decode outbound message");
+			}
+		}
+		for lane_id in OutboundLanes::<T, I>::iter_keys().chain(InboundLanes::<T, I>::iter_keys()) {
+			if !Lanes::<T, I>::contains_key(&lane_id) {
+				return Err("Existing lane is missing from the lanes registry");
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Runtime upgrade that registers the owner of outbound messages that have been sent before
+/// messages owners have been introduced.
+///
+/// Dispatch results of messages without owner are never passed to the `MessageOwner`. So if the
+/// pallet that has been sending messages over the `Lane`, is now using
+/// `MessagesBridge::send_owned_message`, this upgrade must be used to register the pallet as the
+/// owner of its undelivered messages. The `Nonces` are nonces of these messages. Only messages
+/// that are still stored and have no owner are updated, so it is safe to run the upgrade
+/// multiple times.
+pub struct RegisterMessagesOwner<T, I, Owner, Lane, Nonces>(PhantomData<(T, I, Owner, Lane, Nonces)>);
+
+impl<T, I, Owner, Lane, Nonces> OnRuntimeUpgrade for RegisterMessagesOwner<T, I, Owner, Lane, Nonces>
+where
+	T: Config<I>,
+	I: 'static,
+	Owner: MessageOwner,
+	Lane: Get<LaneId>,
+	Nonces: Get<Vec<MessageNonce>>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		register_messages_owner::<T, I>(Owner::owner_id(), Lane::get(), Nonces::get())
+	}
+}
+
+/// Register owner of given outbound messages.
+///
+/// Returns weight, consumed by the migration. We assume that reading every nonce costs single
+/// db read.
+pub fn register_messages_owner<T: Config<I>, I: 'static>(
+	owner: MessageOwnerId,
+	lane_id: LaneId,
+	nonces: Vec<MessageNonce>,
+) -> Weight {
+	let mut reads = 0;
+	let mut writes = 0;
+	for nonce in nonces {
+		reads += 3;
+		let message_key = MessageKey { lane_id, nonce };
+		if !OutboundMessages::<T, I>::contains_key(&message_key)
+			|| OutboundMessagesOwners::<T, I>::contains_key(&message_key)
+		{
+			continue;
+		}
+
+		writes += 1;
+		OutboundMessagesOwners::<T, I>::insert(&message_key, owner);
+	}
+
+	log::info!(
+		target: "runtime::bridge-messages",
+		"Registered owner {:?} of {} outbound messages at lane {:?}",
+		owner,
+		writes,
+		lane_id,
+	);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
+
+/// Add `latest_expired_nonce` field to every outbound lane data that has been stored without it.
+///
+/// Returns weight, consumed by the migration.
+pub fn add_outbound_lanes_expired_nonces<T: Config<I>, I: 'static>() -> Weight {
+	let mut reads = 0;
+	let mut writes = 0;
+	let lane_ids = OutboundLanes::<T, I>::iter_keys().collect::<Vec<_>>();
+	for lane_id in lane_ids {
+		reads += 1;
+		let lane_key = OutboundLanes::<T, I>::hashed_key_for(&lane_id);
+		let raw_lane_data = match frame_support::storage::unhashed::get_raw(&lane_key) {
+			Some(raw_lane_data) => raw_lane_data,
+			None => continue,
+		};
+
+		// lanes that have been stored before version 1 are decoded with zero `latest_expired_nonce`
+		let lane_data = match OutboundLaneData::decode_all(&raw_lane_data[..]) {
+			Ok(lane_data) => lane_data,
+			Err(_) => {
+				log::error!(
+					target: "runtime::bridge-messages",
+					"Failed to decode outbound lane {:?} data during migration",
+					lane_id,
+				);
+				continue;
+			}
+		};
+
+		// skip lanes that have already been migrated
+		if lane_data.encoded_size() == raw_lane_data.len() {
+			continue;
+		}
+
+		writes += 1;
+		OutboundLanes::<T, I>::insert(&lane_id, lane_data);
+	}
+
+	log::info!(
+		target: "runtime::bridge-messages",
+		"Added latest expired nonce to {} outbound lanes",
+		writes,
+	);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
+
+/// Add `skipped_messages` field to every unrewarded relayers entry that has been stored without it.
+///
+/// Messages that have been delivered before version 1 have never been skipped, so all flags are
+/// unset. Returns weight, consumed by the migration.
+pub fn add_inbound_lanes_skipped_messages<T: Config<I>, I: 'static>() -> Weight {
+	let mut reads = 0;
+	let mut writes = 0;
+	let lane_ids = InboundLanes::<T, I>::iter_keys().collect::<Vec<_>>();
+	for lane_id in lane_ids {
+		reads += 1;
+		let lane_key = InboundLanes::<T, I>::hashed_key_for(&lane_id);
+		let raw_lane_data = match frame_support::storage::unhashed::get_raw(&lane_key) {
+			Some(raw_lane_data) => raw_lane_data,
+			None => continue,
+		};
+
+		// skip lanes that have already been migrated
+		if InboundLaneData::<T::InboundRelayer>::decode_all(&raw_lane_data[..]).is_ok() {
+			continue;
+		}
+
+		let lane_data = match DeprecatedInboundLaneData::<T::InboundRelayer>::decode_all(&raw_lane_data[..]) {
+			Ok(lane_data) => lane_data,
+			Err(_) => {
+				log::error!(
+					target: "runtime::bridge-messages",
+					"Failed to decode inbound lane {:?} data during migration",
+					lane_id,
+				);
+				continue;
+			}
+		};
+
+		writes += 1;
+		InboundLanes::<T, I>::insert(&lane_id, InboundLaneData::from(lane_data));
+	}
+
+	log::info!(
+		target: "runtime::bridge-messages",
+		"Added skipped messages to {} inbound lanes",
+		writes,
+	);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
+
+/// Inbound lane data, as it has been stored before skip flags have been introduced.
+#[derive(Decode)]
+struct DeprecatedInboundLaneData<RelayerId> {
+	relayers: VecDeque<DeprecatedUnrewardedRelayer<RelayerId>>,
+	last_confirmed_nonce: MessageNonce,
+}
+
+/// Unrewarded relayer entry, as it has been stored before skip flags have been introduced.
+#[derive(Decode)]
+struct DeprecatedUnrewardedRelayer<RelayerId> {
+	relayer: RelayerId,
+	begin: MessageNonce,
+	end: MessageNonce,
+	dispatch_results: DispatchResultsBitVec,
+}
+
+impl<RelayerId> From<DeprecatedInboundLaneData<RelayerId>> for InboundLaneData<RelayerId> {
+	fn from(lane_data: DeprecatedInboundLaneData<RelayerId>) -> Self {
+		InboundLaneData {
+			relayers: lane_data
+				.relayers
+				.into_iter()
+				.map(|entry| UnrewardedRelayer {
+					relayer: entry.relayer,
+					messages: DeliveredMessages {
+						begin: entry.begin,
+						end: entry.end,
+						skipped_messages: DispatchResultsBitVec::repeat(false, entry.dispatch_results.len()),
+						dispatch_results: entry.dispatch_results,
+					},
+				})
+				.collect(),
+			last_confirmed_nonce: lane_data.last_confirmed_nonce,
+		}
+	}
+}
+
+/// Register all lanes that have been used before the lanes registry has been introduced.
+///
+/// Lanes are registered in the `Open` state, because they have been used by both chains. Lanes
+/// that are already registered (e.g. at genesis) are left intact.
+///
+/// Returns weight, consumed by the migration.
+pub fn register_existing_lanes<T: Config<I>, I: 'static>() -> Weight {
+	let mut reads = 0;
+	let mut writes = 0;
+	let lane_ids = OutboundLanes::<T, I>::iter_keys()
+		.chain(InboundLanes::<T, I>::iter_keys())
+		.collect::<sp_std::collections::btree_set::BTreeSet<LaneId>>();
+	for lane_id in lane_ids {
+		reads += 2;
+		if Lanes::<T, I>::contains_key(&lane_id) {
+			continue;
+		}
+
+		writes += 1;
+		Lanes::<T, I>::insert(&lane_id, LaneState::Open);
+	}
+
+	log::info!(
+		target: "runtime::bridge-messages",
+		"Registered {} existing lanes",
+		writes,
+	);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
+
+/// Compute number of confirmed, but not yet pruned messages across all outbound lanes.
+///
+/// Returns weight, consumed by the migration.
+pub fn initialize_outbound_messages_to_prune<T: Config<I>, I: 'static>() -> Weight {
+	let mut reads = 0;
+	let mut messages_to_prune: MessageNonce = 0;
+	for lane_data in OutboundLanes::<T, I>::iter_values() {
+		reads += 1;
+		messages_to_prune = messages_to_prune
+			.saturating_add((lane_data.latest_received_nonce + 1).saturating_sub(lane_data.oldest_unpruned_nonce));
+	}
+	OutboundMessagesToPrune::<T, I>::put(messages_to_prune);
+
+	log::info!(
+		target: "runtime::bridge-messages",
+		"Found {} outbound messages waiting to be pruned",
+		messages_to_prune,
+	);
+
+	T::DbWeight::get().reads_writes(reads, 1)
+}
+
+/// Returns true if value, stored under given key, is decodable into given type.
+#[cfg(feature = "try-runtime")]
+fn is_decodable<V: codec::Decode>(key: &[u8]) -> bool {
+	frame_support::storage::unhashed::get_raw(key)
+		.map(|raw_value| V::decode_all(&raw_value[..]).is_ok())
+		.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		message_data, run_test, TestRelayer, TestRuntime, REGULAR_PAYLOAD, TEST_LANE_ID, TEST_LANE_ID_2,
+		TEST_MESSAGE_OWNER_1, TEST_MESSAGE_OWNER_2, UNKNOWN_LANE_ID,
+	};
+	use bitvec::prelude::*;
+	use bp_messages::source_chain::Sender;
+	use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+	fn insert_deprecated_message(nonce: u64) -> MessageKey {
+		let message_key = MessageKey {
+			lane_id: TEST_LANE_ID,
+			nonce,
+		};
+		frame_support::storage::unhashed::put_raw(
+			&OutboundMessages::<TestRuntime, ()>::hashed_key_for(&message_key),
+			&message_data(REGULAR_PAYLOAD).encode(),
+		);
+		message_key
+	}
+
+	fn insert_deprecated_lane(
+		lane_id: bp_messages::LaneId,
+		oldest_unpruned_nonce: MessageNonce,
+		latest_received_nonce: MessageNonce,
+		latest_generated_nonce: MessageNonce,
+	) {
+		frame_support::storage::unhashed::put_raw(
+			&OutboundLanes::<TestRuntime, ()>::hashed_key_for(&lane_id),
+			&(oldest_unpruned_nonce, latest_received_nonce, latest_generated_nonce).encode(),
+		);
+	}
+
+	#[test]
+	fn senders_of_messages_with_ttl_are_known_after_migration() {
+		run_test(|| {
+			StorageVersion::new(0).put::<Pallet<TestRuntime, ()>>();
+			insert_deprecated_lane(TEST_LANE_ID, 1, 0, 2);
+			let message_without_ttl = insert_deprecated_message(1);
+			let message_with_ttl = insert_deprecated_message(2);
+			crate::OutboundMessagesExpiry::<TestRuntime, ()>::insert(&message_with_ttl, (100, Sender::Signed(1)));
+
+			MigrateToV1::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(
+				Pallet::<TestRuntime, ()>::outbound_message_sender(TEST_LANE_ID, message_without_ttl.nonce),
+				None,
+			);
+			assert_eq!(
+				Pallet::<TestRuntime, ()>::outbound_message_sender(TEST_LANE_ID, message_with_ttl.nonce),
+				Some(Sender::Signed(1)),
+			);
+			assert_eq!(
+				OutboundMessages::<TestRuntime, ()>::get(&message_with_ttl),
+				Some(message_data(REGULAR_PAYLOAD)),
+			);
+		});
+	}
+
+	#[test]
+	fn register_messages_owner_registers_owner_of_stored_messages() {
+		run_test(|| {
+			let owned_message = insert_deprecated_message(1);
+			let message_without_owner = insert_deprecated_message(2);
+			OutboundMessagesOwners::<TestRuntime, ()>::insert(&owned_message, TEST_MESSAGE_OWNER_2);
+
+			register_messages_owner::<TestRuntime, ()>(TEST_MESSAGE_OWNER_1, TEST_LANE_ID, vec![1, 2, 3]);
+
+			assert_eq!(
+				OutboundMessagesOwners::<TestRuntime, ()>::get(&owned_message),
+				Some(TEST_MESSAGE_OWNER_2),
+			);
+			assert_eq!(
+				OutboundMessagesOwners::<TestRuntime, ()>::get(&message_without_owner),
+				Some(TEST_MESSAGE_OWNER_1),
+			);
+			assert!(!OutboundMessagesOwners::<TestRuntime, ()>::contains_key(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 3
+			}));
+		});
+	}
+
+	#[test]
+	fn migration_initializes_outbound_messages_to_prune() {
+		run_test(|| {
+			OutboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				OutboundLaneData {
+					oldest_unpruned_nonce: 5,
+					latest_received_nonce: 10,
+					latest_generated_nonce: 15,
+					..Default::default()
+				},
+			);
+			OutboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID_2,
+				OutboundLaneData {
+					oldest_unpruned_nonce: 11,
+					latest_received_nonce: 10,
+					latest_generated_nonce: 10,
+					..Default::default()
+				},
+			);
+
+			initialize_outbound_messages_to_prune::<TestRuntime, ()>();
+
+			assert_eq!(OutboundMessagesToPrune::<TestRuntime, ()>::get(), 6);
+		});
+	}
+
+	#[test]
+	fn migration_adds_expired_nonce_to_deprecated_lanes() {
+		run_test(|| {
+			insert_deprecated_lane(TEST_LANE_ID, 5, 10, 15);
+
+			add_outbound_lanes_expired_nonces::<TestRuntime, ()>();
+
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID),
+				OutboundLaneData {
+					oldest_unpruned_nonce: 5,
+					latest_received_nonce: 10,
+					latest_generated_nonce: 15,
+					latest_expired_nonce: 0,
+				},
+			);
+		});
+	}
+
+	#[test]
+	fn migration_adds_skip_flags_to_deprecated_inbound_lanes() {
+		run_test(|| {
+			// (relayer, begin, end, dispatch_results)
+			let deprecated_lane_data: (
+				Vec<(TestRelayer, MessageNonce, MessageNonce, DispatchResultsBitVec)>,
+				MessageNonce,
+			) = (
+				vec![(100, 1, 2, bitvec![Msb0, u8; 1, 0]), (101, 3, 3, bitvec![Msb0, u8; 1])],
+				0,
+			);
+			frame_support::storage::unhashed::put_raw(
+				&InboundLanes::<TestRuntime, ()>::hashed_key_for(&TEST_LANE_ID),
+				&deprecated_lane_data.encode(),
+			);
+			let migrated_lane_data = InboundLaneData {
+				relayers: vec![
+					UnrewardedRelayer {
+						relayer: 100,
+						messages: DeliveredMessages {
+							begin: 1,
+							end: 2,
+							dispatch_results: bitvec![Msb0, u8; 1, 0],
+							skipped_messages: bitvec![Msb0, u8; 0, 0],
+						},
+					},
+					UnrewardedRelayer {
+						relayer: 101,
+						messages: DeliveredMessages::new(3, true),
+					},
+				]
+				.into_iter()
+				.collect(),
+				last_confirmed_nonce: 0,
+			};
+
+			add_inbound_lanes_skipped_messages::<TestRuntime, ()>();
+			assert_eq!(InboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID), migrated_lane_data);
+
+			// second run leaves migrated lanes intact
+			add_inbound_lanes_skipped_messages::<TestRuntime, ()>();
+			assert_eq!(InboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID), migrated_lane_data);
+		});
+	}
+
+	#[test]
+	fn migration_registers_existing_lanes() {
+		run_test(|| {
+			Lanes::<TestRuntime, ()>::remove(TEST_LANE_ID);
+			Lanes::<TestRuntime, ()>::remove(TEST_LANE_ID_2);
+			Lanes::<TestRuntime, ()>::insert(UNKNOWN_LANE_ID, LaneState::Closing);
+			OutboundLanes::<TestRuntime, ()>::insert(TEST_LANE_ID, OutboundLaneData::default());
+			InboundLanes::<TestRuntime, ()>::insert(TEST_LANE_ID_2, InboundLaneData::default());
+			OutboundLanes::<TestRuntime, ()>::insert(UNKNOWN_LANE_ID, OutboundLaneData::default());
+
+			register_existing_lanes::<TestRuntime, ()>();
+
+			assert_eq!(Lanes::<TestRuntime, ()>::get(TEST_LANE_ID), Some(LaneState::Open));
+			assert_eq!(Lanes::<TestRuntime, ()>::get(TEST_LANE_ID_2), Some(LaneState::Open));
+			assert_eq!(Lanes::<TestRuntime, ()>::get(UNKNOWN_LANE_ID), Some(LaneState::Closing));
+		});
+	}
+
+	#[test]
+	fn migration_to_v1_migrates_v0_storage() {
+		run_test(|| {
+			StorageVersion::new(0).put::<Pallet<TestRuntime, ()>>();
+			Lanes::<TestRuntime, ()>::remove(TEST_LANE_ID);
+			insert_deprecated_lane(TEST_LANE_ID, 1, 1, 2);
+			insert_deprecated_message(1);
+			insert_deprecated_message(2);
+
+			MigrateToV1::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(
+				Pallet::<TestRuntime, ()>::on_chain_storage_version(),
+				Pallet::<TestRuntime, ()>::current_storage_version(),
+			);
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID),
+				OutboundLaneData {
+					oldest_unpruned_nonce: 1,
+					latest_received_nonce: 1,
+					latest_generated_nonce: 2,
+					latest_expired_nonce: 0,
+				},
+			);
+			assert_eq!(
+				OutboundMessages::<TestRuntime, ()>::iter_values()
+					.filter(|stored_message_data| *stored_message_data == message_data(REGULAR_PAYLOAD))
+					.count(),
+				2,
+			);
+			assert_eq!(
+				crate::OutboundMessagesSenders::<TestRuntime, ()>::iter_keys().count(),
+				0
+			);
+			assert_eq!(OutboundMessagesToPrune::<TestRuntime, ()>::get(), 1);
+			assert_eq!(Lanes::<TestRuntime, ()>::get(TEST_LANE_ID), Some(LaneState::Open));
+		});
+	}
+
+	#[test]
+	fn migration_to_v1_is_skipped_if_storage_is_already_migrated() {
+		run_test(|| {
+			StorageVersion::new(1).put::<Pallet<TestRuntime, ()>>();
+			insert_deprecated_lane(TEST_LANE_ID, 1, 1, 2);
+
+			MigrateToV1::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(
+				frame_support::storage::unhashed::get_raw(&OutboundLanes::<TestRuntime, ()>::hashed_key_for(
+					&TEST_LANE_ID
+				)),
+				Some((1u64, 1u64, 2u64).encode()),
+			);
+		});
+	}
+}
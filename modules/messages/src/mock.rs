@@ -22,14 +22,20 @@ use crate::Config;
 use bitvec::prelude::*;
 use bp_messages::{
 	source_chain::{
-		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed, OnMessageAccepted,
-		RelayersRewards, Sender, TargetHeaderChain,
+		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, MessageOwner, MessageOwnerId, MessagesSenders,
+		OnDeliveryConfirmed, OnMessageAccepted, RelayersRewards, Sender, TargetHeaderChain,
+	},
+	target_chain::{
+		DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, QueuedMessagesDeposit, RelayersRegistry,
+		SourceHeaderChain,
 	},
-	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain},
 	DeliveredMessages, InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
 	Parameter as MessagesParameter, UnrewardedRelayer,
 };
-use bp_runtime::{messages::MessageDispatchResult, Size};
+use bp_runtime::{
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	Size,
+};
 use codec::{Decode, Encode};
 use frame_support::{
 	parameter_types,
@@ -83,7 +89,7 @@ frame_support::construct_runtime! {
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Event<T>},
-		Messages: pallet_bridge_messages::{Pallet, Call, Event<T>},
+		Messages: pallet_bridge_messages::{Pallet, Call, Config<T>, Event<T>},
 	}
 }
 
@@ -142,18 +148,28 @@ parameter_types! {
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: u64 = 16;
 	pub const MaxUnconfirmedMessagesAtInboundLane: u64 = 32;
 	pub storage TokenConversionRate: FixedU128 = 1.into();
+	pub const QueuedMessagesDispatchWeight: Weight = 1_000_000_000_000;
+	pub const AccountRateLimitPeriod: u64 = 10;
+	pub storage MaxMessagesPerAccountPerPeriod: MessageNonce = 1024;
+	pub storage MaxLaneBytesPerBlock: u32 = 1024 * 1024;
   pub const TestBridgedChainId: bp_runtime::ChainId = *b"test";
 }
 
 #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
 pub enum TestMessagesParameter {
 	TokenConversionRate(FixedU128),
+	MaxMessagesPerAccountPerPeriod(MessageNonce),
+	MaxLaneBytesPerBlock(u32),
 }
 
 impl MessagesParameter for TestMessagesParameter {
 	fn save(&self) {
 		match *self {
 			TestMessagesParameter::TokenConversionRate(conversion_rate) => TokenConversionRate::set(&conversion_rate),
+			TestMessagesParameter::MaxMessagesPerAccountPerPeriod(max_messages) => {
+				MaxMessagesPerAccountPerPeriod::set(&max_messages)
+			}
+			TestMessagesParameter::MaxLaneBytesPerBlock(max_bytes) => MaxLaneBytesPerBlock::set(&max_bytes),
 		}
 	}
 }
@@ -165,6 +181,10 @@ impl Config for TestRuntime {
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type QueuedMessagesDispatchWeight = QueuedMessagesDispatchWeight;
+	type AccountRateLimitPeriod = AccountRateLimitPeriod;
+	type MaxMessagesPerAccountPerPeriod = MaxMessagesPerAccountPerPeriod;
+	type MaxLaneBytesPerBlock = MaxLaneBytesPerBlock;
 
 	type OutboundPayload = TestPayload;
 	type OutboundMessageFee = TestMessageFee;
@@ -180,9 +200,12 @@ impl Config for TestRuntime {
 	type MessageDeliveryAndDispatchPayment = TestMessageDeliveryAndDispatchPayment;
 	type OnMessageAccepted = TestOnMessageAccepted;
 	type OnDeliveryConfirmed = (TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2);
+	type MessageOwners = (TestMessageOwner1, TestMessageOwner2);
 
 	type SourceHeaderChain = TestSourceHeaderChain;
 	type MessageDispatch = TestMessageDispatch;
+	type RelayersRegistry = TestRelayersRegistry;
+	type QueuedMessagesDeposit = TestQueuedMessagesDeposit;
 	type BridgedChainId = TestBridgedChainId;
 }
 
@@ -192,6 +215,12 @@ impl Size for TestPayload {
 	}
 }
 
+/// Identifier of the first test message owner.
+pub const TEST_MESSAGE_OWNER_1: MessageOwnerId = *b"own1";
+
+/// Identifier of the second test message owner.
+pub const TEST_MESSAGE_OWNER_2: MessageOwnerId = *b"own2";
+
 /// Account that has balance to use in tests.
 pub const ENDOWED_ACCOUNT: AccountId = 0xDEAD;
 
@@ -204,12 +233,24 @@ pub const TEST_RELAYER_B: AccountId = 101;
 /// Account id of additional test relayer - C.
 pub const TEST_RELAYER_C: AccountId = 102;
 
+/// Account id of the relayer that is registered in the `TestRelayersRegistry`.
+pub const TEST_REGISTERED_RELAYER: AccountId = 200;
+
+/// Account id of the relayer that can't pay deposit for queued messages.
+pub const TEST_RELAYER_WITHOUT_DEPOSIT: AccountId = 300;
+
 /// Error that is returned by all test implementations.
 pub const TEST_ERROR: &str = "Test error";
 
 /// Lane that we're using in tests.
 pub const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
 
+/// Additional lane that we're using in tests.
+pub const TEST_LANE_ID_2: LaneId = [0, 0, 0, 2];
+
+/// Lane that is not registered in the lanes registry.
+pub const UNKNOWN_LANE_ID: LaneId = [0, 0, 0, 3];
+
 /// Regular message payload.
 pub const REGULAR_PAYLOAD: TestPayload = message_payload(0, 50);
 
@@ -321,6 +362,21 @@ impl TestMessageDeliveryAndDispatchPayment {
 		frame_support::storage::unhashed::get(b":message-fee:") == Some((Sender::Signed(submitter), fee))
 	}
 
+	/// Returns true if given fee has been refunded to given submitter.
+	pub fn is_fee_refunded(submitter: AccountId, fee: TestMessageFee) -> bool {
+		frame_support::storage::unhashed::get(b":refunded-message-fee:") == Some((Sender::Signed(submitter), fee))
+	}
+
+	/// Returns current balance of the relayers fund.
+	pub fn fund_balance() -> TestMessageFee {
+		frame_support::storage::unhashed::get_or_default(b":relayer-fund-balance:")
+	}
+
+	/// Update balance of the relayers fund.
+	fn update_fund_balance(update: impl FnOnce(TestMessageFee) -> TestMessageFee) {
+		frame_support::storage::unhashed::put(b":relayer-fund-balance:", &update(Self::fund_balance()));
+	}
+
 	/// Returns true if given relayer has been rewarded with given balance. The reward-paid flag is
 	/// cleared after the call.
 	pub fn is_reward_paid(relayer: AccountId, fee: TestMessageFee) -> bool {
@@ -342,10 +398,22 @@ impl MessageDeliveryAndDispatchPayment<AccountId, TestMessageFee> for TestMessag
 		}
 
 		frame_support::storage::unhashed::put(b":message-fee:", &(submitter, fee));
+		Self::update_fund_balance(|balance| balance.saturating_add(*fee));
+		Ok(())
+	}
+
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<AccountId>,
+		fee: &TestMessageFee,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		frame_support::storage::unhashed::put(b":refunded-message-fee:", &(submitter, fee));
+		Self::update_fund_balance(|balance| balance.saturating_sub(*fee));
 		Ok(())
 	}
 
 	fn pay_relayers_rewards(
+		_lane_id: LaneId,
 		_confirmation_relayer: &AccountId,
 		relayers_rewards: RelayersRewards<AccountId, TestMessageFee>,
 		_relayer_fund_account: &AccountId,
@@ -353,8 +421,13 @@ impl MessageDeliveryAndDispatchPayment<AccountId, TestMessageFee> for TestMessag
 		for (relayer, reward) in relayers_rewards {
 			let key = (b":relayer-reward:", relayer, reward.reward).encode();
 			frame_support::storage::unhashed::put(&key, &true);
+			Self::update_fund_balance(|balance| balance.saturating_sub(reward.reward));
 		}
 	}
+
+	fn relayer_fund_balance(_relayer_fund_account: &AccountId) -> TestMessageFee {
+		Self::fund_balance()
+	}
 }
 
 #[derive(Debug)]
@@ -362,9 +435,9 @@ pub struct TestOnMessageAccepted;
 
 impl TestOnMessageAccepted {
 	/// Verify that the callback has been called when the message is accepted.
-	pub fn ensure_called(lane: &LaneId, message: &MessageNonce) {
+	pub fn ensure_called(lane: &LaneId, message: &MessageNonce, sender: &Sender<AccountId>) {
 		let key = (b"TestOnMessageAccepted", lane, message).encode();
-		assert_eq!(frame_support::storage::unhashed::get(&key), Some(true));
+		assert_eq!(frame_support::storage::unhashed::get(&key), Some(sender.clone()));
 	}
 
 	/// Set consumed weight returned by the callback.
@@ -378,10 +451,10 @@ impl TestOnMessageAccepted {
 	}
 }
 
-impl OnMessageAccepted for TestOnMessageAccepted {
-	fn on_messages_accepted(lane: &LaneId, message: &MessageNonce) -> Weight {
+impl OnMessageAccepted<AccountId> for TestOnMessageAccepted {
+	fn on_messages_accepted(lane: &LaneId, message: &MessageNonce, sender: &Sender<AccountId>) -> Weight {
 		let key = (b"TestOnMessageAccepted", lane, message).encode();
-		frame_support::storage::unhashed::put(&key, &true);
+		frame_support::storage::unhashed::put(&key, sender);
 		Self::get_consumed_weight_per_message().unwrap_or_else(|| DbWeight::get().reads_writes(1, 1))
 	}
 }
@@ -391,10 +464,10 @@ impl OnMessageAccepted for TestOnMessageAccepted {
 pub struct TestOnDeliveryConfirmed1;
 
 impl TestOnDeliveryConfirmed1 {
-	/// Verify that the callback has been called with given delivered messages.
-	pub fn ensure_called(lane: &LaneId, messages: &DeliveredMessages) {
+	/// Verify that the callback has been called with given delivered messages and their senders.
+	pub fn ensure_called(lane: &LaneId, messages: &DeliveredMessages, senders: &MessagesSenders<AccountId>) {
 		let key = (b"TestOnDeliveryConfirmed1", lane, messages).encode();
-		assert_eq!(frame_support::storage::unhashed::get(&key), Some(true));
+		assert_eq!(frame_support::storage::unhashed::get(&key), Some(senders.clone()));
 	}
 
 	/// Set consumed weight returned by the callback.
@@ -408,10 +481,14 @@ impl TestOnDeliveryConfirmed1 {
 	}
 }
 
-impl OnDeliveryConfirmed for TestOnDeliveryConfirmed1 {
-	fn on_messages_delivered(lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+impl OnDeliveryConfirmed<AccountId> for TestOnDeliveryConfirmed1 {
+	fn on_messages_delivered(
+		lane: &LaneId,
+		messages: &DeliveredMessages,
+		senders: &MessagesSenders<AccountId>,
+	) -> Weight {
 		let key = (b"TestOnDeliveryConfirmed1", lane, messages).encode();
-		frame_support::storage::unhashed::put(&key, &true);
+		frame_support::storage::unhashed::put(&key, senders);
 		Self::get_consumed_weight_per_message()
 			.unwrap_or_else(|| DbWeight::get().reads_writes(1, 1))
 			.saturating_mul(messages.total_messages())
@@ -430,14 +507,88 @@ impl TestOnDeliveryConfirmed2 {
 	}
 }
 
-impl OnDeliveryConfirmed for TestOnDeliveryConfirmed2 {
-	fn on_messages_delivered(lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+impl OnDeliveryConfirmed<AccountId> for TestOnDeliveryConfirmed2 {
+	fn on_messages_delivered(
+		lane: &LaneId,
+		messages: &DeliveredMessages,
+		_senders: &MessagesSenders<AccountId>,
+	) -> Weight {
 		let key = (b"TestOnDeliveryConfirmed2", lane, messages).encode();
 		frame_support::storage::unhashed::put(&key, &true);
 		0
 	}
 }
 
+/// First message owner.
+#[derive(Debug)]
+pub struct TestMessageOwner1;
+
+impl TestMessageOwner1 {
+	/// Verify that the callback has been called with given dispatch result.
+	pub fn ensure_called(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) {
+		let key = (b"TestMessageOwner1", lane, nonce).encode();
+		assert_eq!(frame_support::storage::unhashed::get(&key), Some(dispatch_result));
+	}
+
+	/// Verify that the callback has not been called for given message.
+	pub fn ensure_not_called(lane: &LaneId, nonce: MessageNonce) {
+		let key = (b"TestMessageOwner1", lane, nonce).encode();
+		assert_eq!(frame_support::storage::unhashed::get::<bool>(&key), None);
+	}
+
+	/// Set consumed weight returned by the callback.
+	pub fn set_consumed_weight_per_message(weight: Weight) {
+		frame_support::storage::unhashed::put(b"TestMessageOwner1_Weight", &weight);
+	}
+
+	/// Get consumed weight returned by the callback.
+	pub fn get_consumed_weight_per_message() -> Option<Weight> {
+		frame_support::storage::unhashed::get(b"TestMessageOwner1_Weight")
+	}
+}
+
+impl MessageOwner for TestMessageOwner1 {
+	fn owner_id() -> MessageOwnerId {
+		TEST_MESSAGE_OWNER_1
+	}
+
+	fn on_message_dispatched(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) -> Weight {
+		let key = (b"TestMessageOwner1", lane, nonce).encode();
+		frame_support::storage::unhashed::put(&key, &dispatch_result);
+		Self::get_consumed_weight_per_message().unwrap_or_else(|| DbWeight::get().reads_writes(1, 1))
+	}
+}
+
+/// Second message owner.
+#[derive(Debug)]
+pub struct TestMessageOwner2;
+
+impl TestMessageOwner2 {
+	/// Verify that the callback has been called with given dispatch result.
+	pub fn ensure_called(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) {
+		let key = (b"TestMessageOwner2", lane, nonce).encode();
+		assert_eq!(frame_support::storage::unhashed::get(&key), Some(dispatch_result));
+	}
+
+	/// Verify that the callback has not been called for given message.
+	pub fn ensure_not_called(lane: &LaneId, nonce: MessageNonce) {
+		let key = (b"TestMessageOwner2", lane, nonce).encode();
+		assert_eq!(frame_support::storage::unhashed::get::<bool>(&key), None);
+	}
+}
+
+impl MessageOwner for TestMessageOwner2 {
+	fn owner_id() -> MessageOwnerId {
+		TEST_MESSAGE_OWNER_2
+	}
+
+	fn on_message_dispatched(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) -> Weight {
+		let key = (b"TestMessageOwner2", lane, nonce).encode();
+		frame_support::storage::unhashed::put(&key, &dispatch_result);
+		0
+	}
+}
+
 /// Source header chain that is used in tests.
 #[derive(Debug)]
 pub struct TestSourceHeaderChain;
@@ -472,6 +623,15 @@ impl MessageDispatch<AccountId, TestMessageFee> for TestMessageDispatch {
 		}
 	}
 
+	fn dispatch_fee_payment(message: &DispatchMessage<TestPayload, TestMessageFee>) -> DispatchFeePayment {
+		match message.data.payload.as_ref() {
+			Ok(payload) if payload.dispatch_result.dispatch_fee_paid_during_dispatch => {
+				DispatchFeePayment::AtTargetChain
+			}
+			_ => DispatchFeePayment::AtSourceChain,
+		}
+	}
+
 	fn dispatch(
 		_relayer_account: &AccountId,
 		message: DispatchMessage<TestPayload, TestMessageFee>,
@@ -483,6 +643,69 @@ impl MessageDispatch<AccountId, TestMessageFee> for TestMessageDispatch {
 	}
 }
 
+/// Queued messages deposit that is used in tests.
+///
+/// The deposit is one unit per byte of the queued message. It can't be reserved from the
+/// `TEST_RELAYER_WITHOUT_DEPOSIT` account.
+#[derive(Debug)]
+pub struct TestQueuedMessagesDeposit;
+
+impl TestQueuedMessagesDeposit {
+	/// Returns deposit that is currently reserved from given relayer account.
+	pub fn reserved_deposit(relayer: AccountId) -> Balance {
+		let key = (b":queued-messages-deposit:", relayer).encode();
+		frame_support::storage::unhashed::get_or_default(&key)
+	}
+}
+
+impl QueuedMessagesDeposit<AccountId> for TestQueuedMessagesDeposit {
+	type Error = &'static str;
+
+	fn reserve_deposit(relayer: &AccountId, message_size: u32) -> Result<(), Self::Error> {
+		if *relayer == TEST_RELAYER_WITHOUT_DEPOSIT {
+			return Err(TEST_ERROR);
+		}
+
+		let key = (b":queued-messages-deposit:", relayer).encode();
+		let reserved_deposit = Self::reserved_deposit(*relayer) + message_size as Balance;
+		frame_support::storage::unhashed::put(&key, &reserved_deposit);
+		Ok(())
+	}
+
+	fn unreserve_deposit(relayer: &AccountId, message_size: u32) {
+		let key = (b":queued-messages-deposit:", relayer).encode();
+		let reserved_deposit = Self::reserved_deposit(*relayer).saturating_sub(message_size as Balance);
+		frame_support::storage::unhashed::put(&key, &reserved_deposit);
+	}
+}
+
+/// Relayers registry that is used in tests.
+///
+/// Only `TEST_REGISTERED_RELAYER` is registered (at all lanes).
+#[derive(Debug)]
+pub struct TestRelayersRegistry;
+
+impl TestRelayersRegistry {
+	/// Returns true if given relayer has been slashed for submitting invalid messages proof.
+	pub fn is_relayer_slashed(relayer: AccountId) -> bool {
+		let key = (b":slashed-relayer:", relayer).encode();
+		frame_support::storage::unhashed::get(&key) == Some(true)
+	}
+}
+
+impl RelayersRegistry<AccountId> for TestRelayersRegistry {
+	fn is_registered(relayer: &AccountId, _lane: &LaneId) -> bool {
+		*relayer == TEST_REGISTERED_RELAYER
+	}
+
+	fn on_invalid_messages_proof(relayer: &AccountId) {
+		if *relayer == TEST_REGISTERED_RELAYER {
+			let key = (b":slashed-relayer:", relayer).encode();
+			frame_support::storage::unhashed::put(&key, &true);
+		}
+	}
+}
+
 /// Return test lane message with given nonce and payload.
 pub fn message(nonce: MessageNonce, payload: TestPayload) -> Message<TestMessageFee> {
 	Message {
@@ -491,6 +714,7 @@ pub fn message(nonce: MessageNonce, payload: TestPayload) -> Message<TestMessage
 			nonce,
 		},
 		data: message_data(payload),
+		cancelled: false,
 	}
 }
 
@@ -537,6 +761,11 @@ pub fn unrewarded_relayer(
 			} else {
 				Default::default()
 			},
+			skipped_messages: if end >= begin {
+				bitvec![Msb0, u8; 0; (end - begin + 1) as _]
+			} else {
+				Default::default()
+			},
 		},
 	}
 }
@@ -551,6 +780,12 @@ pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
 	}
 	.assimilate_storage(&mut t)
 	.unwrap();
+	pallet_bridge_messages::GenesisConfig::<TestRuntime> {
+		opened_lanes: vec![TEST_LANE_ID, TEST_LANE_ID_2],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
 	let mut ext = sp_io::TestExternalities::new(t);
 	ext.execute_with(test)
 }
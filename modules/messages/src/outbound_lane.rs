@@ -18,7 +18,8 @@
 
 use bitvec::prelude::*;
 use bp_messages::{
-	DeliveredMessages, DispatchResultsBitVec, LaneId, MessageData, MessageNonce, OutboundLaneData, UnrewardedRelayer,
+	source_chain::Sender, DeliveredMessages, DispatchResultsBitVec, LaneId, MessageData, MessageNonce,
+	OutboundLaneData, UnrewardedRelayer,
 };
 use frame_support::RuntimeDebug;
 use sp_std::collections::vec_deque::VecDeque;
@@ -27,6 +28,8 @@ use sp_std::collections::vec_deque::VecDeque;
 pub trait OutboundLaneStorage {
 	/// Delivery and dispatch fee type on source chain.
 	type MessageFee;
+	/// Account id type on source chain.
+	type AccountId;
 
 	/// Lane id.
 	fn id(&self) -> LaneId;
@@ -37,8 +40,13 @@ pub trait OutboundLaneStorage {
 	/// Returns saved outbound message payload.
 	#[cfg(test)]
 	fn message(&self, nonce: &MessageNonce) -> Option<MessageData<Self::MessageFee>>;
-	/// Save outbound message in the storage.
-	fn save_message(&mut self, nonce: MessageNonce, message_data: MessageData<Self::MessageFee>);
+	/// Save outbound message and its sender in the storage.
+	fn save_message(
+		&mut self,
+		nonce: MessageNonce,
+		message_data: MessageData<Self::MessageFee>,
+		sender: Sender<Self::AccountId>,
+	);
 	/// Remove outbound message from the storage.
 	fn remove_message(&mut self, nonce: &MessageNonce);
 }
@@ -60,8 +68,8 @@ pub enum ReceivalConfirmationResult {
 	/// The unrewarded relayers vec contains non-consecutive entries. May be a result of invalid bridged
 	/// chain storage.
 	NonConsecutiveUnrewardedRelayerEntries,
-	/// The unrewarded relayers vec contains entry with mismatched number of dispatch results. May be
-	/// a result of invalid bridged chain storage.
+	/// The unrewarded relayers vec contains entry with mismatched number of dispatch results or skip
+	/// flags. May be a result of invalid bridged chain storage.
 	InvalidNumberOfDispatchResults,
 	/// The chain has more messages that need to be confirmed than there is in the proof.
 	TryingToConfirmMoreMessagesThanExpected(MessageNonce),
@@ -86,12 +94,16 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 	/// Send message over lane.
 	///
 	/// Returns new message nonce.
-	pub fn send_message(&mut self, message_data: MessageData<S::MessageFee>) -> MessageNonce {
+	pub fn send_message(
+		&mut self,
+		message_data: MessageData<S::MessageFee>,
+		sender: Sender<S::AccountId>,
+	) -> MessageNonce {
 		let mut data = self.storage.data();
 		let nonce = data.latest_generated_nonce + 1;
 		data.latest_generated_nonce = nonce;
 
-		self.storage.save_message(nonce, message_data);
+		self.storage.save_message(nonce, message_data, sender);
 		self.storage.set_data(data);
 
 		nonce
@@ -122,7 +134,7 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 			);
 		}
 
-		let dispatch_results =
+		let (dispatch_results, skipped_messages) =
 			match extract_dispatch_results(data.latest_received_nonce, latest_delivered_nonce, relayers) {
 				Ok(dispatch_results) => dispatch_results,
 				Err(extract_error) => return extract_error,
@@ -136,6 +148,7 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 			begin: prev_latest_received_nonce + 1,
 			end: latest_delivered_nonce,
 			dispatch_results,
+			skipped_messages,
 		})
 	}
 
@@ -162,7 +175,7 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 	}
 }
 
-/// Extract new dispatch results from the unrewarded relayers vec.
+/// Extract new dispatch results and skip flags from the unrewarded relayers vec.
 ///
 /// Returns `Err(_)` if unrewarded relayers vec contains invalid data, meaning that the bridged
 /// chain has invalid runtime storage.
@@ -170,12 +183,14 @@ fn extract_dispatch_results<RelayerId>(
 	prev_latest_received_nonce: MessageNonce,
 	latest_received_nonce: MessageNonce,
 	relayers: &VecDeque<UnrewardedRelayer<RelayerId>>,
-) -> Result<DispatchResultsBitVec, ReceivalConfirmationResult> {
+) -> Result<(DispatchResultsBitVec, DispatchResultsBitVec), ReceivalConfirmationResult> {
 	// the only caller of this functions checks that the prev_latest_received_nonce..=latest_received_nonce
 	// is valid, so we're ready to accept messages in this range
 	// => with_capacity call must succeed here or we'll be unable to receive confirmations at all
 	let mut received_dispatch_result =
 		BitVec::with_capacity((latest_received_nonce - prev_latest_received_nonce + 1) as _);
+	let mut received_skipped_messages =
+		BitVec::with_capacity((latest_received_nonce - prev_latest_received_nonce + 1) as _);
 	let mut last_entry_end: Option<MessageNonce> = None;
 	for entry in relayers {
 		// unrewarded relayer entry must have at least 1 unconfirmed message
@@ -199,9 +214,12 @@ fn extract_dispatch_results<RelayerId>(
 			// but to guarantee safety of loop operations below this is detected now
 			return Err(ReceivalConfirmationResult::FailedToConfirmFutureMessages);
 		}
-		// entry must have single dispatch result for every message
+		// entry must have single dispatch result and single skip flag for every message
 		// (guaranteed by the `InboundLane::receive_message()`)
-		if entry.messages.dispatch_results.len() as MessageNonce != entry.messages.end - entry.messages.begin + 1 {
+		let entry_messages = entry.messages.end - entry.messages.begin + 1;
+		if entry.messages.dispatch_results.len() as MessageNonce != entry_messages
+			|| entry.messages.skipped_messages.len() as MessageNonce != entry_messages
+		{
 			return Err(ReceivalConfirmationResult::InvalidNumberOfDispatchResults);
 		}
 
@@ -215,13 +233,16 @@ fn extract_dispatch_results<RelayerId>(
 		}
 
 		// now we know that entry brings new confirmations
-		// => let's extract dispatch results
+		// => let's extract dispatch results and skip flags
 		received_dispatch_result.extend_from_bitslice(
 			&entry.messages.dispatch_results[(new_messages_begin - entry.messages.begin) as usize..],
 		);
+		received_skipped_messages.extend_from_bitslice(
+			&entry.messages.skipped_messages[(new_messages_begin - entry.messages.begin) as usize..],
+		);
 	}
 
-	Ok(received_dispatch_result)
+	Ok((received_dispatch_result, received_skipped_messages))
 }
 
 #[cfg(test)]
@@ -244,6 +265,7 @@ mod tests {
 			begin: *nonces.start(),
 			end: *nonces.end(),
 			dispatch_results: bitvec![Msb0, u8; 1; (nonces.end() - nonces.start() + 1) as _],
+			skipped_messages: bitvec![Msb0, u8; 0; (nonces.end() - nonces.start() + 1) as _],
 		}
 	}
 
@@ -253,9 +275,9 @@ mod tests {
 	) -> ReceivalConfirmationResult {
 		run_test(|| {
 			let mut lane = outbound_lane::<TestRuntime, _>(TEST_LANE_ID);
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
 			assert_eq!(lane.storage.data().latest_generated_nonce, 3);
 			assert_eq!(lane.storage.data().latest_received_nonce, 0);
 			let result = lane.confirm_delivery(3, latest_received_nonce, relayers);
@@ -270,7 +292,7 @@ mod tests {
 		run_test(|| {
 			let mut lane = outbound_lane::<TestRuntime, _>(TEST_LANE_ID);
 			assert_eq!(lane.storage.data().latest_generated_nonce, 0);
-			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD)), 1);
+			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1)), 1);
 			assert!(lane.storage.message(&1).is_some());
 			assert_eq!(lane.storage.data().latest_generated_nonce, 1);
 		});
@@ -280,9 +302,9 @@ mod tests {
 	fn confirm_delivery_works() {
 		run_test(|| {
 			let mut lane = outbound_lane::<TestRuntime, _>(TEST_LANE_ID);
-			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD)), 1);
-			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD)), 2);
-			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD)), 3);
+			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1)), 1);
+			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1)), 2);
+			assert_eq!(lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1)), 3);
 			assert_eq!(lane.storage.data().latest_generated_nonce, 3);
 			assert_eq!(lane.storage.data().latest_received_nonce, 0);
 			assert_eq!(
@@ -298,9 +320,9 @@ mod tests {
 	fn confirm_delivery_rejects_nonce_lesser_than_latest_received() {
 		run_test(|| {
 			let mut lane = outbound_lane::<TestRuntime, _>(TEST_LANE_ID);
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
 			assert_eq!(lane.storage.data().latest_generated_nonce, 3);
 			assert_eq!(lane.storage.data().latest_received_nonce, 0);
 			assert_eq!(
@@ -391,6 +413,20 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn confirm_delivery_fails_if_number_of_skip_flags_in_entry_is_invalid() {
+		let mut relayers: VecDeque<_> = unrewarded_relayers(1..=1)
+			.into_iter()
+			.chain(unrewarded_relayers(2..=2).into_iter())
+			.chain(unrewarded_relayers(3..=3).into_iter())
+			.collect();
+		relayers[0].messages.skipped_messages.clear();
+		assert_eq!(
+			assert_3_messages_confirmation_fails(3, &relayers),
+			ReceivalConfirmationResult::InvalidNumberOfDispatchResults,
+		);
+	}
+
 	#[test]
 	fn prune_messages_works() {
 		run_test(|| {
@@ -399,9 +435,9 @@ mod tests {
 			assert_eq!(lane.prune_messages(100), 0);
 			assert_eq!(lane.storage.data().oldest_unpruned_nonce, 1);
 			// when nothing is confirmed, nothing is pruned
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
 			assert_eq!(lane.prune_messages(100), 0);
 			assert_eq!(lane.storage.data().oldest_unpruned_nonce, 1);
 			// after confirmation, some messages are received
@@ -425,9 +461,9 @@ mod tests {
 	fn confirm_delivery_detects_when_more_than_expected_messages_are_confirmed() {
 		run_test(|| {
 			let mut lane = outbound_lane::<TestRuntime, _>(TEST_LANE_ID);
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
-			lane.send_message(message_data(REGULAR_PAYLOAD));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
+			lane.send_message(message_data(REGULAR_PAYLOAD), Sender::Signed(1));
 			assert_eq!(
 				lane.confirm_delivery(0, 3, &unrewarded_relayers(1..=3)),
 				ReceivalConfirmationResult::TryingToConfirmMoreMessagesThanExpected(3),
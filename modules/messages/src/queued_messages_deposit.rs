@@ -0,0 +1,104 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of `QueuedMessagesDeposit` trait on top of `ReservableCurrency` trait.
+
+use bp_messages::target_chain::QueuedMessagesDeposit;
+use frame_support::traits::{Get, ReservableCurrency};
+use sp_runtime::traits::{Saturating, Zero};
+
+/// Queued messages deposit that is reserved in given currency.
+///
+/// The deposit is `DepositPerByte` for every byte of the queued message payload. It is reserved
+/// from the relayer account when the message is queued and unreserved when the message leaves
+/// the queue.
+pub struct CurrencyQueuedMessagesDeposit<Currency, DepositPerByte> {
+	_phantom: sp_std::marker::PhantomData<(Currency, DepositPerByte)>,
+}
+
+impl<AccountId, Currency, DepositPerByte> QueuedMessagesDeposit<AccountId>
+	for CurrencyQueuedMessagesDeposit<Currency, DepositPerByte>
+where
+	Currency: ReservableCurrency<AccountId>,
+	DepositPerByte: Get<Currency::Balance>,
+{
+	type Error = &'static str;
+
+	fn reserve_deposit(relayer: &AccountId, message_size: u32) -> Result<(), Self::Error> {
+		Currency::reserve(relayer, deposit::<Currency::Balance, DepositPerByte>(message_size)).map_err(Into::into)
+	}
+
+	fn unreserve_deposit(relayer: &AccountId, message_size: u32) {
+		let deposit = deposit::<Currency::Balance, DepositPerByte>(message_size);
+		let not_unreserved = Currency::unreserve(relayer, deposit);
+		if !not_unreserved.is_zero() {
+			log::debug!(
+				target: "runtime::bridge-messages",
+				"Failed to unreserve {:?} of queued message deposit",
+				not_unreserved,
+			);
+		}
+	}
+}
+
+/// Compute deposit for the queued message of given size.
+fn deposit<Balance: From<u32> + Saturating, DepositPerByte: Get<Balance>>(message_size: u32) -> Balance {
+	DepositPerByte::get().saturating_mul(message_size.into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, AccountId as TestAccountId, Balance as TestBalance, TestRuntime};
+	use frame_support::{parameter_types, traits::Currency};
+
+	type Balances = pallet_balances::Pallet<TestRuntime>;
+
+	parameter_types! {
+		const DepositPerByte: TestBalance = 10;
+	}
+
+	type Deposit = CurrencyQueuedMessagesDeposit<Balances, DepositPerByte>;
+
+	const RELAYER: TestAccountId = 1;
+
+	#[test]
+	fn deposit_is_reserved_and_unreserved() {
+		run_test(|| {
+			let _ = Balances::deposit_creating(&RELAYER, 1_000);
+
+			assert_eq!(
+				<Deposit as QueuedMessagesDeposit<TestAccountId>>::reserve_deposit(&RELAYER, 50),
+				Ok(())
+			);
+			assert_eq!(Balances::reserved_balance(&RELAYER), 500);
+
+			<Deposit as QueuedMessagesDeposit<TestAccountId>>::unreserve_deposit(&RELAYER, 50);
+			assert_eq!(Balances::reserved_balance(&RELAYER), 0);
+			assert_eq!(Balances::free_balance(&RELAYER), 1_000);
+		});
+	}
+
+	#[test]
+	fn deposit_is_not_reserved_if_relayer_has_no_funds() {
+		run_test(|| {
+			let _ = Balances::deposit_creating(&RELAYER, 100);
+
+			assert!(<Deposit as QueuedMessagesDeposit<TestAccountId>>::reserve_deposit(&RELAYER, 50).is_err());
+			assert_eq!(Balances::reserved_balance(&RELAYER), 0);
+		});
+	}
+}
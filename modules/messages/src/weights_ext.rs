@@ -246,11 +246,66 @@ pub trait WeightInfoExt: WeightInfo {
 			.total_messages
 			.saturating_mul(Self::single_message_callback_overhead(db_weight));
 
+		// and cost of passing dispatch result of every confirmed message to its owner
+		let owners_overhead = relayers_state
+			.total_messages
+			.saturating_mul(Self::single_message_owner_callback_overhead(db_weight));
+
+		// and cost of refunding fee of every confirmed message that has been skipped by the bridged chain
+		let refunds_overhead = relayers_state
+			.total_messages
+			.saturating_mul(Self::single_message_refund_overhead(db_weight));
+
+		// and cost of reading sender and cancellation entry of every confirmed message
+		let senders_overhead = relayers_state.total_messages.saturating_mul(db_weight.reads(2));
+
 		transaction_overhead
 			.saturating_add(messages_overhead)
 			.saturating_add(relayers_overhead)
 			.saturating_add(proof_size_overhead)
 			.saturating_add(callback_overhead)
+			.saturating_add(owners_overhead)
+			.saturating_add(refunds_overhead)
+			.saturating_add(senders_overhead)
+	}
+
+	/// Weight of expired message refund extrinsic.
+	fn expire_message_weight(proof: &impl Size, db_weight: RuntimeDbWeight) -> Weight {
+		// non-delivery proof is verified the same way as the delivery confirmation proof
+		let proof_verification_weight =
+			Self::receive_messages_delivery_proof_weight(proof, &UnrewardedRelayersState::default(), db_weight);
+
+		// then we need to read and update the lane, to read and remove the message expiration entry
+		// and to record the message sender
+		let expiration_overhead = db_weight.reads_writes(2, 3);
+
+		proof_verification_weight.saturating_add(expiration_overhead)
+	}
+
+	/// Weight of message cancellation extrinsic.
+	fn cancel_message_weight(proof: &impl Size, db_weight: RuntimeDbWeight) -> Weight {
+		// cancellation proof is verified the same way as the delivery confirmation proof
+		let proof_verification_weight =
+			Self::receive_messages_delivery_proof_weight(proof, &UnrewardedRelayersState::default(), db_weight);
+
+		// then we need to read the lane, the message sender (it may be stored in the expiration
+		// entry) and the cancellation entry, and to insert the cancellation entry
+		let cancellation_overhead = db_weight.reads_writes(4, 1);
+
+		proof_verification_weight.saturating_add(cancellation_overhead)
+	}
+
+	/// Weight of undelivered messages proof extrinsic.
+	fn prove_undelivered_messages_weight(proof: &impl Size, db_weight: RuntimeDbWeight) -> Weight {
+		// the proof is verified the same way as the delivery confirmation proof
+		let proof_verification_weight =
+			Self::receive_messages_delivery_proof_weight(proof, &UnrewardedRelayersState::default(), db_weight);
+
+		// then we need to read the operating mode, the lane and the previously proved nonce and
+		// to store the new proved nonce
+		let proof_overhead = db_weight.reads_writes(3, 1);
+
+		proof_verification_weight.saturating_add(proof_overhead)
 	}
 
 	// Functions that are used by extrinsics weights formulas.
@@ -352,6 +407,24 @@ pub trait WeightInfoExt: WeightInfo {
 	fn single_message_callback_overhead(db_weight: RuntimeDbWeight) -> Weight {
 		db_weight.reads_writes(1, 1)
 	}
+
+	/// Returns pre-dispatch weight of passing single message dispatch result to its owner.
+	///
+	/// It includes reading and removing the message owner and the `MessageOwner::on_message_dispatched`
+	/// call, which may spend at most `single_message_callback_overhead`.
+	fn single_message_owner_callback_overhead(db_weight: RuntimeDbWeight) -> Weight {
+		db_weight
+			.reads_writes(1, 1)
+			.saturating_add(Self::single_message_callback_overhead(db_weight))
+	}
+
+	/// Returns pre-dispatch weight of refunding fee of single skipped message.
+	///
+	/// It includes the transfer from the relayers fund to the message sender and the update of
+	/// the decommissioning report.
+	fn single_message_refund_overhead(db_weight: RuntimeDbWeight) -> Weight {
+		db_weight.reads_writes(3, 3)
+	}
 }
 
 impl WeightInfoExt for () {
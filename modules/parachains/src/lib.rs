@@ -0,0 +1,845 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parachains finality module.
+//!
+//! This module needs to be deployed together with the GRANDPA module, which is syncing headers of
+//! the bridged relay chain. The main entry point of this module is `submit_parachain_heads`, which
+//! accepts storage proof of some parachain `Heads` entries (stored by the `paras` pallet) from the
+//! bridged relay chain. The relay chain header, used to generate this proof, must be already
+//! finalized by the GRANDPA module.
+//!
+//! The module stores best known head of every tracked parachain and some number of previous heads.
+//! Other modules may use stored heads to verify storage proofs of parachains state (see
+//! `Pallet::parse_finalized_storage_proof`).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// Generated by `#[pallet::event]`
+#![allow(clippy::unused_unit)]
+
+use bp_polkadot_core::parachains::{parachain_head_storage_key_at_source, ParaHash, ParaHead, ParaHeadsProof, ParaId};
+use bp_runtime::StorageProofChecker;
+use codec::{Decode, Encode};
+use frame_support::{traits::Filter, weights::Weight, RuntimeDebug};
+use sp_runtime::traits::Header as HeaderT;
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+// Re-export in crate namespace for `construct_runtime!`
+pub use pallet::*;
+
+/// Block hash of the bridged relay chain.
+pub type RelayBlockHash<T, I> =
+	pallet_bridge_grandpa::BridgedBlockHash<T, <T as Config<I>>::BridgesGrandpaPalletInstance>;
+/// Block number of the bridged relay chain.
+pub type RelayBlockNumber<T, I> =
+	pallet_bridge_grandpa::BridgedBlockNumber<T, <T as Config<I>>::BridgesGrandpaPalletInstance>;
+/// Best known parachain head, as it is stored in the runtime storage.
+pub type BestParaHeadOf<T, I> = BestParaHead<RelayBlockNumber<T, I>>;
+
+/// Best known parachain head.
+#[derive(Clone, Decode, Encode, PartialEq, RuntimeDebug)]
+pub struct BestParaHead<RelayBlockNumber> {
+	/// Number of the relay chain block where this head has been read from.
+	///
+	/// The parachain head is only updated if it has been read from the better relay chain block.
+	pub at_relay_block_number: RelayBlockNumber,
+	/// Hash of the parachain head.
+	pub head_hash: ParaHash,
+	/// Current position of the parachain in the `ImportedParaHashes` ring buffer.
+	pub next_imported_hash_position: u32,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
+	use frame_system::{pallet_prelude::*, RawOrigin};
+	use sp_runtime::traits::BadOrigin;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: pallet_bridge_grandpa::Config<Self::BridgesGrandpaPalletInstance> {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Instance of the GRANDPA pallet, that is syncing headers of the bridged relay chain.
+		type BridgesGrandpaPalletInstance: 'static;
+
+		/// Name of the `paras` pallet in the `construct_runtime!()` macro of the bridged relay chain.
+		#[pallet::constant]
+		type ParasPalletName: Get<&'static str>;
+
+		/// Set of parachains that are tracked by the pallet.
+		///
+		/// Heads of all other parachains are ignored, even if they are included in the proof.
+		type TrackedParachains: Filter<ParaId>;
+
+		/// Maximal number of heads of every parachain to keep in the storage.
+		///
+		/// The setting is there to prevent growing the on-chain state indefinitely. Note the setting
+		/// does not relate to parachain block numbers - we will simply keep as much items in the
+		/// storage, so it doesn't guarantee any fixed timeframe for heads.
+		#[pallet::constant]
+		type HeadsToKeep: Get<u32>;
+	}
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Submit proof of one or several parachain heads.
+		///
+		/// The proof is supposed to be proof of some `Heads` entries from the `paras` pallet at
+		/// the bridged relay chain. The proof is supposed to be crafted at the `relay_block_hash`
+		/// that must already be finalized by the bridge GRANDPA pallet.
+		///
+		/// Heads of untracked parachains and heads that are missing from the proof are ignored.
+		/// The parachain head is also ignored if we already know the head that has been read from
+		/// the same or better relay chain block.
+		#[pallet::weight(submit_parachain_heads_weight::<T, I>(parachains.len() as u32))]
+		pub fn submit_parachain_heads(
+			origin: OriginFor<T>,
+			relay_block_hash: RelayBlockHash<T, I>,
+			parachains: Vec<ParaId>,
+			parachain_heads_proof: ParaHeadsProof,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure_operational::<T, I>()?;
+
+			// we'll need relay chain header to verify that parachain heads are always increasing
+			let relay_block =
+				pallet_bridge_grandpa::Pallet::<T, T::BridgesGrandpaPalletInstance>::finalized_header(relay_block_hash)
+					.ok_or(Error::<T, I>::UnknownRelayChainBlock)?;
+			let relay_block_number = *relay_block.number();
+
+			// now parse storage proof and read parachain heads
+			pallet_bridge_grandpa::Pallet::<T, T::BridgesGrandpaPalletInstance>::parse_finalized_storage_proof(
+				relay_block_hash,
+				sp_trie::StorageProof::new(parachain_heads_proof.0),
+				move |storage| {
+					for parachain in parachains {
+						if !T::TrackedParachains::filter(&parachain) {
+							log::trace!(
+								target: "runtime::bridge-parachains",
+								"The head of parachain {:?} has been provided, but it is not tracked by the pallet",
+								parachain,
+							);
+							Self::deposit_event(Event::UntrackedParachainRejected(parachain));
+							continue;
+						}
+
+						let parachain_head = match read_parachain_head::<T, I>(&storage, parachain) {
+							Some(parachain_head) => parachain_head,
+							None => {
+								log::trace!(
+									target: "runtime::bridge-parachains",
+									"The head of parachain {:?} is missing from the proof, or it is invalid",
+									parachain,
+								);
+								Self::deposit_event(Event::MissingParachainHead(parachain));
+								continue;
+							}
+						};
+
+						update_parachain_head::<T, I>(parachain, relay_block_number, parachain_head);
+					}
+				},
+			)?;
+
+			Ok(())
+		}
+
+		/// Change `PalletOwner`.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			match new_owner {
+				Some(new_owner) => {
+					PalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-parachains", "Setting pallet Owner to: {:?}", new_owner);
+				}
+				None => {
+					PalletOwner::<T, I>::kill();
+					log::info!(target: "runtime::bridge-parachains", "Removed Owner of pallet.");
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Halt or resume all pallet operations.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_operational(origin: OriginFor<T>, operational: bool) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			IsHalted::<T, I>::put(!operational);
+
+			if operational {
+				log::info!(target: "runtime::bridge-parachains", "Resuming pallet operations.");
+			} else {
+				log::warn!(target: "runtime::bridge-parachains", "Stopping pallet operations.");
+			}
+
+			Ok(())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// The head of untracked parachain has been provided and ignored.
+		UntrackedParachainRejected(ParaId),
+		/// The head of parachain is missing from the proof, or it can't be decoded.
+		MissingParachainHead(ParaId),
+		/// The head of parachain has been read from the same or older relay chain block than the
+		/// best known head. It has been ignored.
+		RejectedObsoleteParachainHead(ParaId, ParaHash),
+		/// The best known head of parachain has been updated.
+		UpdatedParachainHead(ParaId, ParaHash),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// Relay chain block is unknown to us.
+		UnknownRelayChainBlock,
+		/// Parachain head is unknown to us.
+		UnknownParaHead,
+		/// Parachain head can't be decoded into the parachain header.
+		InvalidParaHead,
+		/// The storage proof doesn't contains storage root. So it is invalid for given header.
+		StorageRootMismatch,
+		/// All pallet operations are halted.
+		Halted,
+	}
+
+	/// Best known heads of tracked parachains.
+	#[pallet::storage]
+	pub(super) type BestParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, ParaId, BestParaHeadOf<T, I>>;
+
+	/// Parachain heads which have been imported into the pallet.
+	#[pallet::storage]
+	pub(super) type ImportedParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ParaId, Blake2_128Concat, ParaHash, ParaHead>;
+
+	/// A ring buffer of imported parachain head hashes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedParaHashes<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ParaId, Twox64Concat, u32, ParaHash>;
+
+	/// Optional pallet owner.
+	///
+	/// Pallet owner has a right to halt all pallet operations and then resume them. If it is
+	/// `None`, then there are no direct ways to halt/resume pallet operations, but other
+	/// runtime methods may still be used to do that (i.e. democracy::referendum to update halt
+	/// flag directly or call the `set_operational`).
+	#[pallet::storage]
+	pub(super) type PalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// If true, all pallet transactions are failed immediately.
+	#[pallet::storage]
+	pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Optional module owner account.
+		pub owner: Option<T::AccountId>,
+		/// Whether the pallet operations are halted at genesis.
+		pub is_halted: bool,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self {
+				owner: None,
+				is_halted: false,
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			if let Some(ref owner) = self.owner {
+				PalletOwner::<T, I>::put(owner);
+			}
+			IsHalted::<T, I>::put(self.is_halted);
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Get the best known head of given parachain.
+		pub fn best_parachain_head(parachain: ParaId) -> Option<ParaHead> {
+			let best_para_head_hash = BestParaHeads::<T, I>::get(parachain)?.head_hash;
+			ImportedParaHeads::<T, I>::get(parachain, best_para_head_hash)
+		}
+
+		/// Get parachain head with given hash, if it is known to the pallet.
+		pub fn parachain_head(parachain: ParaId, hash: ParaHash) -> Option<ParaHead> {
+			ImportedParaHeads::<T, I>::get(parachain, hash)
+		}
+
+		/// Verify that the passed storage proof is valid, given it is crafted using known
+		/// parachain head. If the proof is valid, then the `parse` callback is called and the
+		/// function returns its result.
+		///
+		/// The parachain head is decoded as `ParachainHeader`, which is normally the header type
+		/// of the parachain.
+		pub fn parse_finalized_storage_proof<ParachainHeader: HeaderT, R>(
+			parachain: ParaId,
+			hash: ParaHash,
+			storage_proof: sp_trie::StorageProof,
+			parse: impl FnOnce(StorageProofChecker<ParachainHeader::Hashing>) -> R,
+		) -> Result<R, DispatchError> {
+			let parachain_head =
+				ImportedParaHeads::<T, I>::get(parachain, hash).ok_or(Error::<T, I>::UnknownParaHead)?;
+			let parachain_header =
+				ParachainHeader::decode(&mut &parachain_head.0[..]).map_err(|_| Error::<T, I>::InvalidParaHead)?;
+			let storage_proof_checker = StorageProofChecker::new(*parachain_header.state_root(), storage_proof)
+				.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
+
+			Ok(parse(storage_proof_checker))
+		}
+	}
+
+	/// Read parachain head from the storage proof.
+	fn read_parachain_head<T: Config<I>, I: 'static>(
+		storage: &StorageProofChecker<pallet_bridge_grandpa::BridgedBlockHasher<T, T::BridgesGrandpaPalletInstance>>,
+		parachain: ParaId,
+	) -> Option<ParaHead> {
+		let parachain_head_key = parachain_head_storage_key_at_source(T::ParasPalletName::get(), parachain);
+		let parachain_head = storage.read_value(parachain_head_key.0.as_ref()).ok()??;
+		ParaHead::decode(&mut &parachain_head[..]).ok()
+	}
+
+	/// Try to update parachain head, read from the relay chain block with given number.
+	fn update_parachain_head<T: Config<I>, I: 'static>(
+		parachain: ParaId,
+		relay_block_number: RelayBlockNumber<T, I>,
+		parachain_head: ParaHead,
+	) {
+		let parachain_head_hash = parachain_head.hash();
+		let stored_best_head = BestParaHeads::<T, I>::get(parachain);
+		let next_imported_hash_position = match stored_best_head {
+			Some(ref stored_best_head) if stored_best_head.at_relay_block_number >= relay_block_number => {
+				log::trace!(
+					target: "runtime::bridge-parachains",
+					"The head {:?} of parachain {:?} has been read at relay block {:?}, but we already know the \
+					head {:?} that has been read at relay block {:?}",
+					parachain_head_hash,
+					parachain,
+					relay_block_number,
+					stored_best_head.head_hash,
+					stored_best_head.at_relay_block_number,
+				);
+				Pallet::<T, I>::deposit_event(Event::RejectedObsoleteParachainHead(parachain, parachain_head_hash));
+				return;
+			}
+			Some(ref stored_best_head) if stored_best_head.head_hash == parachain_head_hash => {
+				// the parachain head hasn't changed since the last update, so we only need to
+				// remember that it has been read from the better relay chain block
+				BestParaHeads::<T, I>::insert(
+					parachain,
+					BestParaHead {
+						at_relay_block_number: relay_block_number,
+						head_hash: parachain_head_hash,
+						next_imported_hash_position: stored_best_head.next_imported_hash_position,
+					},
+				);
+				return;
+			}
+			Some(stored_best_head) => stored_best_head.next_imported_hash_position,
+			None => 0,
+		};
+
+		// insert new head and update the ring buffer
+		let pruning = ImportedParaHashes::<T, I>::try_get(parachain, next_imported_hash_position);
+		ImportedParaHeads::<T, I>::insert(parachain, parachain_head_hash, parachain_head);
+		ImportedParaHashes::<T, I>::insert(parachain, next_imported_hash_position, parachain_head_hash);
+		BestParaHeads::<T, I>::insert(
+			parachain,
+			BestParaHead {
+				at_relay_block_number: relay_block_number,
+				head_hash: parachain_head_hash,
+				next_imported_hash_position: (next_imported_hash_position + 1) % T::HeadsToKeep::get(),
+			},
+		);
+
+		// remove old head
+		if let Ok(head_hash_to_prune) = pruning {
+			log::trace!(
+				target: "runtime::bridge-parachains",
+				"Pruning old head {:?} of parachain {:?}",
+				head_hash_to_prune,
+				parachain,
+			);
+			ImportedParaHeads::<T, I>::remove(parachain, head_hash_to_prune);
+		}
+
+		log::trace!(
+			target: "runtime::bridge-parachains",
+			"Updated head of parachain {:?} to {:?}, read at relay block {:?}",
+			parachain,
+			parachain_head_hash,
+			relay_block_number,
+		);
+		Pallet::<T, I>::deposit_event(Event::UpdatedParachainHead(parachain, parachain_head_hash));
+	}
+
+	/// Ensure that the origin is either root, or `PalletOwner`.
+	fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(), BadOrigin> {
+		match origin.into() {
+			Ok(RawOrigin::Root) => Ok(()),
+			Ok(RawOrigin::Signed(ref signer)) if Some(signer) == PalletOwner::<T, I>::get().as_ref() => Ok(()),
+			_ => Err(BadOrigin),
+		}
+	}
+
+	/// Ensure that the pallet is in operational mode (not halted).
+	fn ensure_operational<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+		if IsHalted::<T, I>::get() {
+			Err(Error::<T, I>::Halted)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+/// Weight of the `submit_parachain_heads` call with given number of parachains.
+///
+/// The pallet isn't benchmarked yet, so this only accounts db reads and writes. For every
+/// parachain we're reading the best head and the ring buffer entry, and writing the best head,
+/// new head, new ring buffer entry and removing the pruned head.
+fn submit_parachain_heads_weight<T: Config<I>, I: 'static>(parachains_count: u32) -> Weight {
+	let parachains_count = parachains_count as Weight;
+	T::DbWeight::get().reads_writes(
+		2u64.saturating_add(parachains_count.saturating_mul(2)),
+		parachains_count.saturating_mul(4),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, test_relay_header, Event as TestEvent, Origin, RelayBlockHeader, TestRuntime, PARAS_PALLET_NAME,
+		UNTRACKED_PARACHAIN_ID,
+	};
+	use bp_test_utils::{authority_list, make_default_justification};
+	use frame_support::{assert_noop, assert_ok, dispatch::DispatchResult, traits::OnInitialize};
+	use frame_system::{EventRecord, Phase};
+	use sp_runtime::{traits::BlakeTwo256, DispatchError};
+	use sp_state_machine::{backend::Backend, prove_read, InMemoryBackend};
+
+	type BridgesGrandpaPalletInstance = ();
+
+	fn initialize(state_root: RelayBlockHash<TestRuntime, ()>) {
+		pallet_bridge_grandpa::Pallet::<TestRuntime, BridgesGrandpaPalletInstance>::initialize(
+			Origin::root(),
+			bp_header_chain::InitializationData {
+				header: test_relay_header(0, state_root),
+				authority_list: authority_list(),
+				set_id: 1,
+				is_halted: false,
+			},
+		)
+		.unwrap();
+	}
+
+	fn proceed(num: RelayBlockNumber<TestRuntime, ()>, state_root: RelayBlockHash<TestRuntime, ()>) {
+		pallet_bridge_grandpa::Pallet::<TestRuntime, BridgesGrandpaPalletInstance>::on_initialize(0);
+
+		let header = test_relay_header(num, state_root);
+		let justification = make_default_justification(&header);
+		assert_ok!(
+			pallet_bridge_grandpa::Pallet::<TestRuntime, BridgesGrandpaPalletInstance>::submit_finality_proof(
+				Origin::signed(1),
+				header,
+				justification,
+			)
+		);
+	}
+
+	fn prepare_parachain_heads_proof(heads: Vec<(u32, ParaHead)>) -> (RelayBlockHash<TestRuntime, ()>, ParaHeadsProof) {
+		let storage_keys = heads
+			.iter()
+			.map(|(parachain, _)| parachain_head_storage_key_at_source(PARAS_PALLET_NAME, ParaId(*parachain)).0)
+			.collect::<Vec<_>>();
+		let backend = InMemoryBackend::<BlakeTwo256>::from(vec![(
+			None,
+			heads
+				.into_iter()
+				.zip(storage_keys.iter())
+				.map(|((_, head), storage_key)| (storage_key.clone(), Some(head.encode())))
+				.collect::<Vec<_>>(),
+		)]);
+		let root = backend.storage_root(std::iter::empty()).0;
+
+		// generate storage proof to be delivered to This chain
+		let storage_proof = prove_read(backend, &storage_keys).unwrap().iter_nodes().collect();
+
+		(root, ParaHeadsProof(storage_proof))
+	}
+
+	fn head_data(parachain: u32, head_number: u32) -> ParaHead {
+		ParaHead((parachain, head_number).encode())
+	}
+
+	fn head_hash(parachain: u32, head_number: u32) -> ParaHash {
+		head_data(parachain, head_number).hash()
+	}
+
+	fn import_parachain_1_head(
+		relay_chain_block: RelayBlockNumber<TestRuntime, ()>,
+		relay_state_root: RelayBlockHash<TestRuntime, ()>,
+		proof: ParaHeadsProof,
+	) -> DispatchResult {
+		Pallet::<TestRuntime>::submit_parachain_heads(
+			Origin::signed(1),
+			test_relay_header(relay_chain_block, relay_state_root).hash(),
+			vec![ParaId(1)],
+			proof,
+		)
+	}
+
+	fn best_para_head(
+		at_relay_block_number: RelayBlockNumber<TestRuntime, ()>,
+		head_hash: ParaHash,
+		next_imported_hash_position: u32,
+	) -> BestParaHeadOf<TestRuntime, ()> {
+		BestParaHead {
+			at_relay_block_number,
+			head_hash,
+			next_imported_hash_position,
+		}
+	}
+
+	#[test]
+	fn submit_parachain_heads_checks_operating_mode() {
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 0))]);
+
+		run_test(|| {
+			initialize(state_root);
+
+			// `submit_parachain_heads()` should fail when the pallet is halted.
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+			assert_noop!(
+				import_parachain_1_head(0, state_root, proof.clone()),
+				Error::<TestRuntime>::Halted,
+			);
+
+			// `submit_parachain_heads()` should succeed now that the pallet is resumed.
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), true));
+			assert_ok!(import_parachain_1_head(0, state_root, proof));
+		});
+	}
+
+	#[test]
+	fn only_owner_or_root_may_halt_pallet() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operational(Origin::signed(1), false),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(1)));
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::signed(1), false));
+			assert!(IsHalted::<TestRuntime>::get());
+		});
+	}
+
+	#[test]
+	fn imports_initial_parachain_heads() {
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 0)), (3, head_data(3, 10))]);
+		run_test(|| {
+			initialize(state_root);
+
+			// we're trying to update heads of parachains 1, 2 and 3
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				test_relay_header(0, state_root).hash(),
+				vec![ParaId(1), ParaId(2), ParaId(3)],
+				proof,
+			));
+
+			// but only 1 and 3 are updated, because proof is missing head of parachain#2
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(0, head_hash(1, 0), 1)),
+			);
+			assert_eq!(BestParaHeads::<TestRuntime>::get(ParaId(2)), None);
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(3)),
+				Some(best_para_head(0, head_hash(3, 10), 1)),
+			);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(1)),
+				Some(head_data(1, 0))
+			);
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(ParaId(2)), None);
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(3)),
+				Some(head_data(3, 10))
+			);
+
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Parachains(Event::UpdatedParachainHead(ParaId(1), head_hash(1, 0))),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Parachains(Event::MissingParachainHead(ParaId(2))),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Parachains(Event::UpdatedParachainHead(ParaId(3), head_hash(3, 10))),
+						topics: vec![],
+					},
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn imports_parachain_heads_is_able_to_progress() {
+		let (state_root_5, proof_5) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		let (state_root_10, proof_10) = prepare_parachain_heads_proof(vec![(1, head_data(1, 10))]);
+		run_test(|| {
+			// start with relay block #0 and import head#5 of parachain#1
+			initialize(state_root_5);
+			assert_ok!(import_parachain_1_head(0, state_root_5, proof_5));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(0, head_hash(1, 5), 1)),
+			);
+			assert_eq!(
+				ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, 5)),
+				Some(head_data(1, 5))
+			);
+			assert_eq!(ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, 10)), None);
+
+			// import head#10 of parachain#1 at relay block #1
+			proceed(1, state_root_10);
+			assert_ok!(import_parachain_1_head(1, state_root_10, proof_10));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(1, head_hash(1, 10), 2)),
+			);
+			assert_eq!(
+				ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, 5)),
+				Some(head_data(1, 5))
+			);
+			assert_eq!(
+				ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, 10)),
+				Some(head_data(1, 10))
+			);
+		});
+	}
+
+	#[test]
+	fn ignores_untracked_parachain() {
+		let (state_root, proof) =
+			prepare_parachain_heads_proof(vec![(1, head_data(1, 5)), (UNTRACKED_PARACHAIN_ID, head_data(1, 5))]);
+		run_test(|| {
+			// start with relay block #0 and try to import head#5 of parachain#1 and untracked parachain
+			initialize(state_root);
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				test_relay_header(0, state_root).hash(),
+				vec![ParaId(1), ParaId(UNTRACKED_PARACHAIN_ID)],
+				proof,
+			));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(0, head_hash(1, 5), 1)),
+			);
+			assert_eq!(BestParaHeads::<TestRuntime>::get(ParaId(UNTRACKED_PARACHAIN_ID)), None);
+			assert!(frame_system::Pallet::<TestRuntime>::events().contains(&EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Parachains(Event::UntrackedParachainRejected(ParaId(UNTRACKED_PARACHAIN_ID))),
+				topics: vec![],
+			}));
+		});
+	}
+
+	#[test]
+	fn does_nothing_when_already_imported_this_head_at_previous_relay_header() {
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 0))]);
+		run_test(|| {
+			// import head#0 of parachain#1 at relay block#0
+			initialize(state_root);
+			assert_ok!(import_parachain_1_head(0, state_root, proof.clone()));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(0, head_hash(1, 0), 1)),
+			);
+
+			// try to import head#0 of parachain#1 at relay block#1
+			// => we'll leave previous value, but update `at_relay_block_number`
+			proceed(1, state_root);
+			assert_ok!(import_parachain_1_head(1, state_root, proof));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(1, head_hash(1, 0), 1)),
+			);
+		});
+	}
+
+	#[test]
+	fn does_nothing_when_already_imported_head_at_better_relay_header() {
+		let (state_root_5, proof_5) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		let (state_root_10, proof_10) = prepare_parachain_heads_proof(vec![(1, head_data(1, 10))]);
+		run_test(|| {
+			// start with relay block #0
+			initialize(state_root_5);
+
+			// head#10 of parachain#1 at relay block#1
+			proceed(1, state_root_10);
+			assert_ok!(import_parachain_1_head(1, state_root_10, proof_10));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(1, head_hash(1, 10), 1)),
+			);
+
+			// now try to import head#5 at relay block#0
+			// => nothing is changed, because better head has already been imported
+			assert_ok!(import_parachain_1_head(0, state_root_5, proof_5));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(best_para_head(1, head_hash(1, 10), 1)),
+			);
+			assert_eq!(ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, 5)), None);
+		});
+	}
+
+	#[test]
+	fn prunes_old_heads() {
+		run_test(|| {
+			let heads_to_keep = crate::mock::HeadsToKeep::get();
+
+			// import exactly `HeadsToKeep` headers
+			for i in 0..heads_to_keep {
+				let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, i))]);
+				if i == 0 {
+					initialize(state_root);
+				} else {
+					proceed(i, state_root);
+				}
+
+				assert_ok!(import_parachain_1_head(i, state_root, proof));
+			}
+
+			// nothing is pruned yet
+			for i in 0..heads_to_keep {
+				assert!(ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, i)).is_some());
+			}
+
+			// import next relay chain header and next parachain head
+			let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, heads_to_keep))]);
+			proceed(heads_to_keep, state_root);
+			assert_ok!(import_parachain_1_head(heads_to_keep, state_root, proof));
+
+			// and the head#0 is pruned
+			assert!(ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, 0)).is_none());
+			for i in 1..=heads_to_keep {
+				assert!(ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_hash(1, i)).is_some());
+			}
+		});
+	}
+
+	#[test]
+	fn fails_on_unknown_relay_chain_block() {
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		run_test(|| {
+			// start with relay block #0
+			initialize(state_root);
+
+			// try to import head#5 of parachain#1 at unknown relay chain block #1
+			assert_noop!(
+				import_parachain_1_head(1, state_root, proof),
+				Error::<TestRuntime>::UnknownRelayChainBlock
+			);
+		});
+	}
+
+	#[test]
+	fn fails_on_invalid_storage_proof() {
+		let (_state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		run_test(|| {
+			// start with relay block #0
+			initialize(Default::default());
+
+			// try to import head#5 of parachain#1 at relay chain block #0
+			assert_noop!(
+				import_parachain_1_head(0, Default::default(), proof),
+				pallet_bridge_grandpa::Error::<TestRuntime, BridgesGrandpaPalletInstance>::StorageRootMismatch
+			);
+		});
+	}
+
+	#[test]
+	fn parses_storage_proof_of_parachain_state() {
+		type ParachainHeader = RelayBlockHeader;
+
+		let (parachain_state_root, parachain_storage_proof) = bp_runtime::craft_valid_storage_proof();
+		let parachain_head = ParaHead(test_relay_header(42, parachain_state_root).encode());
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, parachain_head.clone())]);
+		run_test(|| {
+			initialize(state_root);
+			assert_ok!(import_parachain_1_head(0, state_root, proof));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof::<ParachainHeader, _>(
+					ParaId(1),
+					parachain_head.hash(),
+					parachain_storage_proof.clone(),
+					|storage| storage.read_value(b"key1").ok().flatten(),
+				),
+				Ok(Some(b"value1".to_vec())),
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof::<ParachainHeader, _>(
+					ParaId(2),
+					parachain_head.hash(),
+					parachain_storage_proof,
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownParaHead,
+			);
+		});
+	}
+}
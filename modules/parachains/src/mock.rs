@@ -0,0 +1,151 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use bp_polkadot_core::parachains::ParaId;
+use bp_runtime::Chain;
+use frame_support::{construct_runtime, parameter_types, traits::Filter, weights::Weight};
+use sp_runtime::{
+	testing::{Header, H256},
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
+	AnySignature, Perbill,
+};
+
+use crate as pallet_bridge_parachains;
+
+pub type AccountId = u64;
+pub type RelayBlockHeader = sp_runtime::generic::Header<u32, BlakeTwo256>;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+pub const PARAS_PALLET_NAME: &str = "Paras";
+pub const UNTRACKED_PARACHAIN_ID: u32 = 10;
+
+construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Grandpa: pallet_bridge_grandpa::{Pallet, Event<T>},
+		Parachains: pallet_bridge_parachains::{Pallet, Call, Event<T>},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type DbWeight = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const MaxRequests: u32 = 32;
+	pub const HeadersToKeep: u32 = 5;
+}
+
+impl pallet_bridge_grandpa::Config for TestRuntime {
+	type Event = Event;
+	type BridgedChain = TestBridgedChain;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+	type MaxHeaderAge = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ParasPalletName: &'static str = PARAS_PALLET_NAME;
+	pub const HeadsToKeep: u32 = 4;
+}
+
+/// All parachains, except the `UNTRACKED_PARACHAIN_ID` are tracked by the pallet.
+pub struct TrackedParachains;
+
+impl Filter<ParaId> for TrackedParachains {
+	fn filter(parachain: &ParaId) -> bool {
+		parachain.0 != UNTRACKED_PARACHAIN_ID
+	}
+}
+
+impl pallet_bridge_parachains::Config for TestRuntime {
+	type Event = Event;
+	type BridgesGrandpaPalletInstance = ();
+	type ParasPalletName = ParasPalletName;
+	type TrackedParachains = TrackedParachains;
+	type HeadsToKeep = HeadsToKeep;
+}
+
+#[derive(Debug)]
+pub struct TestBridgedChain;
+
+impl Chain for TestBridgedChain {
+	type BlockNumber = u32;
+	type Hash = H256;
+	type Hasher = BlakeTwo256;
+	type Header = RelayBlockHeader;
+
+	type AccountId = AccountId;
+	type Balance = u64;
+	type Index = u64;
+	type Signature = AnySignature;
+}
+
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		System::reset_events();
+		test()
+	})
+}
+
+pub fn test_relay_header(
+	num: crate::RelayBlockNumber<TestRuntime, ()>,
+	state_root: crate::RelayBlockHash<TestRuntime, ()>,
+) -> RelayBlockHeader {
+	let mut header: RelayBlockHeader = bp_test_utils::test_header(num);
+	header.set_state_root(state_root);
+	header
+}
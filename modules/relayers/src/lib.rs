@@ -0,0 +1,472 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that is used to store relayer rewards.
+//!
+//! Instead of paying every relayer reward as soon as messages delivery is confirmed, rewards
+//! are accumulated in the `RelayerRewards` storage map. Relayer may claim its rewards (at any
+//! time) using the `claim_rewards` call. The `MessageDeliveryAndDispatchPaymentAdapter` is the
+//! `MessageDeliveryAndDispatchPayment` implementation that is registering rewards here.
+//!
+//! The pallet is also a registry of relayers that are delivering messages to this chain. Relayer
+//! registers at the lane using the `register` call, which reserves `RelayerStake` at its account.
+//! Registered relayers are not paying for delivery transactions that bring new messages. But if
+//! registered relayer submits invalid messages proof, the `RelayerSlashAmount` is slashed from its
+//! stakes (moved to the `RelayersFundAccount`). The relayer is deregistered from lanes where its
+//! stake is fully slashed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_messages::{target_chain::RelayersRegistry, LaneId};
+use frame_support::traits::{BalanceStatus, Currency, ExistenceRequirement, ReservableCurrency};
+use num_traits::Zero;
+use sp_runtime::traits::Saturating;
+use sp_std::prelude::*;
+
+pub use payment_adapter::MessageDeliveryAndDispatchPaymentAdapter;
+
+mod payment_adapter;
+
+#[cfg(test)]
+mod mock;
+
+/// The target that will be used when publishing logs related to this pallet.
+const LOG_TARGET: &str = "runtime::bridge-relayers";
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Currency that is used to pay relayers rewards and to reserve relayers stakes.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Account that holds all unclaimed relayers rewards.
+		///
+		/// Normally it is the relayers fund account of the messages pallet.
+		type RelayersFundAccount: Get<Self::AccountId>;
+		/// Stake that is reserved at the relayer account when it registers at the lane.
+		type RelayerStake: Get<BalanceOf<Self>>;
+		/// Amount that is slashed from relayer stakes when it submits invalid messages proof.
+		type RelayerSlashAmount: Get<BalanceOf<Self>>;
+	}
+
+	/// Relayers reward balance.
+	pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Claim accumulated rewards for delivering messages over given lane.
+		///
+		/// The reward is transferred from the `RelayersFundAccount` to the relayer account.
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+		pub fn claim_rewards(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+
+			let reward = RelayerRewards::<T>::get(&relayer, &lane_id).ok_or(Error::<T>::NoRewardForRelayer)?;
+			T::Currency::transfer(
+				&T::RelayersFundAccount::get(),
+				&relayer,
+				reward,
+				// the relayers fund account must stay above ED (needs to be pre-funded)
+				ExistenceRequirement::KeepAlive,
+			)
+			.map_err(|err| {
+				log::trace!(
+					target: LOG_TARGET,
+					"Failed to pay relayer {:?} reward {:?} for lane {:?}: {:?}",
+					relayer,
+					reward,
+					lane_id,
+					err,
+				);
+
+				Error::<T>::FailedToPayReward
+			})?;
+			RelayerRewards::<T>::remove(&relayer, &lane_id);
+
+			Self::deposit_event(Event::RewardsClaimed(relayer, lane_id, reward));
+			Ok(())
+		}
+
+		/// Register as a relayer that is delivering messages over given lane.
+		///
+		/// The `RelayerStake` is reserved at the relayer account until it deregisters.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn register(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			ensure!(
+				!RegisteredRelayers::<T>::contains_key(&relayer, &lane_id),
+				Error::<T>::AlreadyRegistered,
+			);
+
+			let stake = T::RelayerStake::get();
+			T::Currency::reserve(&relayer, stake).map_err(|err| {
+				log::trace!(
+					target: LOG_TARGET,
+					"Failed to reserve relayer {:?} stake {:?} for lane {:?}: {:?}",
+					relayer,
+					stake,
+					lane_id,
+					err,
+				);
+
+				Error::<T>::FailedToReserveStake
+			})?;
+			RegisteredRelayers::<T>::insert(&relayer, &lane_id, stake);
+
+			Self::deposit_event(Event::RelayerRegistered(relayer, lane_id, stake));
+			Ok(())
+		}
+
+		/// Stop being a relayer that is delivering messages over given lane.
+		///
+		/// The stake that has been reserved during registration is unreserved.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn deregister(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+
+			let stake = RegisteredRelayers::<T>::take(&relayer, &lane_id).ok_or(Error::<T>::NotRegistered)?;
+			T::Currency::unreserve(&relayer, stake);
+
+			Self::deposit_event(Event::RelayerDeregistered(relayer, lane_id));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Get rewards that have been earned by the relayer at given lane and are not yet claimed.
+		pub fn unclaimed_rewards(relayer: T::AccountId, lane_id: LaneId) -> BalanceOf<T> {
+			RelayerRewards::<T>::get(&relayer, &lane_id).unwrap_or_else(Zero::zero)
+		}
+
+		/// Register reward for the relayer that has delivered messages over given lane.
+		pub fn register_relayer_reward(lane_id: LaneId, relayer: &T::AccountId, reward: BalanceOf<T>) {
+			if reward.is_zero() {
+				return;
+			}
+
+			RelayerRewards::<T>::mutate(relayer, lane_id, |relayer_reward| {
+				let new_reward = relayer_reward.unwrap_or_else(Zero::zero).saturating_add(reward);
+				*relayer_reward = Some(new_reward);
+
+				log::trace!(
+					target: LOG_TARGET,
+					"Relayer {:?} can now claim reward {:?} for lane {:?}",
+					relayer,
+					new_reward,
+					lane_id,
+				);
+			});
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// Relayer has claimed its rewards for delivering messages over the lane.
+		RewardsClaimed(T::AccountId, LaneId, BalanceOf<T>),
+		/// Relayer has registered at the lane and its stake has been reserved.
+		RelayerRegistered(T::AccountId, LaneId, BalanceOf<T>),
+		/// Relayer has deregistered from the lane and its stake has been unreserved.
+		RelayerDeregistered(T::AccountId, LaneId),
+		/// Relayer stake at the lane has been slashed for submitting invalid messages proof.
+		RelayerSlashed(T::AccountId, LaneId, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// No reward can be claimed by given relayer.
+		NoRewardForRelayer,
+		/// Reward payment procedure has failed.
+		FailedToPayReward,
+		/// Relayer is already registered at the lane.
+		AlreadyRegistered,
+		/// Relayer is not registered at the lane.
+		NotRegistered,
+		/// Failed to reserve relayer stake.
+		FailedToReserveStake,
+	}
+
+	/// Map of (relayer, lane) => unclaimed relayer reward.
+	#[pallet::storage]
+	pub type RelayerRewards<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, LaneId, BalanceOf<T>, OptionQuery>;
+
+	/// Map of (relayer, lane) => reserved stake of registered relayer.
+	#[pallet::storage]
+	pub type RegisteredRelayers<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, LaneId, BalanceOf<T>, OptionQuery>;
+}
+
+impl<T: Config> RelayersRegistry<T::AccountId> for Pallet<T> {
+	fn is_registered(relayer: &T::AccountId, lane: &LaneId) -> bool {
+		RegisteredRelayers::<T>::contains_key(relayer, lane)
+	}
+
+	fn on_invalid_messages_proof(relayer: &T::AccountId) {
+		let relayers_fund_account = T::RelayersFundAccount::get();
+		let mut to_slash = T::RelayerSlashAmount::get();
+		// we don't know the lane of the invalid proof, so the amount is slashed from relayer stakes
+		// at all lanes, in order
+		let stakes = RegisteredRelayers::<T>::iter_prefix(relayer).collect::<Vec<_>>();
+		for (lane_id, stake) in stakes {
+			if to_slash.is_zero() {
+				break;
+			}
+
+			let lane_slash = sp_std::cmp::min(to_slash, stake);
+			let not_slashed =
+				T::Currency::repatriate_reserved(relayer, &relayers_fund_account, lane_slash, BalanceStatus::Free)
+					.unwrap_or_else(|err| {
+						log::trace!(
+							target: LOG_TARGET,
+							"Failed to move slashed relayer {:?} stake {:?} to the relayers fund: {:?}. Burning it",
+							relayer,
+							lane_slash,
+							err,
+						);
+
+						T::Currency::slash_reserved(relayer, lane_slash).1
+					});
+			let slashed = lane_slash.saturating_sub(not_slashed);
+			to_slash = to_slash.saturating_sub(lane_slash);
+
+			let remaining_stake = stake.saturating_sub(lane_slash);
+			if remaining_stake.is_zero() {
+				RegisteredRelayers::<T>::remove(relayer, &lane_id);
+			} else {
+				RegisteredRelayers::<T>::insert(relayer, &lane_id, remaining_stake);
+			}
+
+			log::trace!(
+				target: LOG_TARGET,
+				"Relayer {:?} stake {:?} at lane {:?} has been slashed for submitting invalid messages proof. \
+				Remaining stake: {:?}",
+				relayer,
+				slashed,
+				lane_id,
+				remaining_stake,
+			);
+
+			Self::deposit_event(Event::RelayerSlashed(relayer.clone(), lane_id, slashed));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, Balances, Event as TestEvent, Origin, Relayers, TestRuntime, REGULAR_RELAYER, RELAYERS_FUND_ACCOUNT,
+		RELAYER_SLASH_AMOUNT, RELAYER_STAKE, STAKED_RELAYER, TEST_LANE_ID, TEST_LANE_ID_2,
+	};
+	use frame_support::{assert_noop, assert_ok};
+	use frame_system::{EventRecord, Pallet as System, Phase};
+	use sp_runtime::DispatchError;
+
+	fn get_ready_for_events() {
+		System::<TestRuntime>::set_block_number(1);
+		System::<TestRuntime>::reset_events();
+	}
+
+	#[test]
+	fn root_cant_claim_anything() {
+		run_test(|| {
+			assert_noop!(
+				Relayers::claim_rewards(Origin::root(), TEST_LANE_ID),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_claim_if_no_reward_exists() {
+		run_test(|| {
+			assert_noop!(
+				Relayers::claim_rewards(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::NoRewardForRelayer,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_claim_if_payment_procedure_fails() {
+		run_test(|| {
+			let fund_balance = Balances::free_balance(&RELAYERS_FUND_ACCOUNT);
+			Relayers::register_relayer_reward(TEST_LANE_ID, &REGULAR_RELAYER, fund_balance);
+			assert_noop!(
+				Relayers::claim_rewards(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::FailedToPayReward,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_can_claim_reward() {
+		run_test(|| {
+			get_ready_for_events();
+
+			Relayers::register_relayer_reward(TEST_LANE_ID, &REGULAR_RELAYER, 50);
+			Relayers::register_relayer_reward(TEST_LANE_ID, &REGULAR_RELAYER, 50);
+			assert_eq!(Relayers::unclaimed_rewards(REGULAR_RELAYER, TEST_LANE_ID), 100);
+
+			assert_ok!(Relayers::claim_rewards(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID));
+			assert_eq!(Relayers::unclaimed_rewards(REGULAR_RELAYER, TEST_LANE_ID), 0);
+			assert_eq!(Balances::free_balance(&REGULAR_RELAYER), 100);
+
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(Event::RewardsClaimed(REGULAR_RELAYER, TEST_LANE_ID, 100)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_register_without_funds() {
+		run_test(|| {
+			assert_noop!(
+				Relayers::register(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::FailedToReserveStake,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_register_twice() {
+		run_test(|| {
+			assert_ok!(Relayers::register(Origin::signed(STAKED_RELAYER), TEST_LANE_ID));
+			assert_noop!(
+				Relayers::register(Origin::signed(STAKED_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::AlreadyRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_can_register_and_deregister() {
+		run_test(|| {
+			assert_ok!(Relayers::register(Origin::signed(STAKED_RELAYER), TEST_LANE_ID));
+			assert!(Relayers::is_registered(&STAKED_RELAYER, &TEST_LANE_ID));
+			assert!(!Relayers::is_registered(&STAKED_RELAYER, &TEST_LANE_ID_2));
+			assert_eq!(Balances::reserved_balance(&STAKED_RELAYER), RELAYER_STAKE);
+
+			assert_ok!(Relayers::deregister(Origin::signed(STAKED_RELAYER), TEST_LANE_ID));
+			assert!(!Relayers::is_registered(&STAKED_RELAYER, &TEST_LANE_ID));
+			assert_eq!(Balances::reserved_balance(&STAKED_RELAYER), 0);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_deregister_if_not_registered() {
+		run_test(|| {
+			assert_noop!(
+				Relayers::deregister(Origin::signed(STAKED_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::NotRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_stake_is_partially_slashed_for_invalid_messages_proof() {
+		run_test(|| {
+			get_ready_for_events();
+
+			assert_ok!(Relayers::register(Origin::signed(STAKED_RELAYER), TEST_LANE_ID));
+			let fund_balance = Balances::free_balance(&RELAYERS_FUND_ACCOUNT);
+
+			Relayers::on_invalid_messages_proof(&STAKED_RELAYER);
+
+			assert!(Relayers::is_registered(&STAKED_RELAYER, &TEST_LANE_ID));
+			assert_eq!(
+				RegisteredRelayers::<TestRuntime>::get(&STAKED_RELAYER, &TEST_LANE_ID),
+				Some(RELAYER_STAKE - RELAYER_SLASH_AMOUNT),
+			);
+			assert_eq!(
+				Balances::reserved_balance(&STAKED_RELAYER),
+				RELAYER_STAKE - RELAYER_SLASH_AMOUNT
+			);
+			assert_eq!(
+				Balances::free_balance(&RELAYERS_FUND_ACCOUNT),
+				fund_balance + RELAYER_SLASH_AMOUNT
+			);
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(Event::RelayerSlashed(
+						STAKED_RELAYER,
+						TEST_LANE_ID,
+						RELAYER_SLASH_AMOUNT
+					)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_is_deregistered_when_its_stake_is_fully_slashed() {
+		run_test(|| {
+			assert_ok!(Relayers::register(Origin::signed(STAKED_RELAYER), TEST_LANE_ID));
+			assert_ok!(Relayers::register(Origin::signed(STAKED_RELAYER), TEST_LANE_ID_2));
+			let fund_balance = Balances::free_balance(&RELAYERS_FUND_ACCOUNT);
+
+			// every invalid proof costs the same amount, no matter how many lanes relayer is registered at
+			let invalid_proofs = 2 * RELAYER_STAKE / RELAYER_SLASH_AMOUNT;
+			for _ in 0..invalid_proofs {
+				Relayers::on_invalid_messages_proof(&STAKED_RELAYER);
+			}
+
+			assert!(!Relayers::is_registered(&STAKED_RELAYER, &TEST_LANE_ID));
+			assert!(!Relayers::is_registered(&STAKED_RELAYER, &TEST_LANE_ID_2));
+			assert_eq!(Balances::reserved_balance(&STAKED_RELAYER), 0);
+			assert_eq!(Balances::free_balance(&STAKED_RELAYER), 1_000 - 2 * RELAYER_STAKE);
+			assert_eq!(
+				Balances::free_balance(&RELAYERS_FUND_ACCOUNT),
+				fund_balance + 2 * RELAYER_STAKE
+			);
+		});
+	}
+
+	#[test]
+	fn unregistered_relayer_is_not_slashed_for_invalid_messages_proof() {
+		run_test(|| {
+			Relayers::on_invalid_messages_proof(&STAKED_RELAYER);
+			assert_eq!(Balances::free_balance(&STAKED_RELAYER), 1_000);
+		});
+	}
+}
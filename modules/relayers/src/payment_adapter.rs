@@ -0,0 +1,162 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Code that allows relayers pallet to be used as a delivery+dispatch payment mechanism
+//! for the messages pallet.
+
+use crate::{BalanceOf, Config, Pallet};
+
+use bp_messages::{
+	source_chain::{MessageDeliveryAndDispatchPayment, RelayersRewards, Sender},
+	LaneId, MessageNonce,
+};
+use frame_support::traits::Get;
+use pallet_bridge_messages::instant_payments::{distribute_relayers_rewards, InstantCurrencyPayments};
+use sp_std::marker::PhantomData;
+
+/// Adapter that allows relayers pallet to be used as a delivery+dispatch payment mechanism
+/// for the messages pallet.
+///
+/// Message fees are paid to (and refunded from) the relayers fund account, exactly like the
+/// `InstantCurrencyPayments` does. But relayers rewards are not transferred when delivery is
+/// confirmed. Instead, they're registered in the relayers pallet and may be claimed later.
+pub struct MessageDeliveryAndDispatchPaymentAdapter<T, GetConfirmationFee, RootAccount>(
+	PhantomData<(T, GetConfirmationFee, RootAccount)>,
+);
+
+impl<T, GetConfirmationFee, RootAccount> MessageDeliveryAndDispatchPayment<T::AccountId, BalanceOf<T>>
+	for MessageDeliveryAndDispatchPaymentAdapter<T, GetConfirmationFee, RootAccount>
+where
+	T: Config,
+	BalanceOf<T>: From<MessageNonce>,
+	GetConfirmationFee: Get<BalanceOf<T>>,
+	RootAccount: Get<Option<T::AccountId>>,
+{
+	type Error = &'static str;
+
+	fn pay_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &BalanceOf<T>,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		InstantCurrencyPayments::<T, T::Currency, GetConfirmationFee, RootAccount>::pay_delivery_and_dispatch_fee(
+			submitter,
+			fee,
+			relayer_fund_account,
+		)
+	}
+
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &BalanceOf<T>,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		InstantCurrencyPayments::<T, T::Currency, GetConfirmationFee, RootAccount>::refund_delivery_and_dispatch_fee(
+			submitter,
+			fee,
+			relayer_fund_account,
+		)
+	}
+
+	fn pay_relayers_rewards(
+		lane_id: LaneId,
+		confirmation_relayer: &T::AccountId,
+		relayers_rewards: RelayersRewards<T::AccountId, BalanceOf<T>>,
+		_relayer_fund_account: &T::AccountId,
+	) {
+		distribute_relayers_rewards(
+			confirmation_relayer,
+			relayers_rewards,
+			GetConfirmationFee::get(),
+			|relayer, reward| Pallet::<T>::register_relayer_reward(lane_id, relayer, reward),
+		);
+	}
+
+	fn relayer_fund_balance(relayer_fund_account: &T::AccountId) -> BalanceOf<T> {
+		InstantCurrencyPayments::<T, T::Currency, GetConfirmationFee, RootAccount>::relayer_fund_balance(
+			relayer_fund_account,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, AccountId, Balance, Relayers, TestRuntime, RELAYERS_FUND_ACCOUNT, TEST_LANE_ID};
+	use bp_messages::source_chain::RelayerRewards;
+
+	const RELAYER_1: AccountId = 1;
+	const RELAYER_2: AccountId = 2;
+	const RELAYER_3: AccountId = 3;
+
+	frame_support::parameter_types! {
+		pub const ConfirmationFee: Balance = 10;
+	}
+
+	type PaymentAdapter = MessageDeliveryAndDispatchPaymentAdapter<TestRuntime, ConfirmationFee, ()>;
+
+	fn relayers_rewards() -> RelayersRewards<AccountId, Balance> {
+		vec![
+			(
+				RELAYER_1,
+				RelayerRewards {
+					reward: 100,
+					messages: 2,
+				},
+			),
+			(
+				RELAYER_2,
+				RelayerRewards {
+					reward: 100,
+					messages: 3,
+				},
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+
+	#[test]
+	fn confirmation_relayer_is_rewarded_if_it_has_also_delivered_messages() {
+		run_test(|| {
+			PaymentAdapter::pay_relayers_rewards(TEST_LANE_ID, &RELAYER_2, relayers_rewards(), &RELAYERS_FUND_ACCOUNT);
+
+			assert_eq!(Relayers::unclaimed_rewards(RELAYER_1, TEST_LANE_ID), 80);
+			assert_eq!(Relayers::unclaimed_rewards(RELAYER_2, TEST_LANE_ID), 120);
+		});
+	}
+
+	#[test]
+	fn confirmation_relayer_is_rewarded_if_it_has_not_delivered_any_delivered_messages() {
+		run_test(|| {
+			PaymentAdapter::pay_relayers_rewards(TEST_LANE_ID, &RELAYER_3, relayers_rewards(), &RELAYERS_FUND_ACCOUNT);
+
+			assert_eq!(Relayers::unclaimed_rewards(RELAYER_1, TEST_LANE_ID), 80);
+			assert_eq!(Relayers::unclaimed_rewards(RELAYER_2, TEST_LANE_ID), 70);
+			assert_eq!(Relayers::unclaimed_rewards(RELAYER_3, TEST_LANE_ID), 50);
+		});
+	}
+
+	#[test]
+	fn rewards_are_registered_per_lane() {
+		run_test(|| {
+			PaymentAdapter::pay_relayers_rewards(TEST_LANE_ID, &RELAYER_1, relayers_rewards(), &RELAYERS_FUND_ACCOUNT);
+
+			assert_eq!(Relayers::unclaimed_rewards(RELAYER_1, TEST_LANE_ID), 200);
+			assert_eq!(Relayers::unclaimed_rewards(RELAYER_1, [0, 0, 0, 0]), 0);
+		});
+	}
+}
@@ -52,8 +52,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use bp_messages::{
-	source_chain::{MessagesBridge, OnDeliveryConfirmed},
-	DeliveredMessages, LaneId, MessageNonce,
+	source_chain::{MessageOwner, MessageOwnerId, MessagesBridge},
+	LaneId, MessageNonce,
 };
 use bp_runtime::{messages::DispatchFeePayment, ChainId};
 use bp_token_swap::{TokenSwap, TokenSwapType};
@@ -92,7 +92,7 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
 	use frame_system::pallet_prelude::*;
 
 	#[pallet::config]
@@ -104,13 +104,11 @@ pub mod pallet {
 		type BridgedChainId: Get<ChainId>;
 		/// The identifier of outbound message lane on This chain used to send token transfer
 		/// messages to the Bridged chain.
-		///
-		/// It is highly recommended to use dedicated lane for every instance of token swap
-		/// pallet. Messages delivery confirmation callback is implemented in the way that
-		/// for every confirmed message, there is (at least) a storage read. Which mean,
-		/// that if pallet will see unrelated confirmations, it'll just burn storage-read
-		/// weight, achieving nothing.
 		type OutboundMessageLaneId: Get<LaneId>;
+		/// The identifier of this pallet instance as an owner of token transfer messages.
+		///
+		/// Must be unique among all owners of messages, sent using `MessagesBridge`.
+		type OutboundMessagesOwnerId: Get<MessageOwnerId>;
 		/// Messages bridge with Bridged chain.
 		type MessagesBridge: MessagesBridge<
 			Self::AccountId,
@@ -162,8 +160,12 @@ pub mod pallet {
 		BridgedAccountIdOf<T, I>,
 	>;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
 	#[pallet::hooks]
@@ -272,7 +274,8 @@ pub mod pallet {
 				// the transfer message is sent over the bridge. The message is supposed to be a
 				// `Currency::transfer` call on the bridged chain, but no checks are made - it is
 				// the transaction submitter to ensure it is valid.
-				let send_message_result = T::MessagesBridge::send_message(
+				let send_message_result = T::MessagesBridge::send_owned_message(
+					T::OutboundMessagesOwnerId::get(),
 					bp_messages::source_chain::Sender::from(Some(swap_account.clone())),
 					T::OutboundMessageLaneId::get(),
 					bp_message_dispatch::MessagePayload {
@@ -459,34 +462,41 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type PendingMessages<T: Config<I>, I: 'static = ()> = StorageMap<_, Identity, MessageNonce, H256>;
 
-	impl<T: Config<I>, I: 'static> OnDeliveryConfirmed for Pallet<T, I> {
-		fn on_messages_delivered(lane: &LaneId, delivered_messages: &DeliveredMessages) -> Weight {
-			// we're only interested in our lane messages
-			if *lane != T::OutboundMessageLaneId::get() {
-				return 0;
-			}
+	impl<T: Config<I>, I: 'static> MessageOwner for Pallet<T, I> {
+		fn owner_id() -> MessageOwnerId {
+			T::OutboundMessagesOwnerId::get()
+		}
 
-			// so now we're dealing with our lane messages. Ideally we'll have dedicated lane
-			// and every message from `delivered_messages` is actually our transfer message.
-			// But it may be some shared lane (which is not recommended).
-			let mut reads = 0;
-			let mut writes = 0;
-			for message_nonce in delivered_messages.begin..=delivered_messages.end {
-				reads += 1;
-				if let Some(swap_hash) = PendingMessages::<T, I>::take(message_nonce) {
-					writes += 1;
-					PendingSwaps::<T, I>::insert(
-						swap_hash,
-						if delivered_messages.message_dispatch_result(message_nonce) {
-							TokenSwapState::Confirmed
-						} else {
-							TokenSwapState::Failed
-						},
-					);
-				}
-			}
+		fn on_message_dispatched(_lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) -> Weight {
+			let swap_hash = match PendingMessages::<T, I>::take(nonce) {
+				Some(swap_hash) => swap_hash,
+				None => return <T as frame_system::Config>::DbWeight::get().reads(1),
+			};
+
+			PendingSwaps::<T, I>::insert(
+				swap_hash,
+				if dispatch_result {
+					TokenSwapState::Confirmed
+				} else {
+					TokenSwapState::Failed
+				},
+			);
+
+			<T as frame_system::Config>::DbWeight::get().reads_writes(1, 1)
+		}
+	}
 
-			<T as frame_system::Config>::DbWeight::get().reads_writes(reads, writes)
+	/// Nonces of all pending transfer messages.
+	///
+	/// Transfer messages that have been sent before messages owners have been introduced have
+	/// no registered owner at the messages pallet. This may be used to register this pallet as
+	/// the owner of such messages during runtime upgrade, so that their dispatch results are
+	/// still passed to this pallet.
+	pub struct PendingMessagesNonces<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> Get<Vec<MessageNonce>> for PendingMessagesNonces<T, I> {
+		fn get() -> Vec<MessageNonce> {
+			PendingMessages::<T, I>::iter_keys().collect()
 		}
 	}
 
@@ -598,10 +608,7 @@ mod tests {
 	}
 
 	fn receive_test_swap_confirmation(success: bool) {
-		Pallet::<TestRuntime, ()>::on_messages_delivered(
-			&OutboundMessageLaneId::get(),
-			&DeliveredMessages::new(MESSAGE_NONCE, success),
-		);
+		Pallet::<TestRuntime, ()>::on_message_dispatched(&OutboundMessageLaneId::get(), MESSAGE_NONCE, success);
 	}
 
 	#[test]
@@ -1029,10 +1036,8 @@ mod tests {
 				Some(TokenSwapState::Started)
 			);
 
-			// when unrelated messages are delivered
-			let mut messages = DeliveredMessages::new(MESSAGE_NONCE - 2, true);
-			messages.note_dispatched_message(false);
-			Pallet::<TestRuntime, ()>::on_messages_delivered(&OutboundMessageLaneId::get(), &messages);
+			// when unrelated message is dispatched
+			Pallet::<TestRuntime, ()>::on_message_dispatched(&OutboundMessageLaneId::get(), MESSAGE_NONCE - 1, true);
 			assert_eq!(
 				PendingMessages::<TestRuntime, ()>::get(MESSAGE_NONCE),
 				Some(test_swap_hash())
@@ -1042,11 +1047,8 @@ mod tests {
 				Some(TokenSwapState::Started)
 			);
 
-			// when message we're interested in is accompanied by a bunch of other messages
-			let mut messages = DeliveredMessages::new(MESSAGE_NONCE - 1, false);
-			messages.note_dispatched_message(true);
-			messages.note_dispatched_message(false);
-			Pallet::<TestRuntime, ()>::on_messages_delivered(&OutboundMessageLaneId::get(), &messages);
+			// when message we're interested in is dispatched
+			Pallet::<TestRuntime, ()>::on_message_dispatched(&OutboundMessageLaneId::get(), MESSAGE_NONCE, true);
 			assert_eq!(PendingMessages::<TestRuntime, ()>::get(MESSAGE_NONCE), None);
 			assert_eq!(
 				PendingSwaps::<TestRuntime, ()>::get(test_swap_hash()),
@@ -17,7 +17,10 @@
 use crate as pallet_bridge_token_swap;
 use crate::MessagePayloadOf;
 
-use bp_messages::{source_chain::MessagesBridge, LaneId, MessageNonce};
+use bp_messages::{
+	source_chain::{MessageOwnerId, MessagesBridge},
+	LaneId, MessageNonce,
+};
 use bp_runtime::ChainId;
 use frame_support::weights::Weight;
 use sp_core::H256;
@@ -26,6 +29,7 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
 	Perbill,
 };
+use std::ops::RangeInclusive;
 
 pub type AccountId = u64;
 pub type Balance = u64;
@@ -110,6 +114,7 @@ impl pallet_balances::Config for TestRuntime {
 frame_support::parameter_types! {
 	pub const BridgedChainId: ChainId = *b"inst";
 	pub const OutboundMessageLaneId: LaneId = *b"lane";
+	pub const OutboundMessagesOwnerId: MessageOwnerId = *b"swap";
 }
 
 impl pallet_bridge_token_swap::Config for TestRuntime {
@@ -117,6 +122,7 @@ impl pallet_bridge_token_swap::Config for TestRuntime {
 
 	type BridgedChainId = BridgedChainId;
 	type OutboundMessageLaneId = OutboundMessageLaneId;
+	type OutboundMessagesOwnerId = OutboundMessagesOwnerId;
 	type MessagesBridge = TestMessagesBridge;
 
 	type ThisCurrency = pallet_balances::Pallet<TestRuntime>;
@@ -146,11 +152,30 @@ impl MessagesBridge<AccountId, Balance, MessagePayloadOf<TestRuntime, ()>> for T
 	type Error = ();
 
 	fn send_message(
+		_sender: frame_system::RawOrigin<AccountId>,
+		_lane: LaneId,
+		_message: MessagePayloadOf<TestRuntime, ()>,
+		_delivery_and_dispatch_fee: Balance,
+	) -> Result<MessageNonce, Self::Error> {
+		unreachable!("token swap pallet always sends owned messages")
+	}
+
+	fn send_messages(
+		_sender: frame_system::RawOrigin<AccountId>,
+		_lane: LaneId,
+		_messages: Vec<(MessagePayloadOf<TestRuntime, ()>, Balance)>,
+	) -> Result<RangeInclusive<MessageNonce>, Self::Error> {
+		unreachable!("token swap pallet never sends messages in batches")
+	}
+
+	fn send_owned_message(
+		owner: MessageOwnerId,
 		sender: frame_system::RawOrigin<AccountId>,
 		lane: LaneId,
 		message: MessagePayloadOf<TestRuntime, ()>,
 		delivery_and_dispatch_fee: Balance,
 	) -> Result<MessageNonce, Self::Error> {
+		assert_eq!(owner, OutboundMessagesOwnerId::get());
 		assert_ne!(sender, frame_system::RawOrigin::Signed(THIS_CHAIN_ACCOUNT));
 		assert_eq!(lane, OutboundMessageLaneId::get());
 		assert_eq!(delivery_and_dispatch_fee, SWAP_DELIVERY_AND_DISPATCH_FEE);
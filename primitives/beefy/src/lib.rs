@@ -0,0 +1,394 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives of the BEEFY-based bridge light client.
+//!
+//! The BEEFY validators are signing commitments, which are including root of the Merkle Mountain
+//! Range (MMR) of the bridged chain. Every leaf of this MMR contains the hash of some header of
+//! the bridged chain and the (Merkle root of the) next BEEFY validator set. So with a single
+//! signed commitment and the MMR leaf proof, we may prove finality of any header that has been
+//! added to the MMR before the commitment block.
+//!
+//! All types here are SCALE-compatible with the types, used by the BEEFY and MMR pallets of the
+//! Polkadot-like chains.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use sp_core::{ecdsa, H256};
+use sp_runtime::traits::Hash as HashT;
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Identifier of the BEEFY validator set.
+pub type ValidatorSetId = u64;
+/// Identifier of the BEEFY validator (authority).
+pub type BeefyAuthorityId = ecdsa::Public;
+/// Signature of the BEEFY validator.
+pub type BeefySignature = ecdsa::Signature;
+/// Hash of the MMR root, that is signed by BEEFY validators.
+pub type MmrRootHash = H256;
+
+/// A commitment that is signed by the BEEFY validators.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct Commitment<BlockNumber> {
+	/// Root of the MMR at the `block_number`.
+	pub payload: MmrRootHash,
+	/// Number of the block where the MMR root has been read from.
+	pub block_number: BlockNumber,
+	/// Identifier of the validator set that is expected to sign this commitment.
+	pub validator_set_id: ValidatorSetId,
+}
+
+/// A commitment with signatures of the BEEFY validators.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct SignedCommitment<BlockNumber> {
+	/// The commitment itself.
+	pub commitment: Commitment<BlockNumber>,
+	/// Signatures of the validators, in the same order as validators in the validator set.
+	///
+	/// Validators that haven't signed the commitment have `None` here.
+	pub signatures: Vec<Option<BeefySignature>>,
+}
+
+/// The BEEFY validator set.
+#[derive(Clone, Decode, Default, Encode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BeefyValidatorSet {
+	/// Identifier of the validator set.
+	pub id: ValidatorSetId,
+	/// Public keys of the validators.
+	pub validators: Vec<BeefyAuthorityId>,
+}
+
+/// Compact information on the next BEEFY validator set, that is stored in every MMR leaf.
+#[derive(Clone, Decode, Default, Encode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BeefyNextAuthoritySet {
+	/// Identifier of the next validator set.
+	pub id: ValidatorSetId,
+	/// Number of validators in the next validator set.
+	pub len: u32,
+	/// Merkle root of the next validator set.
+	///
+	/// See `validators_merkle_root` for details.
+	pub root: H256,
+}
+
+/// The MMR leaf, that is added to the MMR at every block of the bridged chain.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct BeefyMmrLeaf<BlockNumber, Hash> {
+	/// Version of the leaf format.
+	pub version: u8,
+	/// Number and hash of the parent of the block, where this leaf has been added.
+	pub parent_number_and_hash: (BlockNumber, Hash),
+	/// Information on the next validator set.
+	pub beefy_next_authority_set: BeefyNextAuthoritySet,
+	/// Merkle root of parachain heads, included in the leaf block.
+	pub parachain_heads: H256,
+}
+
+/// Proof of the MMR leaf inclusion.
+///
+/// This is an equivalent of the `pallet_mmr_primitives::Proof`.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct MmrProof<Hash> {
+	/// Index of the leaf the proof is for.
+	pub leaf_index: u64,
+	/// Number of leaves in the MMR, when the proof has been generated.
+	pub leaf_count: u64,
+	/// Proof elements (hashes of siblings of inner nodes on the path to the leaf).
+	pub items: Vec<Hash>,
+}
+
+/// Data required for initializing the BEEFY bridge pallet.
+#[derive(Clone, Decode, Default, Encode, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct InitializationData<Header> {
+	/// The header from which we should start syncing.
+	pub header: Header,
+	/// The current BEEFY validator set.
+	pub authority_set: BeefyValidatorSet,
+	/// The next BEEFY validator set.
+	pub next_authority_set: BeefyNextAuthoritySet,
+	/// Should the pallet block transaction immediately after initialization.
+	pub is_halted: bool,
+}
+
+/// Errors that may happen during BEEFY commitments and MMR proofs verification.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum Error {
+	/// The validator set is empty.
+	EmptyValidatorSet,
+	/// The commitment is signed by unexpected validator set.
+	InvalidValidatorSetId,
+	/// Number of signatures doesn't match number of validators.
+	InvalidSignaturesLength,
+	/// There are not enough correct signatures in the signed commitment.
+	NotEnoughCorrectSignatures,
+	/// The MMR leaf proof is invalid.
+	InvalidMmrProof,
+}
+
+/// Returns number of correct signatures, that are required to accept commitment of the validator
+/// set with given number of validators.
+pub fn signatures_required(validators_len: usize) -> usize {
+	validators_len - validators_len.saturating_sub(1) / 3
+}
+
+/// Verify that the commitment is signed by the supermajority of the given validator set.
+pub fn verify_signed_commitment<BlockNumber: Encode>(
+	signed_commitment: &SignedCommitment<BlockNumber>,
+	validator_set: &BeefyValidatorSet,
+) -> Result<(), Error> {
+	if validator_set.validators.is_empty() {
+		return Err(Error::EmptyValidatorSet);
+	}
+	if signed_commitment.commitment.validator_set_id != validator_set.id {
+		return Err(Error::InvalidValidatorSetId);
+	}
+	if signed_commitment.signatures.len() != validator_set.validators.len() {
+		return Err(Error::InvalidSignaturesLength);
+	}
+
+	// BEEFY validators are signing keccak-256 hash of the encoded commitment
+	let message = sp_io::hashing::keccak_256(&signed_commitment.commitment.encode());
+	let required_signatures = signatures_required(validator_set.validators.len());
+	let mut correct_signatures = 0;
+	for (signature, validator) in signed_commitment.signatures.iter().zip(validator_set.validators.iter()) {
+		let signature = match signature {
+			Some(signature) => signature,
+			None => continue,
+		};
+
+		let is_correct_signature = sp_io::crypto::secp256k1_ecdsa_recover_compressed(&signature.0, &message)
+			.map(|recovered| recovered == validator.0)
+			.unwrap_or(false);
+		if is_correct_signature {
+			correct_signatures += 1;
+			// stop verifying signatures as soon as we have enough of them
+			if correct_signatures >= required_signatures {
+				return Ok(());
+			}
+		}
+	}
+
+	Err(Error::NotEnoughCorrectSignatures)
+}
+
+/// Compute Merkle root of the validator set.
+///
+/// The `leaves` are the validators public keys, converted to the form that is used by the bridged
+/// chain (e.g. Ethereum addresses). The tree is built the same way as in the `beefy-merkle-tree`
+/// crate: leaves are hashed, then pairs of hashes are hashed together until the root is computed.
+/// If there's an odd number of nodes at some level, the last node is promoted to the next level.
+pub fn validators_merkle_root<H: HashT<Output = H256>>(leaves: impl IntoIterator<Item = Vec<u8>>) -> H256 {
+	let mut level = leaves.into_iter().map(|leaf| H::hash(&leaf)).collect::<Vec<_>>();
+	if level.is_empty() {
+		return Default::default();
+	}
+
+	while level.len() > 1 {
+		level = level
+			.chunks(2)
+			.map(|pair| match pair.get(1) {
+				Some(right) => merge_hashes::<H>(&pair[0], right),
+				None => pair[0],
+			})
+			.collect();
+	}
+
+	level[0]
+}
+
+/// Verify that the MMR leaf with given hash is included in the MMR with given root.
+///
+/// The leaf hash is the hash of the SCALE-encoded leaf.
+pub fn verify_mmr_leaf_proof<H: HashT<Output = H256>>(
+	root: MmrRootHash,
+	leaf_hash: H256,
+	proof: MmrProof<H256>,
+) -> Result<(), Error> {
+	if proof.leaf_index >= proof.leaf_count {
+		return Err(Error::InvalidMmrProof);
+	}
+
+	let mmr_size = mmr_lib::leaf_index_to_mmr_size(proof.leaf_count - 1);
+	let leaf_position = mmr_lib::leaf_index_to_pos(proof.leaf_index);
+	let proof = mmr_lib::MerkleProof::<H256, MmrHasher<H>>::new(mmr_size, proof.items);
+	match proof.verify(root, vec![(leaf_position, leaf_hash)]) {
+		Ok(true) => Ok(()),
+		_ => Err(Error::InvalidMmrProof),
+	}
+}
+
+/// MMR nodes merging, compatible with the `pallet-mmr`.
+pub struct MmrHasher<H>(PhantomData<H>);
+
+impl<H: HashT<Output = H256>> mmr_lib::Merge for MmrHasher<H> {
+	type Item = H256;
+
+	fn merge(left: &H256, right: &H256) -> H256 {
+		merge_hashes::<H>(left, right)
+	}
+}
+
+/// Hash concatenation of two hashes.
+fn merge_hashes<H: HashT<Output = H256>>(left: &H256, right: &H256) -> H256 {
+	let mut concat = left.as_bytes().to_vec();
+	concat.extend_from_slice(right.as_bytes());
+	H::hash(&concat)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::Pair;
+	use sp_runtime::traits::Keccak256;
+
+	fn validators(count: u8) -> Vec<ecdsa::Pair> {
+		(0..count).map(|i| ecdsa::Pair::from_seed(&[i + 1; 32])).collect()
+	}
+
+	fn validator_set(validators: &[ecdsa::Pair]) -> BeefyValidatorSet {
+		BeefyValidatorSet {
+			id: 1,
+			validators: validators.iter().map(|pair| pair.public()).collect(),
+		}
+	}
+
+	fn signed_commitment(validators: &[ecdsa::Pair], signed_by: usize) -> SignedCommitment<u32> {
+		let commitment = Commitment {
+			payload: H256::repeat_byte(42),
+			block_number: 100,
+			validator_set_id: 1,
+		};
+		let message = sp_io::hashing::keccak_256(&commitment.encode());
+		let signatures = validators
+			.iter()
+			.enumerate()
+			.map(|(index, pair)| {
+				if index < signed_by {
+					Some(pair.sign_prehashed(&message))
+				} else {
+					None
+				}
+			})
+			.collect();
+		SignedCommitment { commitment, signatures }
+	}
+
+	#[test]
+	fn signatures_required_is_supermajority() {
+		assert_eq!(signatures_required(1), 1);
+		assert_eq!(signatures_required(3), 3);
+		assert_eq!(signatures_required(4), 3);
+		assert_eq!(signatures_required(5), 4);
+		assert_eq!(signatures_required(100), 67);
+	}
+
+	#[test]
+	fn accepts_commitment_signed_by_supermajority() {
+		let validators = validators(5);
+		assert_eq!(
+			verify_signed_commitment(&signed_commitment(&validators, 4), &validator_set(&validators)),
+			Ok(()),
+		);
+	}
+
+	#[test]
+	fn rejects_commitment_signed_by_minority() {
+		let validators = validators(5);
+		assert_eq!(
+			verify_signed_commitment(&signed_commitment(&validators, 3), &validator_set(&validators)),
+			Err(Error::NotEnoughCorrectSignatures),
+		);
+	}
+
+	#[test]
+	fn rejects_commitment_with_invalid_signatures() {
+		let validators = validators(5);
+		let mut commitment = signed_commitment(&validators, 5);
+		commitment.signatures[0] = commitment.signatures[4].clone();
+		commitment.signatures[1] = commitment.signatures[4].clone();
+		assert_eq!(
+			verify_signed_commitment(&commitment, &validator_set(&validators)),
+			Err(Error::NotEnoughCorrectSignatures),
+		);
+	}
+
+	#[test]
+	fn rejects_commitment_of_other_validator_set() {
+		let validators = validators(5);
+		let mut commitment = signed_commitment(&validators, 5);
+		commitment.commitment.validator_set_id = 2;
+		assert_eq!(
+			verify_signed_commitment(&commitment, &validator_set(&validators)),
+			Err(Error::InvalidValidatorSetId),
+		);
+	}
+
+	#[test]
+	fn rejects_commitment_with_wrong_signatures_count() {
+		let validators = validators(5);
+		let mut commitment = signed_commitment(&validators, 5);
+		commitment.signatures.pop();
+		assert_eq!(
+			verify_signed_commitment(&commitment, &validator_set(&validators)),
+			Err(Error::InvalidSignaturesLength),
+		);
+	}
+
+	#[test]
+	fn validators_merkle_root_promotes_odd_nodes() {
+		let leaves = vec![vec![1u8], vec![2u8], vec![3u8]];
+		let hashes = leaves.iter().map(|leaf| Keccak256::hash(leaf)).collect::<Vec<_>>();
+		let expected_root = merge_hashes::<Keccak256>(&merge_hashes::<Keccak256>(&hashes[0], &hashes[1]), &hashes[2]);
+
+		assert_eq!(validators_merkle_root::<Keccak256>(leaves), expected_root);
+		assert_eq!(validators_merkle_root::<Keccak256>(vec![vec![1u8]]), hashes[0]);
+		assert_eq!(validators_merkle_root::<Keccak256>(Vec::new()), H256::default());
+	}
+
+	#[test]
+	fn verifies_mmr_leaf_proof() {
+		let mut mmr = mmr_lib::util::MemMMR::<H256, MmrHasher<Keccak256>>::default();
+		let leaves = (0u8..7).map(|i| Keccak256::hash(&[i])).collect::<Vec<_>>();
+		let positions = leaves.iter().map(|leaf| mmr.push(*leaf).unwrap()).collect::<Vec<_>>();
+		let root = mmr.get_root().unwrap();
+		let proof = MmrProof {
+			leaf_index: 3,
+			leaf_count: leaves.len() as u64,
+			items: mmr.gen_proof(vec![positions[3]]).unwrap().proof_items().to_vec(),
+		};
+
+		assert_eq!(
+			verify_mmr_leaf_proof::<Keccak256>(root, leaves[3], proof.clone()),
+			Ok(())
+		);
+		assert_eq!(
+			verify_mmr_leaf_proof::<Keccak256>(root, leaves[4], proof.clone()),
+			Err(Error::InvalidMmrProof),
+		);
+		assert_eq!(
+			verify_mmr_leaf_proof::<Keccak256>(root, leaves[3], MmrProof { leaf_index: 7, ..proof }),
+			Err(Error::InvalidMmrProof),
+		);
+	}
+}
@@ -22,8 +22,11 @@
 
 mod millau_hash;
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
-use bp_runtime::Chain;
+use bp_messages::{
+	InboundLaneData, LaneId, LaneState, MessageDetails, MessageDetailsV1, MessageDetailsV2, MessageNonce,
+	MessagePayloadDetails, UnrewardedRelayersState,
+};
+use bp_runtime::{messages::AssetId, Chain};
 use frame_support::{
 	weights::{constants::WEIGHT_PER_SECOND, DispatchClass, IdentityFee, Weight},
 	Parameter, RuntimeDebug,
@@ -72,7 +75,7 @@ pub const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 pub const MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE: MessageNonce = 1024;
 
 /// Maximal number of unconfirmed messages at inbound lane.
-pub const MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE: MessageNonce = 1024;
+pub const MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE: MessageNonce = 512;
 
 /// Weight of single regular message delivery transaction on Millau chain.
 ///
@@ -93,7 +96,7 @@ pub const ADDITIONAL_MESSAGE_BYTE_DELIVERY_WEIGHT: Weight = 25_000;
 /// This value is a result of `pallet_bridge_messages::Pallet::receive_messages_delivery_proof` weight formula computation
 /// for the case when single message is confirmed. The result then must be rounded up to account possible future
 /// runtime upgrades.
-pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 2_000_000_000;
+pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 3_000_000_000;
 
 /// Weight of pay-dispatch-fee operation for inbound messages at Millau chain.
 ///
@@ -102,6 +105,26 @@ pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 2_000_000
 /// `DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT` cost. So putting large reserve would make delivery transactions cheaper.
 pub const PAY_INBOUND_DISPATCH_FEE_WEIGHT: Weight = 600_000_000;
 
+/// Maximal size (in bytes) of the decompressed message call at Millau chain.
+///
+/// Messages with compressed calls that are decompressed into larger calls are rejected.
+pub const MAX_DECOMPRESSED_MESSAGE_CALL_SIZE: u32 = 128 * 1024;
+
+/// Weight of decompressing single message call at Millau chain.
+///
+/// This is the upper bound of the weight, required to decompress the call of `MAX_DECOMPRESSED_MESSAGE_CALL_SIZE`
+/// bytes. The value is rounded up to account possible future runtime upgrades.
+pub const MESSAGE_CALL_DECOMPRESSION_WEIGHT: Weight = 100_000_000;
+
+/// Identifier of the Millau asset that represents Rialto tokens, bridged to Millau chain.
+pub const BRIDGED_RIALTO_TOKEN_ASSET_ID: AssetId = 0;
+
+/// Identifiers of Millau assets that may be used to pay dispatch fee of messages, coming from other chains.
+///
+/// The fee in these assets is paid by the dispatch origin account, when message sender has chosen
+/// the `DispatchFeePayment::AtTargetChainInAsset` option.
+pub const DISPATCH_FEE_ASSETS: [AssetId; 1] = [BRIDGED_RIALTO_TOKEN_ASSET_ID];
+
 /// The target length of a session (how often authorities change) on Millau measured in of number of
 /// blocks.
 ///
@@ -271,6 +294,12 @@ pub const TO_MILLAU_MESSAGE_DETAILS_METHOD: &str = "ToMillauOutboundLaneApi_mess
 pub const TO_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str = "ToMillauOutboundLaneApi_latest_received_nonce";
 /// Name of the `ToMillauOutboundLaneApi::latest_generated_nonce` runtime method.
 pub const TO_MILLAU_LATEST_GENERATED_NONCE_METHOD: &str = "ToMillauOutboundLaneApi_latest_generated_nonce";
+/// Name of the `ToMillauOutboundLaneApi` runtime API.
+pub const TO_MILLAU_OUTBOUND_LANE_API: &str = "ToMillauOutboundLaneApi";
+/// Name of the `ToMillauOutboundLaneApi::lanes` runtime method.
+pub const TO_MILLAU_LANES_METHOD: &str = "ToMillauOutboundLaneApi_lanes";
+/// Name of the `ToMillauOutboundLaneApi::message_payloads` runtime method.
+pub const TO_MILLAU_MESSAGE_PAYLOADS_METHOD: &str = "ToMillauOutboundLaneApi_message_payloads";
 
 /// Name of the `FromMillauInboundLaneApi::latest_received_nonce` runtime method.
 pub const FROM_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str = "FromMillauInboundLaneApi_latest_received_nonce";
@@ -278,24 +307,37 @@ pub const FROM_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str = "FromMillauInboundLan
 pub const FROM_MILLAU_LATEST_CONFIRMED_NONCE_METHOD: &str = "FromMillauInboundLaneApi_latest_confirmed_nonce";
 /// Name of the `FromMillauInboundLaneApi::unrewarded_relayers_state` runtime method.
 pub const FROM_MILLAU_UNREWARDED_RELAYERS_STATE: &str = "FromMillauInboundLaneApi_unrewarded_relayers_state";
+/// Name of the `FromMillauInboundLaneApi` runtime API.
+pub const FROM_MILLAU_INBOUND_LANE_API: &str = "FromMillauInboundLaneApi";
+/// Name of the `FromMillauInboundLaneApi::latest_queued_nonce` runtime method.
+pub const FROM_MILLAU_LATEST_QUEUED_NONCE_METHOD: &str = "FromMillauInboundLaneApi_latest_queued_nonce";
+/// Name of the `FromMillauInboundLaneApi::inbound_lane_data` runtime method.
+pub const FROM_MILLAU_INBOUND_LANE_DATA_METHOD: &str = "FromMillauInboundLaneApi_inbound_lane_data";
 
 sp_api::decl_runtime_apis! {
 	/// API for querying information about the finalized Millau headers.
 	///
 	/// This API is implemented by runtimes that are bridging with the Millau chain, not the
 	/// Millau runtime itself.
+	#[api_version(2)]
 	pub trait MillauFinalityApi {
 		/// Returns number and hash of the best finalized header known to the bridge module.
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns the finalized header with given number or, if it isn't known to the bridge
+		/// module, the most recently imported header with lesser number.
+		///
+		/// This method has been added in version 2 of the API.
+		fn finalized_header_at_or_before(number: BlockNumber) -> Option<Header>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Millau chain.
 	///
 	/// This API is implemented by runtimes that are sending messages to Millau chain, not the
 	/// Millau runtime itself.
-	pub trait ToMillauOutboundLaneApi<OutboundMessageFee: Parameter, OutboundPayload: Parameter> {
+	#[api_version(4)]
+	pub trait ToMillauOutboundLaneApi<AccountId: Parameter, OutboundMessageFee: Parameter, OutboundPayload: Parameter> {
 		/// Estimate message delivery and dispatch fee that needs to be paid by the sender on
 		/// this chain.
 		///
@@ -312,23 +354,61 @@ sp_api::decl_runtime_apis! {
 		/// Returns dispatch weight, encoded payload size and delivery+dispatch fee of all
 		/// messages in given inclusive range.
 		///
+		/// This is the version of the method that has been used before message senders have been
+		/// added to the message details.
+		#[changed_in(3)]
+		fn message_details(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessageDetailsV1<OutboundMessageFee>>;
+		/// Returns dispatch weight, encoded payload size, delivery+dispatch fee and sender of all
+		/// messages in given inclusive range.
+		///
+		/// This is the version of the method that has been used before cancellation flags have been
+		/// added to the message details.
+		#[changed_in(4)]
+		fn message_details(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessageDetailsV2<OutboundMessageFee, AccountId>>;
+		/// Returns dispatch weight, encoded payload size, delivery+dispatch fee, sender and
+		/// cancellation flag of all messages in given inclusive range.
+		///
 		/// If some (or all) messages are missing from the storage, they'll also will
 		/// be missing from the resulting vector. The vector is ordered by the nonce.
 		fn message_details(
 			lane: LaneId,
 			begin: MessageNonce,
 			end: MessageNonce,
-		) -> Vec<MessageDetails<OutboundMessageFee>>;
+		) -> Vec<MessageDetails<OutboundMessageFee, AccountId>>;
 		/// Returns nonce of the latest message, received by bridged chain.
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Returns all lanes from the lanes registry with their states.
+		///
+		/// This method has been added in version 2 of the API.
+		fn lanes() -> Vec<(LaneId, LaneState)>;
+		/// Returns encoded payloads and delivery+dispatch fees of all messages in given inclusive range.
+		///
+		/// If some (or all) messages are missing from the storage, they'll also will
+		/// be missing from the resulting vector. The vector is ordered by the nonce. The range
+		/// is clamped to messages that are not yet pruned and the number of returned messages
+		/// is limited by the runtime.
+		fn message_payloads(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessagePayloadDetails<OutboundMessageFee>>;
 	}
 
 	/// Inbound message lane API for messages sent by Millau chain.
 	///
 	/// This API is implemented by runtimes that are receiving messages from Millau chain, not the
 	/// Millau runtime itself.
+	#[api_version(3)]
 	pub trait FromMillauInboundLaneApi {
 		/// Returns nonce of the latest message, received by given lane.
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
@@ -336,6 +416,16 @@ sp_api::decl_runtime_apis! {
 		fn latest_confirmed_nonce(lane: LaneId) -> MessageNonce;
 		/// State of the unrewarded relayers set at given lane.
 		fn unrewarded_relayers_state(lane: LaneId) -> UnrewardedRelayersState;
+		/// Returns nonce of the latest message, queued for dispatch at given lane, or `None` if
+		/// the lane has no queued messages.
+		///
+		/// This method has been added in version 2 of the API.
+		fn latest_queued_nonce(lane: LaneId) -> Option<MessageNonce>;
+		/// Returns inbound lane data, including all unrewarded relayers entries with their
+		/// dispatch results.
+		///
+		/// This method has been added in version 3 of the API.
+		fn inbound_lane_data(lane: LaneId) -> InboundLaneData<AccountId>;
 	}
 }
 
@@ -20,7 +20,9 @@
 // Runtime-generated DecodeLimit::decode_all_with_depth_limit
 #![allow(clippy::unnecessary_mut_passed)]
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{
+	LaneId, LaneState, MessageDetails, MessageDetailsV1, MessageDetailsV2, MessageNonce, UnrewardedRelayersState,
+};
 use frame_support::weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial};
 use sp_std::prelude::*;
 
@@ -74,6 +76,10 @@ pub const TO_POLKADOT_MESSAGE_DETAILS_METHOD: &str = "ToPolkadotOutboundLaneApi_
 pub const TO_POLKADOT_LATEST_GENERATED_NONCE_METHOD: &str = "ToPolkadotOutboundLaneApi_latest_generated_nonce";
 /// Name of the `ToPolkadotOutboundLaneApi::latest_received_nonce` runtime method.
 pub const TO_POLKADOT_LATEST_RECEIVED_NONCE_METHOD: &str = "ToPolkadotOutboundLaneApi_latest_received_nonce";
+/// Name of the `ToPolkadotOutboundLaneApi` runtime API.
+pub const TO_POLKADOT_OUTBOUND_LANE_API: &str = "ToPolkadotOutboundLaneApi";
+/// Name of the `ToPolkadotOutboundLaneApi::lanes` runtime method.
+pub const TO_POLKADOT_LANES_METHOD: &str = "ToPolkadotOutboundLaneApi_lanes";
 
 /// Name of the `FromPolkadotInboundLaneApi::latest_received_nonce` runtime method.
 pub const FROM_POLKADOT_LATEST_RECEIVED_NONCE_METHOD: &str = "FromPolkadotInboundLaneApi_latest_received_nonce";
@@ -81,24 +87,35 @@ pub const FROM_POLKADOT_LATEST_RECEIVED_NONCE_METHOD: &str = "FromPolkadotInboun
 pub const FROM_POLKADOT_LATEST_CONFIRMED_NONCE_METHOD: &str = "FromPolkadotInboundLaneApi_latest_confirmed_nonce";
 /// Name of the `FromPolkadotInboundLaneApi::unrewarded_relayers_state` runtime method.
 pub const FROM_POLKADOT_UNREWARDED_RELAYERS_STATE: &str = "FromPolkadotInboundLaneApi_unrewarded_relayers_state";
+/// Name of the `FromPolkadotInboundLaneApi` runtime API.
+pub const FROM_POLKADOT_INBOUND_LANE_API: &str = "FromPolkadotInboundLaneApi";
+/// Name of the `FromPolkadotInboundLaneApi::latest_queued_nonce` runtime method.
+pub const FROM_POLKADOT_LATEST_QUEUED_NONCE_METHOD: &str = "FromPolkadotInboundLaneApi_latest_queued_nonce";
 
 sp_api::decl_runtime_apis! {
 	/// API for querying information about the finalized Polkadot headers.
 	///
 	/// This API is implemented by runtimes that are bridging with the Polkadot chain, not the
 	/// Polkadot runtime itself.
+	#[api_version(2)]
 	pub trait PolkadotFinalityApi {
 		/// Returns number and hash of the best finalized header known to the bridge module.
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns the finalized header with given number or, if it isn't known to the bridge
+		/// module, the most recently imported header with lesser number.
+		///
+		/// This method has been added in version 2 of the API.
+		fn finalized_header_at_or_before(number: BlockNumber) -> Option<Header>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Polkadot chain.
 	///
 	/// This API is implemented by runtimes that are sending messages to Polkadot chain, not the
 	/// Polkadot runtime itself.
-	pub trait ToPolkadotOutboundLaneApi<OutboundMessageFee: Parameter, OutboundPayload: Parameter> {
+	#[api_version(4)]
+	pub trait ToPolkadotOutboundLaneApi<AccountId: Parameter, OutboundMessageFee: Parameter, OutboundPayload: Parameter> {
 		/// Estimate message delivery and dispatch fee that needs to be paid by the sender on
 		/// this chain.
 		///
@@ -115,23 +132,50 @@ sp_api::decl_runtime_apis! {
 		/// Returns dispatch weight, encoded payload size and delivery+dispatch fee of all
 		/// messages in given inclusive range.
 		///
+		/// This is the version of the method that has been used before message senders have been
+		/// added to the message details.
+		#[changed_in(3)]
+		fn message_details(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessageDetailsV1<OutboundMessageFee>>;
+		/// Returns dispatch weight, encoded payload size, delivery+dispatch fee and sender of all
+		/// messages in given inclusive range.
+		///
+		/// This is the version of the method that has been used before cancellation flags have been
+		/// added to the message details.
+		#[changed_in(4)]
+		fn message_details(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessageDetailsV2<OutboundMessageFee, AccountId>>;
+		/// Returns dispatch weight, encoded payload size, delivery+dispatch fee, sender and
+		/// cancellation flag of all messages in given inclusive range.
+		///
 		/// If some (or all) messages are missing from the storage, they'll also will
 		/// be missing from the resulting vector. The vector is ordered by the nonce.
 		fn message_details(
 			lane: LaneId,
 			begin: MessageNonce,
 			end: MessageNonce,
-		) -> Vec<MessageDetails<OutboundMessageFee>>;
+		) -> Vec<MessageDetails<OutboundMessageFee, AccountId>>;
 		/// Returns nonce of the latest message, received by bridged chain.
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Returns all lanes from the lanes registry with their states.
+		///
+		/// This method has been added in version 2 of the API.
+		fn lanes() -> Vec<(LaneId, LaneState)>;
 	}
 
 	/// Inbound message lane API for messages sent by Polkadot chain.
 	///
 	/// This API is implemented by runtimes that are receiving messages from Polkadot chain, not the
 	/// Polkadot runtime itself.
+	#[api_version(2)]
 	pub trait FromPolkadotInboundLaneApi {
 		/// Returns nonce of the latest message, received by given lane.
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
@@ -139,5 +183,10 @@ sp_api::decl_runtime_apis! {
 		fn latest_confirmed_nonce(lane: LaneId) -> MessageNonce;
 		/// State of the unrewarded relayers set at given lane.
 		fn unrewarded_relayers_state(lane: LaneId) -> UnrewardedRelayersState;
+		/// Returns nonce of the latest message, queued for dispatch at given lane, or `None` if
+		/// the lane has no queued messages.
+		///
+		/// This method has been added in version 2 of the API.
+		fn latest_queued_nonce(lane: LaneId) -> Option<MessageNonce>;
 	}
 }
@@ -20,8 +20,11 @@
 // Runtime-generated DecodeLimit::decode_all_With_depth_limit
 #![allow(clippy::unnecessary_mut_passed)]
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
-use bp_runtime::Chain;
+use bp_messages::{
+	InboundLaneData, LaneId, LaneState, MessageDetails, MessageDetailsV1, MessageDetailsV2, MessageNonce,
+	MessagePayloadDetails, UnrewardedRelayersState,
+};
+use bp_runtime::{messages::AssetId, Chain};
 use frame_support::{
 	weights::{constants::WEIGHT_PER_SECOND, DispatchClass, IdentityFee, Weight},
 	Parameter, RuntimeDebug,
@@ -84,7 +87,7 @@ pub const ADDITIONAL_MESSAGE_BYTE_DELIVERY_WEIGHT: Weight = 25_000;
 /// This value is a result of `pallet_bridge_messages::Pallet::receive_messages_delivery_proof` weight formula computation
 /// for the case when single message is confirmed. The result then must be rounded up to account possible future
 /// runtime upgrades.
-pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 2_000_000_000;
+pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 3_000_000_000;
 
 /// Weight of pay-dispatch-fee operation for inbound messages at Rialto chain.
 ///
@@ -93,6 +96,26 @@ pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 2_000_000
 /// `DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT` cost. So putting large reserve would make delivery transactions cheaper.
 pub const PAY_INBOUND_DISPATCH_FEE_WEIGHT: Weight = 600_000_000;
 
+/// Maximal size (in bytes) of the decompressed message call at Rialto chain.
+///
+/// Messages with compressed calls that are decompressed into larger calls are rejected.
+pub const MAX_DECOMPRESSED_MESSAGE_CALL_SIZE: u32 = 128 * 1024;
+
+/// Weight of decompressing single message call at Rialto chain.
+///
+/// This is the upper bound of the weight, required to decompress the call of `MAX_DECOMPRESSED_MESSAGE_CALL_SIZE`
+/// bytes. The value is rounded up to account possible future runtime upgrades.
+pub const MESSAGE_CALL_DECOMPRESSION_WEIGHT: Weight = 100_000_000;
+
+/// Identifier of the Rialto asset that represents Millau tokens, bridged to Rialto chain.
+pub const BRIDGED_MILLAU_TOKEN_ASSET_ID: AssetId = 0;
+
+/// Identifiers of Rialto assets that may be used to pay dispatch fee of messages, coming from other chains.
+///
+/// The fee in these assets is paid by the dispatch origin account, when message sender has chosen
+/// the `DispatchFeePayment::AtTargetChainInAsset` option.
+pub const DISPATCH_FEE_ASSETS: [AssetId; 1] = [BRIDGED_MILLAU_TOKEN_ASSET_ID];
+
 /// The target length of a session (how often authorities change) on Rialto measured in of number of
 /// blocks.
 ///
@@ -240,6 +263,12 @@ pub const TO_RIALTO_MESSAGE_DETAILS_METHOD: &str = "ToRialtoOutboundLaneApi_mess
 pub const TO_RIALTO_LATEST_GENERATED_NONCE_METHOD: &str = "ToRialtoOutboundLaneApi_latest_generated_nonce";
 /// Name of the `ToRialtoOutboundLaneApi::latest_received_nonce` runtime method.
 pub const TO_RIALTO_LATEST_RECEIVED_NONCE_METHOD: &str = "ToRialtoOutboundLaneApi_latest_received_nonce";
+/// Name of the `ToRialtoOutboundLaneApi` runtime API.
+pub const TO_RIALTO_OUTBOUND_LANE_API: &str = "ToRialtoOutboundLaneApi";
+/// Name of the `ToRialtoOutboundLaneApi::lanes` runtime method.
+pub const TO_RIALTO_LANES_METHOD: &str = "ToRialtoOutboundLaneApi_lanes";
+/// Name of the `ToRialtoOutboundLaneApi::message_payloads` runtime method.
+pub const TO_RIALTO_MESSAGE_PAYLOADS_METHOD: &str = "ToRialtoOutboundLaneApi_message_payloads";
 
 /// Name of the `FromRialtoInboundLaneApi::latest_received_nonce` runtime method.
 pub const FROM_RIALTO_LATEST_RECEIVED_NONCE_METHOD: &str = "FromRialtoInboundLaneApi_latest_received_nonce";
@@ -247,24 +276,37 @@ pub const FROM_RIALTO_LATEST_RECEIVED_NONCE_METHOD: &str = "FromRialtoInboundLan
 pub const FROM_RIALTO_LATEST_CONFIRMED_NONCE_METHOD: &str = "FromRialtoInboundLaneApi_latest_confirmed_nonce";
 /// Name of the `FromRialtoInboundLaneApi::unrewarded_relayers_state` runtime method.
 pub const FROM_RIALTO_UNREWARDED_RELAYERS_STATE: &str = "FromRialtoInboundLaneApi_unrewarded_relayers_state";
+/// Name of the `FromRialtoInboundLaneApi` runtime API.
+pub const FROM_RIALTO_INBOUND_LANE_API: &str = "FromRialtoInboundLaneApi";
+/// Name of the `FromRialtoInboundLaneApi::latest_queued_nonce` runtime method.
+pub const FROM_RIALTO_LATEST_QUEUED_NONCE_METHOD: &str = "FromRialtoInboundLaneApi_latest_queued_nonce";
+/// Name of the `FromRialtoInboundLaneApi::inbound_lane_data` runtime method.
+pub const FROM_RIALTO_INBOUND_LANE_DATA_METHOD: &str = "FromRialtoInboundLaneApi_inbound_lane_data";
 
 sp_api::decl_runtime_apis! {
 	/// API for querying information about the finalized Rialto headers.
 	///
 	/// This API is implemented by runtimes that are bridging with the Rialto chain, not the
 	/// Millau runtime itself.
+	#[api_version(2)]
 	pub trait RialtoFinalityApi {
 		/// Returns number and hash of the best finalized header known to the bridge module.
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns the finalized header with given number or, if it isn't known to the bridge
+		/// module, the most recently imported header with lesser number.
+		///
+		/// This method has been added in version 2 of the API.
+		fn finalized_header_at_or_before(number: BlockNumber) -> Option<Header>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Rialto chain.
 	///
 	/// This API is implemented by runtimes that are sending messages to Rialto chain, not the
 	/// Rialto runtime itself.
-	pub trait ToRialtoOutboundLaneApi<OutboundMessageFee: Parameter, OutboundPayload: Parameter> {
+	#[api_version(4)]
+	pub trait ToRialtoOutboundLaneApi<AccountId: Parameter, OutboundMessageFee: Parameter, OutboundPayload: Parameter> {
 		/// Estimate message delivery and dispatch fee that needs to be paid by the sender on
 		/// this chain.
 		///
@@ -281,23 +323,61 @@ sp_api::decl_runtime_apis! {
 		/// Returns dispatch weight, encoded payload size and delivery+dispatch fee of all
 		/// messages in given inclusive range.
 		///
+		/// This is the version of the method that has been used before message senders have been
+		/// added to the message details.
+		#[changed_in(3)]
+		fn message_details(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessageDetailsV1<OutboundMessageFee>>;
+		/// Returns dispatch weight, encoded payload size, delivery+dispatch fee and sender of all
+		/// messages in given inclusive range.
+		///
+		/// This is the version of the method that has been used before cancellation flags have been
+		/// added to the message details.
+		#[changed_in(4)]
+		fn message_details(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessageDetailsV2<OutboundMessageFee, AccountId>>;
+		/// Returns dispatch weight, encoded payload size, delivery+dispatch fee, sender and
+		/// cancellation flag of all messages in given inclusive range.
+		///
 		/// If some (or all) messages are missing from the storage, they'll also will
 		/// be missing from the resulting vector. The vector is ordered by the nonce.
 		fn message_details(
 			lane: LaneId,
 			begin: MessageNonce,
 			end: MessageNonce,
-		) -> Vec<MessageDetails<OutboundMessageFee>>;
+		) -> Vec<MessageDetails<OutboundMessageFee, AccountId>>;
 		/// Returns nonce of the latest message, received by bridged chain.
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Returns all lanes from the lanes registry with their states.
+		///
+		/// This method has been added in version 2 of the API.
+		fn lanes() -> Vec<(LaneId, LaneState)>;
+		/// Returns encoded payloads and delivery+dispatch fees of all messages in given inclusive range.
+		///
+		/// If some (or all) messages are missing from the storage, they'll also will
+		/// be missing from the resulting vector. The vector is ordered by the nonce. The range
+		/// is clamped to messages that are not yet pruned and the number of returned messages
+		/// is limited by the runtime.
+		fn message_payloads(
+			lane: LaneId,
+			begin: MessageNonce,
+			end: MessageNonce,
+		) -> Vec<MessagePayloadDetails<OutboundMessageFee>>;
 	}
 
 	/// Inbound message lane API for messages sent by Rialto chain.
 	///
 	/// This API is implemented by runtimes that are receiving messages from Rialto chain, not the
 	/// Rialto runtime itself.
+	#[api_version(3)]
 	pub trait FromRialtoInboundLaneApi {
 		/// Returns nonce of the latest message, received by given lane.
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
@@ -305,6 +385,16 @@ sp_api::decl_runtime_apis! {
 		fn latest_confirmed_nonce(lane: LaneId) -> MessageNonce;
 		/// State of the unrewarded relayers set at given lane.
 		fn unrewarded_relayers_state(lane: LaneId) -> UnrewardedRelayersState;
+		/// Returns nonce of the latest message, queued for dispatch at given lane, or `None` if
+		/// the lane has no queued messages.
+		///
+		/// This method has been added in version 2 of the API.
+		fn latest_queued_nonce(lane: LaneId) -> Option<MessageNonce>;
+		/// Returns inbound lane data, including all unrewarded relayers entries with their
+		/// dispatch results.
+		///
+		/// This method has been added in version 3 of the API.
+		fn inbound_lane_data(lane: LaneId) -> InboundLaneData<AccountId>;
 	}
 }
 
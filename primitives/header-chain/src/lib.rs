@@ -29,6 +29,7 @@ use serde::{Deserialize, Serialize};
 use sp_finality_grandpa::{AuthorityList, ConsensusLog, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::RuntimeDebug;
 use sp_runtime::{generic::OpaqueDigestItemId, traits::Header as HeaderT};
+use sp_std::vec::Vec;
 
 pub mod justification;
 
@@ -84,6 +85,20 @@ pub trait InclusionProofVerifier {
 	fn verify_transaction_inclusion_proof(proof: &Self::TransactionInclusionProof) -> Option<Self::Transaction>;
 }
 
+/// Proof of Substrate transaction inclusion into the finalized block.
+///
+/// The proof is the subset of nodes of the block extrinsics trie, that is rooted at the
+/// `extrinsics_root` of the block header.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct SubstrateTransactionInclusionProof<Hash> {
+	/// Hash of the block that has included the transaction.
+	pub block: Hash,
+	/// Index of the transaction within the block.
+	pub index: u32,
+	/// Extrinsics trie nodes that are proving transaction inclusion.
+	pub proof: Vec<Vec<u8>>,
+}
+
 /// A trait for pallets which want to keep track of finalized headers from a bridged chain.
 pub trait HeaderChain<H, E> {
 	/// Get the best finalized header known to the header chain.
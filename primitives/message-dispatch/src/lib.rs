@@ -19,6 +19,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+use bp_messages::{LaneId, MessageNonce};
 use bp_runtime::{
 	messages::{DispatchFeePayment, MessageDispatchResult},
 	ChainId, Size,
@@ -123,10 +124,33 @@ pub struct MessagePayload<SourceChainAccountId, TargetChainAccountPublic, Target
 	pub origin: CallOrigin<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature>,
 	/// Where the fee for dispatching message is paid?
 	pub dispatch_fee_payment: DispatchFeePayment,
-	/// The call itself.
+	/// The call itself. It may be compressed (see `CallEncoding`).
 	pub call: Call,
 }
 
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call: AsRef<[u8]>>
+	MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+{
+	/// Returns encoding of the call of this payload.
+	pub fn call_encoding(&self) -> CallEncoding {
+		call_encoding(self.call.as_ref())
+	}
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature>
+	MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Vec<u8>>
+{
+	/// Compress the call of this payload.
+	///
+	/// Does nothing if the call is already compressed.
+	pub fn compress(mut self) -> Self {
+		if self.call_encoding() == CallEncoding::Plain {
+			self.call = compress_call(&self.call);
+		}
+		self
+	}
+}
+
 impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
 	for MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Vec<u8>>
 {
@@ -134,3 +158,106 @@ impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
 		self.call.len() as _
 	}
 }
+
+/// Encoding of the call, carried by the message payload.
+///
+/// The encoding isn't stored in the message payload. Instead, compressed calls are prefixed with
+/// the `COMPRESSED_CALL_PREFIX`. So payloads with plain calls are encoded exactly as before the
+/// compression has been introduced.
+#[derive(RuntimeDebug, Clone, Copy, PartialEq, Eq)]
+pub enum CallEncoding {
+	/// The call is SCALE-encoded.
+	Plain,
+	/// The SCALE-encoded call is compressed using DEFLATE algorithm.
+	///
+	/// Compressed calls are cheaper to deliver, because both delivery transaction size and
+	/// message fee depend on the size of the message payload. But the target chain spends
+	/// some additional weight to decompress the call.
+	Compressed,
+}
+
+/// Prefix of the compressed call.
+///
+/// The first byte of the plain SCALE-encoded call is the index of the pallet in the runtime. So
+/// runtimes that are accepting compressed calls must not have pallet with index `255`.
+pub const COMPRESSED_CALL_PREFIX: [u8; 4] = [0xFF, b'z', b'i', b'p'];
+
+/// Returns encoding of the call, carried by the message payload.
+pub fn call_encoding(call: &[u8]) -> CallEncoding {
+	if call.starts_with(&COMPRESSED_CALL_PREFIX) {
+		CallEncoding::Compressed
+	} else {
+		CallEncoding::Plain
+	}
+}
+
+/// Compress SCALE-encoded call.
+///
+/// The compressed call is prefixed with the `COMPRESSED_CALL_PREFIX`.
+pub fn compress_call(encoded_call: &[u8]) -> Vec<u8> {
+	let mut compressed_call = COMPRESSED_CALL_PREFIX.to_vec();
+	compressed_call.extend(miniz_oxide::deflate::compress_to_vec(
+		encoded_call,
+		CALL_COMPRESSION_LEVEL,
+	));
+	compressed_call
+}
+
+/// Decompress call that has been compressed with `compress_call`.
+///
+/// Returns error if the call is not prefixed with `COMPRESSED_CALL_PREFIX`, if the compressed data
+/// is malformed, or if the decompressed call is larger than `max_decompressed_size` bytes.
+pub fn decompress_call(compressed_call: &[u8], max_decompressed_size: u32) -> Result<Vec<u8>, ()> {
+	let compressed_call = compressed_call.strip_prefix(&COMPRESSED_CALL_PREFIX[..]).ok_or(())?;
+	miniz_oxide::inflate::decompress_to_vec_with_limit(compressed_call, max_decompressed_size as usize).map_err(drop)
+}
+
+/// Compression level used by `compress_call`.
+const CALL_COMPRESSION_LEVEL: u8 = 9;
+
+/// Payload of the message that may be forwarded by the target chain to the next chain of the route.
+///
+/// It is used by lanes that are connecting chains with forwarding dispatchers. The `Dispatch` variant
+/// is dispatched at the target chain, using regular dispatcher.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub enum ForwardingPayload<DispatchPayload, Balance> {
+	/// The message is dispatched at the target chain.
+	Dispatch(DispatchPayload),
+	/// The message is forwarded by the target chain to the next chain of the route.
+	Forward(ForwardedMessage<Balance>),
+	/// Dispatch result of the message that has been forwarded by the target chain.
+	DispatchReport(ForwardedMessageDispatchReport),
+}
+
+impl<DispatchPayload, Balance> From<ForwardedMessageDispatchReport> for ForwardingPayload<DispatchPayload, Balance> {
+	fn from(report: ForwardedMessageDispatchReport) -> Self {
+		ForwardingPayload::DispatchReport(report)
+	}
+}
+
+/// The message that is forwarded by the target chain to the next chain of the route.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct ForwardedMessage<Balance> {
+	/// Lane between the target chain and the next chain of the route.
+	pub lane: LaneId,
+	/// Encoded payload of the message at the next chain of the route. It may be the
+	/// `ForwardingPayload` itself, if the next chain also forwards the message.
+	pub payload: Vec<u8>,
+	/// Delivery and dispatch fee of the forwarded message. It is paid at the target chain from
+	/// the sovereign account of the source chain.
+	pub delivery_and_dispatch_fee: Balance,
+}
+
+/// Dispatch result of the message that has been forwarded by the target chain.
+///
+/// The report is sent back to the source chain once the target chain receives delivery
+/// confirmation from the next chain of the route.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct ForwardedMessageDispatchReport {
+	/// Lane of the original message at the source chain.
+	pub lane: LaneId,
+	/// Nonce of the original message at the source chain.
+	pub nonce: MessageNonce,
+	/// Dispatch result of the message at the next chain of the route.
+	pub dispatch_result: bool,
+}
@@ -50,6 +50,12 @@ pub enum OperatingMode {
 	RejectingOutboundMessages,
 	/// The pallet is halted. All operations (except operating mode change) are prohibited.
 	Halted,
+	/// The bridge is being decommissioned. All operations (except operating mode change) are
+	/// prohibited. Fees of all undelivered outbound messages are refunded to their senders and
+	/// confirmed messages are pruned in the background.
+	///
+	/// Only root may switch the pallet to or from this mode.
+	Decommissioning,
 }
 
 impl Default for OperatingMode {
@@ -58,6 +64,49 @@ impl Default for OperatingMode {
 	}
 }
 
+/// Progress of the messages pallet decommissioning.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct DecommissioningProgress<Fee> {
+	/// Number of undelivered messages, whose fees have been refunded to their senders.
+	pub refunded_messages: MessageNonce,
+	/// Total fee that has been refunded to senders of undelivered messages.
+	pub refunded_fee: Fee,
+	/// Number of undelivered messages, whose fees have not been refunded because of errors.
+	pub failed_refunds: MessageNonce,
+	/// Balance of the relayers fund account after all lanes have been drained. It is `None` while
+	/// decommissioning is in progress.
+	pub final_relayer_fund_balance: Option<Fee>,
+}
+
+/// State of the lane in the lanes registry of the messages pallet.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum LaneState {
+	/// The lane has been opened at this chain, but we haven't yet received any proof from the
+	/// bridged chain at this lane. All operations are allowed.
+	Opening,
+	/// The lane is opened at both chains. All operations are allowed.
+	Open,
+	/// The lane is closing. New outbound messages are rejected, but delivery proofs and confirmations
+	/// are still accepted until all already sent messages are delivered.
+	Closing,
+	/// The lane is closed and all messages that have been sent over this lane are delivered. New
+	/// outbound messages are rejected.
+	Closed,
+}
+
+impl LaneState {
+	/// Returns true if new outbound messages may be sent over the lane in this state.
+	pub fn is_accepting_outbound_messages(&self) -> bool {
+		matches!(*self, LaneState::Opening | LaneState::Open)
+	}
+
+	/// Returns true if the lane in this state needs to be served by relayers.
+	pub fn is_active(&self) -> bool {
+		*self != LaneState::Closed
+	}
+}
+
 /// Messages pallet parameter.
 pub trait Parameter: frame_support::Parameter {
 	/// Save parameter value in the runtime storage.
@@ -98,13 +147,30 @@ pub struct MessageData<Fee> {
 	pub fee: Fee,
 }
 
-/// Message as it is stored in the storage.
+/// Inbound message that has been received, but has not been dispatched yet.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct QueuedMessage<RelayerId, AccountId, Fee> {
+	/// Relayer (at the source chain) that has delivered the message.
+	pub relayer_at_bridged_chain: RelayerId,
+	/// Relayer (at this chain) that has delivered the message.
+	pub relayer_at_this_chain: AccountId,
+	/// Message data, as it has been sent by the source chain.
+	pub data: MessageData<Fee>,
+	/// True if the message must be received without dispatch. The source chain asks us to skip
+	/// dispatch of messages that have been expired or cancelled.
+	pub skipped: bool,
+}
+
+/// Message, as it is proved by the source chain.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct Message<Fee> {
 	/// Message key.
 	pub key: MessageKey,
 	/// Message data.
 	pub data: MessageData<Fee>,
+	/// True if the message has been cancelled at the source chain. Cancelled messages are
+	/// received without dispatch.
+	pub cancelled: bool,
 }
 
 /// Inbound lane data.
@@ -159,9 +225,10 @@ impl<RelayerId> InboundLaneData<RelayerId> {
 		let relayers_size = relayers_entries.checked_mul(relayers_entry_size)?;
 		let dispatch_results_per_byte = 8;
 		let dispatch_result_size = sp_std::cmp::max(relayers_entries, messages_count / dispatch_results_per_byte);
+		// every entry has both dispatch results and skip flags bit vectors of the same size
 		relayers_size
 			.checked_add(message_nonce_size)
-			.and_then(|result| result.checked_add(dispatch_result_size))
+			.and_then(|result| result.checked_add(dispatch_result_size.checked_mul(2)?))
 	}
 
 	/// Nonce of the last message that has been delivered to this (target) chain.
@@ -175,7 +242,63 @@ impl<RelayerId> InboundLaneData<RelayerId> {
 
 /// Message details, returned by runtime APIs.
 #[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
-pub struct MessageDetails<OutboundMessageFee> {
+pub struct MessageDetails<OutboundMessageFee, AccountId> {
+	/// Nonce assigned to the message.
+	pub nonce: MessageNonce,
+	/// Message dispatch weight, declared by the submitter.
+	pub dispatch_weight: Weight,
+	/// Size of the encoded message.
+	pub size: u32,
+	/// Delivery+dispatch fee paid by the message submitter at the source chain.
+	pub delivery_and_dispatch_fee: OutboundMessageFee,
+	/// Where the fee for dispatching message is paid?
+	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Message submitter. It is `None` if the message has been sent before senders of outbound
+	/// messages have been recorded.
+	pub sender: Option<source_chain::Sender<AccountId>>,
+	/// True if the message has been cancelled by its submitter. Cancelled messages still need to
+	/// be delivered to the target chain, but they're never dispatched there and their delivery
+	/// is not rewarded.
+	pub cancelled: bool,
+}
+
+/// Message details, returned by the version 2 of outbound lane runtime APIs.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct MessageDetailsV2<OutboundMessageFee, AccountId> {
+	/// Nonce assigned to the message.
+	pub nonce: MessageNonce,
+	/// Message dispatch weight, declared by the submitter.
+	pub dispatch_weight: Weight,
+	/// Size of the encoded message.
+	pub size: u32,
+	/// Delivery+dispatch fee paid by the message submitter at the source chain.
+	pub delivery_and_dispatch_fee: OutboundMessageFee,
+	/// Where the fee for dispatching message is paid?
+	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Message submitter. It is `None` if the message has been sent before senders of outbound
+	/// messages have been recorded.
+	pub sender: Option<source_chain::Sender<AccountId>>,
+}
+
+impl<OutboundMessageFee, AccountId> From<MessageDetailsV2<OutboundMessageFee, AccountId>>
+	for MessageDetails<OutboundMessageFee, AccountId>
+{
+	fn from(details: MessageDetailsV2<OutboundMessageFee, AccountId>) -> Self {
+		MessageDetails {
+			nonce: details.nonce,
+			dispatch_weight: details.dispatch_weight,
+			size: details.size,
+			delivery_and_dispatch_fee: details.delivery_and_dispatch_fee,
+			dispatch_fee_payment: details.dispatch_fee_payment,
+			sender: details.sender,
+			cancelled: false,
+		}
+	}
+}
+
+/// Message details, returned by the version 1 of outbound lane runtime APIs.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct MessageDetailsV1<OutboundMessageFee> {
 	/// Nonce assigned to the message.
 	pub nonce: MessageNonce,
 	/// Message dispatch weight, declared by the submitter.
@@ -188,6 +311,33 @@ pub struct MessageDetails<OutboundMessageFee> {
 	pub dispatch_fee_payment: DispatchFeePayment,
 }
 
+impl<OutboundMessageFee, AccountId> From<MessageDetailsV1<OutboundMessageFee>>
+	for MessageDetails<OutboundMessageFee, AccountId>
+{
+	fn from(details: MessageDetailsV1<OutboundMessageFee>) -> Self {
+		MessageDetails {
+			nonce: details.nonce,
+			dispatch_weight: details.dispatch_weight,
+			size: details.size,
+			delivery_and_dispatch_fee: details.delivery_and_dispatch_fee,
+			dispatch_fee_payment: details.dispatch_fee_payment,
+			sender: None,
+			cancelled: false,
+		}
+	}
+}
+
+/// Outbound message payload details, returned by runtime APIs.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct MessagePayloadDetails<OutboundMessageFee> {
+	/// Nonce assigned to the message.
+	pub nonce: MessageNonce,
+	/// Encoded message payload.
+	pub payload: MessagePayload,
+	/// Delivery+dispatch fee paid by the message submitter at the source chain.
+	pub delivery_and_dispatch_fee: OutboundMessageFee,
+}
+
 /// Bit vector of message dispatch results.
 pub type DispatchResultsBitVec = BitVec<Msb0, u8>;
 
@@ -214,6 +364,10 @@ pub struct DeliveredMessages {
 	/// message in the `[begin; end]` range. See `dispatch_result` field of the
 	/// `bp_runtime::messages::MessageDispatchResult` structure for more information.
 	pub dispatch_results: DispatchResultsBitVec,
+	/// Skip flag for every message in the `[begin; end]` range. The message is skipped if it has
+	/// been received, but not dispatched, because it has expired at the source chain. Dispatch result
+	/// of skipped message is always `false`.
+	pub skipped_messages: DispatchResultsBitVec,
 }
 
 impl DeliveredMessages {
@@ -223,6 +377,17 @@ impl DeliveredMessages {
 			begin: nonce,
 			end: nonce,
 			dispatch_results: bitvec![Msb0, u8; if dispatch_result { 1 } else { 0 }],
+			skipped_messages: bitvec![Msb0, u8; 0],
+		}
+	}
+
+	/// Create new `DeliveredMessages` struct that confirms delivery of single skipped nonce.
+	pub fn new_skipped(nonce: MessageNonce) -> Self {
+		DeliveredMessages {
+			begin: nonce,
+			end: nonce,
+			dispatch_results: bitvec![Msb0, u8; 0],
+			skipped_messages: bitvec![Msb0, u8; 1],
 		}
 	}
 
@@ -239,6 +404,14 @@ impl DeliveredMessages {
 	pub fn note_dispatched_message(&mut self, dispatch_result: bool) {
 		self.end += 1;
 		self.dispatch_results.push(dispatch_result);
+		self.skipped_messages.push(false);
+	}
+
+	/// Note new skipped message.
+	pub fn note_skipped_message(&mut self) {
+		self.end += 1;
+		self.dispatch_results.push(false);
+		self.skipped_messages.push(true);
 	}
 
 	/// Returns true if delivered messages contain message with given nonce.
@@ -260,6 +433,17 @@ impl DeliveredMessages {
 		let index = nonce.checked_sub(self.begin).expect(INVALID_NONCE) as usize;
 		*self.dispatch_results.get(index).expect(INVALID_NONCE)
 	}
+
+	/// Returns true if message with given nonce has been skipped (received without dispatch).
+	///
+	/// Panics if message nonce is not in the `begin..=end` range. Typically you'll first
+	/// check if message is within the range by calling `contains_message`.
+	pub fn message_skipped(&self, nonce: MessageNonce) -> bool {
+		const INVALID_NONCE: &str = "Invalid nonce used to index skipped_messages";
+
+		let index = nonce.checked_sub(self.begin).expect(INVALID_NONCE) as usize;
+		*self.skipped_messages.get(index).expect(INVALID_NONCE)
+	}
 }
 
 /// Gist of `InboundLaneData::relayers` field used by runtime APIs.
@@ -275,7 +459,12 @@ pub struct UnrewardedRelayersState {
 }
 
 /// Outbound lane data.
-#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+///
+/// The `latest_expired_nonce` field has been added after the structure has been used by deployed
+/// chains. So it is optional when the structure is decoded: if there are no bytes left for the
+/// field, it is set to zero. That's how we are able to read lanes of chains that are not aware
+/// of messages expiration (and their proofs).
+#[derive(Encode, Clone, RuntimeDebug, PartialEq, Eq)]
 pub struct OutboundLaneData {
 	/// Nonce of the oldest message that we haven't yet pruned. May point to not-yet-generated message if
 	/// all sent messages are already pruned.
@@ -284,6 +473,29 @@ pub struct OutboundLaneData {
 	pub latest_received_nonce: MessageNonce,
 	/// Nonce of the latest message, generated by us.
 	pub latest_generated_nonce: MessageNonce,
+	/// Nonce of the latest expired message. All messages in the
+	/// `latest_received_nonce+1..=latest_expired_nonce` range have been refunded at this chain
+	/// and must not be dispatched at the bridged chain.
+	pub latest_expired_nonce: MessageNonce,
+}
+
+impl Decode for OutboundLaneData {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let oldest_unpruned_nonce = MessageNonce::decode(input)?;
+		let latest_received_nonce = MessageNonce::decode(input)?;
+		let latest_generated_nonce = MessageNonce::decode(input)?;
+		let latest_expired_nonce = match input.remaining_len()? {
+			Some(0) => 0,
+			_ => MessageNonce::decode(input)?,
+		};
+
+		Ok(OutboundLaneData {
+			oldest_unpruned_nonce,
+			latest_received_nonce,
+			latest_generated_nonce,
+			latest_expired_nonce,
+		})
+	}
 }
 
 impl Default for OutboundLaneData {
@@ -293,6 +505,7 @@ impl Default for OutboundLaneData {
 			oldest_unpruned_nonce: 1,
 			latest_received_nonce: 0,
 			latest_generated_nonce: 0,
+			latest_expired_nonce: 0,
 		}
 	}
 }
@@ -362,6 +575,11 @@ mod tests {
 							1;
 							(messages_count / relayer_entries) as _
 						];
+						entry.messages.skipped_messages = bitvec![
+							Msb0, u8;
+							0;
+							(messages_count / relayer_entries) as _
+						];
 						entry
 					})
 					.collect(),
@@ -387,6 +605,7 @@ mod tests {
 			begin: 100,
 			end: 150,
 			dispatch_results: bitvec![Msb0, u8; 1; 151],
+			skipped_messages: bitvec![Msb0, u8; 0; 151],
 		};
 
 		assert!(!delivered_messages.contains_message(99));
@@ -395,5 +614,40 @@ mod tests {
 		assert!(!delivered_messages.contains_message(151));
 
 		assert!(delivered_messages.message_dispatch_result(125));
+		assert!(!delivered_messages.message_skipped(125));
+	}
+
+	#[test]
+	fn skipped_messages_are_not_dispatched() {
+		let mut delivered_messages = DeliveredMessages::new(100, true);
+		delivered_messages.note_skipped_message();
+		delivered_messages.note_dispatched_message(false);
+
+		assert!(delivered_messages.message_dispatch_result(100));
+		assert!(!delivered_messages.message_skipped(100));
+		assert!(!delivered_messages.message_dispatch_result(101));
+		assert!(delivered_messages.message_skipped(101));
+		assert!(!delivered_messages.message_dispatch_result(102));
+		assert!(!delivered_messages.message_skipped(102));
+	}
+
+	#[test]
+	fn outbound_lane_data_without_expired_nonce_is_decoded() {
+		let lane_data = OutboundLaneData {
+			oldest_unpruned_nonce: 1,
+			latest_received_nonce: 2,
+			latest_generated_nonce: 3,
+			latest_expired_nonce: 0,
+		};
+		assert_eq!(
+			OutboundLaneData::decode(&mut &(1u64, 2u64, 3u64).encode()[..]),
+			Ok(lane_data)
+		);
+
+		let lane_data = OutboundLaneData {
+			latest_expired_nonce: 2,
+			..lane_data
+		};
+		assert_eq!(OutboundLaneData::decode(&mut &lane_data.encode()[..]), Ok(lane_data));
 	}
 }
@@ -20,11 +20,14 @@ use crate::{DeliveredMessages, InboundLaneData, LaneId, MessageNonce, OutboundLa
 
 use bp_runtime::Size;
 use frame_support::{weights::Weight, Parameter, RuntimeDebug};
-use sp_std::{collections::btree_map::BTreeMap, fmt::Debug};
+use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, ops::RangeInclusive, prelude::*};
 
 /// The sender of the message on the source chain.
 pub type Sender<AccountId> = frame_system::RawOrigin<AccountId>;
 
+/// Identifier of the pallet that owns outbound messages.
+pub type MessageOwnerId = [u8; 4];
+
 /// Relayers rewards, grouped by relayer account id.
 pub type RelayersRewards<AccountId, Balance> = BTreeMap<AccountId, RelayerRewards<Balance>>;
 
@@ -116,15 +119,27 @@ pub trait MessageDeliveryAndDispatchPayment<AccountId, Balance> {
 		relayer_fund_account: &AccountId,
 	) -> Result<(), Self::Error>;
 
-	/// Pay rewards for delivering messages to the given relayers.
+	/// Return delivery_and_dispatch_fee of the expired message from the relayers-fund account
+	/// back to the message submitter.
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<AccountId>,
+		fee: &Balance,
+		relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error>;
+
+	/// Pay rewards for delivering messages over the lane to the given relayers.
 	///
 	/// The implementation may also choose to pay reward to the `confirmation_relayer`, which is
 	/// a relayer that has submitted delivery confirmation transaction.
 	fn pay_relayers_rewards(
+		lane_id: LaneId,
 		confirmation_relayer: &AccountId,
 		relayers_rewards: RelayersRewards<AccountId, Balance>,
 		relayer_fund_account: &AccountId,
 	);
+
+	/// Returns current balance of the relayers-fund account.
+	fn relayer_fund_balance(relayer_fund_account: &AccountId) -> Balance;
 }
 
 /// Messages bridge API to be used from other pallets.
@@ -141,14 +156,45 @@ pub trait MessagesBridge<AccountId, Balance, Payload> {
 		message: Payload,
 		delivery_and_dispatch_fee: Balance,
 	) -> Result<MessageNonce, Self::Error>;
+
+	/// Send several messages over the bridge.
+	///
+	/// Messages are either all accepted, or all rejected. Every message comes with its own
+	/// delivery and dispatch fee. Returns the (inclusive) range of assigned message nonces
+	/// or error if send has failed.
+	fn send_messages(
+		sender: Sender<AccountId>,
+		lane: LaneId,
+		messages: Vec<(Payload, Balance)>,
+	) -> Result<RangeInclusive<MessageNonce>, Self::Error>;
+
+	/// Send message over the bridge on behalf of the message owner.
+	///
+	/// Once the message delivery is confirmed, its dispatch result is passed to the
+	/// `MessageOwner::on_message_dispatched` of the owner with given identifier. Returns
+	/// unique message nonce or error if send has failed.
+	fn send_owned_message(
+		owner: MessageOwnerId,
+		sender: Sender<AccountId>,
+		lane: LaneId,
+		message: Payload,
+		delivery_and_dispatch_fee: Balance,
+	) -> Result<MessageNonce, Self::Error>;
 }
 
+/// Senders of confirmed messages, grouped by message nonce.
+///
+/// Messages that have expired and have been refunded before their delivery has been confirmed
+/// are missing from this map.
+pub type MessagesSenders<AccountId> = BTreeMap<MessageNonce, Sender<AccountId>>;
+
 /// Handler for messages delivery confirmation.
-pub trait OnDeliveryConfirmed {
+pub trait OnDeliveryConfirmed<AccountId> {
 	/// Called when we receive confirmation that our messages have been delivered to the
 	/// target chain. The confirmation also has single bit dispatch result for every
 	/// confirmed message (see `DeliveredMessages` for details). Guaranteed to be called
-	/// only when at least one message is delivered.
+	/// only when at least one message is delivered. Senders of confirmed messages are
+	/// passed in the `senders` map.
 	///
 	/// Should return total weight consumed by the call.
 	///
@@ -157,16 +203,24 @@ pub trait OnDeliveryConfirmed {
 	/// return weight that is larger than total number of messages * (db read + db write).
 	/// If your pallet needs more time for processing single message, please do it
 	/// from `on_initialize` call(s) of the next block(s).
-	fn on_messages_delivered(_lane: &LaneId, _messages: &DeliveredMessages) -> Weight;
+	fn on_messages_delivered(
+		_lane: &LaneId,
+		_messages: &DeliveredMessages,
+		_senders: &MessagesSenders<AccountId>,
+	) -> Weight;
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(30)]
-impl OnDeliveryConfirmed for Tuple {
-	fn on_messages_delivered(lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+impl<AccountId> OnDeliveryConfirmed<AccountId> for Tuple {
+	fn on_messages_delivered(
+		lane: &LaneId,
+		messages: &DeliveredMessages,
+		senders: &MessagesSenders<AccountId>,
+	) -> Weight {
 		let mut total_weight: Weight = 0;
 		for_tuples!(
 			#(
-				total_weight = total_weight.saturating_add(Tuple::on_messages_delivered(lane, messages));
+				total_weight = total_weight.saturating_add(Tuple::on_messages_delivered(lane, messages, senders));
 			)*
 		);
 		total_weight
@@ -174,17 +228,78 @@ impl OnDeliveryConfirmed for Tuple {
 }
 
 /// Handler for messages have been accepted
-pub trait OnMessageAccepted {
+pub trait OnMessageAccepted<AccountId> {
 	/// Called when a message has been accepted by message pallet.
-	fn on_messages_accepted(lane: &LaneId, message: &MessageNonce) -> Weight;
+	fn on_messages_accepted(lane: &LaneId, message: &MessageNonce, sender: &Sender<AccountId>) -> Weight;
 }
 
-impl OnMessageAccepted for () {
-	fn on_messages_accepted(_lane: &LaneId, _message: &MessageNonce) -> Weight {
+impl<AccountId> OnMessageAccepted<AccountId> for () {
+	fn on_messages_accepted(_lane: &LaneId, _message: &MessageNonce, _sender: &Sender<AccountId>) -> Weight {
 		0
 	}
 }
 
+/// Pallet that owns outbound messages (see `MessagesBridge::send_owned_message`).
+pub trait MessageOwner {
+	/// Returns identifier of the owner. Must be unique among all owners of the messages pallet.
+	fn owner_id() -> MessageOwnerId;
+
+	/// Called when we receive confirmation that the message, owned by this pallet, has been
+	/// delivered to the target chain. The `dispatch_result` is the single bit dispatch result
+	/// of the message (see `DeliveredMessages` for details).
+	///
+	/// Should return weight consumed by the call.
+	///
+	/// NOTE: messages pallet assumes that maximal weight that may be spent on processing
+	/// single message is single DB read + single DB write. So this function shall never
+	/// return weight that is larger than db read + db write.
+	fn on_message_dispatched(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) -> Weight;
+}
+
+/// Router of messages dispatch results to their owners.
+pub trait MessageOwners {
+	/// Pass dispatch result of the message to its owner.
+	///
+	/// Returns weight consumed by the owner or `None` if there's no owner with given identifier.
+	fn on_message_dispatched(
+		owner: &MessageOwnerId,
+		lane: &LaneId,
+		nonce: MessageNonce,
+		dispatch_result: bool,
+	) -> Option<Weight>;
+}
+
+impl MessageOwners for () {
+	fn on_message_dispatched(
+		_owner: &MessageOwnerId,
+		_lane: &LaneId,
+		_nonce: MessageNonce,
+		_dispatch_result: bool,
+	) -> Option<Weight> {
+		None
+	}
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(1, 30)]
+#[tuple_types_custom_trait_bound(MessageOwner)]
+impl MessageOwners for Tuple {
+	fn on_message_dispatched(
+		owner: &MessageOwnerId,
+		lane: &LaneId,
+		nonce: MessageNonce,
+		dispatch_result: bool,
+	) -> Option<Weight> {
+		for_tuples!(
+			#(
+				if Tuple::owner_id() == *owner {
+					return Some(Tuple::on_message_dispatched(lane, nonce, dispatch_result));
+				}
+			)*
+		);
+		None
+	}
+}
+
 /// Structure that may be used in place of `TargetHeaderChain`, `LaneMessageVerifier` and
 /// `MessageDeliveryAndDispatchPayment` on chains, where outbound messages are forbidden.
 pub struct ForbidOutboundMessages;
@@ -222,7 +337,7 @@ impl<Submitter, Payload, Fee> LaneMessageVerifier<Submitter, Payload, Fee> for F
 	}
 }
 
-impl<AccountId, Balance> MessageDeliveryAndDispatchPayment<AccountId, Balance> for ForbidOutboundMessages {
+impl<AccountId, Balance: Default> MessageDeliveryAndDispatchPayment<AccountId, Balance> for ForbidOutboundMessages {
 	type Error = &'static str;
 
 	fn pay_delivery_and_dispatch_fee(
@@ -233,10 +348,23 @@ impl<AccountId, Balance> MessageDeliveryAndDispatchPayment<AccountId, Balance> f
 		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		_submitter: &Sender<AccountId>,
+		_fee: &Balance,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
+	}
+
 	fn pay_relayers_rewards(
+		_lane_id: LaneId,
 		_confirmation_relayer: &AccountId,
 		_relayers_rewards: RelayersRewards<AccountId, Balance>,
 		_relayer_fund_account: &AccountId,
 	) {
 	}
+
+	fn relayer_fund_balance(_relayer_fund_account: &AccountId) -> Balance {
+		Default::default()
+	}
 }
@@ -18,7 +18,10 @@
 
 use crate::{LaneId, Message, MessageData, MessageKey, OutboundLaneData};
 
-use bp_runtime::{messages::MessageDispatchResult, Size};
+use bp_runtime::{
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	Size,
+};
 use codec::{Decode, Encode, Error as CodecError};
 use frame_support::{weights::Weight, Parameter, RuntimeDebug};
 use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, prelude::*};
@@ -96,6 +99,13 @@ pub trait MessageDispatch<AccountId, Fee> {
 	/// of dispatch weight.
 	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Fee>) -> Weight;
 
+	/// Returns the way the message dispatch fee is paid.
+	///
+	/// By default it is assumed that the dispatch fee is paid at the source chain.
+	fn dispatch_fee_payment(_message: &DispatchMessage<Self::DispatchPayload, Fee>) -> DispatchFeePayment {
+		DispatchFeePayment::AtSourceChain
+	}
+
 	/// Called when inbound message is received.
 	///
 	/// It is up to the implementers of this trait to determine whether the message
@@ -109,6 +119,51 @@ pub trait MessageDispatch<AccountId, Fee> {
 	) -> MessageDispatchResult;
 }
 
+/// Registry of relayers that are delivering messages to this chain.
+///
+/// Registered relayers are getting delivery transaction fees back (if they're delivering new
+/// messages) and are punished for submitting invalid messages proofs.
+pub trait RelayersRegistry<AccountId> {
+	/// Returns true if relayer is registered to deliver messages over given lane.
+	fn is_registered(relayer: &AccountId, lane: &LaneId) -> bool;
+	/// Called when relayer has submitted messages proof that has failed verification.
+	fn on_invalid_messages_proof(relayer: &AccountId);
+}
+
+/// Default relayers registry, that has no registered relayers.
+impl<AccountId> RelayersRegistry<AccountId> for () {
+	fn is_registered(_relayer: &AccountId, _lane: &LaneId) -> bool {
+		false
+	}
+
+	fn on_invalid_messages_proof(_relayer: &AccountId) {}
+}
+
+/// Deposit that is reserved from the relayer account for every message in the inbound dispatch queue.
+///
+/// Queued messages are occupying runtime storage until they're dispatched, so the relayer that has
+/// delivered them must pay for this storage. The deposit is returned when the message is dispatched.
+pub trait QueuedMessagesDeposit<AccountId> {
+	/// Error type.
+	type Error: Debug;
+
+	/// Reserve deposit for the queued message of given size.
+	fn reserve_deposit(relayer: &AccountId, message_size: u32) -> Result<(), Self::Error>;
+	/// Return deposit that has been reserved for the queued message of given size.
+	fn unreserve_deposit(relayer: &AccountId, message_size: u32);
+}
+
+/// Queued messages deposit that is always zero.
+impl<AccountId> QueuedMessagesDeposit<AccountId> for () {
+	type Error = &'static str;
+
+	fn reserve_deposit(_relayer: &AccountId, _message_size: u32) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn unreserve_deposit(_relayer: &AccountId, _message_size: u32) {}
+}
+
 impl<Message> Default for ProvedLaneMessages<Message> {
 	fn default() -> Self {
 		ProvedLaneMessages {
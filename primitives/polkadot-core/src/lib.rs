@@ -37,6 +37,8 @@ use sp_runtime::{
 };
 use sp_std::prelude::Vec;
 
+pub mod parachains;
+
 // Re-export's to avoid extra substrate dependencies in chain-specific crates.
 pub use frame_support::{weights::constants::ExtrinsicBaseWeight, Parameter};
 pub use sp_runtime::{traits::Convert, Perbill};
@@ -0,0 +1,107 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives of polkadot-like chains, that are related to parachains functionality.
+//!
+//! Even though this (bridges) repository references polkadot repository, we can't
+//! reference polkadot crates from pallets. That's because bridges repository is
+//! included in the polkadot repository and included pallets are used by polkadot
+//! chains. Having pallets that are referencing polkadot, would mean that there may
+//! be two versions of polkadot crates included in the runtime. Which is bad.
+
+use bp_runtime::Size;
+use frame_support::{RuntimeDebug, StorageHasher, Twox128, Twox64Concat};
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{storage::StorageKey, Hasher as HasherT};
+use sp_std::vec::Vec;
+
+/// Parachain id.
+///
+/// This is an equivalent of the `polkadot_parachain::Id`, which is a `u32` wrapper.
+#[derive(Clone, Copy, Decode, Default, Encode, Eq, Ord, PartialOrd, PartialEq, RuntimeDebug)]
+pub struct ParaId(pub u32);
+
+impl From<u32> for ParaId {
+	fn from(id: u32) -> Self {
+		ParaId(id)
+	}
+}
+
+/// Parachain head.
+///
+/// This is an equivalent of the `polkadot_parachain::HeadData`.
+///
+/// The parachain head means (at least in Cumulus) a SCALE-encoded parachain header.
+#[derive(Clone, Decode, Default, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct ParaHead(pub Vec<u8>);
+
+impl ParaHead {
+	/// Returns the hash of this head data.
+	pub fn hash(&self) -> ParaHash {
+		ParaHasher::hash(&self.0)
+	}
+}
+
+/// Parachain head hash.
+pub type ParaHash = crate::Hash;
+
+/// Parachain head hasher.
+pub type ParaHasher = crate::Hasher;
+
+/// Raw storage proof of parachain heads, stored in polkadot-like chain runtime.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct ParaHeadsProof(pub Vec<Vec<u8>>);
+
+impl Size for ParaHeadsProof {
+	fn size_hint(&self) -> u32 {
+		self.0
+			.iter()
+			.fold(0u32, |sum, node| sum.saturating_add(node.len() as u32))
+	}
+}
+
+/// Returns runtime storage key of given parachain head at the relay chain.
+///
+/// The head is stored by the `paras` pallet in the `Heads` map. The `paras_pallet_name` is the name
+/// of this pallet in the relay chain runtime (it is `Paras` in all known relay chains).
+pub fn parachain_head_storage_key_at_source(paras_pallet_name: &str, para_id: ParaId) -> StorageKey {
+	let pallet_prefix_hashed = Twox128::hash(paras_pallet_name.as_bytes());
+	let storage_prefix_hashed = Twox128::hash(b"Heads");
+	let key_hashed = Twox64Concat::hash(&para_id.encode());
+
+	let mut final_key = Vec::with_capacity(pallet_prefix_hashed.len() + storage_prefix_hashed.len() + key_hashed.len());
+
+	final_key.extend_from_slice(&pallet_prefix_hashed[..]);
+	final_key.extend_from_slice(&storage_prefix_hashed[..]);
+	final_key.extend_from_slice(&key_hashed[..]);
+
+	StorageKey(final_key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parachain_head_storage_key_is_computed_correctly() {
+		// this is the key of the `Paras::Heads(2000)` storage entry
+		assert_eq!(
+			parachain_head_storage_key_at_source("Paras", ParaId(2000)).0,
+			hex::decode("cd710b30bd2eab0352ddcc26417aa1941b3c252fcb29d88eff4f3de5de4476c363f5a4efb16ffa83d0070000")
+				.unwrap(),
+		);
+	}
+}
@@ -0,0 +1,41 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives of the `pallet-bridge-relayers` pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// RuntimeApi generated functions
+#![allow(clippy::too_many_arguments)]
+// Runtime-generated DecodeLimit::decode_all_With_depth_limit
+#![allow(clippy::unnecessary_mut_passed)]
+
+use bp_messages::LaneId;
+use frame_support::Parameter;
+
+/// Name of the `RelayersApi::unclaimed_rewards` runtime method.
+pub const RELAYERS_API_UNCLAIMED_REWARDS_METHOD: &str = "RelayersApi_unclaimed_rewards";
+
+sp_api::decl_runtime_apis! {
+	/// API for querying relayers rewards that are stored in the `pallet-bridge-relayers` pallet.
+	///
+	/// This API is implemented by runtimes that are paying relayers rewards using the
+	/// `pallet-bridge-relayers` pallet.
+	pub trait RelayersApi<AccountId: Parameter, Balance: Parameter> {
+		/// Returns rewards that have been earned by the relayer for delivering messages over
+		/// given lane and that are not yet claimed.
+		fn unclaimed_rewards(relayer: AccountId, lane: LaneId) -> Balance;
+	}
+}
@@ -28,7 +28,8 @@ pub use chain::{
 	AccountIdOf, AccountPublicOf, BalanceOf, BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf, IndexOf, SignatureOf,
 	TransactionEraOf,
 };
-pub use storage_proof::{Error as StorageProofError, StorageProofChecker};
+pub use migration::{StorageMigrationStep, VersionedMigration};
+pub use storage_proof::{Error as StorageProofError, FinalizedStorageProofParser, StorageProofChecker};
 
 #[cfg(feature = "std")]
 pub use storage_proof::craft_valid_storage_proof;
@@ -36,6 +37,7 @@ pub use storage_proof::craft_valid_storage_proof;
 pub mod messages;
 
 mod chain;
+mod migration;
 mod storage_proof;
 
 /// Use this when something must be shared among all instances.
@@ -19,6 +19,9 @@
 use codec::{Decode, Encode};
 use frame_support::{weights::Weight, RuntimeDebug};
 
+/// Identifier of the target chain asset that is used to pay dispatch fee.
+pub type AssetId = u32;
+
 /// Where message dispatch fee is paid?
 #[derive(Encode, Decode, RuntimeDebug, Clone, Copy, PartialEq, Eq)]
 pub enum DispatchFeePayment {
@@ -31,6 +34,17 @@ pub enum DispatchFeePayment {
 	/// do any direct transfers. Instead, it'll return fee related to this message dispatch to the
 	/// relayer.
 	AtTargetChain,
+	/// The dispatch fee is paid at the target chain, using given non-native asset of the target chain.
+	///
+	/// Apart from the asset that is used to pay the fee, this is the same as `AtTargetChain`.
+	AtTargetChainInAsset(AssetId),
+}
+
+impl DispatchFeePayment {
+	/// Returns true if the dispatch fee is paid at the target chain (in any asset).
+	pub fn is_paid_at_target_chain(&self) -> bool {
+		!matches!(*self, DispatchFeePayment::AtSourceChain)
+	}
 }
 
 /// Message dispatch result.
@@ -0,0 +1,119 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for writing versioned storage migrations of bridge pallets.
+
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::{RuntimeDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Single step of the pallet storage migration.
+///
+/// The step upgrades pallet storage from the `FROM` version to the `TO` version.
+pub trait StorageMigrationStep {
+	/// Storage version that this step is able to migrate from.
+	const FROM: u16;
+	/// Storage version of the pallet after this step is applied.
+	const TO: u16;
+
+	/// Migrate pallet storage.
+	///
+	/// Returns weight, consumed by the migration.
+	fn migrate() -> Weight;
+
+	/// Verify pallet storage before the migration.
+	///
+	/// Only called if the on-chain storage version is equal to `FROM`.
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		Ok(())
+	}
+
+	/// Verify pallet storage after the migration.
+	///
+	/// Only called if the on-chain storage version is equal to `TO`.
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		Ok(())
+	}
+}
+
+/// Runtime upgrade that applies given migration step if the on-chain storage version
+/// of the pallet is equal to the `Step::FROM`.
+///
+/// After the step is applied, the on-chain storage version is set to `Step::TO`. If the
+/// on-chain storage version differs from the `Step::FROM`, the step is ignored. So
+/// the upgrade may safely be executed several times.
+pub struct VersionedMigration<Pallet, Step, DbWeight>(PhantomData<(Pallet, Step, DbWeight)>);
+
+impl<Pallet, Step, DbWeight> OnRuntimeUpgrade for VersionedMigration<Pallet, Step, DbWeight>
+where
+	Pallet: GetStorageVersion + PalletInfoAccess,
+	Step: StorageMigrationStep,
+	DbWeight: Get<RuntimeDbWeight>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		let on_chain_version = Pallet::on_chain_storage_version();
+		if on_chain_version != StorageVersion::new(Step::FROM) {
+			log::info!(
+				target: "runtime::bridge",
+				"Skipping {} storage migration from version {} to version {}: on-chain version is {:?}",
+				Pallet::name(),
+				Step::FROM,
+				Step::TO,
+				on_chain_version,
+			);
+			return DbWeight::get().reads(1);
+		}
+
+		let weight = Step::migrate();
+		StorageVersion::new(Step::TO).put::<Pallet>();
+
+		log::info!(
+			target: "runtime::bridge",
+			"Migrated {} storage from version {} to version {}",
+			Pallet::name(),
+			Step::FROM,
+			Step::TO,
+		);
+
+		weight.saturating_add(DbWeight::get().reads_writes(1, 1))
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		if Pallet::on_chain_storage_version() != StorageVersion::new(Step::FROM) {
+			return Ok(());
+		}
+
+		Step::pre_upgrade()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		let on_chain_version = Pallet::on_chain_storage_version();
+		if on_chain_version < StorageVersion::new(Step::TO) {
+			return Err("On-chain storage version is lower than the version after migration");
+		}
+		if on_chain_version != StorageVersion::new(Step::TO) {
+			return Ok(());
+		}
+
+		Step::post_upgrade()
+	}
+}
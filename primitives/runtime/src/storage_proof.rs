@@ -17,7 +17,7 @@
 //! Logic for checking Substrate storage proofs.
 
 use hash_db::{HashDB, Hasher, EMPTY_PREFIX};
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{DispatchError, RuntimeDebug};
 use sp_std::vec::Vec;
 use sp_trie::{read_trie_value, Layout, MemoryDB, StorageProof};
 
@@ -62,6 +62,27 @@ pub enum Error {
 	StorageValueUnavailable,
 }
 
+/// Something that tracks finalized headers of the bridged chain and is able to verify storage
+/// proofs, crafted at these headers.
+///
+/// This is implemented by the finality pallets (GRANDPA, BEEFY, ...), so that the code that only
+/// needs to verify storage proofs (e.g. messages proofs verification) may work with any of them.
+pub trait FinalizedStorageProofParser {
+	/// Hash of the bridged chain header.
+	type Hash;
+	/// Hasher that is used by the bridged chain trie.
+	type Hasher: Hasher;
+
+	/// Verify that the passed storage proof is valid, given it is crafted using known finalized
+	/// header. If the proof is valid, then the `parse` callback is called and the function returns
+	/// its result.
+	fn parse_finalized_storage_proof<R>(
+		hash: Self::Hash,
+		storage_proof: StorageProof,
+		parse: impl FnOnce(StorageProofChecker<Self::Hasher>) -> R,
+	) -> Result<R, DispatchError>;
+}
+
 /// Return valid storage proof and state root.
 ///
 /// NOTE: This should only be used for **testing**.
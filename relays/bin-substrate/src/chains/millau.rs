@@ -19,9 +19,7 @@
 use crate::cli::{
 	bridge,
 	encode_call::{self, Call, CliEncodeCall},
-	encode_message,
-	send_message::{self, DispatchFeePayment},
-	CliChain,
+	encode_message, send_message, CliChain,
 };
 use bp_message_dispatch::{CallOrigin, MessagePayload};
 use codec::Decode;
@@ -55,6 +53,7 @@ impl CliEncodeCall for Millau {
 						lane.0,
 						payload,
 						fee.cast(),
+						None,
 					))
 				}
 				_ => anyhow::bail!(
@@ -89,7 +88,11 @@ impl CliChain for Millau {
 		match message {
 			encode_message::MessagePayload::Raw { data } => MessagePayload::decode(&mut &*data.0)
 				.map_err(|e| format!("Failed to decode Millau's MessagePayload: {:?}", e)),
-			encode_message::MessagePayload::Call { mut call, mut sender } => {
+			encode_message::MessagePayload::Call {
+				mut call,
+				mut sender,
+				dispatch_fee_payment,
+			} => {
 				type Source = Millau;
 				type Target = relay_rialto_client::Rialto;
 
@@ -105,7 +108,9 @@ impl CliChain for Millau {
 					weight,
 					origin,
 					&call,
-					DispatchFeePayment::AtSourceChain,
+					dispatch_fee_payment
+						.into_dispatch_fee_payment()
+						.map_err(|e| e.to_string())?,
 				))
 			}
 		}
@@ -58,6 +58,8 @@ impl SubstrateMessageLane for MillauMessagesToRialto {
 	const INBOUND_LANE_LATEST_CONFIRMED_NONCE_METHOD: &'static str =
 		bp_millau::FROM_MILLAU_LATEST_CONFIRMED_NONCE_METHOD;
 	const INBOUND_LANE_UNREWARDED_RELAYERS_STATE: &'static str = bp_millau::FROM_MILLAU_UNREWARDED_RELAYERS_STATE;
+	const INBOUND_LANE_API: &'static str = bp_millau::FROM_MILLAU_INBOUND_LANE_API;
+	const INBOUND_LANE_LATEST_QUEUED_NONCE_METHOD: &'static str = bp_millau::FROM_MILLAU_LATEST_QUEUED_NONCE_METHOD;
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = bp_millau::BEST_FINALIZED_MILLAU_HEADER_METHOD;
 	const BEST_FINALIZED_TARGET_HEADER_ID_AT_SOURCE: &'static str = bp_rialto::BEST_FINALIZED_RIALTO_HEADER_METHOD;
@@ -128,7 +128,7 @@ mod tests {
 			call.get_dispatch_info().weight,
 			bp_message_dispatch::CallOrigin::SourceRoot,
 			&call,
-			send_message::DispatchFeePayment::AtSourceChain,
+			bp_runtime::messages::DispatchFeePayment::AtSourceChain,
 		);
 		assert_eq!(Millau::verify_message(&payload), Ok(()));
 
@@ -139,7 +139,7 @@ mod tests {
 			call.get_dispatch_info().weight,
 			bp_message_dispatch::CallOrigin::SourceRoot,
 			&call,
-			send_message::DispatchFeePayment::AtSourceChain,
+			bp_runtime::messages::DispatchFeePayment::AtSourceChain,
 		);
 		assert!(Millau::verify_message(&payload).is_err());
 	}
@@ -167,7 +167,7 @@ mod tests {
 			maximal_dispatch_weight,
 			bp_message_dispatch::CallOrigin::SourceRoot,
 			&call,
-			send_message::DispatchFeePayment::AtSourceChain,
+			bp_runtime::messages::DispatchFeePayment::AtSourceChain,
 		);
 		assert_eq!(Millau::verify_message(&payload), Ok(()));
 
@@ -176,7 +176,7 @@ mod tests {
 			maximal_dispatch_weight + 1,
 			bp_message_dispatch::CallOrigin::SourceRoot,
 			&call,
-			send_message::DispatchFeePayment::AtSourceChain,
+			bp_runtime::messages::DispatchFeePayment::AtSourceChain,
 		);
 		assert!(Millau::verify_message(&payload).is_err());
 	}
@@ -194,7 +194,7 @@ mod tests {
 			maximal_dispatch_weight,
 			bp_message_dispatch::CallOrigin::SourceRoot,
 			&call,
-			send_message::DispatchFeePayment::AtSourceChain,
+			bp_runtime::messages::DispatchFeePayment::AtSourceChain,
 		);
 		assert_eq!(Rialto::verify_message(&payload), Ok(()));
 
@@ -203,7 +203,7 @@ mod tests {
 			maximal_dispatch_weight + 1,
 			bp_message_dispatch::CallOrigin::SourceRoot,
 			&call,
-			send_message::DispatchFeePayment::AtSourceChain,
+			bp_runtime::messages::DispatchFeePayment::AtSourceChain,
 		);
 		assert!(Rialto::verify_message(&payload).is_err());
 	}
@@ -19,9 +19,7 @@
 use crate::cli::{
 	bridge,
 	encode_call::{self, Call, CliEncodeCall},
-	encode_message,
-	send_message::{self, DispatchFeePayment},
-	CliChain,
+	encode_message, send_message, CliChain,
 };
 use bp_message_dispatch::{CallOrigin, MessagePayload};
 use codec::Decode;
@@ -52,7 +50,7 @@ impl CliEncodeCall for Rialto {
 				bridge::RIALTO_TO_MILLAU_INDEX => {
 					let payload = Decode::decode(&mut &*payload.0)?;
 					rialto_runtime::Call::BridgeMillauMessages(rialto_runtime::MessagesCall::send_message(
-						lane.0, payload, fee.0,
+						lane.0, payload, fee.0, None,
 					))
 				}
 				_ => anyhow::bail!(
@@ -86,7 +84,11 @@ impl CliChain for Rialto {
 		match message {
 			encode_message::MessagePayload::Raw { data } => MessagePayload::decode(&mut &*data.0)
 				.map_err(|e| format!("Failed to decode Rialto's MessagePayload: {:?}", e)),
-			encode_message::MessagePayload::Call { mut call, mut sender } => {
+			encode_message::MessagePayload::Call {
+				mut call,
+				mut sender,
+				dispatch_fee_payment,
+			} => {
 				type Source = Rialto;
 				type Target = relay_millau_client::Millau;
 
@@ -102,7 +104,9 @@ impl CliChain for Rialto {
 					weight,
 					origin,
 					&call,
-					DispatchFeePayment::AtSourceChain,
+					dispatch_fee_payment
+						.into_dispatch_fee_payment()
+						.map_err(|e| e.to_string())?,
 				))
 			}
 		}
@@ -52,7 +52,9 @@ impl CliEncodeCall for Rococo {
 				bridge::ROCOCO_TO_WOCOCO_INDEX => {
 					let payload = Decode::decode(&mut &*payload.0)?;
 					relay_rococo_client::runtime::Call::BridgeMessagesWococo(
-						relay_rococo_client::runtime::BridgeMessagesWococoCall::send_message(lane.0, payload, fee.0),
+						relay_rococo_client::runtime::BridgeMessagesWococoCall::send_message(
+							lane.0, payload, fee.0, None,
+						),
 					)
 				}
 				_ => anyhow::bail!(
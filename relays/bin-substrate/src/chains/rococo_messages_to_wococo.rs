@@ -57,6 +57,8 @@ impl SubstrateMessageLane for RococoMessagesToWococo {
 	const INBOUND_LANE_LATEST_CONFIRMED_NONCE_METHOD: &'static str =
 		bp_rococo::FROM_ROCOCO_LATEST_CONFIRMED_NONCE_METHOD;
 	const INBOUND_LANE_UNREWARDED_RELAYERS_STATE: &'static str = bp_rococo::FROM_ROCOCO_UNREWARDED_RELAYERS_STATE;
+	const INBOUND_LANE_API: &'static str = bp_rococo::FROM_ROCOCO_INBOUND_LANE_API;
+	const INBOUND_LANE_LATEST_QUEUED_NONCE_METHOD: &'static str = bp_rococo::FROM_ROCOCO_LATEST_QUEUED_NONCE_METHOD;
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = bp_rococo::BEST_FINALIZED_ROCOCO_HEADER_METHOD;
 	const BEST_FINALIZED_TARGET_HEADER_ID_AT_SOURCE: &'static str = bp_wococo::BEST_FINALIZED_WOCOCO_HEADER_METHOD;
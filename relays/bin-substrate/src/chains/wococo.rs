@@ -46,7 +46,9 @@ impl CliEncodeCall for Wococo {
 				bridge::WOCOCO_TO_ROCOCO_INDEX => {
 					let payload = Decode::decode(&mut &*payload.0)?;
 					relay_wococo_client::runtime::Call::BridgeMessagesRococo(
-						relay_wococo_client::runtime::BridgeMessagesRococoCall::send_message(lane.0, payload, fee.0),
+						relay_wococo_client::runtime::BridgeMessagesRococoCall::send_message(
+							lane.0, payload, fee.0, None,
+						),
 					)
 				}
 				_ => anyhow::bail!(
@@ -63,6 +63,12 @@ macro_rules! select_full_bridge {
 				// Relay-messages
 				#[allow(unused_imports)]
 				use crate::chains::millau_messages_to_rialto::run as relay_messages;
+				#[allow(unused_imports)]
+				use crate::chains::millau_messages_to_rialto::MillauMessagesToRialto as MessagesLane;
+				#[allow(unused_imports)]
+				use bp_rialto::TO_RIALTO_LANES_METHOD as LANES_METHOD;
+				#[allow(unused_imports)]
+				use bp_rialto::TO_RIALTO_OUTBOUND_LANE_API as OUTBOUND_LANE_API;
 
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
@@ -85,6 +91,12 @@ macro_rules! select_full_bridge {
 				// Relay-messages
 				#[allow(unused_imports)]
 				use crate::chains::rialto_messages_to_millau::run as relay_messages;
+				#[allow(unused_imports)]
+				use crate::chains::rialto_messages_to_millau::RialtoMessagesToMillau as MessagesLane;
+				#[allow(unused_imports)]
+				use bp_millau::TO_MILLAU_LANES_METHOD as LANES_METHOD;
+				#[allow(unused_imports)]
+				use bp_millau::TO_MILLAU_OUTBOUND_LANE_API as OUTBOUND_LANE_API;
 
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
@@ -108,6 +120,12 @@ macro_rules! select_full_bridge {
 				// Relay-messages
 				#[allow(unused_imports)]
 				use crate::chains::rococo_messages_to_wococo::run as relay_messages;
+				#[allow(unused_imports)]
+				use crate::chains::rococo_messages_to_wococo::RococoMessagesToWococo as MessagesLane;
+				#[allow(unused_imports)]
+				use bp_wococo::TO_WOCOCO_LANES_METHOD as LANES_METHOD;
+				#[allow(unused_imports)]
+				use bp_wococo::TO_WOCOCO_OUTBOUND_LANE_API as OUTBOUND_LANE_API;
 
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
@@ -130,6 +148,12 @@ macro_rules! select_full_bridge {
 				// Relay-messages
 				#[allow(unused_imports)]
 				use crate::chains::wococo_messages_to_rococo::run as relay_messages;
+				#[allow(unused_imports)]
+				use crate::chains::wococo_messages_to_rococo::WococoMessagesToRococo as MessagesLane;
+				#[allow(unused_imports)]
+				use bp_rococo::TO_ROCOCO_LANES_METHOD as LANES_METHOD;
+				#[allow(unused_imports)]
+				use bp_rococo::TO_ROCOCO_OUTBOUND_LANE_API as OUTBOUND_LANE_API;
 
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
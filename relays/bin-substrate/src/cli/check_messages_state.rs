@@ -0,0 +1,118 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use codec::Decode;
+use sp_core::storage::Storage;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Check invariants of the messages pallet storage, using the state snapshot.
+#[derive(StructOpt)]
+pub struct CheckMessagesState {
+	/// A chain, which state snapshot is checked.
+	#[structopt(possible_values = SnapshotChain::VARIANTS, case_insensitive = true)]
+	chain: SnapshotChain,
+	/// Path to the state snapshot file.
+	///
+	/// The file must contain SCALE-encoded vector of all (key, value) pairs of the chain state.
+	/// This is the format of snapshots, created by the `try-runtime` tool.
+	#[structopt(long)]
+	snapshot: PathBuf,
+}
+
+/// Chain, which state snapshot we're going to check.
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SnapshotChain {
+	Millau,
+	Rialto,
+}
+
+macro_rules! select_chain {
+	($chain: expr, $generic: tt) => {
+		match $chain {
+			SnapshotChain::Millau => {
+				type Runtime = millau_runtime::Runtime;
+				type MessagesInstance = millau_runtime::WithRialtoMessagesInstance;
+
+				$generic
+			}
+			SnapshotChain::Rialto => {
+				type Runtime = rialto_runtime::Runtime;
+				type MessagesInstance = rialto_runtime::WithMillauMessagesInstance;
+
+				$generic
+			}
+		}
+	};
+}
+
+impl CheckMessagesState {
+	/// Read the snapshot and check messages pallet storage invariants.
+	fn check(&self) -> anyhow::Result<()> {
+		let encoded_snapshot = std::fs::read(&self.snapshot)?;
+		let snapshot = Vec::<(Vec<u8>, Vec<u8>)>::decode(&mut &encoded_snapshot[..])
+			.map_err(|e| anyhow::format_err!("Failed to decode state snapshot: {:?}", e))?;
+		log::info!(
+			target: "bridge",
+			"Read {} storage entries from the {:?} state snapshot",
+			snapshot.len(),
+			self.chain,
+		);
+
+		let mut ext = sp_io::TestExternalities::new(Storage {
+			top: snapshot.into_iter().collect(),
+			children_default: Default::default(),
+		});
+		select_chain!(self.chain, {
+			ext.execute_with(pallet_bridge_messages::Pallet::<Runtime, MessagesInstance>::do_try_state)
+				.map_err(|e| anyhow::format_err!("Broken messages pallet invariant: {}", e))
+		})
+	}
+
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		self.check()?;
+		log::info!(
+			target: "bridge",
+			"All {:?} messages pallet invariants are satisfied",
+			self.chain,
+		);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+
+	#[test]
+	fn empty_state_snapshot_is_consistent() {
+		let dir = tempdir::TempDir::new("check-messages-state").unwrap();
+		let snapshot = dir.path().join("snapshot");
+		std::fs::write(&snapshot, Vec::<(Vec<u8>, Vec<u8>)>::new().encode()).unwrap();
+
+		let command = CheckMessagesState::from_iter(vec![
+			"check-messages-state",
+			"millau",
+			"--snapshot",
+			snapshot.to_str().unwrap(),
+		]);
+		assert!(command.check().is_ok());
+	}
+}
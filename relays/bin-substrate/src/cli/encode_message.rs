@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{bridge::FullBridge, AccountId, CliChain, HexBytes};
+use crate::cli::{bridge::FullBridge, send_message::DispatchFeePaymentParams, AccountId, CliChain, HexBytes};
 use crate::select_full_bridge;
 use structopt::StructOpt;
 use strum::VariantNames;
@@ -35,6 +35,9 @@ pub enum MessagePayload {
 		/// SS58 encoded Source account that will send the payload.
 		#[structopt(long)]
 		sender: AccountId,
+		/// Dispatch fee payment parameters.
+		#[structopt(flatten)]
+		dispatch_fee_payment: DispatchFeePaymentParams,
 	},
 }
 
@@ -85,6 +85,7 @@ pub(crate) async fn estimate_message_delivery_and_dispatch_fee<Fee: Decode, C: C
 mod tests {
 	use super::*;
 	use crate::cli::encode_call;
+	use crate::cli::send_message::{DispatchFeePayment, DispatchFeePaymentParams};
 	use sp_core::crypto::Ss58Codec;
 
 	#[test]
@@ -122,9 +123,46 @@ mod tests {
 					call: encode_call::Call::Remark {
 						remark_payload: Some(HexBytes(vec![0x12, 0x34])),
 						remark_size: None,
-					}
+					},
+					dispatch_fee_payment: DispatchFeePaymentParams {
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						dispatch_fee_asset: None,
+					},
 				}
 			}
 		);
 	}
+
+	#[test]
+	fn should_parse_dispatch_fee_payment_options() {
+		// given
+		let alice = sp_keyring::AccountKeyring::Alice.to_account_id().to_ss58check();
+
+		// when
+		let res = EstimateFee::from_iter(vec![
+			"estimate_fee",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"call",
+			"--sender",
+			&alice,
+			"--dispatch-fee-payment",
+			"at-target-chain-in-asset",
+			"--dispatch-fee-asset",
+			"42",
+			"remark",
+		]);
+
+		// then
+		match res.payload {
+			crate::cli::encode_message::MessagePayload::Call {
+				dispatch_fee_payment, ..
+			} => assert_eq!(
+				dispatch_fee_payment.into_dispatch_fee_payment().unwrap(),
+				bp_runtime::messages::DispatchFeePayment::AtTargetChainInAsset(42),
+			),
+			_ => panic!("Unexpected payload: {:?}", res.payload),
+		}
+	}
 }
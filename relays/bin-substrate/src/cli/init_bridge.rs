@@ -35,6 +35,26 @@ pub struct InitBridge {
 	target: TargetConnectionParams,
 	#[structopt(flatten)]
 	target_sign: TargetSigningParams,
+	/// Forcibly re-initialize already initialized bridge, using the `force_reinitialize` call.
+	///
+	/// The call must be signed by the bridge pallet owner (or wrapped into sudo call).
+	#[structopt(long)]
+	force: bool,
+	/// Remove all previously imported headers when forcibly re-initializing the bridge.
+	#[structopt(long, requires = "force")]
+	prune_imported_headers: bool,
+}
+
+/// The way bridge pallet is initialized.
+#[derive(Clone, Copy, Debug)]
+enum InitializationMode {
+	/// Initialize the bridge for the first time, using the `initialize` call.
+	Initialize,
+	/// Forcibly re-initialize the bridge, using the `force_reinitialize` call.
+	ForceReinitialize {
+		/// Whether previously imported headers should be removed from the storage.
+		prune_imported_headers: bool,
+	},
 }
 
 #[derive(Debug, EnumString, EnumVariantNames)]
@@ -57,11 +77,16 @@ macro_rules! select_bridge {
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
+					mode: InitializationMode,
 				) -> <Target as Chain>::Call {
-					rialto_runtime::SudoCall::sudo(Box::new(
-						rialto_runtime::BridgeGrandpaMillauCall::initialize(init_data).into(),
-					))
-					.into()
+					use rialto_runtime::BridgeGrandpaMillauCall;
+					let initialize_call = match mode {
+						InitializationMode::Initialize => BridgeGrandpaMillauCall::initialize(init_data),
+						InitializationMode::ForceReinitialize { prune_imported_headers } => {
+							BridgeGrandpaMillauCall::force_reinitialize(init_data, prune_imported_headers)
+						}
+					};
+					rialto_runtime::SudoCall::sudo(Box::new(initialize_call.into())).into()
 				}
 
 				$generic
@@ -72,11 +97,18 @@ macro_rules! select_bridge {
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
+					mode: InitializationMode,
 				) -> <Target as Chain>::Call {
-					let initialize_call = millau_runtime::BridgeGrandpaRialtoCall::<
+					type BridgeGrandpaRialtoCall = millau_runtime::BridgeGrandpaRialtoCall<
 						millau_runtime::Runtime,
 						millau_runtime::RialtoGrandpaInstance,
-					>::initialize(init_data);
+					>;
+					let initialize_call = match mode {
+						InitializationMode::Initialize => BridgeGrandpaRialtoCall::initialize(init_data),
+						InitializationMode::ForceReinitialize { prune_imported_headers } => {
+							BridgeGrandpaRialtoCall::force_reinitialize(init_data, prune_imported_headers)
+						}
+					};
 					millau_runtime::SudoCall::sudo(Box::new(initialize_call.into())).into()
 				}
 
@@ -88,14 +120,21 @@ macro_rules! select_bridge {
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
+					mode: InitializationMode,
 				) -> <Target as Chain>::Call {
 					// at Westend -> Millau initialization we're not using sudo, because otherwise our deployments
 					// may fail, because we need to initialize both Rialto -> Millau and Westend -> Millau bridge.
 					// => since there's single possible sudo account, one of transaction may fail with duplicate nonce error
-					millau_runtime::BridgeGrandpaWestendCall::<
+					type BridgeGrandpaWestendCall = millau_runtime::BridgeGrandpaWestendCall<
 						millau_runtime::Runtime,
 						millau_runtime::WestendGrandpaInstance,
-					>::initialize(init_data)
+					>;
+					match mode {
+						InitializationMode::Initialize => BridgeGrandpaWestendCall::initialize(init_data),
+						InitializationMode::ForceReinitialize { prune_imported_headers } => {
+							BridgeGrandpaWestendCall::force_reinitialize(init_data, prune_imported_headers)
+						}
+					}
 					.into()
 				}
 
@@ -107,10 +146,15 @@ macro_rules! select_bridge {
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
+					mode: InitializationMode,
 				) -> <Target as Chain>::Call {
-					relay_wococo_client::runtime::Call::BridgeGrandpaRococo(
-						relay_wococo_client::runtime::BridgeGrandpaRococoCall::initialize(init_data),
-					)
+					use relay_wococo_client::runtime::BridgeGrandpaRococoCall;
+					relay_wococo_client::runtime::Call::BridgeGrandpaRococo(match mode {
+						InitializationMode::Initialize => BridgeGrandpaRococoCall::initialize(init_data),
+						InitializationMode::ForceReinitialize { prune_imported_headers } => {
+							BridgeGrandpaRococoCall::force_reinitialize(init_data, prune_imported_headers)
+						}
+					})
 				}
 
 				$generic
@@ -121,10 +165,15 @@ macro_rules! select_bridge {
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
+					mode: InitializationMode,
 				) -> <Target as Chain>::Call {
-					relay_rococo_client::runtime::Call::BridgeGrandpaWococo(
-						relay_rococo_client::runtime::BridgeGrandpaWococoCall::initialize(init_data),
-					)
+					use relay_rococo_client::runtime::BridgeGrandpaWococoCall;
+					relay_rococo_client::runtime::Call::BridgeGrandpaWococo(match mode {
+						InitializationMode::Initialize => BridgeGrandpaWococoCall::initialize(init_data),
+						InitializationMode::ForceReinitialize { prune_imported_headers } => {
+							BridgeGrandpaWococoCall::force_reinitialize(init_data, prune_imported_headers)
+						}
+					})
 				}
 
 				$generic
@@ -140,6 +189,13 @@ impl InitBridge {
 			let source_client = self.source.to_client::<Source>().await?;
 			let target_client = self.target.to_client::<Target>().await?;
 			let target_sign = self.target_sign.to_keypair::<Target>()?;
+			let mode = if self.force {
+				InitializationMode::ForceReinitialize {
+					prune_imported_headers: self.prune_imported_headers,
+				}
+			} else {
+				InitializationMode::Initialize
+			};
 
 			substrate_relay_helper::headers_initialize::initialize(
 				source_client,
@@ -151,7 +207,7 @@ impl InitBridge {
 							*target_client.genesis_hash(),
 							&target_sign,
 							relay_substrate_client::TransactionEra::immortal(),
-							UnsignedTransaction::new(encode_init_bridge(initialization_data), transaction_nonce),
+							UnsignedTransaction::new(encode_init_bridge(initialization_data, mode), transaction_nonce),
 						)
 						.encode(),
 					)
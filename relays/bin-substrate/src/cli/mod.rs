@@ -30,6 +30,7 @@ pub(crate) mod encode_message;
 pub(crate) mod estimate_fee;
 pub(crate) mod send_message;
 
+mod check_messages_state;
 mod derive_account;
 mod init_bridge;
 mod relay_headers;
@@ -89,6 +90,10 @@ pub enum Command {
 	DeriveAccount(derive_account::DeriveAccount),
 	/// Resubmit transactions with increased tip if they are stalled.
 	ResubmitTransactions(resubmit_transactions::ResubmitTransactions),
+	/// Check invariants of the messages pallet storage, using the chain state snapshot.
+	///
+	/// The check is performed offline, so it doesn't require connection to the chain node.
+	CheckMessagesState(check_messages_state::CheckMessagesState),
 }
 
 impl Command {
@@ -120,6 +125,7 @@ impl Command {
 			Self::EstimateFee(arg) => arg.run().await?,
 			Self::DeriveAccount(arg) => arg.run().await?,
 			Self::ResubmitTransactions(arg) => arg.run().await?,
+			Self::CheckMessagesState(arg) => arg.run().await?,
 		}
 		Ok(())
 	}
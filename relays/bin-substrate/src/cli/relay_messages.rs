@@ -14,10 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
+use codec::Decode;
+use futures::{stream::FuturesUnordered, FutureExt, StreamExt, TryFutureExt};
+use std::{collections::BTreeSet, time::Duration};
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
 
-use substrate_relay_helper::messages_lane::MessagesRelayParams;
+use bp_messages::{LaneId, LaneState};
+use relay_substrate_client::{Chain, Client};
+use relay_utils::metrics::MetricsParams;
+use sp_core::Bytes;
+use substrate_relay_helper::messages_lane::{MessagesRelayParams, SubstrateMessageLane};
 
 use crate::cli::bridge::FullBridge;
 use crate::cli::{
@@ -51,9 +58,12 @@ pub struct RelayMessages {
 	/// A bridge instance to relay messages for.
 	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
 	bridge: FullBridge,
-	/// Hex-encoded lane id that should be served by the relay. Defaults to `00000000`.
-	#[structopt(long, default_value = "00000000")]
-	lane: HexLaneId,
+	/// Hex-encoded lane ids that should be served by the relay. If not specified, the relay
+	/// serves all active lanes from the lanes registry of the source chain. The registry is re-read
+	/// periodically, so lanes that are opened later are also served. If the source chain runtime
+	/// has no lanes registry, the `00000000` lane is served.
+	#[structopt(long)]
+	lane: Vec<HexLaneId>,
 	#[structopt(long, possible_values = RelayerMode::VARIANTS, case_insensitive = true, default_value = "rational")]
 	relayer_mode: RelayerMode,
 	#[structopt(flatten)]
@@ -77,23 +87,132 @@ impl RelayMessages {
 			let target_client = self.target.to_client::<Target>().await?;
 			let target_sign = self.target_sign.to_keypair::<Target>()?;
 
-			relay_messages(MessagesRelayParams {
-				source_client,
-				source_sign,
-				target_client,
-				target_sign,
-				source_to_target_headers_relay: None,
-				target_to_source_headers_relay: None,
-				lane_id: self.lane.into(),
-				relayer_mode: self.relayer_mode.into(),
-				metrics_params: self.prometheus_params.into(),
-			})
-			.await
-			.map_err(|e| anyhow::format_err!("{}", e))
+			// lanes from the lanes registry are re-read periodically, so that relays for new lanes
+			// are started without restarting the whole process
+			let explicit_lanes: Vec<LaneId> = self.lane.into_iter().map(Into::into).collect();
+			let refresh_lanes = explicit_lanes.is_empty();
+			let lanes: Vec<LaneId> = if refresh_lanes {
+				active_lanes(&source_client, OUTBOUND_LANE_API, LANES_METHOD).await?
+			} else {
+				explicit_lanes
+			};
+			if lanes.is_empty() {
+				return Err(anyhow::format_err!(
+					"There are no active lanes in the {} lanes registry",
+					Source::NAME,
+				));
+			}
+
+			let metrics_params: MetricsParams = self.prometheus_params.into();
+			let metrics_params = relay_utils::relay_metrics(None, metrics_params).into_params();
+			relay_utils::relay_metrics(None, metrics_params.clone())
+				.expose()
+				.await
+				.map_err(|e| anyhow::format_err!("{}", e))?;
+
+			let relayer_mode: messages_relay::message_lane_loop::RelayerMode = self.relayer_mode.into();
+			let start_lane_relay = |lane: LaneId| {
+				log::info!(target: "bridge", "Starting relay for lane {}", hex::encode(lane));
+				relay_messages(MessagesRelayParams {
+					source_client: source_client.clone(),
+					source_sign: source_sign.clone(),
+					target_client: target_client.clone(),
+					target_sign: target_sign.clone(),
+					source_to_target_headers_relay: None,
+					target_to_source_headers_relay: None,
+					lane_id: lane,
+					relayer_mode,
+					metrics_params: metrics_params.clone().disable().metrics_prefix(
+						messages_relay::message_lane_loop::metrics_prefix::<
+							<MessagesLane as SubstrateMessageLane>::MessageLane,
+						>(&lane),
+					),
+				})
+				.map_err(|e| anyhow::format_err!("{}", e))
+				.boxed()
+			};
+
+			let mut served_lanes = BTreeSet::new();
+			let mut message_relays = FuturesUnordered::new();
+			let mut lanes = lanes;
+			loop {
+				for lane in lanes {
+					if served_lanes.insert(lane) {
+						message_relays.push(start_lane_relay(lane));
+					}
+				}
+
+				if !refresh_lanes {
+					return message_relays
+						.next()
+						.await
+						.expect("there's at least one lane served by the relay; qed");
+				}
+
+				futures::select! {
+					result = message_relays.select_next_some() => return result,
+					_ = async_std::task::sleep(LANES_REFRESH_INTERVAL).fuse() => {},
+				}
+
+				// relays for lanes that are no longer active are left running - they still need to
+				// deliver confirmations and there's nothing to do when the lane is drained
+				lanes = match active_lanes(&source_client, OUTBOUND_LANE_API, LANES_METHOD).await {
+					Ok(lanes) => lanes,
+					Err(error) => {
+						log::warn!(
+							target: "bridge",
+							"Failed to refresh lanes registry of {}: {:?}",
+							Source::NAME,
+							error,
+						);
+						Vec::new()
+					}
+				};
+			}
 		})
 	}
 }
 
+/// Lane that is served by the relay if the source chain runtime has no lanes registry.
+const DEFAULT_LANE: LaneId = [0, 0, 0, 0];
+
+/// Version of the outbound lane runtime API, where the `lanes` method has been added.
+const LANES_METHOD_API_VERSION: u32 = 2;
+
+/// Interval of reading lanes registry of the source chain.
+const LANES_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Read lanes registry of the source chain and return all lanes that need to be served by relayers.
+///
+/// Runtimes that have been deployed before the lanes registry has been introduced, are not
+/// implementing the lanes runtime API method. The relay serves the default lane of such chains.
+async fn active_lanes<C: Chain>(
+	client: &Client<C>,
+	outbound_lane_api: &str,
+	lanes_method: &str,
+) -> anyhow::Result<Vec<LaneId>> {
+	let runtime_version = client.runtime_version().await?;
+	let outbound_lane_api_id = sp_core::hashing::blake2_64(outbound_lane_api.as_bytes());
+	if !runtime_version.has_api_with(&outbound_lane_api_id, |version| version >= LANES_METHOD_API_VERSION) {
+		log::info!(
+			target: "bridge",
+			"Runtime of {} has no lanes registry. Serving default lane {}",
+			C::NAME,
+			hex::encode(DEFAULT_LANE),
+		);
+		return Ok(vec![DEFAULT_LANE]);
+	}
+
+	let encoded_response = client.state_call(lanes_method.into(), Bytes(Vec::new()), None).await?;
+	let lanes: Vec<(LaneId, LaneState)> =
+		Decode::decode(&mut &encoded_response.0[..]).map_err(relay_substrate_client::Error::ResponseParseFailed)?;
+	Ok(lanes
+		.into_iter()
+		.filter(|(_, lane_state)| lane_state.is_active())
+		.map(|(lane, _)| lane)
+		.collect())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -132,4 +251,36 @@ mod tests {
 			RelayerMode::Altruistic,
 		);
 	}
+
+	#[test]
+	fn should_accept_multiple_lanes() {
+		assert_eq!(
+			RelayMessages::from_iter(vec![
+				"relay-messages",
+				"rialto-to-millau",
+				"--source-port=0",
+				"--source-signer=//Alice",
+				"--target-port=0",
+				"--target-signer=//Alice",
+				"--lane=00000000",
+				"--lane=73776170",
+			])
+			.lane,
+			vec![HexLaneId([0, 0, 0, 0]), HexLaneId(*b"swap")],
+		);
+	}
+
+	#[test]
+	fn should_discover_lanes_if_no_lanes_specified() {
+		assert!(RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+		])
+		.lane
+		.is_empty());
+	}
 }
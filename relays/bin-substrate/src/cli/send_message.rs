@@ -22,7 +22,7 @@ use crate::cli::{
 	TargetSigningParams,
 };
 use bp_message_dispatch::{CallOrigin, MessagePayload};
-use bp_runtime::BalanceOf;
+use bp_runtime::{messages::AssetId, BalanceOf};
 use codec::Encode;
 use frame_support::weights::Weight;
 use relay_substrate_client::{Chain, TransactionSignScheme, UnsignedTransaction};
@@ -32,7 +32,7 @@ use std::fmt::Debug;
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
 
-/// Relayer operating mode.
+/// Where dispatch fee is paid?
 #[derive(Debug, EnumString, EnumVariantNames, Clone, Copy, PartialEq, Eq)]
 #[strum(serialize_all = "kebab_case")]
 pub enum DispatchFeePayment {
@@ -40,13 +40,42 @@ pub enum DispatchFeePayment {
 	AtSourceChain,
 	/// The dispatch fee is paid at the target chain.
 	AtTargetChain,
+	/// The dispatch fee is paid at the target chain, using non-native asset of the target chain.
+	AtTargetChainInAsset,
 }
 
-impl From<DispatchFeePayment> for bp_runtime::messages::DispatchFeePayment {
-	fn from(dispatch_fee_payment: DispatchFeePayment) -> Self {
-		match dispatch_fee_payment {
-			DispatchFeePayment::AtSourceChain => Self::AtSourceChain,
-			DispatchFeePayment::AtTargetChain => Self::AtTargetChain,
+/// Dispatch fee payment parameters.
+#[derive(StructOpt, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DispatchFeePaymentParams {
+	/// Where dispatch fee is paid?
+	#[structopt(
+		long,
+		possible_values = DispatchFeePayment::VARIANTS,
+		case_insensitive = true,
+		default_value = "at-source-chain",
+	)]
+	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Id of the target chain asset that is used to pay dispatch fee. Required if dispatch fee is paid
+	/// at the target chain in asset.
+	#[structopt(long)]
+	pub dispatch_fee_asset: Option<AssetId>,
+}
+
+impl DispatchFeePaymentParams {
+	/// Convert CLI parameters into dispatch fee payment option.
+	pub fn into_dispatch_fee_payment(self) -> anyhow::Result<bp_runtime::messages::DispatchFeePayment> {
+		match (self.dispatch_fee_payment, self.dispatch_fee_asset) {
+			(DispatchFeePayment::AtSourceChain, None) => Ok(bp_runtime::messages::DispatchFeePayment::AtSourceChain),
+			(DispatchFeePayment::AtTargetChain, None) => Ok(bp_runtime::messages::DispatchFeePayment::AtTargetChain),
+			(DispatchFeePayment::AtTargetChainInAsset, Some(asset_id)) => {
+				Ok(bp_runtime::messages::DispatchFeePayment::AtTargetChainInAsset(asset_id))
+			}
+			(DispatchFeePayment::AtTargetChainInAsset, None) => Err(anyhow::format_err!(
+				"--dispatch-fee-asset is required when dispatch fee is paid at the target chain in asset"
+			)),
+			(_, Some(_)) => Err(anyhow::format_err!(
+				"--dispatch-fee-asset may only be used when dispatch fee is paid at the target chain in asset"
+			)),
 		}
 	}
 }
@@ -66,17 +95,15 @@ pub struct SendMessage {
 	/// Hex-encoded lane id. Defaults to `00000000`.
 	#[structopt(long, default_value = "00000000")]
 	lane: HexLaneId,
-	/// Where dispatch fee is paid?
-	#[structopt(
-		long,
-		possible_values = DispatchFeePayment::VARIANTS,
-		case_insensitive = true,
-		default_value = "at-source-chain",
-	)]
-	dispatch_fee_payment: DispatchFeePayment,
+	#[structopt(flatten)]
+	dispatch_fee_payment: DispatchFeePaymentParams,
 	/// Dispatch weight of the message. If not passed, determined automatically.
 	#[structopt(long)]
 	dispatch_weight: Option<ExplicitOrMaximal<Weight>>,
+	/// Compress the message call. Compressed messages are cheaper to deliver, but the target chain
+	/// spends some additional weight to decompress the call.
+	#[structopt(long)]
+	compress: bool,
 	/// Delivery and dispatch fee in source chain base currency units. If not passed, determined automatically.
 	#[structopt(long)]
 	fee: Option<Balance>,
@@ -100,6 +127,7 @@ impl SendMessage {
 				ref mut message,
 				dispatch_fee_payment,
 				dispatch_weight,
+				compress,
 				origin,
 				bridge,
 				..
@@ -141,9 +169,10 @@ impl SendMessage {
 						}
 					},
 					&target_call,
-					*dispatch_fee_payment,
+					dispatch_fee_payment.into_dispatch_fee_payment()?,
 				)
 			};
+			let payload = if *compress { payload.compress() } else { payload };
 			Ok(payload)
 		})
 	}
@@ -252,7 +281,7 @@ pub(crate) fn message_payload<SAccountId, TPublic, TSignature>(
 	weight: Weight,
 	origin: CallOrigin<SAccountId, TPublic, TSignature>,
 	call: &impl Encode,
-	dispatch_fee_payment: DispatchFeePayment,
+	dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment,
 ) -> MessagePayload<SAccountId, TPublic, TSignature, Vec<u8>>
 where
 	SAccountId: Encode + Debug,
@@ -264,7 +293,7 @@ where
 		spec_version,
 		weight,
 		origin,
-		dispatch_fee_payment: dispatch_fee_payment.into(),
+		dispatch_fee_payment,
 		call: HexBytes::encode(call),
 	};
 
@@ -372,6 +401,33 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn send_compressed_remark_rialto_to_millau() {
+		// given
+		let mut send_message = SendMessage::from_iter(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--compress",
+			"remark",
+			"--remark-payload",
+			"1234",
+		]);
+
+		// when
+		let payload = send_message.encode_payload().unwrap();
+
+		// then
+		assert_eq!(payload.call_encoding(), bp_message_dispatch::CallEncoding::Compressed);
+		assert_eq!(
+			bp_message_dispatch::decompress_call(&payload.call, 1024),
+			Ok(hex!("0001081234").to_vec()),
+		);
+	}
+
 	#[test]
 	fn accepts_send_message_command_without_target_sign_options() {
 		// given
@@ -416,4 +472,53 @@ mod tests {
 			bp_runtime::messages::DispatchFeePayment::AtTargetChain
 		);
 	}
+
+	#[test]
+	fn accepts_dispatch_fee_payment_in_asset() {
+		// given
+		let mut send_message = SendMessage::from_iter(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--dispatch-fee-payment",
+			"at-target-chain-in-asset",
+			"--dispatch-fee-asset",
+			"42",
+			"remark",
+		]);
+
+		// when
+		let payload = send_message.encode_payload().unwrap();
+
+		// then
+		assert_eq!(
+			payload.dispatch_fee_payment,
+			bp_runtime::messages::DispatchFeePayment::AtTargetChainInAsset(42)
+		);
+	}
+
+	#[test]
+	fn rejects_dispatch_fee_payment_in_asset_without_asset_id() {
+		// given
+		let mut send_message = SendMessage::from_iter(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--dispatch-fee-payment",
+			"at-target-chain-in-asset",
+			"remark",
+		]);
+
+		// when
+		let result = send_message.encode_payload();
+
+		// then
+		assert!(result.is_err());
+	}
 }
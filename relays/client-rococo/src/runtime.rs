@@ -90,6 +90,11 @@ pub enum BridgeGrandpaWococoCall {
 	),
 	#[codec(index = 1)]
 	initialize(bp_header_chain::InitializationData<<PolkadotLike as Chain>::Header>),
+	#[codec(index = 4)]
+	force_reinitialize(
+		bp_header_chain::InitializationData<<PolkadotLike as Chain>::Header>,
+		bool,
+	),
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
@@ -105,6 +110,7 @@ pub enum BridgeMessagesWococoCall {
 			Vec<u8>,
 		>,
 		bp_rococo::Balance,
+		Option<bp_rococo::BlockNumber>,
 	),
 	#[codec(index = 5)]
 	receive_messages_proof(
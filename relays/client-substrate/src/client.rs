@@ -243,9 +243,15 @@ impl<C: Chain> Client<C> {
 	}
 
 	/// Read value from runtime storage.
-	pub async fn storage_value<T: Send + Decode + 'static>(&self, storage_key: StorageKey) -> Result<Option<T>> {
+	///
+	/// If `at_block` is `None`, the value is read at the best block.
+	pub async fn storage_value<T: Send + Decode + 'static>(
+		&self,
+		storage_key: StorageKey,
+		at_block: Option<C::Hash>,
+	) -> Result<Option<T>> {
 		self.jsonrpsee_execute(move |client| async move {
-			Substrate::<C>::state_get_storage(&*client, storage_key)
+			Substrate::<C>::state_get_storage(&*client, storage_key, at_block)
 				.await?
 				.map(|encoded_value| T::decode(&mut &encoded_value.0[..]).map_err(Error::ResponseParseFailed))
 				.transpose()
@@ -260,7 +266,7 @@ impl<C: Chain> Client<C> {
 	{
 		self.jsonrpsee_execute(move |client| async move {
 			let storage_key = C::account_info_storage_key(&account);
-			let encoded_account_data = Substrate::<C>::state_get_storage(&*client, storage_key)
+			let encoded_account_data = Substrate::<C>::state_get_storage(&*client, storage_key, None)
 				.await?
 				.ok_or(Error::AccountDoesNotExist)?;
 			let decoded_account_data =
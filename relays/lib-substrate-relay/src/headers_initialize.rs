@@ -20,6 +20,9 @@
 //! `pallet-bridge-grandpa` pallet. This transaction brings initial header
 //! and authorities set from source to target chain. The headers sync starts
 //! with this header.
+//!
+//! The same data may be used to build the `force_reinitialize()` call, which
+//! resets already initialized bridge to the new trusted header.
 
 use bp_header_chain::InitializationData;
 use bp_header_chain::{
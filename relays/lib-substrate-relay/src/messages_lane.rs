@@ -78,6 +78,10 @@ pub trait SubstrateMessageLane: 'static + Clone + Send + Sync {
 	const INBOUND_LANE_LATEST_CONFIRMED_NONCE_METHOD: &'static str;
 	/// Number of the runtime method that returns state of "unrewarded relayers" set at the target chain.
 	const INBOUND_LANE_UNREWARDED_RELAYERS_STATE: &'static str;
+	/// Name of the inbound lane runtime API at the target chain.
+	const INBOUND_LANE_API: &'static str;
+	/// Name of the runtime method that returns latest queued nonce at the target chain.
+	const INBOUND_LANE_LATEST_QUEUED_NONCE_METHOD: &'static str;
 
 	/// Name of the runtime method that returns id of best finalized source header at target chain.
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str;
@@ -23,11 +23,11 @@ use crate::messages_target::SubstrateMessagesReceivingProof;
 use crate::on_demand_headers::OnDemandHeadersRelay;
 
 use async_trait::async_trait;
-use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{LaneId, MessageNonce, OutboundLaneData, UnrewardedRelayersState};
 use bridge_runtime_common::messages::{
 	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
 };
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
 use frame_support::weights::Weight;
 use messages_relay::message_lane::MessageLane;
 use messages_relay::{
@@ -53,6 +53,12 @@ use std::ops::RangeInclusive;
 /// the proof itself.
 pub type SubstrateMessagesProof<C> = (Weight, FromBridgedChainMessagesProof<HashOf<C>>);
 
+/// Expected size (in bytes) of the proof of message cancellation entry (or of its absence).
+///
+/// Cancellation entries of consecutive messages are stored in different trie branches, so every
+/// message adds its own cancellation entry path to the messages proof.
+const EXPECTED_MESSAGE_CANCELLATION_PROOF_SIZE: u32 = 256;
+
 /// Substrate client as Substrate messages source.
 pub struct SubstrateMessagesSource<P: SubstrateMessageLane> {
 	client: Client<P::SourceChain>,
@@ -186,10 +192,34 @@ where
 			)
 			.await?;
 
-		make_message_details_map::<P::SourceChain>(
-			Decode::decode(&mut &encoded_response.0[..]).map_err(SubstrateError::ResponseParseFailed)?,
+		let mut messages = make_message_details_map::<P::SourceChain>(
+			decode_message_details::<P::SourceChain>(&encoded_response.0[..])
+				.map_err(SubstrateError::ResponseParseFailed)?,
 			nonces,
-		)
+		)?;
+
+		// expired messages are never dispatched at the target chain and their delivery is not
+		// rewarded (unless target chain dispatches them before it learns about expiration). The
+		// lane state is read at the same block that is used to prove messages, so the target
+		// chain sees the same expired messages
+		let latest_expired_nonce = self
+			.client
+			.storage_value::<OutboundLaneData>(
+				pallet_bridge_messages::storage_keys::outbound_lane_data_key(
+					P::MESSAGE_PALLET_NAME_AT_SOURCE,
+					&self.lane_id,
+				),
+				Some(id.1),
+			)
+			.await?
+			.map(|lane_data| lane_data.latest_expired_nonce)
+			.unwrap_or(0);
+		for (_, details) in messages.range_mut(..=latest_expired_nonce) {
+			details.skipped = true;
+			details.reward = Zero::zero();
+		}
+
+		Ok(messages)
 	}
 
 	async fn prove_messages(
@@ -205,7 +235,9 @@ where
 		),
 		SubstrateError,
 	> {
-		let mut storage_keys = Vec::with_capacity(nonces.end().saturating_sub(*nonces.start()) as usize + 1);
+		// target chain learns about cancelled messages from the cancellation entries, so we're
+		// proving either presence or absence of the cancellation entry for every message
+		let mut storage_keys = Vec::with_capacity(2 * (nonces.end().saturating_sub(*nonces.start()) as usize + 1));
 		let mut message_nonce = *nonces.start();
 		while message_nonce <= *nonces.end() {
 			let message_key = pallet_bridge_messages::storage_keys::message_key(
@@ -213,10 +245,18 @@ where
 				&self.lane_id,
 				message_nonce,
 			);
+			let message_cancellation_key = pallet_bridge_messages::storage_keys::message_cancellation_key(
+				P::MESSAGE_PALLET_NAME_AT_SOURCE,
+				&self.lane_id,
+				message_nonce,
+			);
 			storage_keys.push(message_key);
+			storage_keys.push(message_cancellation_key);
 			message_nonce += 1;
 		}
-		if proof_parameters.outbound_state_proof_required {
+		// target chain learns about expired messages from the outbound lane state, so it requires
+		// the state to be included into every proof that has messages
+		if proof_parameters.outbound_state_proof_required || !nonces.is_empty() {
 			storage_keys.push(pallet_bridge_messages::storage_keys::outbound_lane_data_key(
 				P::MESSAGE_PALLET_NAME_AT_SOURCE,
 				&self.lane_id,
@@ -343,8 +383,27 @@ where
 	})
 }
 
+/// Decode `message_details` runtime call result.
+///
+/// Source chain runtime may still be using one of previous versions of the outbound lane API, that
+/// return message details without cancellation flags, or even without message senders. So if we
+/// fail to decode the current version, we fall back to previous versions.
+fn decode_message_details<C: Chain>(
+	encoded_response: &[u8],
+) -> Result<Vec<bp_messages::MessageDetails<C::Balance, C::AccountId>>, codec::Error> {
+	Vec::<bp_messages::MessageDetails<C::Balance, C::AccountId>>::decode_all(encoded_response)
+		.or_else(|_| {
+			Vec::<bp_messages::MessageDetailsV2<C::Balance, C::AccountId>>::decode_all(encoded_response)
+				.map(|details| details.into_iter().map(Into::into).collect())
+		})
+		.or_else(|_| {
+			Vec::<bp_messages::MessageDetailsV1<C::Balance>>::decode_all(encoded_response)
+				.map(|details| details.into_iter().map(Into::into).collect())
+		})
+}
+
 fn make_message_details_map<C: Chain>(
-	weights: Vec<bp_messages::MessageDetails<C::Balance>>,
+	weights: Vec<bp_messages::MessageDetails<C::Balance, C::AccountId>>,
 	nonces: RangeInclusive<MessageNonce>,
 ) -> Result<MessageDetailsMap<C::Balance>, SubstrateError> {
 	let make_missing_nonce_error = |expected_nonce| {
@@ -399,9 +458,12 @@ fn make_message_details_map<C: Chain>(
 			details.nonce,
 			MessageDetails {
 				dispatch_weight: details.dispatch_weight,
-				size: details.size as _,
+				// the cancellation entry (or its absence) is proved for every message, so it adds
+				// to both size and weight of the delivery transaction
+				size: details.size.saturating_add(EXPECTED_MESSAGE_CANCELLATION_PROOF_SIZE),
 				reward: details.delivery_and_dispatch_fee,
 				dispatch_fee_payment: details.dispatch_fee_payment,
+				skipped: details.cancelled,
 			},
 		);
 		expected_nonce = details.nonce + 1;
@@ -420,7 +482,7 @@ mod tests {
 
 	fn message_details_from_rpc(
 		nonces: RangeInclusive<MessageNonce>,
-	) -> Vec<bp_messages::MessageDetails<bp_wococo::Balance>> {
+	) -> Vec<bp_messages::MessageDetails<bp_wococo::Balance, bp_wococo::AccountId>> {
 		nonces
 			.into_iter()
 			.map(|nonce| bp_messages::MessageDetails {
@@ -429,10 +491,55 @@ mod tests {
 				size: 0,
 				delivery_and_dispatch_fee: 0,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				sender: Some(bp_messages::source_chain::Sender::Root),
+				cancelled: false,
 			})
 			.collect()
 	}
 
+	#[test]
+	fn decode_message_details_supports_previous_api_version() {
+		let details = message_details_from_rpc(1..=3);
+		assert_eq!(decode_message_details::<Wococo>(&details.encode()), Ok(details.clone()));
+
+		let v2_details = details
+			.iter()
+			.map(|details| bp_messages::MessageDetailsV2 {
+				nonce: details.nonce,
+				dispatch_weight: details.dispatch_weight,
+				size: details.size,
+				delivery_and_dispatch_fee: details.delivery_and_dispatch_fee,
+				dispatch_fee_payment: details.dispatch_fee_payment,
+				sender: details.sender.clone(),
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			decode_message_details::<Wococo>(&v2_details.encode()),
+			Ok(details.clone())
+		);
+
+		let v1_details = details
+			.iter()
+			.map(|details| bp_messages::MessageDetailsV1 {
+				nonce: details.nonce,
+				dispatch_weight: details.dispatch_weight,
+				size: details.size,
+				delivery_and_dispatch_fee: details.delivery_and_dispatch_fee,
+				dispatch_fee_payment: details.dispatch_fee_payment,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			decode_message_details::<Wococo>(&v1_details.encode()),
+			Ok(details
+				.into_iter()
+				.map(|details| bp_messages::MessageDetails {
+					sender: None,
+					..details
+				})
+				.collect()),
+		);
+	}
+
 	#[test]
 	fn make_message_details_map_succeeds_if_no_messages_are_missing() {
 		assert_eq!(
@@ -442,27 +549,30 @@ mod tests {
 					1,
 					MessageDetails {
 						dispatch_weight: 0,
-						size: 0,
+						size: EXPECTED_MESSAGE_CANCELLATION_PROOF_SIZE,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						skipped: false,
 					}
 				),
 				(
 					2,
 					MessageDetails {
 						dispatch_weight: 0,
-						size: 0,
+						size: EXPECTED_MESSAGE_CANCELLATION_PROOF_SIZE,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						skipped: false,
 					}
 				),
 				(
 					3,
 					MessageDetails {
 						dispatch_weight: 0,
-						size: 0,
+						size: EXPECTED_MESSAGE_CANCELLATION_PROOF_SIZE,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						skipped: false,
 					}
 				),
 			]
@@ -480,18 +590,20 @@ mod tests {
 					2,
 					MessageDetails {
 						dispatch_weight: 0,
-						size: 0,
+						size: EXPECTED_MESSAGE_CANCELLATION_PROOF_SIZE,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						skipped: false,
 					}
 				),
 				(
 					3,
 					MessageDetails {
 						dispatch_weight: 0,
-						size: 0,
+						size: EXPECTED_MESSAGE_CANCELLATION_PROOF_SIZE,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						skipped: false,
 					}
 				),
 			]
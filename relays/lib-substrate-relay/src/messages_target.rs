@@ -44,6 +44,9 @@ use sp_core::Bytes;
 use sp_runtime::{traits::Saturating, DeserializeOwned, FixedPointNumber, FixedU128};
 use std::{convert::TryFrom, ops::RangeInclusive};
 
+/// Version of the inbound lane runtime API, where the `latest_queued_nonce` method has been added.
+const LATEST_QUEUED_NONCE_METHOD_API_VERSION: u32 = 2;
+
 /// Message receiving proof returned by the target Substrate node.
 pub type SubstrateMessagesReceivingProof<C> = (
 	UnrewardedRelayersState,
@@ -155,6 +158,33 @@ where
 		Ok((id, latest_received_nonce))
 	}
 
+	async fn latest_queued_nonce(
+		&self,
+		id: TargetHeaderIdOf<P::MessageLane>,
+	) -> Result<(TargetHeaderIdOf<P::MessageLane>, Option<MessageNonce>), SubstrateError> {
+		// runtimes that have been deployed before the dispatch queue has been introduced, are not
+		// implementing the method
+		let runtime_version = self.client.runtime_version().await?;
+		let inbound_lane_api_id = sp_core::hashing::blake2_64(P::INBOUND_LANE_API.as_bytes());
+		if !runtime_version.has_api_with(&inbound_lane_api_id, |version| {
+			version >= LATEST_QUEUED_NONCE_METHOD_API_VERSION
+		}) {
+			return Ok((id, None));
+		}
+
+		let encoded_response = self
+			.client
+			.state_call(
+				P::INBOUND_LANE_LATEST_QUEUED_NONCE_METHOD.into(),
+				Bytes(self.lane_id.encode()),
+				Some(id.1),
+			)
+			.await?;
+		let latest_queued_nonce: Option<MessageNonce> =
+			Decode::decode(&mut &encoded_response.0[..]).map_err(SubstrateError::ResponseParseFailed)?;
+		Ok((id, latest_queued_nonce))
+	}
+
 	async fn latest_confirmed_received_nonce(
 		&self,
 		id: TargetHeaderIdOf<P::MessageLane>,
@@ -436,6 +466,8 @@ mod tests {
 		const INBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str = "";
 		const INBOUND_LANE_LATEST_CONFIRMED_NONCE_METHOD: &'static str = "";
 		const INBOUND_LANE_UNREWARDED_RELAYERS_STATE: &'static str = "";
+		const INBOUND_LANE_API: &'static str = "";
+		const INBOUND_LANE_LATEST_QUEUED_NONCE_METHOD: &'static str = "";
 
 		const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = "";
 		const BEST_FINALIZED_TARGET_HEADER_ID_AT_SOURCE: &'static str = "";
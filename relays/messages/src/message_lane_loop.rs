@@ -100,6 +100,10 @@ pub struct MessageDetails<SourceChainBalance> {
 	pub reward: SourceChainBalance,
 	/// Where the fee for dispatching message is paid?
 	pub dispatch_fee_payment: DispatchFeePayment,
+	/// True if the message has been expired or cancelled at the source chain. Skipped messages are
+	/// never dispatched at the target chain and their delivery is not rewarded, but they still need
+	/// to be delivered to unblock the lane.
+	pub skipped: bool,
 }
 
 /// Messages details map.
@@ -176,6 +180,15 @@ pub trait TargetClient<P: MessageLane>: RelayClient {
 		id: TargetHeaderIdOf<P>,
 	) -> Result<(TargetHeaderIdOf<P>, MessageNonce), Self::Error>;
 
+	/// Get nonce of the latest message that has been queued for dispatch.
+	///
+	/// Queued messages are received, but not yet delivered (dispatched). They must not be
+	/// delivered again. Returns `None` if there are no queued messages at the lane.
+	async fn latest_queued_nonce(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, Option<MessageNonce>), Self::Error>;
+
 	/// Get nonce of the latest confirmed message.
 	async fn latest_confirmed_received_nonce(
 		&self,
@@ -597,6 +610,7 @@ pub(crate) mod tests {
 							size: 1,
 							reward: 1,
 							dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+							skipped: false,
 						},
 					)
 				})
@@ -725,6 +739,13 @@ pub(crate) mod tests {
 			))
 		}
 
+		async fn latest_queued_nonce(
+			&self,
+			id: TargetHeaderIdOf<TestMessageLane>,
+		) -> Result<(TargetHeaderIdOf<TestMessageLane>, Option<MessageNonce>), TestError> {
+			Ok((id, None))
+		}
+
 		async fn latest_confirmed_received_nonce(
 			&self,
 			id: TargetHeaderIdOf<TestMessageLane>,
@@ -182,6 +182,7 @@ where
 		update_metrics: bool,
 	) -> Result<(TargetHeaderIdOf<P>, TargetClientNonces<DeliveryRaceTargetNoncesData>), Self::Error> {
 		let (at_block, latest_received_nonce) = self.client.latest_received_nonce(at_block).await?;
+		let (at_block, latest_queued_nonce) = self.client.latest_queued_nonce(at_block).await?;
 		let (at_block, latest_confirmed_nonce) = self.client.latest_confirmed_received_nonce(at_block).await?;
 		let (at_block, unrewarded_relayers) = self.client.unrewarded_relayers_state(at_block).await?;
 
@@ -195,7 +196,8 @@ where
 		Ok((
 			at_block,
 			TargetClientNonces {
-				latest_nonce: latest_received_nonce,
+				// queued messages are not yet delivered, but they must not be delivered again
+				latest_nonce: std::cmp::max(latest_received_nonce, latest_queued_nonce.unwrap_or(0)),
 				nonces_data: DeliveryRaceTargetNoncesData {
 					confirmed_nonce: latest_confirmed_nonce,
 					unrewarded_relayers,
@@ -636,17 +638,20 @@ async fn select_nonces_for_delivery_transaction<P: MessageLane>(
 		// If dispatch fee has been paid at the source chain, it means that it is **relayer** who's
 		// paying for dispatch at the target chain AND reward must cover this dispatch fee.
 		//
-		// If dispatch fee is paid at the target chain, it means that it'll be withdrawn from the
-		// dispatch origin account AND reward is not covering this fee.
+		// If dispatch fee is paid at the target chain (in any asset), it means that it'll be withdrawn
+		// from the dispatch origin account AND reward is not covering this fee.
 		//
 		// So in the latter case we're not adding the dispatch weight to the delivery transaction weight.
+		//
+		// Skipped messages are never dispatched, so there's no dispatch fee at all.
 		let mut new_selected_prepaid_nonces = selected_prepaid_nonces;
 		let new_selected_unpaid_weight = match details.dispatch_fee_payment {
+			_ if details.skipped => selected_unpaid_weight,
 			DispatchFeePayment::AtSourceChain => {
 				new_selected_prepaid_nonces += 1;
 				selected_unpaid_weight.saturating_add(details.dispatch_weight)
 			}
-			DispatchFeePayment::AtTargetChain => selected_unpaid_weight,
+			DispatchFeePayment::AtTargetChain | DispatchFeePayment::AtTargetChainInAsset(_) => selected_unpaid_weight,
 		};
 
 		// now the message has passed all 'strong' checks, and we CAN deliver it. But do we WANT
@@ -678,7 +683,11 @@ async fn select_nonces_for_delivery_transaction<P: MessageLane>(
 				let is_total_reward_less_than_cost = total_reward < total_cost;
 				let prev_total_cost = total_cost;
 				let prev_total_reward = total_reward;
-				total_confirmations_cost = total_confirmations_cost.saturating_add(&confirmation_transaction_cost);
+				// delivery of skipped messages is not rewarded, so there's nothing to confirm. But
+				// we still need to deliver them, because otherwise the lane is blocked
+				if !details.skipped {
+					total_confirmations_cost = total_confirmations_cost.saturating_add(&confirmation_transaction_cost);
+				}
 				total_reward = total_reward.saturating_add(&details.reward);
 				total_cost = total_confirmations_cost.saturating_add(&delivery_transaction_cost);
 				if !is_total_reward_less_than_cost && total_reward < total_cost {
@@ -820,6 +829,7 @@ mod tests {
 							size: DEFAULT_SIZE,
 							reward,
 							dispatch_fee_payment,
+							skipped: false,
 						},
 					)
 				})
@@ -900,6 +910,7 @@ mod tests {
 							size: idx as _,
 							reward: idx as _,
 							dispatch_fee_payment: AtSourceChain,
+							skipped: false,
 						},
 					)
 				})
@@ -1244,12 +1255,55 @@ mod tests {
 			test_with_dispatch_fee_payment(AtTargetChain).await,
 			Some(((20..=24), proof_parameters(false, 5)))
 		);
+		assert_eq!(
+			test_with_dispatch_fee_payment(AtTargetChainInAsset(42)).await,
+			Some(((20..=24), proof_parameters(false, 5)))
+		);
 		assert_eq!(
 			test_with_dispatch_fee_payment(AtSourceChain).await,
 			Some(((20..=23), proof_parameters(false, 4)))
 		);
 	}
 
+	#[async_std::test]
+	async fn rational_relayer_is_delivering_skipped_messages_without_confirmation_cost() {
+		let (mut state, mut strategy) = prepare_strategy();
+		let mut nonces = source_nonces(
+			25..=25,
+			19,
+			DEFAULT_REWARD + BASE_MESSAGE_DELIVERY_TRANSACTION_COST,
+			AtSourceChain,
+		);
+		nonces.new_nonces.insert(
+			24,
+			MessageDetails {
+				dispatch_weight: 0,
+				size: 0,
+				reward: 0,
+				dispatch_fee_payment: AtSourceChain,
+				skipped: true,
+			},
+		);
+		strategy.strategy.source_nonces_updated(header_id(2), nonces);
+		state.best_finalized_source_header_id_at_best_target = Some(header_id(2));
+		strategy.max_unrewarded_relayer_entries_at_target = 100;
+		strategy.max_unconfirmed_nonces_at_target = 100;
+		strategy.max_messages_in_single_batch = 100;
+		strategy.max_messages_weight_in_single_batch = 100;
+		strategy.max_messages_size_in_single_batch = 100;
+		strategy.relayer_mode = RelayerMode::Rational;
+
+		// so now we have:
+		// - 20..=23 with reward = cost
+		// - 24..=24 that is skipped, so it has no reward and we only need to pay for its delivery
+		// - 25..=25 with reward that covers both its cost and the delivery cost of MSG#24
+		// => strategy shall select all 20..=25
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=25), proof_parameters(false, 5)))
+		);
+	}
+
 	#[async_std::test]
 	async fn relayer_uses_flattened_view_of_the_source_queue_to_select_nonces() {
 		// Real scenario that has happened on test deployments: